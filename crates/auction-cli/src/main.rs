@@ -0,0 +1,271 @@
+//! Command line tool for resolving auctions from files.
+//!
+//! ```sh
+//! auction resolve --bids bids.csv --lots 100 --reserve 50 --strategy single-price --out sales.csv
+//! auction explain --bids bids.csv --lots 100 --format json
+//! auction resolve --bid 150x3 --bid 120x1 --lots 3
+//! ```
+//!
+//! Bids are read from CSV as by `auction::io::read_bids_csv`, or given one
+//! at a time as parsed by `Bid`'s `FromStr`. Sales are
+//! written as CSV or JSON Lines, to standard output unless `--out` is given.
+#![warn(clippy::all, clippy::nursery)]
+
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::PathBuf,
+    process::ExitCode,
+};
+
+use auction::{
+    io as auction_io, Auction, AuctionBuilder, AuctionStrategy, Bid, BidOutcome, Bids, Outcome,
+};
+use clap::{
+    builder::PossibleValuesParser, value_parser, Arg, ArgAction, ArgGroup, ArgMatches, Command,
+};
+
+/// Sales were made.
+const EXIT_SOLD: u8 = 0;
+/// The bids were resolved but nothing sold.
+const EXIT_NO_SALES: u8 = 1;
+/// The arguments or the auction they describe are invalid.
+const EXIT_USAGE: u8 = 2;
+/// The bids file could not be parsed.
+const EXIT_PARSE: u8 = 3;
+/// A file could not be read or written.
+const EXIT_IO: u8 = 4;
+
+/// The strategies that need no parameters, by their command line names.
+const STRATEGIES: [(&str, AuctionStrategy); 8] = [
+    ("single-price", AuctionStrategy::SinglePrice),
+    ("multi-price", AuctionStrategy::MultiPrice),
+    ("all-pay", AuctionStrategy::AllPay),
+    (
+        "generalized-second-price",
+        AuctionStrategy::GeneralizedSecondPrice,
+    ),
+    ("proxy", AuctionStrategy::Proxy),
+    ("vcg", AuctionStrategy::Vcg),
+    ("scored", AuctionStrategy::Scored),
+    ("posted-price", AuctionStrategy::PostedPrice),
+];
+
+/// Why the tool failed, each kind exiting with its own code.
+#[derive(Debug)]
+enum CliError {
+    Usage(String),
+    Parse(String),
+    Io(String),
+}
+
+impl CliError {
+    const fn exit_code(&self) -> u8 {
+        match self {
+            Self::Usage(_) => EXIT_USAGE,
+            Self::Parse(_) => EXIT_PARSE,
+            Self::Io(_) => EXIT_IO,
+        }
+    }
+
+    fn message(&self) -> &str {
+        match self {
+            Self::Usage(message) | Self::Parse(message) | Self::Io(message) => message,
+        }
+    }
+}
+
+fn command() -> Command {
+    let args = [
+        Arg::new("bids")
+            .long("bids")
+            .value_name("FILE")
+            .help("CSV of bids with bidder_id, amount and quantity columns")
+            .value_parser(value_parser!(PathBuf)),
+        Arg::new("bid")
+            .long("bid")
+            .value_name("BID")
+            .help("A bid such as 150x3 or id:150x3, may be repeated")
+            .action(ArgAction::Append)
+            .value_parser(value_parser!(Bid)),
+        Arg::new("lots")
+            .long("lots")
+            .help("Number of lots for sale")
+            .default_value("1")
+            .value_parser(value_parser!(usize)),
+        Arg::new("reserve")
+            .long("reserve")
+            .value_name("CENTS")
+            .help("Lowest price a lot sells for")
+            .default_value("0")
+            .value_parser(value_parser!(i64)),
+        Arg::new("strategy")
+            .long("strategy")
+            .help("How winners are chosen and priced")
+            .default_value("single-price")
+            .value_parser(PossibleValuesParser::new(STRATEGIES.map(|(name, _)| name))),
+        Arg::new("format")
+            .long("format")
+            .help("Format of the output")
+            .default_value("csv")
+            .value_parser(["csv", "json"]),
+        Arg::new("out")
+            .long("out")
+            .value_name("FILE")
+            .help("Where to write the output, standard output by default")
+            .value_parser(value_parser!(PathBuf)),
+    ];
+    let input = ArgGroup::new("input")
+        .args(["bids", "bid"])
+        .multiple(true)
+        .required(true);
+    Command::new("auction")
+        .about("Resolve auctions from files")
+        .subcommand_required(true)
+        .subcommand(
+            Command::new("resolve")
+                .about("Write the sales the bids resolve into")
+                .args(args.clone())
+                .group(input.clone()),
+        )
+        .subcommand(
+            Command::new("explain")
+                .about("Write what happened to each bid, for single and multi price auctions")
+                .args(args)
+                .group(input),
+        )
+}
+
+/// The auction the arguments describe.
+fn auction(matches: &ArgMatches) -> Result<Auction, CliError> {
+    let name = matches.get_one::<String>("strategy").expect("defaulted");
+    let strategy = STRATEGIES
+        .into_iter()
+        .find_map(|(known, strategy)| (known == name).then_some(strategy))
+        .expect("validated by the parser");
+    AuctionBuilder::new()
+        .lots(*matches.get_one("lots").expect("defaulted"))
+        .reserve_price(*matches.get_one("reserve").expect("defaulted"))
+        .strategy(strategy)
+        .try_build()
+        .map_err(|error| CliError::Usage(error.to_string()))
+}
+
+/// The bids from the bids file followed by those given with `--bid`.
+fn read_bids(matches: &ArgMatches) -> Result<Bids, CliError> {
+    let mut bids = match matches.get_one::<PathBuf>("bids") {
+        Some(path) => {
+            let file = File::open(path)
+                .map_err(|error| CliError::Io(format!("{}: {error}", path.display())))?;
+            auction_io::read_bids_csv(file)
+                .map_err(|error| CliError::Parse(format!("{}: {error}", path.display())))?
+        }
+        None => Bids::new(),
+    };
+    bids.extend(
+        matches
+            .get_many::<Bid>("bid")
+            .into_iter()
+            .flatten()
+            .copied(),
+    );
+    Ok(bids)
+}
+
+fn output(matches: &ArgMatches) -> Result<Box<dyn Write>, CliError> {
+    matches.get_one::<PathBuf>("out").map_or_else(
+        || Ok(Box::new(io::stdout().lock()) as Box<dyn Write>),
+        |path| {
+            File::create(path)
+                .map(|file| Box::new(BufWriter::new(file)) as Box<dyn Write>)
+                .map_err(|error| CliError::Io(format!("{}: {error}", path.display())))
+        },
+    )
+}
+
+fn is_json(matches: &ArgMatches) -> bool {
+    matches
+        .get_one::<String>("format")
+        .is_some_and(|format| format == "json")
+}
+
+/// Writes the outcomes as CSV with `bid_id`, `outcome`, `filled` and `price`
+/// columns, the price blank unless the bid won in full.
+fn write_outcomes_csv(mut writer: impl Write, outcomes: &[BidOutcome]) -> io::Result<()> {
+    writeln!(writer, "bid_id,outcome,filled,price")?;
+    for outcome in outcomes {
+        let (name, filled, price) = match outcome.outcome() {
+            Outcome::Won { quantity, price } => ("won", quantity, Some(price)),
+            Outcome::PartiallyFilled { filled, .. } => ("partially_filled", filled, None),
+            Outcome::LostBelowClearing => ("lost_below_clearing", 0, None),
+            Outcome::BelowReserve => ("below_reserve", 0, None),
+            Outcome::SkippedNoLots => ("skipped_no_lots", 0, None),
+            Outcome::OffTick => ("off_tick", 0, None),
+            Outcome::ExceedsBidCap => ("exceeds_bid_cap", 0, None),
+            Outcome::CurrencyMismatch => ("currency_mismatch", 0, None),
+        };
+        let price = price.map(|price| price.to_string()).unwrap_or_default();
+        writeln!(writer, "{},{name},{filled},{price}", outcome.bid_id())?;
+    }
+    Ok(())
+}
+
+fn write_outcomes_json_lines(mut writer: impl Write, outcomes: &[BidOutcome]) -> io::Result<()> {
+    for outcome in outcomes {
+        serde_json::to_writer(&mut writer, outcome)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+/// Runs the subcommand, returning whether anything sold.
+fn run(subcommand: &str, matches: &ArgMatches) -> Result<bool, CliError> {
+    let auction = auction(matches)?;
+    let bids = read_bids(matches)?;
+    let mut writer = output(matches)?;
+    let (sold, written) = match subcommand {
+        "resolve" => {
+            let sales = auction.resolve_bids(bids);
+            let written = if is_json(matches) {
+                auction_io::write_sales_json_lines(&mut writer, &sales)
+            } else {
+                auction_io::write_sales_csv(&mut writer, &sales)
+            };
+            (!sales.is_empty(), written)
+        }
+        _ => {
+            if !matches!(
+                auction.strategy(),
+                AuctionStrategy::SinglePrice | AuctionStrategy::MultiPrice
+            ) {
+                return Err(CliError::Usage(
+                    "only single and multi price auctions can be explained".to_string(),
+                ));
+            }
+            let (sales, outcomes) = auction.resolve_explained(bids);
+            let written = if is_json(matches) {
+                write_outcomes_json_lines(&mut writer, &outcomes)
+            } else {
+                write_outcomes_csv(&mut writer, &outcomes)
+            };
+            (!sales.is_empty(), written)
+        }
+    };
+    written
+        .and_then(|()| writer.flush())
+        .map_err(|error| CliError::Io(format!("write failed: {error}")))?;
+    Ok(sold)
+}
+
+fn main() -> ExitCode {
+    let matches = command().get_matches();
+    let (subcommand, matches) = matches.subcommand().expect("subcommand is required");
+    match run(subcommand, matches) {
+        Ok(true) => ExitCode::from(EXIT_SOLD),
+        Ok(false) => ExitCode::from(EXIT_NO_SALES),
+        Err(error) => {
+            eprintln!("error: {}", error.message());
+            ExitCode::from(error.exit_code())
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::{fs, path::PathBuf};
+
+use assert_cmd::Command;
+use predicates::prelude::*;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+fn auction() -> Command {
+    Command::cargo_bin("auction").unwrap()
+}
+
+fn bids() -> String {
+    fixture("bids.csv").display().to_string()
+}
+
+#[test]
+fn resolve_writes_sales_csv() {
+    auction()
+        .args([
+            "resolve",
+            "--bids",
+            &bids(),
+            "--lots",
+            "3",
+            "--reserve",
+            "50",
+        ])
+        .assert()
+        .code(0)
+        .stdout(
+            "bidder_id,amount,quantity\n\
+             00000000-0000-0000-0000-000000000001,120,2\n\
+             00000000-0000-0000-0000-000000000002,120,1\n",
+        );
+}
+
+#[test]
+fn resolve_writes_json_lines_to_a_file() {
+    let out = std::env::temp_dir().join(format!("auction-cli-{}.jsonl", std::process::id()));
+    auction()
+        .args([
+            "resolve",
+            "--bids",
+            &bids(),
+            "--lots",
+            "2",
+            "--strategy",
+            "multi-price",
+            "--format",
+            "json",
+            "--out",
+            &out.display().to_string(),
+        ])
+        .assert()
+        .code(0)
+        .stdout(predicate::str::is_empty());
+    let written = fs::read_to_string(&out).unwrap();
+    fs::remove_file(&out).unwrap();
+    let sales: Vec<serde_json::Value> = written
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(sales.len(), 1);
+    assert_eq!(sales[0]["amount"], 150);
+    assert_eq!(sales[0]["quantity"], 2);
+}
+
+#[test]
+fn explain_writes_each_outcome() {
+    auction()
+        .args([
+            "explain",
+            "--bids",
+            &bids(),
+            "--lots",
+            "2",
+            "--reserve",
+            "50",
+        ])
+        .assert()
+        .code(0)
+        .stdout(
+            "bid_id,outcome,filled,price\n\
+             00000000-0000-0000-0000-0000000000b1,won,2,150\n\
+             00000000-0000-0000-0000-0000000000b2,lost_below_clearing,0,\n\
+             00000000-0000-0000-0000-0000000000b3,below_reserve,0,\n",
+        );
+}
+
+#[test]
+fn nothing_sold_exits_one() {
+    auction()
+        .args(["resolve", "--bids", &bids(), "--reserve", "1000"])
+        .assert()
+        .code(1)
+        .stdout("bidder_id,amount,quantity\n");
+}
+
+#[test]
+fn invalid_arguments_exit_two() {
+    auction()
+        .args(["resolve", "--bids", &bids(), "--lots", "0"])
+        .assert()
+        .code(2);
+    auction()
+        .args(["resolve", "--bids", &bids(), "--strategy", "blind"])
+        .assert()
+        .code(2);
+    auction()
+        .args(["explain", "--bids", &bids(), "--strategy", "vcg"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn unparseable_bids_exit_three() {
+    let path = fixture("invalid_bids.csv").display().to_string();
+    auction()
+        .args(["resolve", "--bids", &path])
+        .assert()
+        .code(3)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains("line 3"));
+}
+
+#[test]
+fn missing_bids_file_exits_four() {
+    let path = fixture("missing.csv").display().to_string();
+    auction()
+        .args(["resolve", "--bids", &path])
+        .assert()
+        .code(4);
+}
+
+#[test]
+fn bids_may_be_given_as_arguments() {
+    auction()
+        .args([
+            "resolve",
+            "--bid",
+            "00000000-0000-0000-0000-000000000001:150x2",
+            "--bid",
+            "120 x 1 [00000000-0000-0000-0000-000000000002]",
+            "--bid",
+            "40",
+            "--lots",
+            "3",
+            "--strategy",
+            "multi-price",
+        ])
+        .assert()
+        .code(0)
+        .stdout(
+            "bidder_id,amount,quantity\n\
+             00000000-0000-0000-0000-000000000001,150,2\n\
+             00000000-0000-0000-0000-000000000002,120,1\n",
+        );
+}
+
+#[test]
+fn unparseable_bid_argument_is_a_usage_error() {
+    auction()
+        .args(["resolve", "--bid", "150xlots"])
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains("byte 4: invalid quantity `lots`"));
+    auction().args(["resolve", "--lots", "2"]).assert().code(2);
+}
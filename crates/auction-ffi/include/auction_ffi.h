@@ -0,0 +1,58 @@
+/* C ABI for resolving auctions, implemented by the auction-ffi crate. */
+#ifndef AUCTION_FFI_H
+#define AUCTION_FFI_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define AUCTION_OK 0
+#define AUCTION_ERROR_NULL_POINTER -1
+#define AUCTION_ERROR_INVALID_CONFIG -2
+#define AUCTION_ERROR_UNKNOWN_STRATEGY -3
+#define AUCTION_ERROR_PANIC -4
+
+#define AUCTION_STRATEGY_SINGLE_PRICE 0
+#define AUCTION_STRATEGY_MULTI_PRICE 1
+#define AUCTION_STRATEGY_ALL_PAY 2
+#define AUCTION_STRATEGY_GENERALIZED_SECOND_PRICE 3
+#define AUCTION_STRATEGY_VCG 4
+
+typedef struct Auction Auction;
+
+typedef struct AuctionBid {
+    uint8_t id[16];
+    int64_t amount;
+    size_t quantity;
+} AuctionBid;
+
+typedef struct AuctionSale {
+    uint8_t bidder_id[16];
+    uint8_t bid_id[16];
+    int64_t amount;
+    size_t quantity;
+} AuctionSale;
+
+/* Writes a handle to `out`, destroy it with auction_free. */
+int32_t auction_new(size_t lots, int64_t reserve_price, uint32_t strategy, Auction **out);
+
+void auction_free(Auction *auction);
+
+/* Writes an allocated array of sales and its length, free it with
+ * auction_sales_free. No sales are a null array of length zero. */
+int32_t auction_resolve(const Auction *auction,
+                        const AuctionBid *bids,
+                        size_t bids_len,
+                        AuctionSale **out_sales,
+                        size_t *out_len);
+
+void auction_sales_free(AuctionSale *sales, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* AUCTION_FFI_H */
@@ -0,0 +1,200 @@
+//! C ABI for resolving auctions.
+//!
+//! Auctions are opaque handles created by [`auction_new`] and destroyed by
+//! [`auction_free`]. [`auction_resolve`] allocates the sales it returns,
+//! which the caller hands back to [`auction_sales_free`]. Every function
+//! returns [`AUCTION_OK`] or a negative error code, and panics are caught
+//! before they reach the caller. The declarations for C and C++ callers are
+//! in `include/auction_ffi.h`.
+#![warn(clippy::all, clippy::nursery)]
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use auction::{Auction, AuctionBuilder, AuctionStrategy, Bids};
+use uuid::Uuid;
+
+/// The call succeeded.
+pub const AUCTION_OK: i32 = 0;
+/// A required pointer was null.
+pub const AUCTION_ERROR_NULL_POINTER: i32 = -1;
+/// The auction configuration was invalid.
+pub const AUCTION_ERROR_INVALID_CONFIG: i32 = -2;
+/// The strategy code was not recognised.
+pub const AUCTION_ERROR_UNKNOWN_STRATEGY: i32 = -3;
+/// The resolver panicked, the outputs are left untouched.
+pub const AUCTION_ERROR_PANIC: i32 = -4;
+
+/// Lots sell at the lowest winning bid.
+pub const AUCTION_STRATEGY_SINGLE_PRICE: u32 = 0;
+/// Lots sell at each winning bid.
+pub const AUCTION_STRATEGY_MULTI_PRICE: u32 = 1;
+/// Every bidder pays their bid.
+pub const AUCTION_STRATEGY_ALL_PAY: u32 = 2;
+/// Lots sell at the next highest bid.
+pub const AUCTION_STRATEGY_GENERALIZED_SECOND_PRICE: u32 = 3;
+/// Lots sell at the price each winner's externality imposes.
+pub const AUCTION_STRATEGY_VCG: u32 = 4;
+
+/// A bid of `amount` cents for each of `quantity` lots. The id is the bytes
+/// of a UUID and is also the bidder's id.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionBid {
+    pub id: [u8; 16],
+    pub amount: i64,
+    pub quantity: usize,
+}
+
+/// `quantity` lots sold to the bidder at `amount` cents each. The bid id is
+/// all zeros for sales not made from a bid.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuctionSale {
+    pub bidder_id: [u8; 16],
+    pub bid_id: [u8; 16],
+    pub amount: i64,
+    pub quantity: usize,
+}
+
+/// The strategy a code stands for.
+const fn strategy_from_code(strategy: u32) -> Option<AuctionStrategy> {
+    match strategy {
+        AUCTION_STRATEGY_SINGLE_PRICE => Some(AuctionStrategy::SinglePrice),
+        AUCTION_STRATEGY_MULTI_PRICE => Some(AuctionStrategy::MultiPrice),
+        AUCTION_STRATEGY_ALL_PAY => Some(AuctionStrategy::AllPay),
+        AUCTION_STRATEGY_GENERALIZED_SECOND_PRICE => Some(AuctionStrategy::GeneralizedSecondPrice),
+        AUCTION_STRATEGY_VCG => Some(AuctionStrategy::Vcg),
+        _ => None,
+    }
+}
+
+/// Run the body, turning a panic into [`AUCTION_ERROR_PANIC`].
+fn guard(body: impl FnOnce() -> i32) -> i32 {
+    panic::catch_unwind(AssertUnwindSafe(body)).unwrap_or(AUCTION_ERROR_PANIC)
+}
+
+/// Create an auction of `lots` lots with the given reserve price and
+/// strategy code, writing its handle to `out`.
+///
+/// # Safety
+/// `out` must be null or valid for writing a pointer. The handle written
+/// must be destroyed with [`auction_free`].
+#[no_mangle]
+pub unsafe extern "C" fn auction_new(
+    lots: usize,
+    reserve_price: i64,
+    strategy: u32,
+    out: *mut *mut Auction,
+) -> i32 {
+    guard(|| {
+        if out.is_null() {
+            return AUCTION_ERROR_NULL_POINTER;
+        }
+        let Some(strategy) = strategy_from_code(strategy) else {
+            return AUCTION_ERROR_UNKNOWN_STRATEGY;
+        };
+        let Ok(auction) = AuctionBuilder::new()
+            .lots(lots)
+            .reserve_price(reserve_price)
+            .strategy(strategy)
+            .try_build()
+        else {
+            return AUCTION_ERROR_INVALID_CONFIG;
+        };
+        // SAFETY: the caller guarantees `out` is valid for writes.
+        unsafe { out.write(Box::into_raw(Box::new(auction))) };
+        AUCTION_OK
+    })
+}
+
+/// Destroy an auction created by [`auction_new`]. Null is ignored.
+///
+/// # Safety
+/// `auction` must be null or a handle from [`auction_new`] not already
+/// destroyed.
+#[no_mangle]
+pub unsafe extern "C" fn auction_free(auction: *mut Auction) {
+    if !auction.is_null() {
+        // SAFETY: the handle came from `Box::into_raw` in `auction_new`.
+        drop(unsafe { Box::from_raw(auction) });
+    }
+}
+
+/// Resolve `bids_len` bids against the auction, writing an allocated array
+/// of the sales to `out_sales` and its length to `out_len`. No sales are
+/// written as a null array of length zero.
+///
+/// # Safety
+/// `auction` must be a live handle from [`auction_new`], `bids` must point
+/// to `bids_len` bids or be null when `bids_len` is zero, and `out_sales`
+/// and `out_len` must be valid for writes. The array written must be freed
+/// with [`auction_sales_free`] along with its length.
+#[no_mangle]
+pub unsafe extern "C" fn auction_resolve(
+    auction: *const Auction,
+    bids: *const AuctionBid,
+    bids_len: usize,
+    out_sales: *mut *mut AuctionSale,
+    out_len: *mut usize,
+) -> i32 {
+    guard(|| {
+        if auction.is_null()
+            || out_sales.is_null()
+            || out_len.is_null()
+            || (bids.is_null() && bids_len > 0)
+        {
+            return AUCTION_ERROR_NULL_POINTER;
+        }
+        // SAFETY: the caller guarantees the handle is live and the bids
+        // point to `bids_len` values.
+        let (auction, bids) = unsafe {
+            let bids = match bids_len {
+                0 => &[],
+                len => slice::from_raw_parts(bids, len),
+            };
+            (&*auction, bids)
+        };
+        let bids: Bids = bids
+            .iter()
+            .map(|bid| auction::Bid::with_id(Uuid::from_bytes(bid.id), bid.amount, bid.quantity))
+            .collect();
+        let sales: Box<[AuctionSale]> = auction
+            .resolve_bids(bids)
+            .iter()
+            .map(|sale| AuctionSale {
+                bidder_id: sale.bidder_id().into_bytes(),
+                bid_id: sale.bid_id().unwrap_or_else(Uuid::nil).into_bytes(),
+                amount: sale.amount(),
+                quantity: sale.quantity(),
+            })
+            .collect();
+        let len = sales.len();
+        let sales = match len {
+            0 => ptr::null_mut(),
+            _ => Box::into_raw(sales).cast::<AuctionSale>(),
+        };
+        // SAFETY: the caller guarantees both outputs are valid for writes.
+        unsafe {
+            out_sales.write(sales);
+            out_len.write(len);
+        }
+        AUCTION_OK
+    })
+}
+
+/// Free sales written by [`auction_resolve`]. Null is ignored.
+///
+/// # Safety
+/// `sales` must be null or an array from [`auction_resolve`] not already
+/// freed, and `len` the length written with it.
+#[no_mangle]
+pub unsafe extern "C" fn auction_sales_free(sales: *mut AuctionSale, len: usize) {
+    if !sales.is_null() {
+        // SAFETY: the array came from a boxed slice of `len` sales in
+        // `auction_resolve`.
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(sales, len)) });
+    }
+}
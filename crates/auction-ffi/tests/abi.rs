@@ -0,0 +1,100 @@
+use std::ptr;
+
+use auction::Auction;
+use auction_ffi::*;
+
+fn bid(id: u8, amount: i64, quantity: usize) -> AuctionBid {
+    AuctionBid {
+        id: [id; 16],
+        amount,
+        quantity,
+    }
+}
+
+fn new_auction(lots: usize, reserve_price: i64, strategy: u32) -> Result<*mut Auction, i32> {
+    let mut auction = ptr::null_mut();
+    match unsafe { auction_new(lots, reserve_price, strategy, &mut auction) } {
+        AUCTION_OK => Ok(auction),
+        code => Err(code),
+    }
+}
+
+/// Resolve through the C ABI, copying the sales out before freeing them.
+fn resolve(auction: *const Auction, bids: &[AuctionBid]) -> Vec<AuctionSale> {
+    let mut sales = ptr::null_mut();
+    let mut len = usize::MAX;
+    let code = unsafe { auction_resolve(auction, bids.as_ptr(), bids.len(), &mut sales, &mut len) };
+    assert_eq!(code, AUCTION_OK);
+    let copied = match len {
+        0 => {
+            assert!(sales.is_null());
+            Vec::new()
+        }
+        len => unsafe { std::slice::from_raw_parts(sales, len) }.to_vec(),
+    };
+    unsafe { auction_sales_free(sales, len) };
+    copied
+}
+
+#[test]
+fn resolves_through_the_c_abi() {
+    let auction = new_auction(2, 0, AUCTION_STRATEGY_MULTI_PRICE).unwrap();
+    let sales = resolve(auction, &[bid(1, 10, 2), bid(2, 20, 1)]);
+    let filled: Vec<_> = sales
+        .iter()
+        .map(|sale| {
+            (
+                sale.bidder_id[0],
+                sale.bid_id[0],
+                sale.amount,
+                sale.quantity,
+            )
+        })
+        .collect();
+    assert_eq!(filled, vec![(2, 2, 20, 1), (1, 1, 10, 1)]);
+    unsafe { auction_free(auction) };
+}
+
+#[test]
+fn no_sales_are_a_null_array() {
+    let auction = new_auction(2, 50, AUCTION_STRATEGY_SINGLE_PRICE).unwrap();
+    assert!(resolve(auction, &[bid(1, 20, 1)]).is_empty());
+    assert!(resolve(auction, &[]).is_empty());
+    let mut sales = ptr::null_mut();
+    let mut len = 0;
+    let code = unsafe { auction_resolve(auction, ptr::null(), 0, &mut sales, &mut len) };
+    assert_eq!((code, len), (AUCTION_OK, 0));
+    unsafe { auction_free(auction) };
+}
+
+#[test]
+fn errors_are_negative_codes() {
+    assert_eq!(
+        new_auction(0, 0, AUCTION_STRATEGY_SINGLE_PRICE).unwrap_err(),
+        AUCTION_ERROR_INVALID_CONFIG
+    );
+    assert_eq!(
+        new_auction(1, 0, 99).unwrap_err(),
+        AUCTION_ERROR_UNKNOWN_STRATEGY
+    );
+    assert_eq!(
+        unsafe { auction_new(1, 0, AUCTION_STRATEGY_SINGLE_PRICE, ptr::null_mut()) },
+        AUCTION_ERROR_NULL_POINTER
+    );
+    let auction = new_auction(1, 0, AUCTION_STRATEGY_SINGLE_PRICE).unwrap();
+    let (mut sales, mut len) = (ptr::null_mut(), 0);
+    let missing_bids = unsafe { auction_resolve(auction, ptr::null(), 1, &mut sales, &mut len) };
+    let missing_auction =
+        unsafe { auction_resolve(ptr::null(), ptr::null(), 0, &mut sales, &mut len) };
+    assert_eq!(missing_bids, AUCTION_ERROR_NULL_POINTER);
+    assert_eq!(missing_auction, AUCTION_ERROR_NULL_POINTER);
+    unsafe { auction_free(auction) };
+}
+
+#[test]
+fn freeing_null_is_ignored() {
+    unsafe {
+        auction_free(ptr::null_mut());
+        auction_sales_free(ptr::null_mut(), 0);
+    }
+}
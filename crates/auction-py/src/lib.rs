@@ -0,0 +1,221 @@
+//! Python bindings for resolving auctions.
+//!
+//! The `pyauction` module wraps the auction crate so the clearing logic used
+//! from Python is the same code resolving auctions in Rust. Ids are accepted
+//! as `uuid.UUID` or strings and returned as `uuid.UUID`.
+//!
+//! ```python
+//! from pyauction import Auction, Bid
+//!
+//! auction = Auction(lots=2, reserve_price=50, strategy="MultiPrice")
+//! sales = auction.resolve_bids([Bid(55, 1), Bid(20, 1)])
+//! rows = [sale.to_dict() for sale in sales]
+//! ```
+#![warn(clippy::all, clippy::nursery)]
+// The `#[pymethods]` wrappers of pyo3 0.22 convert a `PyErr` into itself.
+#![allow(clippy::useless_conversion)]
+
+use auction::{AuctionBuilder, AuctionStrategy};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyString},
+};
+use uuid::Uuid;
+
+/// A bid for one or more lots.
+#[pyclass(name = "Bid", module = "pyauction", frozen)]
+#[derive(Debug, Clone)]
+struct PyBid(auction::Bid);
+
+#[pymethods]
+impl PyBid {
+    /// A bid of `amount` cents for each of `quantity` lots, with a random id
+    /// unless one is given. The bidder defaults to the bid's id.
+    #[new]
+    #[pyo3(signature = (amount, quantity, id=None, bidder_id=None))]
+    fn new(
+        amount: i64,
+        quantity: usize,
+        id: Option<&Bound<'_, PyAny>>,
+        bidder_id: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let id = id.map_or_else(|| Ok(Uuid::new_v4()), uuid_from_py)?;
+        let mut bid = auction::Bid::with_id(id, amount, quantity);
+        if let Some(bidder_id) = bidder_id {
+            bid = bid.with_bidder_id(uuid_from_py(bidder_id)?);
+        }
+        Ok(Self(bid))
+    }
+
+    #[getter]
+    fn id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        uuid_to_py(py, self.0.id())
+    }
+
+    #[getter]
+    fn bidder_id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        uuid_to_py(py, self.0.bidder_id())
+    }
+
+    #[getter]
+    const fn amount(&self) -> i64 {
+        self.0.amount()
+    }
+
+    #[getter]
+    const fn quantity(&self) -> usize {
+        self.0.quantity()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Bid(amount={}, quantity={}, id='{}')",
+            self.0.amount(),
+            self.0.quantity(),
+            self.0.id()
+        )
+    }
+}
+
+/// A number of lots sold to a bidder at a price.
+#[pyclass(name = "Sale", module = "pyauction", frozen)]
+#[derive(Debug, Clone)]
+struct PySale(auction::Sale);
+
+#[pymethods]
+impl PySale {
+    #[getter]
+    fn bidder_id<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyAny>> {
+        uuid_to_py(py, self.0.bidder_id())
+    }
+
+    /// The bid the sale fills, None for sales not made from a bid.
+    #[getter]
+    fn bid_id<'py>(&self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+        self.0.bid_id().map(|id| uuid_to_py(py, id)).transpose()
+    }
+
+    #[getter]
+    const fn amount(&self) -> i64 {
+        self.0.amount()
+    }
+
+    #[getter]
+    const fn quantity(&self) -> usize {
+        self.0.quantity()
+    }
+
+    /// The amount paid for every lot in the sale, in cents.
+    fn total(&self) -> i128 {
+        self.0.total()
+    }
+
+    /// The sale as a dict, ready to become a row of a data frame.
+    fn to_dict<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let dict = PyDict::new_bound(py);
+        dict.set_item("bidder_id", self.bidder_id(py)?)?;
+        dict.set_item("bid_id", self.bid_id(py)?)?;
+        dict.set_item("amount", self.0.amount())?;
+        dict.set_item("quantity", self.0.quantity())?;
+        Ok(dict)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Sale(bidder_id='{}', amount={}, quantity={})",
+            self.0.bidder_id(),
+            self.0.amount(),
+            self.0.quantity()
+        )
+    }
+}
+
+/// An auction of a number of lots.
+#[pyclass(name = "Auction", module = "pyauction", frozen)]
+#[derive(Debug, Clone)]
+struct PyAuction(auction::Auction);
+
+#[pymethods]
+impl PyAuction {
+    /// An auction of `lots` lots, selling for no less than `reserve_price`
+    /// cents, resolved by the strategy of the given name. Strategies with
+    /// parameters are given as a dict, such as
+    /// `{"Dutch": {"start_price": 100, "decrement": 10}}`.
+    #[new]
+    #[pyo3(signature = (*, lots=1, reserve_price=0, strategy=None))]
+    fn new(lots: usize, reserve_price: i64, strategy: Option<&Bound<'_, PyAny>>) -> PyResult<Self> {
+        let mut builder = AuctionBuilder::new()
+            .lots(lots)
+            .reserve_price(reserve_price);
+        if let Some(strategy) = strategy {
+            builder = builder.strategy(strategy_from_py(strategy)?);
+        }
+        let auction = builder
+            .try_build()
+            .map_err(|error| PyValueError::new_err(error.to_string()))?;
+        Ok(Self(auction))
+    }
+
+    #[getter]
+    const fn lots(&self) -> usize {
+        self.0.lots()
+    }
+
+    #[getter]
+    const fn reserve_price(&self) -> i64 {
+        self.0.reserve_price()
+    }
+
+    /// Resolve the bids into sales, dropping invalid bids as the auction
+    /// crate does.
+    fn resolve_bids(&self, bids: Vec<PyBid>) -> Vec<PySale> {
+        let bids = bids.into_iter().map(|bid| bid.0).collect();
+        self.0.resolve_bids(bids).into_iter().map(PySale).collect()
+    }
+}
+
+/// Read an id from a `uuid.UUID` or its string form.
+fn uuid_from_py(id: &Bound<'_, PyAny>) -> PyResult<Uuid> {
+    let text = if id.is_instance_of::<PyString>() {
+        id.extract::<String>()?
+    } else {
+        let uuid_type = id.py().import_bound("uuid")?.getattr("UUID")?;
+        if !id.is_instance(&uuid_type)? {
+            return Err(PyValueError::new_err("ids must be uuid.UUID or str"));
+        }
+        id.str()?.extract::<String>()?
+    };
+    Uuid::parse_str(&text).map_err(|error| PyValueError::new_err(format!("invalid id: {error}")))
+}
+
+/// The id as a `uuid.UUID`.
+fn uuid_to_py(py: Python<'_>, id: Uuid) -> PyResult<Bound<'_, PyAny>> {
+    py.import_bound("uuid")?
+        .getattr("UUID")?
+        .call1((id.to_string(),))
+}
+
+/// Read a strategy from its name, or from a dict of its name to its
+/// parameters, as the strategy serializes.
+fn strategy_from_py(strategy: &Bound<'_, PyAny>) -> PyResult<AuctionStrategy> {
+    let json = if strategy.is_instance_of::<PyString>() {
+        serde_json::Value::String(strategy.extract()?).to_string()
+    } else {
+        strategy
+            .py()
+            .import_bound("json")?
+            .call_method1("dumps", (strategy,))?
+            .extract()?
+    };
+    serde_json::from_str(&json)
+        .map_err(|error| PyValueError::new_err(format!("invalid strategy: {error}")))
+}
+
+#[pymodule]
+fn pyauction(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyBid>()?;
+    module.add_class::<PySale>()?;
+    module.add_class::<PyAuction>()?;
+    Ok(())
+}
@@ -0,0 +1,118 @@
+"""Scenarios from the auction crate's unit tests, resolved through Python.
+
+Build the module with `maturin develop` and run with `pytest`.
+"""
+
+import uuid
+
+import pytest
+
+from pyauction import Auction, Bid
+
+
+def bids(*pairs):
+    return [Bid(amount, quantity) for amount, quantity in pairs]
+
+
+def filled(sales):
+    return [(sale.amount, sale.quantity) for sale in sales]
+
+
+@pytest.mark.parametrize("strategy", ["SinglePrice", "MultiPrice"])
+def test_returns_empty_for_no_bids(strategy):
+    assert Auction(lots=10, strategy=strategy).resolve_bids([]) == []
+
+
+def test_single_price_returns_all_bids_with_large_lot():
+    sales = Auction(lots=3).resolve_bids(bids((10, 1), (20, 1)))
+    assert filled(sales) == [(10, 1), (10, 1)]
+
+
+def test_single_price_return_some_of_bids_with_small_lot():
+    sales = Auction(lots=1).resolve_bids(bids((10, 1), (20, 1)))
+    assert filled(sales) == [(20, 1)]
+
+
+def test_single_price_partially_fulfilled():
+    sales = Auction(lots=2).resolve_bids(bids((10, 2), (20, 1)))
+    assert filled(sales) == [(10, 1), (10, 1)]
+
+
+def test_single_price_reserve_price_applied():
+    sales = Auction(lots=2, reserve_price=50).resolve_bids(bids((55, 1), (20, 1)))
+    assert filled(sales) == [(55, 1)]
+
+
+def test_multi_price_returns_all_bids_with_large_lot():
+    auction = Auction(lots=3, strategy="MultiPrice")
+    assert filled(auction.resolve_bids(bids((10, 1), (20, 1)))) == [(20, 1), (10, 1)]
+
+
+def test_multi_price_return_some_of_bids_with_small_lot():
+    auction = Auction(lots=1, strategy="MultiPrice")
+    assert filled(auction.resolve_bids(bids((10, 1), (20, 1)))) == [(20, 1)]
+
+
+def test_multi_price_partially_fulfilled():
+    auction = Auction(lots=2, strategy="MultiPrice")
+    assert filled(auction.resolve_bids(bids((10, 2), (20, 1)))) == [(20, 1), (10, 1)]
+
+
+def test_multi_price_reserve_price_applied():
+    auction = Auction(lots=2, reserve_price=50, strategy="MultiPrice")
+    assert filled(auction.resolve_bids(bids((55, 1), (20, 1)))) == [(55, 1)]
+
+
+def test_multi_price_partial_fill_keeps_bidder_id():
+    partial = Bid(10, 2)
+    sales = Auction(lots=2, strategy="MultiPrice").resolve_bids([partial, Bid(20, 1)])
+    assert sales[1].quantity == 1
+    assert sales[1].bidder_id == partial.id
+    assert sales[1].bid_id == partial.id
+
+
+def test_dutch_stops_at_first_covering_price():
+    strategy = {"Dutch": {"start_price": 100, "decrement": 10}}
+    auction = Auction(lots=2, strategy=strategy)
+    assert filled(auction.resolve_bids(bids((75, 1), (55, 1), (92, 1)))) == [(70, 1), (70, 1)]
+
+
+def test_invalid_bids_are_dropped():
+    sales = Auction(lots=2).resolve_bids(bids((-5, 1), (20, 0), (30, 1)))
+    assert filled(sales) == [(30, 1)]
+
+
+def test_ids_accept_uuid_or_str():
+    bidder = uuid.uuid4()
+    from_uuid = Bid(30, 1, id=bidder)
+    from_str = Bid(20, 1, id=str(uuid.uuid4()), bidder_id=str(bidder))
+    assert from_uuid.id == bidder
+    assert from_str.bidder_id == bidder
+    sales = Auction(lots=2).resolve_bids([from_uuid, from_str])
+    assert {sale.bidder_id for sale in sales} == {bidder}
+
+
+def test_invalid_ids_are_rejected():
+    with pytest.raises(ValueError):
+        Bid(10, 1, id="not-a-uuid")
+    with pytest.raises(ValueError):
+        Bid(10, 1, id=42)
+
+
+def test_invalid_auction_is_rejected():
+    with pytest.raises(ValueError):
+        Auction(lots=0)
+    with pytest.raises(ValueError):
+        Auction(strategy="Blind")
+
+
+def test_sale_to_dict():
+    bid = Bid(25, 2)
+    (sale,) = Auction(lots=2).resolve_bids([bid])
+    assert sale.total() == 50
+    assert sale.to_dict() == {
+        "bidder_id": bid.id,
+        "bid_id": bid.id,
+        "amount": 25,
+        "quantity": 2,
+    }
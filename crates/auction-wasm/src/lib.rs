@@ -0,0 +1,128 @@
+//! WebAssembly bindings for resolving auctions from JavaScript.
+//!
+//! Auctions are configured with the same fields as an [`AuctionBuilder`],
+//! any left out keeping their defaults. Bids are a JSON array of bid
+//! objects, and bids without an `id` are given a random one. Resolving
+//! returns the [`AuctionResult`] as JSON, holding the sales and the
+//! clearing price.
+//!
+//! ```js
+//! import { WasmAuction } from "auction-wasm";
+//!
+//! const auction = new WasmAuction({ lots: 2, strategy: "MultiPrice" });
+//! const result = JSON.parse(auction.resolve('[{"amount": 30, "quantity": 2}]'));
+//! console.log(result.clearing_price, result.sales);
+//! ```
+#![warn(clippy::all, clippy::nursery)]
+
+use auction::{Auction, AuctionBuilder, AuctionResult, Bids};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = JSON, catch)]
+    fn stringify(value: &JsValue) -> Result<JsValue, JsValue>;
+}
+
+/// An auction configured from JavaScript.
+#[wasm_bindgen]
+#[derive(Debug, Clone)]
+pub struct WasmAuction {
+    auction: Auction,
+}
+
+#[wasm_bindgen]
+impl WasmAuction {
+    /// Build an auction from a configuration object, failing if it is not a
+    /// valid auction.
+    #[wasm_bindgen(constructor)]
+    pub fn new(config: &JsValue) -> Result<Self, JsError> {
+        let auction = auction_from_json(&to_json(config)?).map_err(|error| JsError::new(&error))?;
+        Ok(Self { auction })
+    }
+
+    /// Resolve a JSON array of bids, returning the result as JSON.
+    pub fn resolve(&self, bids: &str) -> Result<String, JsError> {
+        resolve_json(&self.auction, bids).map_err(|error| JsError::new(&error))
+    }
+}
+
+/// Build an auction from a configuration object and resolve a JSON array of
+/// bids against it, returning the result as JSON.
+#[wasm_bindgen(js_name = resolveAuction)]
+pub fn resolve_auction(config: &JsValue, bids: &str) -> Result<String, JsError> {
+    WasmAuction::new(config)?.resolve(bids)
+}
+
+/// The JSON text of a JavaScript value.
+fn to_json(value: &JsValue) -> Result<String, JsError> {
+    stringify(value)
+        .ok()
+        .and_then(|json| json.as_string())
+        .ok_or_else(|| JsError::new("auction config is not JSON serializable"))
+}
+
+/// Build an auction from the JSON of an [`AuctionBuilder`], failing with a
+/// message if the JSON or the auction is invalid.
+pub fn auction_from_json(config: &str) -> Result<Auction, String> {
+    let builder: AuctionBuilder =
+        serde_json::from_str(config).map_err(|error| format!("invalid auction config: {error}"))?;
+    builder
+        .try_build()
+        .map_err(|error| format!("invalid auction: {error}"))
+}
+
+/// Resolve a JSON array of bids against the auction, returning the
+/// [`AuctionResult`] as JSON.
+pub fn resolve_json(auction: &Auction, bids: &str) -> Result<String, String> {
+    let bids: Bids =
+        serde_json::from_str(bids).map_err(|error| format!("invalid bids: {error}"))?;
+    let result: AuctionResult = auction.resolve(bids).map_err(|error| error.to_string())?;
+    serde_json::to_string(&result).map_err(|error| error.to_string())
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::Value;
+
+    use super::*;
+
+    const BIDS: &str = r#"[
+        {"id": "00000000-0000-0000-0000-000000000001", "amount": 30, "quantity": 1},
+        {"id": "00000000-0000-0000-0000-000000000002", "amount": 20, "quantity": 1},
+        {"amount": 10, "quantity": 1}
+    ]"#;
+
+    #[test]
+    fn resolve_json_returns_sales_and_clearing_price() {
+        let auction = auction_from_json(r#"{"lots": 2}"#).unwrap();
+        let result: Value = serde_json::from_str(&resolve_json(&auction, BIDS).unwrap()).unwrap();
+        assert_eq!(result["clearing_price"], 20);
+        assert_eq!(result["lots_sold"], 2);
+        let sales = result["sales"].as_array().unwrap();
+        assert_eq!(sales.len(), 2);
+        assert_eq!(
+            sales[0]["bidder_id"],
+            "00000000-0000-0000-0000-000000000001"
+        );
+        assert!(sales.iter().all(|sale| sale["amount"] == 20));
+    }
+
+    #[test]
+    fn invalid_config_is_an_error() {
+        assert!(auction_from_json(r#"{"lots": "two"}"#)
+            .unwrap_err()
+            .starts_with("invalid auction config"));
+        assert!(auction_from_json(r#"{"lots": 0}"#)
+            .unwrap_err()
+            .starts_with("invalid auction:"));
+    }
+
+    #[test]
+    fn invalid_bids_are_an_error() {
+        let auction = auction_from_json("{}").unwrap();
+        assert!(resolve_json(&auction, r#"{"amount": 1}"#)
+            .unwrap_err()
+            .starts_with("invalid bids"));
+    }
+}
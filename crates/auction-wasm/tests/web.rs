@@ -0,0 +1,32 @@
+//! Runs in a headless browser with `wasm-pack test --headless --firefox`.
+#![cfg(target_arch = "wasm32")]
+
+use auction_wasm::{resolve_auction, WasmAuction};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen]
+extern "C" {
+    /// Parses configuration objects the way a JavaScript caller builds them.
+    #[wasm_bindgen(js_namespace = JSON, js_name = parse)]
+    fn config(json: &str) -> JsValue;
+}
+
+#[wasm_bindgen_test]
+fn resolves_a_small_auction() {
+    let auction = WasmAuction::new(&config(r#"{"lots": 2, "strategy": "MultiPrice"}"#)).unwrap();
+    let result = auction
+        .resolve(r#"[{"amount": 30, "quantity": 1}, {"amount": 20, "quantity": 2}]"#)
+        .unwrap();
+    let result: serde_json::Value = serde_json::from_str(&result).unwrap();
+    assert_eq!(result["clearing_price"], 20);
+    assert_eq!(result["revenue"], 50);
+    assert_eq!(result["sales"].as_array().unwrap().len(), 2);
+}
+
+#[wasm_bindgen_test]
+fn rejects_an_invalid_auction() {
+    assert!(resolve_auction(&config(r#"{"lots": 0}"#), "[]").is_err());
+}
@@ -0,0 +1,81 @@
+use auction::{generators, resolve_batch, Auction, AuctionBuilder, AuctionStrategy, Bids};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+const SIZES: [usize; 3] = [1_000, 100_000, 5_000_000];
+
+fn auction(strategy: AuctionStrategy, lots: usize) -> Auction {
+    AuctionBuilder::new()
+        .strategy(strategy)
+        .lots(lots)
+        .reserve_price(10)
+        .build()
+}
+
+fn strategies() -> [(&'static str, AuctionStrategy); 2] {
+    [
+        ("single_price", AuctionStrategy::SinglePrice),
+        ("multi_price", AuctionStrategy::MultiPrice),
+    ]
+}
+
+fn bench_sizes(c: &mut Criterion) {
+    for (name, strategy) in strategies() {
+        let mut group = c.benchmark_group(name);
+        group.sample_size(10);
+        for size in SIZES {
+            let bids = generators::uniform(size as u64, size, 1_000_000);
+            for lots in [10, size / 10] {
+                let auction = auction(strategy.clone(), lots);
+                let id = BenchmarkId::new(format!("lots_{lots}"), size);
+                group.bench_with_input(id, &bids, |b, bids| {
+                    b.iter(|| auction.resolve_bids_ref(bids));
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+fn bench_distributions(c: &mut Criterion) {
+    let size = 100_000;
+    let distributions: [(&str, Bids); 4] = [
+        ("uniform", generators::uniform(1, size, 1_000_000)),
+        ("power_law", generators::power_law(2, size, 1_000_000, 3.0)),
+        ("heavy_ties", generators::heavy_ties(3, size, 5, 1_000_000)),
+        (
+            "large_quantities",
+            generators::large_quantities(4, size, 1_000_000, 1_000),
+        ),
+    ];
+    for (name, strategy) in strategies() {
+        let mut group = c.benchmark_group(format!("{name}_distributions"));
+        for (distribution, bids) in &distributions {
+            for lots in [10, size / 10] {
+                let auction = auction(strategy.clone(), lots);
+                let id = BenchmarkId::new(*distribution, lots);
+                group.bench_with_input(id, bids, |b, bids| {
+                    b.iter(|| auction.resolve_bids_ref(bids));
+                });
+            }
+        }
+        group.finish();
+    }
+}
+
+fn bench_batch(c: &mut Criterion) {
+    let batch: Vec<(Auction, Bids)> = (0..2_000)
+        .map(|seed| {
+            let auction = auction(AuctionStrategy::SinglePrice, 100);
+            (auction, generators::uniform(seed, 2_000, 1_000_000))
+        })
+        .collect();
+    let mut group = c.benchmark_group("resolve_batch");
+    group.sample_size(10);
+    group.bench_function("2000_auctions_of_2000_bids", |b| {
+        b.iter(|| resolve_batch(batch.clone()));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_sizes, bench_distributions, bench_batch);
+criterion_main!(benches);
@@ -0,0 +1,194 @@
+//! Module containing the Anglo-Dutch auction, an ascending stage narrowing the
+//! field followed by a sealed single price round.
+use alloc::vec::Vec;
+
+use uuid::Uuid;
+
+use crate::{
+    collections::HashMap, strategies::fill::rank_bids, Auction, AuctionDirection, AuctionStrategy,
+    Bid, Sales,
+};
+
+/// Enum representing a sealed bid the final stage of an Anglo-Dutch auction
+/// cannot accept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AngloDutchViolation {
+    /// The sealed bid's bidder did not reach the final stage.
+    NotFinalist { bid_id: Uuid, bidder_id: Uuid },
+    /// The sealed bid is worse than its bidder's indicative bid: lower in a
+    /// forward auction, higher in a reverse auction.
+    WorseThanIndicative {
+        bid_id: Uuid,
+        amount: i64,
+        indicative: i64,
+    },
+}
+
+/// A two stage auction. The indicative bids of the ascending stage narrow the
+/// field to the best few bidders, then the finalists' sealed bids are resolved
+/// as a single price auction.
+#[derive(Debug, Clone)]
+pub struct AngloDutch {
+    auction: Auction,
+    finalists: usize,
+}
+
+impl AngloDutch {
+    /// Create an Anglo-Dutch auction selling the auction's lots, taking the
+    /// given number of bidders to the final stage.
+    pub fn new(auction: Auction, finalists: usize) -> Self {
+        let auction = Auction {
+            strategy: AuctionStrategy::SinglePrice,
+            ..auction
+        };
+        Self { auction, finalists }
+    }
+
+    /// The bidders reaching the final stage, best first, each with their best
+    /// qualifying indicative bid.
+    pub fn finalists(&self, indicative: &[Bid]) -> Vec<(Uuid, i64)> {
+        let mut ranked: Vec<&Bid> = indicative
+            .iter()
+            .filter(|bid| self.auction.qualifies(bid.amount))
+            .collect();
+        rank_bids(&self.auction, &mut ranked);
+        let mut finalists: Vec<(Uuid, i64)> = Vec::with_capacity(self.finalists);
+        for bid in ranked {
+            if finalists.len() == self.finalists {
+                break;
+            }
+            if finalists
+                .iter()
+                .all(|(bidder_id, _)| *bidder_id != bid.bidder_id)
+            {
+                finalists.push((bid.bidder_id, bid.amount));
+            }
+        }
+        finalists
+    }
+
+    /// Resolve the final stage, checking every sealed bid first.
+    ///
+    /// # Arguments
+    /// * `indicative` - The bids of the ascending stage.
+    /// * `sealed` - The finalists' sealed bids, matched to their indicative
+    ///   bids by bidder id.
+    ///
+    /// # Returns
+    /// The single price sales of the sealed bids, or every sealed bid from a
+    /// bidder that was not a finalist or that is worse than their indicative
+    /// bid.
+    ///
+    pub fn resolve(
+        &self,
+        indicative: &[Bid],
+        sealed: &[Bid],
+    ) -> Result<Sales, Vec<AngloDutchViolation>> {
+        let finalists: HashMap<Uuid, i64> = self.finalists(indicative).into_iter().collect();
+        let violations: Vec<AngloDutchViolation> = sealed
+            .iter()
+            .filter_map(|bid| match finalists.get(&bid.bidder_id) {
+                None => Some(AngloDutchViolation::NotFinalist {
+                    bid_id: bid.id,
+                    bidder_id: bid.bidder_id,
+                }),
+                Some(&indicative) if self.is_worse(bid.amount, indicative) => {
+                    Some(AngloDutchViolation::WorseThanIndicative {
+                        bid_id: bid.id,
+                        amount: bid.amount,
+                        indicative,
+                    })
+                }
+                Some(_) => None,
+            })
+            .collect();
+        if violations.is_empty() {
+            Ok(self.auction.resolve_bids_ref(sealed))
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Whether the amount is worse for the seller than the indicative amount.
+    const fn is_worse(&self, amount: i64, indicative: i64) -> bool {
+        match self.auction.direction {
+            AuctionDirection::Forward => amount < indicative,
+            AuctionDirection::Reverse => amount > indicative,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::*;
+
+    fn anglo_dutch(lots: usize, finalists: usize) -> AngloDutch {
+        let auction = AuctionBuilder::new().lots(lots).reserve_price(10).build();
+        AngloDutch::new(auction, finalists)
+    }
+
+    #[test]
+    fn anglo_dutch_finalists_are_best_bidders() {
+        let bidder = Uuid::new_v4();
+        let indicative: Bids = vec![
+            bid![40, 1],
+            bid![30, 1].with_bidder_id(bidder),
+            bid![60, 1].with_bidder_id(bidder),
+            bid![50, 1],
+            bid![5, 1],
+        ];
+        let finalists = anglo_dutch(1, 2).finalists(&indicative);
+        assert_eq!(finalists, vec![(bidder, 60), (indicative[3].id, 50)]);
+        assert_eq!(anglo_dutch(1, 9).finalists(&indicative).len(), 3);
+    }
+
+    #[test]
+    fn anglo_dutch_resolves_sealed_round_at_single_price() {
+        let indicative: Bids = vec![bid![40, 1], bid![50, 1], bid![20, 1]];
+        let sealed: Bids = vec![
+            bid![45, 1].with_bidder_id(indicative[0].id),
+            bid![70, 1].with_bidder_id(indicative[1].id),
+        ];
+        let sales = anglo_dutch(1, 2).resolve(&indicative, &sealed).unwrap();
+        assert_eq!(
+            sales,
+            vec![Sale::new(indicative[1].id, 70, 1).with_bid(&sealed[1])]
+        );
+        let sales = anglo_dutch(2, 2).resolve(&indicative, &sealed).unwrap();
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(indicative[1].id, 45, 1).with_bid(&sealed[1]),
+                Sale::new(indicative[0].id, 45, 1).with_bid(&sealed[0])
+            ]
+        );
+    }
+
+    #[test]
+    fn anglo_dutch_reports_every_violation() {
+        let indicative: Bids = vec![bid![40, 1], bid![50, 1], bid![20, 1]];
+        let lowered = bid![35, 1].with_bidder_id(indicative[0].id);
+        let eliminated = bid![90, 1].with_bidder_id(indicative[2].id);
+        let sealed: Bids = vec![
+            lowered,
+            bid![50, 1].with_bidder_id(indicative[1].id),
+            eliminated,
+        ];
+        assert_eq!(
+            anglo_dutch(1, 2).resolve(&indicative, &sealed),
+            Err(vec![
+                AngloDutchViolation::WorseThanIndicative {
+                    bid_id: lowered.id,
+                    amount: 35,
+                    indicative: 40
+                },
+                AngloDutchViolation::NotFinalist {
+                    bid_id: eliminated.id,
+                    bidder_id: indicative[2].id
+                },
+            ])
+        );
+    }
+}
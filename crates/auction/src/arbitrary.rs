@@ -0,0 +1,220 @@
+//! Module of proptest strategies generating bids and auctions.
+//!
+//! The ranges are tunable through [`BidRanges`] and [`AuctionRanges`], and the
+//! `Arbitrary` implementations use their defaults.
+use core::ops::{Range, RangeInclusive};
+
+use proptest::prelude::*;
+
+use crate::{Auction, AuctionBuilder, AuctionStrategy, Bid, Bids};
+
+/// The ranges bids are generated from.
+#[derive(Debug, Clone)]
+pub struct BidRanges {
+    /// The bid amount in cents.
+    pub amount: RangeInclusive<i64>,
+    /// The number of units bid for.
+    pub quantity: RangeInclusive<usize>,
+}
+
+impl Default for BidRanges {
+    fn default() -> Self {
+        Self {
+            amount: 0..=1_000,
+            quantity: 1..=5,
+        }
+    }
+}
+
+/// The ranges auctions are generated from.
+#[derive(Debug, Clone)]
+pub struct AuctionRanges {
+    /// The number of lots for sale.
+    pub lots: RangeInclusive<usize>,
+    /// The reserve price in cents.
+    pub reserve_price: RangeInclusive<i64>,
+}
+
+impl Default for AuctionRanges {
+    fn default() -> Self {
+        Self {
+            lots: 1..=20,
+            reserve_price: 0..=500,
+        }
+    }
+}
+
+/// Generates a bid, sometimes timestamped.
+pub fn bid(ranges: BidRanges) -> impl Strategy<Value = Bid> {
+    (
+        ranges.amount,
+        ranges.quantity,
+        proptest::option::of(0..100_u64),
+    )
+        .prop_map(|(amount, quantity, submitted_at)| {
+            let bid = Bid::new(amount, quantity);
+            submitted_at.map_or(bid, |submitted_at| bid.with_submitted_at(submitted_at))
+        })
+}
+
+/// Generates a list of bids with a length in the given range.
+pub fn bids(ranges: BidRanges, len: Range<usize>) -> impl Strategy<Value = Bids> {
+    proptest::collection::vec(bid(ranges), len)
+}
+
+/// Generates one of the built-in auction strategies.
+pub fn auction_strategy() -> impl Strategy<Value = AuctionStrategy> {
+    prop_oneof![
+        Just(AuctionStrategy::SinglePrice),
+        Just(AuctionStrategy::MultiPrice),
+        (0..=1_000_i64, 1..=50_i64).prop_map(|(start_price, decrement)| {
+            AuctionStrategy::Dutch {
+                start_price,
+                decrement,
+            }
+        }),
+        Just(AuctionStrategy::AllPay),
+        Just(AuctionStrategy::GeneralizedSecondPrice),
+        Just(AuctionStrategy::Proxy),
+        Just(AuctionStrategy::Vcg),
+        any::<u64>().prop_map(|seed| AuctionStrategy::Lottery { seed }),
+        Just(AuctionStrategy::Scored),
+        Just(AuctionStrategy::PostedPrice),
+        (proptest::option::of(0..=1_000_i64), 1..=50_i64)
+            .prop_map(|(start, increment)| AuctionStrategy::Japanese { start, increment }),
+    ]
+}
+
+/// Generates a valid auction with one of the built-in strategies.
+///
+/// Proxy auctions always have a single lot, as [`AuctionBuilder::try_build`]
+/// requires.
+pub fn auction(ranges: AuctionRanges) -> impl Strategy<Value = Auction> {
+    (auction_strategy(), ranges.lots, ranges.reserve_price).prop_map(
+        |(strategy, lots, reserve_price)| {
+            let lots = if matches!(strategy, AuctionStrategy::Proxy) {
+                1
+            } else {
+                lots
+            };
+            AuctionBuilder::new()
+                .strategy(strategy)
+                .lots(lots)
+                .reserve_price(reserve_price)
+                .try_build()
+                .expect("generated auctions are valid")
+        },
+    )
+}
+
+impl Arbitrary for Bid {
+    type Parameters = BidRanges;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(ranges: Self::Parameters) -> Self::Strategy {
+        bid(ranges).boxed()
+    }
+}
+
+impl Arbitrary for AuctionStrategy {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with((): Self::Parameters) -> Self::Strategy {
+        auction_strategy().boxed()
+    }
+}
+
+impl Arbitrary for Auction {
+    type Parameters = AuctionRanges;
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(ranges: Self::Parameters) -> Self::Strategy {
+        auction(ranges).boxed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use proptest::prelude::*;
+    use uuid::Uuid;
+
+    use crate::{arbitrary::*, *};
+
+    proptest! {
+        #[test]
+        fn arbitrary_sales_never_exceed_lots(auction: Auction, bids: Bids) {
+            let sold: usize = auction.resolve_bids(bids).iter().map(Sale::quantity).sum();
+            prop_assert!(sold <= auction.lots());
+        }
+
+        #[test]
+        fn arbitrary_sales_never_below_reserve(auction: Auction, bids: Bids) {
+            let sales = auction.resolve_bids(bids);
+            prop_assert!(sales.iter().all(|sale| sale.amount() >= auction.reserve_price()));
+        }
+
+        #[test]
+        fn arbitrary_resolutions_verify(auction: Auction, bids: Bids) {
+            let sales = auction.resolve_bids_ref(&bids);
+            prop_assert_eq!(verify(&auction, &bids, &sales), Ok(()));
+        }
+
+        #[test]
+        fn arbitrary_fills_never_exceed_requested_quantity(auction: Auction, bids: Bids) {
+            let mut filled: HashMap<Uuid, usize> = HashMap::new();
+            for sale in auction.resolve_bids_ref(&bids) {
+                let bid_id = sale.bid_id().expect("built-in sales reference their bid");
+                let bid = bids.iter().find(|bid| bid.id == bid_id).unwrap();
+                prop_assert_eq!(sale.requested_quantity(), Some(bid.quantity));
+                *filled.entry(bid_id).or_default() += sale.quantity();
+            }
+            for bid in &bids {
+                prop_assert!(filled.get(&bid.id).copied().unwrap_or_default() <= bid.quantity);
+            }
+        }
+
+        #[test]
+        fn arbitrary_single_price_sales_share_one_amount(
+            lots in 1..=20_usize,
+            reserve_price in 0..=500_i64,
+            bids in bids(BidRanges::default(), 0..50),
+        ) {
+            let auction = AuctionBuilder::new()
+                .lots(lots)
+                .reserve_price(reserve_price)
+                .build();
+            let sales = auction.resolve_bids(bids);
+            prop_assert!(sales.windows(2).all(|pair| pair[0].amount() == pair[1].amount()));
+        }
+
+        #[test]
+        fn arbitrary_explained_sales_match_plain_resolution(auction: Auction, bids: Bids) {
+            let (sales, outcomes) = auction.resolve_explained(bids.clone());
+            prop_assert_eq!(&sales, &auction.resolve_bids(bids.clone()));
+            if matches!(
+                auction.strategy(),
+                AuctionStrategy::SinglePrice | AuctionStrategy::MultiPrice
+            ) {
+                prop_assert_eq!(outcomes.len(), bids.len());
+            }
+        }
+
+        #[test]
+        fn arbitrary_full_resolution_partitions_bids(auction: Auction, bids: Bids) {
+            let resolution = auction.resolve_bids_full(bids.clone(), 0);
+            let mut seen: Vec<_> = resolution
+                .winners()
+                .iter()
+                .map(Bid::id)
+                .chain(resolution.rejected().iter().map(|rejected| rejected.bid().id()))
+                .collect();
+            let mut expected: Vec<_> = bids.iter().map(Bid::id).collect();
+            seen.sort();
+            expected.sort();
+            prop_assert_eq!(seen, expected);
+        }
+    }
+}
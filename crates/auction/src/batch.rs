@@ -0,0 +1,63 @@
+//! Module for resolving many independent auctions at once.
+use alloc::vec::Vec;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{Auction, Bids, Sales};
+
+/// Resolves each auction against its own bids, returning the sales in the same
+/// order as the auctions.
+///
+/// With the `rayon` feature the auctions are resolved in parallel across the
+/// rayon thread pool, otherwise one after another.
+///
+/// # Arguments
+/// * `auctions_with_bids` - The auctions to resolve, each paired with its bids.
+///
+/// # Returns
+/// The sales of each auction, in order.
+///
+pub fn resolve_batch(auctions_with_bids: Vec<(Auction, Bids)>) -> Vec<Sales> {
+    #[cfg(feature = "rayon")]
+    let batch = auctions_with_bids.into_par_iter();
+    #[cfg(not(feature = "rayon"))]
+    let batch = auctions_with_bids.into_iter();
+    batch
+        .map(|(auction, bids)| auction.resolve_bids(bids))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn resolve_batch_matches_sequential_resolution() {
+        let batch: Vec<(Auction, Bids)> = (0..200)
+            .map(|seed| {
+                let strategy = if seed % 2 == 0 {
+                    AuctionStrategy::SinglePrice
+                } else {
+                    AuctionStrategy::MultiPrice
+                };
+                let auction = AuctionBuilder::new()
+                    .strategy(strategy)
+                    .lots(seed as usize % 30 + 1)
+                    .reserve_price(10)
+                    .build();
+                (auction, generators::large_quantities(seed, 100, 49, 4))
+            })
+            .collect();
+        let sequential: Vec<Sales> = batch
+            .iter()
+            .map(|(auction, bids)| auction.resolve_bids(bids.clone()))
+            .collect();
+        assert_eq!(resolve_batch(batch), sequential);
+    }
+
+    #[test]
+    fn resolve_batch_of_nothing() {
+        assert!(resolve_batch(Vec::new()).is_empty());
+    }
+}
@@ -0,0 +1,131 @@
+//! Module for capping the quantity a single bid may ask for.
+use alloc::borrow::Cow;
+
+use crate::{Auction, Bid, BidCapPolicy};
+
+/// The most lots one bid may ask for, the auction's lots times the maximum
+/// bid fraction rounded down. None without a cap.
+pub fn bid_cap(auction: &Auction) -> Option<usize> {
+    let (numerator, denominator) = auction.max_bid_fraction?;
+    let cap = auction.lots as u128 * numerator as u128 / denominator.max(1) as u128;
+    Some(usize::try_from(cap).unwrap_or(usize::MAX))
+}
+
+/// The bids as the auction sees them under its bid cap.
+///
+/// Bids asking for more than the cap are dropped or clamped to it, going by
+/// the cap policy. A bid that will not accept a fill as small as the cap is
+/// always dropped. The bids are borrowed untouched when every bid is within
+/// the cap.
+pub fn apply_bid_cap<'a>(auction: &Auction, bids: &'a [Bid]) -> Cow<'a, [Bid]> {
+    let Some(cap) = bid_cap(auction) else {
+        return Cow::Borrowed(bids);
+    };
+    if bids.iter().all(|bid| bid.quantity <= cap) {
+        return Cow::Borrowed(bids);
+    }
+    let capped = bids
+        .iter()
+        .filter(|bid| !is_rejected(auction, bid))
+        .map(|bid| bid.with_quantity(bid.quantity.min(cap)))
+        .collect();
+    Cow::Owned(capped)
+}
+
+/// Whether the auction drops the bid for asking for more than its cap.
+pub fn is_rejected(auction: &Auction, bid: &Bid) -> bool {
+    bid_cap(auction).is_some_and(|cap| {
+        bid.quantity > cap
+            && (auction.bid_cap_policy == BidCapPolicy::Reject || bid.min_fill() > cap)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn capped_auction(lots: usize, policy: BidCapPolicy) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(lots)
+            .max_bid_fraction(1, 4)
+            .bid_cap_policy(policy)
+            .build()
+    }
+
+    #[test]
+    fn bid_cap_accepts_request_at_the_boundary() {
+        let bids: Bids = vec![bid![20, 2], bid![10, 3]];
+        let auction = capped_auction(8, BidCapPolicy::Reject);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(sales, vec![Sale::from(&bids[0])]);
+    }
+
+    #[test]
+    fn bid_cap_rounds_down_for_awkward_lot_counts() {
+        let bids: Bids = vec![bid![20, 3], bid![10, 2]];
+        let auction = capped_auction(11, BidCapPolicy::Reject);
+        assert_eq!(bid_cap::bid_cap(&auction), Some(2));
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(sales, vec![Sale::from(&bids[1])]);
+
+        let auction = capped_auction(3, BidCapPolicy::Reject);
+        assert_eq!(bid_cap::bid_cap(&auction), Some(0));
+        assert!(auction.resolve_bids(bids).is_empty());
+    }
+
+    #[test]
+    fn bid_cap_rejects_oversized_bids() {
+        let bids: Bids = vec![bid![30, 5], bid![20, 2]];
+        let auction = capped_auction(8, BidCapPolicy::Reject);
+        let resolution = auction.resolve_bids_full(bids.clone(), 0);
+        assert_eq!(resolution.winners(), &vec![bids[1]]);
+        assert_eq!(resolution.rejected().len(), 1);
+        assert_eq!(resolution.rejected()[0].bid().quantity(), 5);
+        assert_eq!(
+            resolution.rejected()[0].reason(),
+            RejectReason::ExceedsBidCap
+        );
+    }
+
+    #[test]
+    fn bid_cap_clamps_oversized_bids() {
+        let bids: Bids = vec![bid![30, 5], bid![20, 2]];
+        let auction = capped_auction(8, BidCapPolicy::Clamp);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].quantity(), 2);
+        assert_eq!(sales[0].requested_quantity(), Some(5));
+
+        let (_, outcomes) = auction.resolve_explained(bids);
+        assert_eq!(
+            outcomes[0].outcome(),
+            Outcome::PartiallyFilled {
+                requested: 5,
+                filled: 2
+            }
+        );
+    }
+
+    #[test]
+    fn bid_cap_clamp_rejects_bids_refusing_the_cap() {
+        let all_or_nothing = bid![30, 5].with_all_or_nothing(true);
+        let bids: Bids = vec![all_or_nothing, bid![20, 2]];
+        let auction = capped_auction(8, BidCapPolicy::Clamp);
+        let resolution = auction.resolve_bids_full(bids.clone(), 0);
+        assert_eq!(resolution.winners(), &vec![bids[1]]);
+        assert_eq!(
+            resolution.rejected()[0].reason(),
+            RejectReason::ExceedsBidCap
+        );
+    }
+
+    #[test]
+    fn bid_cap_needs_a_denominator() {
+        let error = AuctionBuilder::new()
+            .max_bid_fraction(1, 0)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(error, AuctionConfigError::ZeroBidFractionDenominator);
+    }
+}
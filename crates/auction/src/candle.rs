@@ -0,0 +1,141 @@
+//! Module containing the candle auction, closing at a random time.
+use crate::{rng::BidRng, Auction, Bids, Sales, Timestamp};
+
+/// The outcome of a candle auction.
+#[derive(Debug, Clone)]
+pub struct CandleResult {
+    sales: Sales,
+    /// The close drawn for the auction.
+    close: Timestamp,
+    /// The bids submitted after the close, which did not count.
+    late: Bids,
+}
+
+impl CandleResult {
+    /// The sales made from the bids that counted.
+    pub const fn sales(&self) -> &Sales {
+        &self.sales
+    }
+
+    /// The close drawn for the auction.
+    pub const fn close(&self) -> Timestamp {
+        self.close
+    }
+
+    /// The bids submitted after the close, which did not count.
+    pub const fn late(&self) -> &Bids {
+        &self.late
+    }
+
+    /// Consume the result, returning the sales.
+    pub fn into_sales(self) -> Sales {
+        self.sales
+    }
+}
+
+/// An auction whose close is drawn at random within a window, so bidders
+/// cannot know whether a last moment bid will count.
+///
+/// The close is drawn from the seed, so the same seed always closes at the
+/// same time and a draw can be replayed for an audit. Bids submitted at or
+/// before the close count, those after it do not, and untimed bids follow the
+/// wrapped auction's untimed bid policy. The drawn close replaces any close
+/// the wrapped auction has.
+#[derive(Debug, Clone)]
+pub struct CandleAuction {
+    auction: Auction,
+    window_start: Timestamp,
+    window_end: Timestamp,
+    seed: u64,
+}
+
+impl CandleAuction {
+    /// Create a candle auction closing somewhere from `window_start` up to and
+    /// including `window_end`.
+    pub const fn new(
+        auction: Auction,
+        window_start: Timestamp,
+        window_end: Timestamp,
+        seed: u64,
+    ) -> Self {
+        Self {
+            auction,
+            window_start,
+            window_end,
+            seed,
+        }
+    }
+
+    /// The close drawn from the seed. A window ending before it starts always
+    /// closes at its start.
+    pub const fn close(&self) -> Timestamp {
+        let span = self.window_end.saturating_sub(self.window_start);
+        self.window_start + BidRng::new(self.seed).up_to(span)
+    }
+
+    /// Resolve the bids submitted by the drawn close with the wrapped
+    /// auction's strategy.
+    pub fn resolve(&self, bids: Bids) -> CandleResult {
+        let close = self.close();
+        let auction = Auction {
+            closes_at: Some(close),
+            ..self.auction.clone()
+        };
+        let (late, on_time): (Bids, Bids) = bids.into_iter().partition(|bid| auction.is_late(bid));
+        CandleResult {
+            sales: auction.resolve_bids(on_time),
+            close,
+            late,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn candle_auction(seed: u64) -> CandleAuction {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(10)
+            .build();
+        CandleAuction::new(auction, 100, 200, seed)
+    }
+
+    #[test]
+    fn candle_same_seed_gives_same_close() {
+        let closes: Vec<Timestamp> = (0..50).map(|seed| candle_auction(seed).close()).collect();
+        for (seed, close) in closes.iter().enumerate() {
+            assert_eq!(candle_auction(seed as u64).close(), *close);
+            assert!((100..=200).contains(close));
+        }
+        assert!(closes.iter().any(|close| *close != closes[0]));
+    }
+
+    #[test]
+    fn candle_counts_bids_at_the_close() {
+        let candle = candle_auction(3);
+        let close = candle.close();
+        let at_close = bid![30, 1].with_submitted_at(close);
+        let after = bid![40, 1].with_submitted_at(close + 1);
+        let untimed = bid![20, 1];
+        let result = candle.resolve(vec![at_close, after, untimed]);
+        assert_eq!(result.close(), close);
+        assert_eq!(
+            result.sales(),
+            &vec![
+                Sale::new(at_close.id, 30, 1).with_bid(&at_close),
+                Sale::new(untimed.id, 20, 1).with_bid(&untimed)
+            ]
+        );
+        assert_eq!(result.late().len(), 1);
+        assert_eq!(result.late()[0].id, after.id);
+    }
+
+    #[test]
+    fn candle_empty_window_closes_at_start() {
+        let auction = AuctionBuilder::new().build();
+        assert_eq!(CandleAuction::new(auction.clone(), 50, 50, 9).close(), 50);
+        assert_eq!(CandleAuction::new(auction, 50, 10, 9).close(), 50);
+    }
+}
@@ -0,0 +1,20 @@
+//! Module choosing the maps and sets behind the crate.
+//!
+//! With `std` these are the standard hash maps. Without it they are B-trees
+//! from `alloc`, whose iteration order the crate never relies on either.
+#[cfg(not(feature = "std"))]
+pub use alloc::collections::{BTreeMap as HashMap, BTreeSet as HashSet};
+#[cfg(feature = "std")]
+pub use std::collections::{HashMap, HashSet};
+
+/// An empty map with room for the given number of entries where the map
+/// supports it.
+pub fn map_with_capacity<K, V>(capacity: usize) -> HashMap<K, V> {
+    #[cfg(feature = "std")]
+    return HashMap::with_capacity(capacity);
+    #[cfg(not(feature = "std"))]
+    {
+        let _ = capacity;
+        HashMap::default()
+    }
+}
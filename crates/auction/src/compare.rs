@@ -0,0 +1,218 @@
+//! Module for comparing how strategies resolve the same bids.
+use alloc::{collections::BTreeSet, format, string::String, string::ToString, vec::Vec};
+use core::fmt;
+
+use uuid::Uuid;
+
+use crate::{invoice::Cents, Auction, AuctionDirection, AuctionStrategy, Bid, SalesSummary};
+
+/// The outcome of resolving the bids under one strategy.
+#[derive(Debug, Clone)]
+pub struct StrategyOutcome {
+    strategy: AuctionStrategy,
+    /// None if the revenue overflows an `i128`.
+    revenue: Option<i128>,
+    lots_sold: usize,
+    clearing_price: Option<i64>,
+    winners: BTreeSet<Uuid>,
+}
+
+impl StrategyOutcome {
+    /// The strategy the bids were resolved under.
+    pub const fn strategy(&self) -> &AuctionStrategy {
+        &self.strategy
+    }
+
+    /// The total revenue in cents, None if it overflows an `i128`.
+    pub const fn revenue(&self) -> Option<i128> {
+        self.revenue
+    }
+
+    /// The number of lots sold.
+    pub const fn lots_sold(&self) -> usize {
+        self.lots_sold
+    }
+
+    /// The marginal price lots sold at, None when nothing sold.
+    ///
+    /// This is the lowest sale price, or the highest for reverse auctions.
+    pub const fn clearing_price(&self) -> Option<i64> {
+        self.clearing_price
+    }
+
+    /// The bidders who won units.
+    pub const fn winners(&self) -> &BTreeSet<Uuid> {
+        &self.winners
+    }
+
+    /// The number of bidders who won units under both outcomes.
+    pub fn winner_overlap(&self, other: &Self) -> usize {
+        self.winners.intersection(&other.winners).count()
+    }
+
+    /// A table of the outcomes, one row per strategy.
+    pub fn table(outcomes: &[Self]) -> impl fmt::Display + '_ {
+        ComparisonTable(outcomes)
+    }
+}
+
+impl fmt::Display for StrategyOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        ComparisonTable(core::slice::from_ref(self)).fmt(f)
+    }
+}
+
+/// Displays outcomes as a table with a header row.
+struct ComparisonTable<'a>(&'a [StrategyOutcome]);
+
+impl fmt::Display for ComparisonTable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self
+            .0
+            .iter()
+            .map(|outcome| format!("{:?}", outcome.strategy))
+            .collect();
+        let width = names.iter().map(String::len).max().unwrap_or(0).max(8);
+        write!(
+            f,
+            "{:<width$}  {:>14}  {:>6}  {:>10}  {:>7}",
+            "strategy", "revenue", "lots", "clearing", "winners"
+        )?;
+        for (name, outcome) in names.iter().zip(self.0) {
+            let revenue = outcome.revenue.map_or_else(
+                || "overflow".to_string(),
+                |revenue| Cents(revenue).to_string(),
+            );
+            let clearing = outcome
+                .clearing_price
+                .map_or_else(|| "-".to_string(), |price| Cents(price.into()).to_string());
+            write!(
+                f,
+                "\n{name:<width$}  {revenue:>14}  {:>6}  {clearing:>10}  {:>7}",
+                outcome.lots_sold,
+                outcome.winners.len()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves the same bids under each of the strategies, leaving the rest of
+/// the auction's configuration as it is.
+///
+/// # Arguments
+/// * `auction` - The auction whose configuration every strategy shares.
+/// * `bids` - The bids to resolve, borrowed untouched.
+/// * `strategies` - The strategies to compare.
+///
+/// # Returns
+/// The outcome under each strategy, in the order given.
+///
+pub fn compare_strategies(
+    auction: &Auction,
+    bids: &[Bid],
+    strategies: &[AuctionStrategy],
+) -> Vec<StrategyOutcome> {
+    strategies
+        .iter()
+        .map(|strategy| {
+            let auction = Auction {
+                strategy: strategy.clone(),
+                ..auction.clone()
+            };
+            let sales = auction.resolve_bids_ref(bids);
+            let prices = sales
+                .iter()
+                .filter(|sale| sale.quantity > 0)
+                .map(|sale| sale.amount);
+            let clearing_price = match auction.direction {
+                AuctionDirection::Forward => prices.min(),
+                AuctionDirection::Reverse => prices.max(),
+            };
+            StrategyOutcome {
+                strategy: strategy.clone(),
+                revenue: sales.total_revenue(),
+                lots_sold: sales.total_quantity(),
+                clearing_price,
+                winners: sales
+                    .iter()
+                    .filter(|sale| sale.quantity > 0)
+                    .map(|sale| sale.bidder_id)
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn comparison() -> (Bids, Vec<StrategyOutcome>) {
+        let bids: Bids = vec![bid![30, 1], bid![20, 1], bid![10, 1]];
+        let auction = AuctionBuilder::new().lots(2).reserve_price(5).build();
+        let outcomes = compare_strategies(
+            &auction,
+            &bids,
+            &[
+                AuctionStrategy::SinglePrice,
+                AuctionStrategy::MultiPrice,
+                AuctionStrategy::Vcg,
+            ],
+        );
+        (bids, outcomes)
+    }
+
+    #[test]
+    fn compare_strategies_resolves_each_strategy() {
+        let (bids, outcomes) = comparison();
+        let summary: Vec<(Option<i128>, usize, Option<i64>)> = outcomes
+            .iter()
+            .map(|outcome| {
+                (
+                    outcome.revenue(),
+                    outcome.lots_sold(),
+                    outcome.clearing_price(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (Some(40), 2, Some(20)),
+                (Some(50), 2, Some(20)),
+                (Some(20), 2, Some(10)),
+            ]
+        );
+        assert!(matches!(outcomes[2].strategy(), AuctionStrategy::Vcg));
+        let winners = std::collections::BTreeSet::from([bids[0].id, bids[1].id]);
+        assert!(outcomes.iter().all(|outcome| outcome.winners() == &winners));
+        assert_eq!(outcomes[0].winner_overlap(&outcomes[1]), 2);
+        let amounts: Vec<i64> = bids.iter().map(Bid::amount).collect();
+        assert_eq!(amounts, [30, 20, 10]);
+    }
+
+    #[test]
+    fn compare_strategies_table() {
+        let (_, outcomes) = comparison();
+        assert_eq!(
+            StrategyOutcome::table(&outcomes).to_string(),
+            [
+                "strategy            revenue    lots    clearing  winners",
+                "SinglePrice            0.40       2        0.20        2",
+                "MultiPrice             0.50       2        0.20        2",
+                "Vcg                    0.20       2        0.10        2",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn compare_strategies_of_nothing() {
+        let auction = AuctionBuilder::new().build();
+        let outcomes = compare_strategies(&auction, &[], &[AuctionStrategy::SinglePrice]);
+        assert_eq!(outcomes[0].revenue(), Some(0));
+        assert_eq!(outcomes[0].clearing_price(), None);
+        assert!(outcomes[0].to_string().ends_with("-        0"));
+    }
+}
@@ -0,0 +1,367 @@
+//! Module containing the core-selecting payment rule for package auctions.
+//!
+//! Payments start at VCG and blocking coalitions are found one at a time by
+//! re-running winner determination with each winner's bids lowered by their
+//! surplus. Each coalition adds a constraint, and the payments are moved to the
+//! minimum revenue point meeting every constraint so far, nearest to VCG. This
+//! repeats until no coalition could offer the seller more than the winners pay.
+use alloc::{vec, vec::Vec};
+
+use crate::{collections::HashMap, package::Problem};
+
+/// How far a payment may fall short of a whole cent before rounding up.
+const ROUNDING_TOLERANCE: f64 = 1e-6;
+
+/// Below this a simplex coefficient or projection step counts as zero.
+const EPSILON: f64 = 1e-9;
+
+/// The most sweeps the projection onto the core makes.
+const MAX_SWEEPS: usize = 100_000;
+
+/// A blocking coalition's demand: the winners outside it must pay at least
+/// the bound above their VCG payments between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Constraint {
+    members: Vec<bool>,
+    bound: i128,
+}
+
+/// The core-selecting payment of each winner.
+///
+/// # Arguments
+/// * `problem` - The package bids.
+/// * `winners` - The winning candidates.
+/// * `vcg` - The VCG payment of each winner.
+///
+/// # Returns
+/// The payment of each winner, between their VCG payment and their bid,
+/// rounded up to whole cents so no coalition can block.
+///
+pub fn core_payments(problem: &Problem, winners: &[usize], vcg: &[i64]) -> Vec<i64> {
+    let bids: Vec<i64> = winners
+        .iter()
+        .map(|&winner| problem.candidates[winner].amount)
+        .collect();
+    let limits: Vec<f64> = bids
+        .iter()
+        .zip(vcg)
+        .map(|(&bid, &vcg)| (bid - vcg) as f64)
+        .collect();
+    let winner_of: HashMap<usize, usize> = winners
+        .iter()
+        .enumerate()
+        .map(|(index, &winner)| (problem.candidates[winner].bidder, index))
+        .collect();
+
+    let mut payments = vcg.to_vec();
+    let mut constraints = Vec::new();
+    loop {
+        let (blocking, chosen) = problem.solve(|candidate| {
+            winner_of
+                .get(&candidate.bidder)
+                .map_or(candidate.amount, |&index| {
+                    candidate.amount - (bids[index] - payments[index])
+                })
+        });
+        let revenue: i128 = payments.iter().map(|&payment| i128::from(payment)).sum();
+        if blocking <= revenue {
+            return payments;
+        }
+
+        let mut members = vec![true; winners.len()];
+        let mut bound: i128 = 0;
+        for &candidate in &chosen {
+            let candidate = &problem.candidates[candidate];
+            bound += i128::from(candidate.amount);
+            if let Some(&index) = winner_of.get(&candidate.bidder) {
+                members[index] = false;
+                bound -= i128::from(bids[index]);
+            }
+        }
+        for (index, _) in members.iter().enumerate().filter(|(_, &member)| member) {
+            bound -= i128::from(vcg[index]);
+        }
+        let constraint = Constraint { members, bound };
+        if constraints.contains(&constraint) {
+            return payments;
+        }
+        constraints.push(constraint);
+
+        let raises = nearest_core_point(&constraints, &limits);
+        payments = vcg
+            .iter()
+            .zip(&bids)
+            .zip(raises)
+            .map(|((&vcg, &bid), raise)| {
+                let raise = ceil(raise - ROUNDING_TOLERANCE).max(0.0) as i64;
+                vcg.saturating_add(raise).min(bid)
+            })
+            .collect();
+    }
+}
+
+/// The smallest whole number not below the value, as `f64::ceil` needs
+/// `std`. Exact for values well within the `i64` range.
+fn ceil(value: f64) -> f64 {
+    let truncated = value as i64 as f64;
+    if truncated < value {
+        truncated + 1.0
+    } else {
+        truncated
+    }
+}
+
+/// The raises above VCG meeting every constraint with the least total, and
+/// among those the one nearest to no raise at all.
+fn nearest_core_point(constraints: &[Constraint], limits: &[f64]) -> Vec<f64> {
+    let revenue = minimum_raise(constraints, limits);
+
+    // Each half-space is a row of coefficients and a bound, holding
+    // when the row times the raises is at most the bound.
+    let mut half_spaces: Vec<(Vec<f64>, f64)> = Vec::new();
+    for constraint in constraints {
+        let row = constraint
+            .members
+            .iter()
+            .map(|&member| if member { -1.0 } else { 0.0 })
+            .collect();
+        half_spaces.push((row, -(constraint.bound as f64)));
+    }
+    for (index, &limit) in limits.iter().enumerate() {
+        let mut lower = vec![0.0; limits.len()];
+        lower[index] = -1.0;
+        half_spaces.push((lower, 0.0));
+        let mut upper = vec![0.0; limits.len()];
+        upper[index] = 1.0;
+        half_spaces.push((upper, limit));
+    }
+    half_spaces.push((vec![1.0; limits.len()], revenue + EPSILON));
+
+    // Hildreth's method, coordinate ascent on the dual of the projection.
+    let mut raises = vec![0.0; limits.len()];
+    let mut multipliers = vec![0.0; half_spaces.len()];
+    for _ in 0..MAX_SWEEPS {
+        let mut largest_step: f64 = 0.0;
+        for ((row, bound), multiplier) in half_spaces.iter().zip(&mut multipliers) {
+            let norm: f64 = row.iter().map(|a| a * a).sum();
+            if norm < EPSILON {
+                continue;
+            }
+            let excess: f64 = row.iter().zip(&raises).map(|(a, q)| a * q).sum::<f64>() - bound;
+            let updated = (*multiplier + excess / norm).max(0.0);
+            let step = updated - *multiplier;
+            *multiplier = updated;
+            for (raise, a) in raises.iter_mut().zip(row) {
+                *raise -= step * a;
+            }
+            largest_step = largest_step.max(step.abs());
+        }
+        if largest_step < EPSILON {
+            break;
+        }
+    }
+    raises
+}
+
+/// The least total raise meeting every constraint within the limits.
+///
+/// Solved as the dual linear program, maximising the bounds met by the
+/// constraints less the limits used, which starts feasible at zero, with the
+/// simplex method and Bland's rule.
+fn minimum_raise(constraints: &[Constraint], limits: &[f64]) -> f64 {
+    let (m, n) = (constraints.len(), limits.len());
+    let columns = m + 2 * n;
+    let mut rows: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            let mut row = vec![0.0; columns + 1];
+            for (k, constraint) in constraints.iter().enumerate() {
+                if constraint.members[i] {
+                    row[k] = 1.0;
+                }
+            }
+            row[m + i] = -1.0;
+            row[m + n + i] = 1.0;
+            row[columns] = 1.0;
+            row
+        })
+        .collect();
+    let mut objective = vec![0.0; columns + 1];
+    for (k, constraint) in constraints.iter().enumerate() {
+        objective[k] = constraint.bound as f64;
+    }
+    for (i, &limit) in limits.iter().enumerate() {
+        objective[m + i] = -limit;
+    }
+
+    while let Some(entering) = (0..columns).find(|&j| objective[j] > EPSILON) {
+        let leaving = (0..n)
+            .filter(|&r| rows[r][entering] > EPSILON)
+            .min_by(|&a, &b| {
+                let ratio = |r: usize| rows[r][columns] / rows[r][entering];
+                ratio(a).total_cmp(&ratio(b))
+            });
+        let Some(leaving) = leaving else {
+            return limits.iter().sum();
+        };
+        let pivot = rows[leaving][entering];
+        for value in &mut rows[leaving] {
+            *value /= pivot;
+        }
+        let pivot_row = rows[leaving].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            if r != leaving {
+                let factor = row[entering];
+                for (value, p) in row.iter_mut().zip(&pivot_row) {
+                    *value -= factor * p;
+                }
+            }
+        }
+        let factor = objective[entering];
+        for (value, p) in objective.iter_mut().zip(&pivot_row) {
+            *value -= factor * p;
+        }
+    }
+    -objective[columns]
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn payments(auction: &PackageAuction, packages: &[PackageBid]) -> Vec<i64> {
+        auction
+            .resolve(packages)
+            .unwrap()
+            .iter()
+            .map(Sale::amount)
+            .collect()
+    }
+
+    fn core_auction<const N: usize>(lots: [&str; N]) -> PackageAuction {
+        PackageAuction::new(lots).with_payment_rule(PaymentRule::CoreSelecting)
+    }
+
+    #[test]
+    fn core_selecting_local_local_global() {
+        // Two locals bid 20 for A and B, a global bids 28 for both. VCG
+        // charges each local 8, which the global would beat, so the locals
+        // share the 28 the global offers equally.
+        let packages = [
+            PackageBid::new(["A"], 20),
+            PackageBid::new(["B"], 20),
+            PackageBid::new(["A", "B"], 28),
+        ];
+        let vcg = PackageAuction::new(["A", "B"]).with_payment_rule(PaymentRule::Vcg);
+        assert_eq!(payments(&vcg, &packages), vec![8, 8]);
+        assert_eq!(payments(&core_auction(["A", "B"]), &packages), vec![14, 14]);
+    }
+
+    #[test]
+    fn core_selecting_raises_from_vcg_equally() {
+        let packages = [
+            PackageBid::new(["A"], 10),
+            PackageBid::new(["B"], 20),
+            PackageBid::new(["A", "B"], 28),
+        ];
+        // VCG is 8 and 18, two short of the global's 28.
+        assert_eq!(payments(&core_auction(["A", "B"]), &packages), vec![9, 19]);
+    }
+
+    #[test]
+    fn core_selecting_vcg_of_zero() {
+        let packages = [
+            PackageBid::new(["A"], 20),
+            PackageBid::new(["B"], 20),
+            PackageBid::new(["A", "B"], 10),
+        ];
+        let vcg = PackageAuction::new(["A", "B"]).with_payment_rule(PaymentRule::Vcg);
+        assert_eq!(payments(&vcg, &packages), vec![0, 0]);
+        assert_eq!(payments(&core_auction(["A", "B"]), &packages), vec![5, 5]);
+    }
+
+    #[test]
+    fn core_selecting_overlapping_coalitions() {
+        // Three locals bid 10 for A, B and C, globals bid 16 for AB and BC.
+        // VCG charges each local 6. The coalitions of a global with the far
+        // local need 16 from the two locals they displace, which costs least
+        // when the middle local pays 10.
+        let packages = [
+            PackageBid::new(["A"], 10),
+            PackageBid::new(["B"], 10),
+            PackageBid::new(["C"], 10),
+            PackageBid::new(["A", "B"], 16),
+            PackageBid::new(["B", "C"], 16),
+        ];
+        let vcg = PackageAuction::new(["A", "B", "C"]).with_payment_rule(PaymentRule::Vcg);
+        assert_eq!(payments(&vcg, &packages), vec![6, 6, 6]);
+        assert_eq!(
+            payments(&core_auction(["A", "B", "C"]), &packages),
+            vec![6, 10, 6]
+        );
+    }
+
+    #[test]
+    fn core_selecting_without_competition_matches_vcg() {
+        let packages = [PackageBid::new(["A"], 20), PackageBid::new(["B"], 20)];
+        assert_eq!(payments(&core_auction(["A", "B"]), &packages), vec![0, 0]);
+    }
+
+    #[test]
+    fn core_selecting_payments_are_unblocked() {
+        let lots = ["A", "B", "C", "D"];
+        let auction = core_auction(lots);
+        for seed in 0..30_u64 {
+            let mut rng = generators::BidRng::new(seed);
+            let packages: Vec<_> = (0..10)
+                .map(|_| {
+                    let mask = rng.next_u64() % 15 + 1;
+                    let lots = lots
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| mask & (1 << index) != 0)
+                        .map(|(_, lot)| *lot);
+                    PackageBid::new(lots, (rng.next_u64() % 100) as i64)
+                })
+                .collect();
+            let sales = auction.resolve(&packages).unwrap();
+            let vcg = PackageAuction::new(lots)
+                .with_payment_rule(PaymentRule::Vcg)
+                .resolve(&packages)
+                .unwrap();
+            let won: Vec<_> = sales.iter().filter_map(Sale::package_id).collect();
+            for (sale, vcg) in sales.iter().zip(&vcg) {
+                let package = packages
+                    .iter()
+                    .find(|package| Some(package.id()) == sale.package_id())
+                    .unwrap();
+                assert!(vcg.amount <= sale.amount && sale.amount <= package.amount());
+            }
+            // No losing package could pay more than the winners it displaces.
+            for package in packages
+                .iter()
+                .filter(|package| !won.contains(&package.id()))
+            {
+                let displaced: i64 = sales
+                    .iter()
+                    .zip(&packages_of(&packages, &sales))
+                    .filter(|(_, winner)| !winner.lots().is_disjoint(package.lots()))
+                    .map(|(sale, _)| sale.amount)
+                    .sum();
+                assert!(package.amount() <= displaced);
+            }
+        }
+    }
+
+    fn packages_of(packages: &[PackageBid], sales: &Sales) -> Vec<PackageBid> {
+        sales
+            .iter()
+            .map(|sale| {
+                packages
+                    .iter()
+                    .find(|package| Some(package.id()) == sale.package_id())
+                    .unwrap()
+                    .clone()
+            })
+            .collect()
+    }
+}
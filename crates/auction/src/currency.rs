@@ -0,0 +1,144 @@
+//! Module containing the currency bids and auctions are priced in.
+use core::fmt;
+
+/// An ISO 4217 currency code, such as `EUR` or `USD`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Currency([u8; 3]);
+
+impl Currency {
+    /// The euro.
+    pub const EUR: Self = Self(*b"EUR");
+    /// The pound sterling.
+    pub const GBP: Self = Self(*b"GBP");
+    /// The Japanese yen.
+    pub const JPY: Self = Self(*b"JPY");
+    /// The United States dollar.
+    pub const USD: Self = Self(*b"USD");
+
+    /// The currency with the given code, None unless it is three upper case
+    /// ASCII letters.
+    pub fn from_code(code: &str) -> Option<Self> {
+        let code: [u8; 3] = code.as_bytes().try_into().ok()?;
+        code.iter()
+            .all(u8::is_ascii_uppercase)
+            .then_some(Self(code))
+    }
+
+    /// The three letter code.
+    pub fn code(&self) -> &str {
+        core::str::from_utf8(&self.0).expect("currency codes are ASCII")
+    }
+}
+
+impl fmt::Debug for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Currency({})", self.code())
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Currency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Currency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = <alloc::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        Self::from_code(&code)
+            .ok_or_else(|| serde::de::Error::custom(format!("invalid currency code `{code}`")))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn currency_from_code() {
+        assert_eq!(Currency::from_code("EUR"), Some(Currency::EUR));
+        assert_eq!(Currency::from_code("CHF").unwrap().code(), "CHF");
+        assert_eq!(Currency::from_code("eur"), None);
+        assert_eq!(Currency::from_code("EURO"), None);
+        assert_eq!(Currency::USD.to_string(), "USD");
+    }
+
+    fn auction(policy: CurrencyMismatch) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .currency(Currency::EUR)
+            .currency_mismatch(policy)
+            .build()
+    }
+
+    #[test]
+    fn currency_sales_carry_auction_currency() {
+        let bids: Bids = vec![
+            bid![30, 1].with_currency(Currency::EUR),
+            bid![20, 1].with_currency(Currency::EUR),
+        ];
+        let sales = auction(CurrencyMismatch::Reject).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales
+            .iter()
+            .all(|sale| sale.currency() == Some(Currency::EUR)));
+    }
+
+    #[test]
+    fn currency_mixed_batch_is_rejected() {
+        let dollars = bid![50, 1].with_currency(Currency::USD);
+        let untagged = bid![40, 1];
+        let bids: Bids = vec![bid![30, 1].with_currency(Currency::EUR), dollars, untagged];
+        let auction = auction(CurrencyMismatch::Reject);
+        assert_eq!(
+            auction.currency_mismatches(&bids),
+            vec![dollars.id, untagged.id]
+        );
+        assert!(auction.resolve_bids(bids.clone()).is_empty());
+        assert_eq!(
+            auction.try_resolve_bids(bids),
+            Err(AuctionError::CurrencyMismatch {
+                bid_ids: vec![dollars.id, untagged.id]
+            })
+        );
+    }
+
+    #[test]
+    fn currency_mixed_batch_is_filtered() {
+        let euros = bid![30, 1].with_currency(Currency::EUR);
+        let bids: Bids = vec![euros, bid![50, 1].with_currency(Currency::USD)];
+        let auction = auction(CurrencyMismatch::Filter);
+        let expected = vec![Sale::new(euros.id, 30, 1)
+            .with_bid(&euros)
+            .with_currency(Currency::EUR)];
+        assert_eq!(auction.resolve_bids(bids.clone()), expected);
+        assert_eq!(auction.try_resolve_bids(bids), Ok(expected));
+    }
+
+    #[test]
+    fn currency_untagged_legacy_resolution_is_unchanged() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 1]];
+        let auction = AuctionBuilder::new().lots(2).build();
+        assert!(auction.currency_mismatches(&bids).is_empty());
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bids[0].id, 20, 1).with_bid(&bids[0]),
+                Sale::new(bids[1].id, 20, 1).with_bid(&bids[1])
+            ]
+        );
+        assert!(sales.iter().all(|sale| sale.currency().is_none()));
+        let tagged = bid![30, 1].with_currency(Currency::USD);
+        assert_eq!(auction.currency_mismatches(&[tagged]), vec![tagged.id]);
+    }
+}
@@ -0,0 +1,240 @@
+//! Module containing the two-sided call auction.
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    strategies::fill::{rank_highest_first, rank_lowest_first},
+    Bids, RoundingPolicy, Sale, Sales, SecondarySort,
+};
+
+/// Enum representing how the clearing price is chosen within the overlap of
+/// the marginal buy and sell prices.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ClearingRule {
+    /// The midpoint of the overlap, rounded by the rounding policy.
+    #[default]
+    Midpoint,
+    /// The bottom of the overlap, the marginal sell price.
+    BuyerFavouring,
+    /// The top of the overlap, the marginal buy price.
+    SellerFavouring,
+}
+
+/// The outcome of a double auction.
+#[derive(Debug, Clone)]
+pub struct DoubleAuctionResult {
+    /// The sales made to the buyers.
+    buys: Sales,
+    /// The sales made by the sellers.
+    sells: Sales,
+    /// The price every trade happened at, None when nothing traded.
+    clearing_price: Option<i64>,
+}
+
+impl DoubleAuctionResult {
+    /// The sales made to the buyers.
+    pub const fn buys(&self) -> &Sales {
+        &self.buys
+    }
+
+    /// The sales made by the sellers.
+    pub const fn sells(&self) -> &Sales {
+        &self.sells
+    }
+
+    /// The price every trade happened at, None when nothing traded.
+    pub const fn clearing_price(&self) -> Option<i64> {
+        self.clearing_price
+    }
+
+    /// Consume the result, returning the buy and sell sales.
+    pub fn into_sales(self) -> (Sales, Sales) {
+        (self.buys, self.sells)
+    }
+}
+
+/// A two-sided call auction matching buy bids against sell bids.
+#[derive(Debug, Clone, Default)]
+pub struct DoubleAuction {
+    rule: ClearingRule,
+    rounding_policy: RoundingPolicy,
+}
+
+impl DoubleAuction {
+    /// Create a new double auction using the given clearing rule.
+    pub const fn new(rule: ClearingRule) -> Self {
+        Self {
+            rule,
+            rounding_policy: RoundingPolicy::TowardBuyer,
+        }
+    }
+
+    /// Set how a midpoint between whole cents is rounded.
+    pub const fn with_rounding_policy(mut self, rounding_policy: RoundingPolicy) -> Self {
+        self.rounding_policy = rounding_policy;
+        self
+    }
+
+    /// Resolve the buy and sell bids at a single clearing price.
+    ///
+    /// The highest buys are matched against the lowest sells while the buy
+    /// price covers the sell price, which maximises the traded quantity. The
+    /// marginal orders are partially filled when the quantities on each side
+    /// differ.
+    ///
+    /// # Arguments
+    /// * `buys` - The bids to buy lots.
+    /// * `sells` - The bids to sell lots.
+    ///
+    /// # Returns
+    /// The matched sales for both sides and the clearing price.
+    ///
+    pub fn resolve(&self, mut buys: Bids, mut sells: Bids) -> DoubleAuctionResult {
+        rank_highest_first(&mut buys, SecondarySort::EarliestSubmitted);
+        rank_lowest_first(&mut sells, SecondarySort::EarliestSubmitted);
+
+        let mut buy_fills = vec![0; buys.len()];
+        let mut sell_fills = vec![0; sells.len()];
+        let (mut i, mut j) = (0, 0);
+        while i < buys.len() && j < sells.len() && buys[i].amount >= sells[j].amount {
+            let quantity = (buys[i].quantity - buy_fills[i]).min(sells[j].quantity - sell_fills[j]);
+            buy_fills[i] += quantity;
+            sell_fills[j] += quantity;
+            if buy_fills[i] == buys[i].quantity {
+                i += 1;
+            }
+            if sell_fills[j] == sells[j].quantity {
+                j += 1;
+            }
+        }
+
+        let marginal_buy = buy_fills.iter().rposition(|&filled| filled > 0);
+        let marginal_sell = sell_fills.iter().rposition(|&filled| filled > 0);
+        let (Some(marginal_buy), Some(marginal_sell)) = (marginal_buy, marginal_sell) else {
+            return DoubleAuctionResult {
+                buys: Vec::new(),
+                sells: Vec::new(),
+                clearing_price: None,
+            };
+        };
+        let high = buys[marginal_buy].amount;
+        let low = sells[marginal_sell].amount;
+        let price = match self.rule {
+            ClearingRule::Midpoint => {
+                let sum = i128::from(high) + i128::from(low);
+                self.rounding_policy.divide(sum, 2) as i64
+            }
+            ClearingRule::BuyerFavouring => low,
+            ClearingRule::SellerFavouring => high,
+        };
+
+        DoubleAuctionResult {
+            buys: matched_sales(&buys, &buy_fills, price),
+            sells: matched_sales(&sells, &sell_fills, price),
+            clearing_price: Some(price),
+        }
+    }
+}
+
+/// Sales for every bid with a non-zero fill.
+fn matched_sales(bids: &Bids, fills: &[usize], price: i64) -> Sales {
+    bids.iter()
+        .zip(fills)
+        .filter(|(_, &filled)| filled > 0)
+        .map(|(bid, &filled)| Sale::new(bid.bidder_id, price, filled).with_bid(bid))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn quantity(sales: &Sales) -> usize {
+        sales.iter().map(|sale| sale.quantity).sum()
+    }
+
+    #[test]
+    fn double_auction_no_overlap_is_empty() {
+        let buys: Bids = vec![bid![10, 1], bid![8, 1]];
+        let sells: Bids = vec![bid![12, 1], bid![15, 1]];
+        let result = DoubleAuction::default().resolve(buys, sells);
+        assert!(result.buys().is_empty());
+        assert!(result.sells().is_empty());
+        assert_eq!(result.clearing_price(), None);
+    }
+
+    #[test]
+    fn double_auction_empty_side_is_empty() {
+        let buys: Bids = vec![bid![10, 1]];
+        let result = DoubleAuction::default().resolve(buys, vec![]);
+        assert_eq!(result.clearing_price(), None);
+    }
+
+    #[test]
+    fn double_auction_matches_overlap_at_midpoint() {
+        let buys: Bids = vec![bid![20, 1], bid![15, 1], bid![9, 1]];
+        let sells: Bids = vec![bid![10, 1], bid![12, 1], bid![18, 1]];
+        let result = DoubleAuction::new(ClearingRule::Midpoint).resolve(buys, sells);
+        assert_eq!(result.buys().len(), 2);
+        assert_eq!(result.sells().len(), 2);
+        assert_eq!(result.clearing_price(), Some(13));
+        assert!(result.buys().iter().all(|sale| sale.amount == 13));
+        assert!(result.sells().iter().all(|sale| sale.amount == 13));
+    }
+
+    #[test]
+    fn double_auction_midpoint_follows_rounding_policy() {
+        let price = |policy| {
+            let buys: Bids = vec![bid![20, 1], bid![15, 1]];
+            let sells: Bids = vec![bid![10, 1], bid![12, 1]];
+            DoubleAuction::new(ClearingRule::Midpoint)
+                .with_rounding_policy(policy)
+                .resolve(buys, sells)
+                .clearing_price()
+        };
+        assert_eq!(price(RoundingPolicy::TowardBuyer), Some(13));
+        assert_eq!(price(RoundingPolicy::TowardSeller), Some(14));
+        assert_eq!(price(RoundingPolicy::Nearest), Some(14));
+    }
+
+    #[test]
+    fn double_auction_midpoint_of_extreme_amounts() {
+        let buys: Bids = vec![bid![i64::MAX, 1]];
+        let sells: Bids = vec![bid![-i64::MAX, 1]];
+        let result = DoubleAuction::new(ClearingRule::Midpoint).resolve(buys, sells);
+        assert_eq!(result.clearing_price(), Some(0));
+    }
+
+    #[test]
+    fn double_auction_rules_pick_overlap_ends() {
+        let buys = || vec![bid![20, 1], bid![15, 1]];
+        let sells = || vec![bid![10, 1], bid![12, 1]];
+        let buyer = DoubleAuction::new(ClearingRule::BuyerFavouring).resolve(buys(), sells());
+        let seller = DoubleAuction::new(ClearingRule::SellerFavouring).resolve(buys(), sells());
+        assert_eq!(buyer.clearing_price(), Some(12));
+        assert_eq!(seller.clearing_price(), Some(15));
+    }
+
+    #[test]
+    fn double_auction_unequal_quantities_partially_fill() {
+        let buys: Bids = vec![bid![20, 5]];
+        let sells: Bids = vec![bid![10, 2], bid![12, 1], bid![25, 4]];
+        let result = DoubleAuction::default().resolve(buys, sells);
+        assert_eq!(quantity(result.buys()), 3);
+        assert_eq!(quantity(result.sells()), 3);
+        assert_eq!(result.buys()[0].quantity, 3);
+        assert_eq!(result.clearing_price(), Some(16));
+    }
+
+    #[test]
+    fn double_auction_marginal_orders_partially_filled() {
+        let buys: Bids = vec![bid![20, 2], bid![14, 3]];
+        let sells: Bids = vec![bid![10, 4], bid![13, 5]];
+        let sell_ids: Vec<_> = sells.iter().map(|bid| bid.id).collect();
+        let (buys, sells) = DoubleAuction::default().resolve(buys, sells).into_sales();
+        assert_eq!(quantity(&buys), 5);
+        assert_eq!(buys[1].quantity, 3);
+        assert_eq!(sells[0].bidder_id, sell_ids[0]);
+        assert_eq!(sells[0].quantity, 4);
+        assert_eq!(sells[1].quantity, 1);
+    }
+}
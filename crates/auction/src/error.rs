@@ -0,0 +1,309 @@
+//! Module containing the errors raised by the crate.
+use alloc::{string::String, vec::Vec};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use uuid::Uuid;
+
+/// Enum representing the ways an auction can be misconfigured.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuctionConfigError {
+    /// The auction has no lots to sell.
+    ZeroLots,
+    /// The reserve price is below zero.
+    NegativeReservePrice(i64),
+    /// The tick size is not positive.
+    NonPositiveTickSize(i64),
+    /// The buy-now price is below the reserve price.
+    BuyNowBelowReserve {
+        buy_now_price: i64,
+        reserve_price: i64,
+    },
+    /// A later tier of the reserve schedule has a lower reserve.
+    DecreasingReserveSchedule,
+    /// The Dutch clock decrement does not lower the price.
+    NonPositiveDecrement(i64),
+    /// The Japanese clock increment does not raise the price.
+    NonPositiveIncrement(i64),
+    /// The maximum bid fraction has a zero denominator.
+    ZeroBidFractionDenominator,
+    /// The strategy cannot resolve the auction as configured.
+    StrategyMismatch(&'static str),
+}
+
+impl fmt::Display for AuctionConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroLots => write!(f, "auction must have at least one lot"),
+            Self::NegativeReservePrice(price) => {
+                write!(f, "reserve price {price} must not be negative")
+            }
+            Self::NonPositiveTickSize(tick_size) => {
+                write!(f, "tick size {tick_size} must be positive")
+            }
+            Self::BuyNowBelowReserve {
+                buy_now_price,
+                reserve_price,
+            } => write!(
+                f,
+                "buy-now price {buy_now_price} is below reserve price {reserve_price}"
+            ),
+            Self::DecreasingReserveSchedule => {
+                write!(f, "reserve schedule must not decrease")
+            }
+            Self::NonPositiveDecrement(decrement) => {
+                write!(f, "dutch decrement {decrement} must be positive")
+            }
+            Self::NonPositiveIncrement(increment) => {
+                write!(f, "japanese increment {increment} must be positive")
+            }
+            Self::ZeroBidFractionDenominator => {
+                write!(f, "maximum bid fraction must have a non-zero denominator")
+            }
+            Self::StrategyMismatch(reason) => write!(f, "strategy mismatch: {reason}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for AuctionConfigError {}
+
+/// Enum representing the ways a bid can be invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BidError {
+    /// The bid asks for no units.
+    ZeroQuantity,
+    /// The bid amount is below zero.
+    NegativeAmount(i64),
+    /// The minimum fill is larger than the quantity bid for.
+    MinQuantityExceedsQuantity {
+        min_quantity: usize,
+        quantity: usize,
+    },
+    /// The money amount cannot be held in minor units at the given scale.
+    UnrepresentableAmount,
+    /// The ladder has no steps.
+    EmptyLadder,
+    /// The step's amount is not below the amount of the step before it.
+    NonDecreasingLadder { step: usize },
+    /// The package bid covers no lots.
+    EmptyPackage,
+    /// The score weight is not positive.
+    NonPositiveScoreWeight(i64),
+}
+
+impl fmt::Display for BidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ZeroQuantity => write!(f, "bid quantity must be at least one"),
+            Self::NegativeAmount(amount) => write!(f, "bid amount {amount} must not be negative"),
+            Self::MinQuantityExceedsQuantity {
+                min_quantity,
+                quantity,
+            } => write!(
+                f,
+                "minimum quantity {min_quantity} exceeds bid quantity {quantity}"
+            ),
+            Self::UnrepresentableAmount => {
+                write!(f, "amount cannot be held in minor units at this scale")
+            }
+            Self::EmptyLadder => write!(f, "ladder must have at least one step"),
+            Self::NonDecreasingLadder { step } => {
+                write!(f, "ladder step {step} must bid less than the step before")
+            }
+            Self::EmptyPackage => write!(f, "package must cover at least one lot"),
+            Self::NonPositiveScoreWeight(weight) => {
+                write!(f, "score weight {weight} must be positive")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BidError {}
+
+/// Enum representing the ways resolving an auction can fail.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuctionError {
+    /// The auction is misconfigured.
+    Config(AuctionConfigError),
+    /// One of the bids is invalid.
+    InvalidBid { bid_id: Uuid, error: BidError },
+    /// A monetary total does not fit in an `i128` number of cents.
+    AmountOverflow,
+    /// A bid needs a feature the auction's strategy does not have.
+    StrategyMismatch { bid_id: Uuid, reason: &'static str },
+    /// Every lot has already been sold.
+    AllLotsSold,
+    /// There is no round left to run.
+    NoRoundsLeft,
+    /// A package bid covers a lot the auction is not selling.
+    UnknownLot { bid_id: Uuid, lot: String },
+    /// There are more package bids than winner determination can search.
+    TooManyPackageBids { count: usize, limit: usize },
+    /// Bids are not in the auction's currency.
+    CurrencyMismatch { bid_ids: Vec<Uuid> },
+}
+
+impl fmt::Display for AuctionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Config(error) => write!(f, "invalid auction: {error}"),
+            Self::InvalidBid { bid_id, error } => write!(f, "invalid bid {bid_id}: {error}"),
+            Self::AmountOverflow => write!(f, "monetary total overflows i128 cents"),
+            Self::StrategyMismatch { bid_id, reason } => {
+                write!(f, "bid {bid_id} does not suit the strategy: {reason}")
+            }
+            Self::AllLotsSold => write!(f, "every lot has already been sold"),
+            Self::NoRoundsLeft => write!(f, "no round left to run"),
+            Self::UnknownLot { bid_id, lot } => write!(f, "bid {bid_id} covers unknown lot {lot}"),
+            Self::TooManyPackageBids { count, limit } => {
+                write!(f, "{count} package bids exceeds the limit of {limit}")
+            }
+            Self::CurrencyMismatch { bid_ids } => {
+                write!(
+                    f,
+                    "{} bids are not in the auction's currency",
+                    bid_ids.len()
+                )
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for AuctionError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Config(error) => Some(error),
+            Self::InvalidBid { error, .. } => Some(error),
+            Self::AmountOverflow
+            | Self::StrategyMismatch { .. }
+            | Self::AllLotsSold
+            | Self::NoRoundsLeft
+            | Self::UnknownLot { .. }
+            | Self::TooManyPackageBids { .. }
+            | Self::CurrencyMismatch { .. } => None,
+        }
+    }
+}
+
+impl From<AuctionConfigError> for AuctionError {
+    fn from(error: AuctionConfigError) -> Self {
+        Self::Config(error)
+    }
+}
+
+/// Enum representing what was wrong with a field of an imported bid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BidParseErrorKind {
+    /// The header row is missing a required column.
+    MissingColumn(&'static str),
+    /// The row has no value for a required column.
+    MissingField(&'static str),
+    /// The value is not a valid uuid.
+    InvalidUuid(String),
+    /// The value is not a valid amount in cents.
+    InvalidAmount(String),
+    /// The amount is below zero.
+    NegativeAmount(i64),
+    /// The value is not a valid quantity.
+    InvalidQuantity(String),
+    /// The line is not a valid JSON bid.
+    InvalidJson(String),
+    /// The input could not be read.
+    Io(String),
+}
+
+impl fmt::Display for BidParseErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingColumn(name) => write!(f, "missing column `{name}`"),
+            Self::MissingField(name) => write!(f, "missing value for `{name}`"),
+            Self::InvalidUuid(value) => write!(f, "invalid uuid `{value}`"),
+            Self::InvalidAmount(value) => write!(f, "invalid amount `{value}`"),
+            Self::NegativeAmount(amount) => write!(f, "amount {amount} must not be negative"),
+            Self::InvalidQuantity(value) => write!(f, "invalid quantity `{value}`"),
+            Self::InvalidJson(message) => write!(f, "invalid bid: {message}"),
+            Self::Io(message) => write!(f, "read failed: {message}"),
+        }
+    }
+}
+
+/// An error importing bids, pointing at where in the input it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BidParseError {
+    /// The line number, starting from one.
+    line: usize,
+    /// The column number, starting from one, when the error is in a field.
+    column: Option<usize>,
+    kind: BidParseErrorKind,
+}
+
+impl BidParseError {
+    pub(crate) const fn new(line: usize, column: Option<usize>, kind: BidParseErrorKind) -> Self {
+        Self { line, column, kind }
+    }
+
+    /// The line number, starting from one.
+    pub const fn line(&self) -> usize {
+        self.line
+    }
+
+    /// The column number, starting from one, when the error is in a field.
+    pub const fn column(&self) -> Option<usize> {
+        self.column
+    }
+
+    /// What went wrong.
+    pub const fn kind(&self) -> &BidParseErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for BidParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {column}: {}", self.line, self.kind),
+            None => write!(f, "line {}: {}", self.line, self.kind),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BidParseError {}
+
+/// An error parsing a bid from text such as `150x3`, pointing at where in the
+/// text it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseBidError {
+    /// The byte offset of the part that failed to parse.
+    offset: usize,
+    kind: BidParseErrorKind,
+}
+
+impl ParseBidError {
+    pub(crate) const fn new(offset: usize, kind: BidParseErrorKind) -> Self {
+        Self { offset, kind }
+    }
+
+    /// The byte offset of the part that failed to parse.
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// What went wrong.
+    pub const fn kind(&self) -> &BidParseErrorKind {
+        &self.kind
+    }
+}
+
+impl fmt::Display for ParseBidError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "byte {}: {}", self.offset, self.kind)
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for ParseBidError {}
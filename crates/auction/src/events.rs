@@ -0,0 +1,352 @@
+//! Module for the append-only log of events a resolution produces.
+use alloc::vec::Vec;
+
+use uuid::Uuid;
+
+use crate::{
+    collections::HashSet, Auction, AuctionDirection, AuctionObserver, Bid, Currency, RejectReason,
+    Sale, Sales, SalesSummary,
+};
+
+/// Enum representing what happened at one step of a resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EventKind {
+    /// The bid has a sale, though a losing all-pay bid's sale is for no units.
+    BidAccepted { bid_id: Uuid },
+    /// The bid has no sale, for the reason given.
+    BidRejected { bid_id: Uuid, reason: RejectReason },
+    /// A sale was made, carrying everything needed to rebuild it.
+    LotAllocated {
+        /// The bid the sale fills, None for sales not made from a bid.
+        bid_id: Option<Uuid>,
+        bidder_id: Uuid,
+        quantity: usize,
+        price: i64,
+        /// The quantity the bid asked for, None when the bid is not known.
+        requested_quantity: Option<usize>,
+        currency: Option<Currency>,
+    },
+    /// The marginal price lots sold at, the lowest sale price or the highest
+    /// for reverse auctions. Left out when nothing sold.
+    ClearingPriceSet { price: i64 },
+    /// The resolution finished, with the revenue None if it overflows an
+    /// `i128`.
+    AuctionClosed { revenue: Option<i128> },
+}
+
+/// One entry of the event log, numbered in the order it was appended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuctionEvent {
+    sequence: u64,
+    kind: EventKind,
+}
+
+impl AuctionEvent {
+    /// The position of the event in the log, starting from zero and rising by
+    /// one with each event.
+    pub const fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// What happened.
+    pub const fn kind(&self) -> &EventKind {
+        &self.kind
+    }
+}
+
+/// Resolves the bids as [`Auction::resolve_bids_ref`] does, logging each bid
+/// as it is rejected or first allocated lots, then each sale in the order of
+/// the sales, the clearing price, and the close.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// The sales, and the events they can be rebuilt from with
+/// [`replay_events`].
+///
+pub fn resolve_with_events(auction: &Auction, bids: &[Bid]) -> (Sales, Vec<AuctionEvent>) {
+    let mut events = Vec::new();
+    let sales = resolve_logged(auction, bids, |kind| {
+        let sequence = events.len() as u64;
+        events.push(AuctionEvent { sequence, kind });
+    });
+    (sales, events)
+}
+
+/// Resolves the bids as [`resolve_with_events`] does, handing each event to
+/// `log` as the resolution produces it rather than collecting them.
+fn resolve_logged(auction: &Auction, bids: &[Bid], log: impl FnMut(EventKind)) -> Sales {
+    let mut logger = EventLogger {
+        direction: auction.direction,
+        accepted: HashSet::default(),
+        log,
+    };
+    auction.resolve_observed(bids, &mut logger)
+}
+
+/// Turns the observer's hooks into events as a resolution calls them.
+struct EventLogger<F> {
+    direction: AuctionDirection,
+    /// The bids already logged as accepted.
+    accepted: HashSet<Uuid>,
+    log: F,
+}
+
+impl<F: FnMut(EventKind)> AuctionObserver for EventLogger<F> {
+    fn on_bid_rejected(&mut self, bid: &Bid, reason: RejectReason) {
+        (self.log)(EventKind::BidRejected {
+            bid_id: bid.id,
+            reason,
+        });
+    }
+
+    fn on_allocation(&mut self, bid: &Bid, _quantity: usize, _price: i64) {
+        if self.accepted.insert(bid.id) {
+            (self.log)(EventKind::BidAccepted { bid_id: bid.id });
+        }
+    }
+
+    /// Logs the sales once they are complete, so the log carries them as they
+    /// are returned: ordered, tagged with their currency and requested
+    /// quantities.
+    fn on_complete(&mut self, sales: &Sales) {
+        for sale in sales {
+            (self.log)(EventKind::LotAllocated {
+                bid_id: sale.bid_id,
+                bidder_id: sale.bidder_id,
+                quantity: sale.quantity,
+                price: sale.amount,
+                requested_quantity: sale.requested_quantity,
+                currency: sale.currency,
+            });
+        }
+        let prices = sales
+            .iter()
+            .filter(|sale| sale.quantity > 0)
+            .map(|sale| sale.amount);
+        let clearing_price = match self.direction {
+            AuctionDirection::Forward => prices.min(),
+            AuctionDirection::Reverse => prices.max(),
+        };
+        if let Some(price) = clearing_price {
+            (self.log)(EventKind::ClearingPriceSet { price });
+        }
+        (self.log)(EventKind::AuctionClosed {
+            revenue: sales.total_revenue(),
+        });
+    }
+}
+
+/// Rebuilds the sales a resolution made from its events, taken in sequence
+/// order whatever order they are given in.
+///
+/// ```
+/// use auction::{bid, replay_events, AuctionBuilder};
+///
+/// let auction = AuctionBuilder::new().lots(2).build();
+/// let (sales, events) = auction.resolve_with_events(vec![bid![30, 1], bid![20, 2]]);
+/// assert_eq!(replay_events(&events), sales);
+/// ```
+pub fn replay_events(events: &[AuctionEvent]) -> Sales {
+    let mut events: Vec<&AuctionEvent> = events.iter().collect();
+    events.sort_by_key(|event| event.sequence);
+    events
+        .into_iter()
+        .filter_map(|event| match event.kind {
+            EventKind::LotAllocated {
+                bid_id,
+                bidder_id,
+                quantity,
+                price,
+                requested_quantity,
+                currency,
+            } => Some(Sale {
+                bid_id,
+                requested_quantity,
+                currency,
+                ..Sale::new(bidder_id, price, quantity)
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use crate::*;
+
+    fn kinds(events: &[AuctionEvent]) -> Vec<EventKind> {
+        events.iter().map(|event| *event.kind()).collect()
+    }
+
+    fn assert_replays(auction: &Auction, bids: &[Bid]) {
+        let (sales, events) = auction.resolve_with_events(bids.to_vec());
+        assert_eq!(sales, auction.resolve_bids_ref(bids));
+        assert_eq!(replay_events(&events), sales);
+        let sequence: Vec<u64> = events.iter().map(AuctionEvent::sequence).collect();
+        assert_eq!(sequence, (0..events.len() as u64).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn events_log_a_single_price_resolution() {
+        let bids: Bids = vec![bid![30, 1], bid![5, 1], bid![20, 2], bid![10, 1]];
+        let auction = AuctionBuilder::new().lots(2).reserve_price(8).build();
+        let (sales, events) = auction.resolve_with_events(bids.clone());
+        assert_eq!(
+            kinds(&events),
+            vec![
+                EventKind::BidRejected {
+                    bid_id: bids[1].id,
+                    reason: RejectReason::BelowReserve,
+                },
+                EventKind::BidRejected {
+                    bid_id: bids[3].id,
+                    reason: RejectReason::OutbidAtClearing,
+                },
+                EventKind::BidAccepted { bid_id: bids[0].id },
+                EventKind::BidAccepted { bid_id: bids[2].id },
+                EventKind::LotAllocated {
+                    bid_id: Some(bids[0].id),
+                    bidder_id: bids[0].bidder_id,
+                    quantity: 1,
+                    price: 20,
+                    requested_quantity: Some(1),
+                    currency: None,
+                },
+                EventKind::LotAllocated {
+                    bid_id: Some(bids[2].id),
+                    bidder_id: bids[2].bidder_id,
+                    quantity: 1,
+                    price: 20,
+                    requested_quantity: Some(2),
+                    currency: None,
+                },
+                EventKind::ClearingPriceSet { price: 20 },
+                EventKind::AuctionClosed { revenue: Some(40) },
+            ]
+        );
+        assert_eq!(replay_events(&events), sales);
+    }
+
+    #[test]
+    fn events_replay_every_strategy() {
+        let bids = generators::large_quantities(11, 40, 1_000, 5);
+        for strategy in [
+            AuctionStrategy::SinglePrice,
+            AuctionStrategy::MultiPrice,
+            AuctionStrategy::AllPay,
+            AuctionStrategy::GeneralizedSecondPrice,
+            AuctionStrategy::Vcg,
+            AuctionStrategy::Proxy,
+        ] {
+            let auction = AuctionBuilder::new()
+                .strategy(strategy)
+                .lots(12)
+                .reserve_price(100)
+                .build();
+            assert_replays(&auction, &bids);
+        }
+    }
+
+    #[test]
+    fn events_replay_reverse_auctions_in_any_order() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 1], bid![25, 2]];
+        let auction = AuctionBuilder::new()
+            .direction(AuctionDirection::Reverse)
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+        assert_replays(&auction, &bids);
+        let (sales, mut events) = auction.resolve_with_events(bids);
+        assert!(events
+            .iter()
+            .any(|event| *event.kind() == EventKind::ClearingPriceSet { price: 25 }));
+        events.reverse();
+        assert_eq!(replay_events(&events), sales);
+    }
+
+    #[test]
+    fn events_replay_currency_tags() {
+        let usd = Currency::USD;
+        let bids: Bids = vec![
+            bid![30, 1].with_currency(usd),
+            bid![20, 1].with_currency(usd),
+        ];
+        let auction = AuctionBuilder::new().currency(usd).build();
+        assert_replays(&auction, &bids);
+    }
+
+    #[test]
+    fn events_replay_currency_policies_and_sales_orderings() {
+        let usd = Currency::USD;
+        let mut bids = generators::large_quantities(5, 30, 1_000, 4);
+        for (index, bid) in bids.iter_mut().enumerate() {
+            *bid = match index % 3 {
+                0 => bid.with_currency(Currency::EUR),
+                1 => bid.with_currency(usd),
+                _ => *bid,
+            };
+        }
+        let usd_only: Bids = bids.iter().map(|bid| bid.with_currency(usd)).collect();
+        for strategy in [
+            AuctionStrategy::SinglePrice,
+            AuctionStrategy::MultiPrice,
+            AuctionStrategy::AllPay,
+            AuctionStrategy::Vcg,
+        ] {
+            for mismatch in [CurrencyMismatch::Filter, CurrencyMismatch::Reject] {
+                for ordering in [
+                    SalesOrdering::Ranked,
+                    SalesOrdering::PriceDesc,
+                    SalesOrdering::BidderId,
+                    SalesOrdering::QuantityDesc,
+                    SalesOrdering::InputBidOrder,
+                ] {
+                    let auction = AuctionBuilder::new()
+                        .strategy(strategy.clone())
+                        .lots(10)
+                        .reserve_price(100)
+                        .currency(usd)
+                        .currency_mismatch(mismatch)
+                        .sales_ordering(ordering)
+                        .build();
+                    assert_replays(&auction, &bids);
+                    assert_replays(&auction, &usd_only);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn events_reject_off_tick_bids() {
+        let bids: Bids = vec![bid![25, 1], bid![30, 1]];
+        let auction = AuctionBuilder::new().tick_size(10).build();
+        let (_, events) = auction.resolve_with_events(bids.clone());
+        assert_eq!(
+            events[0].kind(),
+            &EventKind::BidRejected {
+                bid_id: bids[0].id,
+                reason: RejectReason::OffTick,
+            }
+        );
+    }
+
+    #[test]
+    fn events_close_an_auction_with_no_sales() {
+        let auction = AuctionBuilder::new().reserve_price(50).build();
+        let (sales, events) = auction.resolve_with_events(vec![bid![10, 1]]);
+        assert!(sales.is_empty());
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[1].kind(),
+            &EventKind::AuctionClosed { revenue: Some(0) }
+        );
+        assert!(replay_events(&events).is_empty());
+    }
+}
@@ -0,0 +1,650 @@
+//! Module for explaining why each bid won or lost.
+use alloc::vec::Vec;
+
+use uuid::Uuid;
+
+use crate::{
+    bid_cap,
+    collections::{HashMap, HashSet},
+    reserve,
+    strategies::{
+        fill::{fill_lots, rank_tiered},
+        price_as_bid, price_uniformly, record_requested,
+    },
+    tick, Auction, AuctionDirection, AuctionStrategy, Bid, Bids, Sales, Timestamp,
+};
+
+/// Enum representing what happened to a bid during resolution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Outcome {
+    /// The bid was filled in full at the price.
+    Won { quantity: usize, price: i64 },
+    /// The bid was filled in part.
+    PartiallyFilled { requested: usize, filled: usize },
+    /// Better bids took every lot, and the bid ranks below the clearing price.
+    LostBelowClearing,
+    /// The bid does not meet the reserve price, or the price ceiling of a
+    /// reverse auction.
+    BelowReserve,
+    /// The bid ranks at or above the clearing price but the lots left could
+    /// not fill it, it lost a tie, refused a partial fill or hit a bidder limit.
+    SkippedNoLots,
+    /// The bid's amount is off the tick grid, so it was not considered.
+    OffTick,
+    /// The bid asks for more lots than one bid may, so it was not considered.
+    ExceedsBidCap,
+    /// The bid is not in the auction's currency, or shares a batch the
+    /// currency policy rejected, so it was not considered.
+    CurrencyMismatch,
+}
+
+/// What happened to one input bid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BidOutcome {
+    bid_id: Uuid,
+    outcome: Outcome,
+}
+
+impl BidOutcome {
+    /// The id of the bid.
+    pub const fn bid_id(&self) -> Uuid {
+        self.bid_id
+    }
+
+    /// What happened to the bid.
+    pub const fn outcome(&self) -> Outcome {
+        self.outcome
+    }
+}
+
+/// Enum representing why a bid did not win any lots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RejectReason {
+    /// The bid does not meet the reserve price, or the price ceiling of a
+    /// reverse auction.
+    BelowReserve,
+    /// Better bids took every lot.
+    OutbidAtClearing,
+    /// The bid ranked high enough but the lots left could not fill it.
+    NoLotsRemaining,
+    /// The bid lapsed before the auction was resolved.
+    Expired,
+    /// The bid was submitted after the auction closed.
+    Late,
+    /// The bid's amount is off the tick grid.
+    OffTick,
+    /// The bid asks for more lots than one bid may.
+    ExceedsBidCap,
+    /// The bid is not in the auction's currency, or shares a batch the
+    /// currency policy rejected.
+    CurrencyMismatch,
+}
+
+/// A bid that won no lots, with the reason why.
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RejectedBid {
+    bid: Bid,
+    reason: RejectReason,
+}
+
+impl RejectedBid {
+    /// The bid that was rejected.
+    pub const fn bid(&self) -> &Bid {
+        &self.bid
+    }
+
+    /// Why the bid won no lots.
+    pub const fn reason(&self) -> RejectReason {
+        self.reason
+    }
+}
+
+/// The sales of a resolution along with every input bid, split into the bids
+/// that won lots and those that were rejected.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FullResolution {
+    sales: Sales,
+    winners: Bids,
+    rejected: Vec<RejectedBid>,
+}
+
+impl FullResolution {
+    /// The sales produced by the auction.
+    pub const fn sales(&self) -> &Sales {
+        &self.sales
+    }
+
+    /// The bids that won lots, in full or in part, in input order.
+    pub const fn winners(&self) -> &Bids {
+        &self.winners
+    }
+
+    /// The bids that won nothing, in input order.
+    pub const fn rejected(&self) -> &Vec<RejectedBid> {
+        &self.rejected
+    }
+
+    /// Consume the resolution, returning the sales.
+    pub fn into_sales(self) -> Sales {
+        self.sales
+    }
+}
+
+/// Resolves the live bids at the given time, accounting for every input bid
+/// as either a winner or a rejection.
+///
+/// Single and multi price auctions give each rejection its precise reason.
+/// Other strategies only report which bids qualified, a qualifying bid whose
+/// bidder made no sale counts as outbid and any bid whose bidder made a sale
+/// counts as a winner.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+/// * `now` - The time of resolution, bids expiring by then are rejected.
+///
+/// # Returns
+/// The sales, winning bids and rejected bids.
+///
+pub fn resolve_full(auction: &Auction, bids: Bids, now: Timestamp) -> FullResolution {
+    let live: Bids = bids
+        .iter()
+        .filter(|bid| !bid.is_expired(now) && !auction.is_late(bid))
+        .copied()
+        .collect();
+    let auction = reserve::apply_dynamic_reserve(auction, &live);
+    let auction = &*auction;
+    let currency_rejected = auction.currency_rejected(&live);
+    let (sales, reasons) = match resolve_explained(auction, &live) {
+        Some((sales, outcomes)) => {
+            let reasons: HashMap<Uuid, Option<RejectReason>> = outcomes
+                .iter()
+                .map(|outcome| (outcome.bid_id, reject_reason(outcome.outcome)))
+                .collect();
+            (sales, reasons)
+        }
+        None => {
+            let sales = auction.resolve_bids_ref(&live);
+            let buyers: HashSet<Uuid> = sales.iter().map(|sale| sale.bidder_id).collect();
+            let reasons = live
+                .iter()
+                .map(|bid| {
+                    let reason = if buyers.contains(&bid.bidder_id) {
+                        None
+                    } else if auction.qualifies(bid.amount) {
+                        Some(RejectReason::OutbidAtClearing)
+                    } else {
+                        Some(RejectReason::BelowReserve)
+                    };
+                    (bid.id, reason)
+                })
+                .collect();
+            (sales, reasons)
+        }
+    };
+
+    let mut winners = Vec::new();
+    let mut rejected = Vec::new();
+    for bid in bids {
+        let reason = if auction.is_late(&bid) {
+            Some(RejectReason::Late)
+        } else if bid.is_expired(now) {
+            Some(RejectReason::Expired)
+        } else if currency_rejected.contains(&bid.id) {
+            Some(RejectReason::CurrencyMismatch)
+        } else if tick::is_rejected(auction, &bid) {
+            Some(RejectReason::OffTick)
+        } else if bid_cap::is_rejected(auction, &bid) {
+            Some(RejectReason::ExceedsBidCap)
+        } else {
+            reasons[&bid.id]
+        };
+        match reason {
+            Some(reason) => rejected.push(RejectedBid { bid, reason }),
+            None => winners.push(bid),
+        }
+    }
+    FullResolution {
+        sales,
+        winners,
+        rejected,
+    }
+}
+
+/// Why a bid with the outcome was rejected, None if it won lots.
+pub const fn reject_reason(outcome: Outcome) -> Option<RejectReason> {
+    match outcome {
+        Outcome::Won { .. } | Outcome::PartiallyFilled { .. } => None,
+        Outcome::LostBelowClearing => Some(RejectReason::OutbidAtClearing),
+        Outcome::BelowReserve => Some(RejectReason::BelowReserve),
+        Outcome::SkippedNoLots => Some(RejectReason::NoLotsRemaining),
+        Outcome::OffTick => Some(RejectReason::OffTick),
+        Outcome::ExceedsBidCap => Some(RejectReason::ExceedsBidCap),
+        Outcome::CurrencyMismatch => Some(RejectReason::CurrencyMismatch),
+    }
+}
+
+/// Resolves the bids with the single or multi price algorithm, recording an
+/// outcome for each bid in the order they were ranked, followed by any bids
+/// rejected for their currency, for being off the tick grid or over the bid
+/// cap.
+///
+/// A bid clamped to the bid cap is partially filled against the quantity it
+/// asked for.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// The sales and outcomes, None for strategies that cannot be explained or
+/// auctions with a buy-now price.
+///
+pub fn resolve_explained(auction: &Auction, bids: &[Bid]) -> Option<(Sales, Vec<BidOutcome>)> {
+    if auction.buy_now_price.is_some() {
+        return None;
+    }
+    let auction = reserve::apply_dynamic_reserve(auction, bids);
+    let auction = &*auction;
+    let in_currency = auction.in_currency(bids);
+    let on_grid = tick::apply_tick_size(auction, &in_currency);
+    let capped = bid_cap::apply_bid_cap(auction, &on_grid);
+    let mut ranked: Vec<&Bid> = capped.iter().collect();
+    rank_tiered(auction, &mut ranked);
+    let winning_bids = fill_lots(
+        auction,
+        &ranked,
+        |bid| auction.qualifies(bid.amount),
+        &mut (),
+    );
+    let mut sales = match auction.strategy {
+        AuctionStrategy::SinglePrice => price_uniformly(auction, &capped, &winning_bids, &mut ()),
+        AuctionStrategy::MultiPrice => price_as_bid(auction, &capped, &winning_bids, &mut ()),
+        _ => return None,
+    };
+    record_requested(&mut sales, bids);
+    auction.tag_currency(&mut sales);
+    auction.sales_ordering.sort(&mut sales, bids);
+
+    let filled: HashMap<Uuid, usize> = winning_bids
+        .iter()
+        .map(|bid| (bid.id, bid.quantity))
+        .collect();
+    let prices: HashMap<Uuid, i64> = sales
+        .iter()
+        .filter_map(|sale| Some((sale.bid_id?, sale.amount)))
+        .collect();
+    let lots_sold: usize = winning_bids.iter().map(|bid| bid.quantity).sum();
+    let clearing = winning_bids.last().map(|bid| bid.amount);
+    let below_clearing = |amount: i64| {
+        lots_sold == auction.lots
+            && clearing.is_some_and(|clearing| match auction.direction {
+                AuctionDirection::Forward => amount < clearing,
+                AuctionDirection::Reverse => amount > clearing,
+            })
+    };
+
+    let currency_rejected = auction.currency_rejected(bids);
+    let mismatched = bids
+        .iter()
+        .filter(|bid| currency_rejected.contains(&bid.id))
+        .map(|bid| BidOutcome {
+            bid_id: bid.id,
+            outcome: Outcome::CurrencyMismatch,
+        });
+    let off_tick = in_currency
+        .iter()
+        .filter(|bid| tick::is_rejected(auction, bid))
+        .map(|bid| BidOutcome {
+            bid_id: bid.id,
+            outcome: Outcome::OffTick,
+        });
+    let over_cap = in_currency
+        .iter()
+        .filter(|bid| !tick::is_rejected(auction, bid) && bid_cap::is_rejected(auction, bid))
+        .map(|bid| BidOutcome {
+            bid_id: bid.id,
+            outcome: Outcome::ExceedsBidCap,
+        });
+    let requested: HashMap<Uuid, usize> = bids.iter().map(|bid| (bid.id, bid.quantity)).collect();
+    let outcomes = ranked
+        .iter()
+        .map(|bid| {
+            let requested = requested.get(&bid.id).copied().unwrap_or(bid.quantity);
+            let outcome = match filled.get(&bid.id) {
+                Some(&quantity) if quantity == requested => Outcome::Won {
+                    quantity,
+                    price: prices[&bid.id],
+                },
+                Some(&quantity) => Outcome::PartiallyFilled {
+                    requested,
+                    filled: quantity,
+                },
+                None if !auction.qualifies(bid.amount)
+                    || (lots_sold < auction.lots
+                        && auction.lots_within_reserve(lots_sold, bid.amount) == 0) =>
+                {
+                    Outcome::BelowReserve
+                }
+                None if below_clearing(bid.amount) => Outcome::LostBelowClearing,
+                None => Outcome::SkippedNoLots,
+            };
+            BidOutcome {
+                bid_id: bid.id,
+                outcome,
+            }
+        })
+        .chain(mismatched)
+        .chain(off_tick)
+        .chain(over_cap)
+        .collect();
+    Some((sales, outcomes))
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn explained_records_every_outcome() {
+        let bids: Bids = vec![
+            bid![30, 1],
+            bid![25, 3].with_all_or_nothing(true),
+            bid![20, 3],
+            bid![15, 1],
+            bid![5, 1],
+        ];
+        let auction = AuctionBuilder::new().lots(3).reserve_price(10).build();
+        let (sales, outcomes) = auction.resolve_explained(bids.clone());
+        let outcome = |index: usize| {
+            outcomes
+                .iter()
+                .find(|outcome| outcome.bid_id() == bids[index].id)
+                .unwrap()
+                .outcome()
+        };
+        assert_eq!(sales.len(), 2);
+        assert_eq!(
+            outcome(0),
+            Outcome::Won {
+                quantity: 1,
+                price: 20
+            }
+        );
+        assert_eq!(outcome(1), Outcome::SkippedNoLots);
+        assert_eq!(
+            outcome(2),
+            Outcome::PartiallyFilled {
+                requested: 3,
+                filled: 2
+            }
+        );
+        assert_eq!(outcome(3), Outcome::LostBelowClearing);
+        assert_eq!(outcome(4), Outcome::BelowReserve);
+    }
+
+    #[test]
+    fn explained_bid_below_next_reserve_tier() {
+        let bids: Bids = vec![bid![150, 1], bid![110, 1]];
+        let auction = AuctionBuilder::new()
+            .lots(2)
+            .reserve_schedule(vec![(1, 100), (1, 120)])
+            .build();
+        let (_, outcomes) = auction.resolve_explained(bids);
+        assert_eq!(outcomes[1].outcome(), Outcome::BelowReserve);
+    }
+
+    #[test]
+    fn explained_outcomes_follow_ranking() {
+        let bids: Bids = vec![bid![10, 1], bid![30, 1], bid![20, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(1)
+            .build();
+        let (_, outcomes) = auction.resolve_explained(bids.clone());
+        let order: Vec<_> = outcomes.iter().map(BidOutcome::bid_id).collect();
+        assert_eq!(order, vec![bids[1].id, bids[2].id, bids[0].id]);
+    }
+
+    #[test]
+    fn explained_prices_follow_every_sales_ordering() {
+        let bids: Bids = vec![bid![10, 1], bid![30, 2], bid![20, 3]];
+        for ordering in [
+            SalesOrdering::Ranked,
+            SalesOrdering::PriceDesc,
+            SalesOrdering::BidderId,
+            SalesOrdering::QuantityDesc,
+            SalesOrdering::InputBidOrder,
+        ] {
+            let auction = AuctionBuilder::new()
+                .strategy(AuctionStrategy::MultiPrice)
+                .lots(6)
+                .sales_ordering(ordering)
+                .build();
+            let (sales, outcomes) = auction.resolve_explained(bids.clone());
+            assert_eq!(sales, auction.resolve_bids(bids.clone()));
+            for bid in &bids {
+                let outcome = outcomes
+                    .iter()
+                    .find(|outcome| outcome.bid_id() == bid.id)
+                    .unwrap();
+                assert_eq!(
+                    outcome.outcome(),
+                    Outcome::Won {
+                        quantity: bid.quantity,
+                        price: bid.amount
+                    }
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn explained_sales_match_plain_resolution() {
+        for seed in 0..50 {
+            let mut bids = generators::large_quantities(seed, 200, 49, 4);
+            for (index, bid) in bids.iter_mut().enumerate() {
+                if index % 6 == 0 {
+                    *bid = bid.with_all_or_nothing(true);
+                }
+            }
+            for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+                let auction = AuctionBuilder::new()
+                    .strategy(strategy)
+                    .lots(seed as usize * 3 + 1)
+                    .reserve_price(10)
+                    .build();
+                let (sales, outcomes) = auction.resolve_explained(bids.clone());
+                assert_eq!(sales, auction.resolve_bids(bids.clone()));
+                assert_eq!(outcomes.len(), bids.len());
+            }
+        }
+    }
+
+    #[test]
+    fn explained_sales_match_plain_resolution_in_a_currency() {
+        for seed in 0..20 {
+            let usd: Bids = generators::large_quantities(seed, 100, 49, 4)
+                .into_iter()
+                .map(|bid| bid.with_currency(Currency::USD))
+                .collect();
+            let mut mixed = usd.clone();
+            for (index, bid) in mixed.iter_mut().enumerate() {
+                match index % 7 {
+                    0 => *bid = bid.with_currency(Currency::EUR),
+                    3 => {
+                        *bid = Bid {
+                            currency: None,
+                            ..*bid
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            for mismatch in [CurrencyMismatch::Filter, CurrencyMismatch::Reject] {
+                for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+                    let auction = AuctionBuilder::new()
+                        .strategy(strategy)
+                        .lots(seed as usize * 3 + 1)
+                        .reserve_price(10)
+                        .currency(Currency::USD)
+                        .currency_mismatch(mismatch)
+                        .build();
+                    for bids in [&usd, &mixed] {
+                        let (sales, outcomes) = auction.resolve_explained(bids.clone());
+                        assert_eq!(sales, auction.resolve_bids(bids.clone()));
+                        assert_eq!(outcomes.len(), bids.len());
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn explained_currency_mismatches() {
+        let bids: Bids = vec![
+            bid![30, 1].with_currency(Currency::USD),
+            bid![20, 1],
+            bid![25, 1].with_currency(Currency::EUR),
+        ];
+        let auction = |mismatch| {
+            AuctionBuilder::new()
+                .lots(2)
+                .currency(Currency::USD)
+                .currency_mismatch(mismatch)
+                .build()
+        };
+        let (sales, outcomes) = auction(CurrencyMismatch::Filter).resolve_explained(bids.clone());
+        assert_eq!(
+            sales,
+            vec![Sale::new(bids[0].bidder_id, 30, 1)
+                .with_bid(&bids[0])
+                .with_currency(Currency::USD)]
+        );
+        let outcomes: Vec<Outcome> = outcomes.iter().map(BidOutcome::outcome).collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                Outcome::Won {
+                    quantity: 1,
+                    price: 30
+                },
+                Outcome::CurrencyMismatch,
+                Outcome::CurrencyMismatch,
+            ]
+        );
+
+        let resolution = auction(CurrencyMismatch::Reject).resolve_bids_full(bids, 0);
+        assert!(resolution.sales().is_empty());
+        assert!(resolution
+            .rejected()
+            .iter()
+            .all(|rejected| rejected.reason() == RejectReason::CurrencyMismatch));
+        assert_eq!(resolution.rejected().len(), 3);
+    }
+
+    fn assert_partitioned(bids: &[Bid], resolution: &FullResolution) {
+        let mut seen: Vec<_> = resolution
+            .winners()
+            .iter()
+            .map(Bid::id)
+            .chain(
+                resolution
+                    .rejected()
+                    .iter()
+                    .map(|rejected| rejected.bid().id),
+            )
+            .collect();
+        let mut expected: Vec<_> = bids.iter().map(Bid::id).collect();
+        seen.sort();
+        expected.sort();
+        assert_eq!(seen, expected);
+    }
+
+    #[test]
+    fn full_resolution_gives_reject_reasons() {
+        let bids: Bids = vec![
+            bid![30, 1],
+            bid![25, 3].with_all_or_nothing(true),
+            bid![20, 3],
+            bid![15, 1],
+            bid![5, 1],
+            bid![40, 1].with_expires_at(100),
+        ];
+        let auction = AuctionBuilder::new().lots(3).reserve_price(10).build();
+        let resolution = auction.resolve_bids_full(bids.clone(), 100);
+        let winners: Vec<_> = resolution.winners().iter().map(Bid::id).collect();
+        assert_eq!(winners, vec![bids[0].id, bids[2].id]);
+        let rejected: Vec<_> = resolution
+            .rejected()
+            .iter()
+            .map(|rejected| (rejected.bid().id, rejected.reason()))
+            .collect();
+        assert_eq!(
+            rejected,
+            vec![
+                (bids[1].id, RejectReason::NoLotsRemaining),
+                (bids[3].id, RejectReason::OutbidAtClearing),
+                (bids[4].id, RejectReason::BelowReserve),
+                (bids[5].id, RejectReason::Expired),
+            ]
+        );
+        assert_eq!(
+            resolution.sales(),
+            &auction.resolve_bids(bids[..5].to_vec())
+        );
+    }
+
+    #[test]
+    fn full_resolution_reports_late_bids() {
+        let on_time = bid![20, 1].with_submitted_at(100);
+        let late = bid![30, 1].with_submitted_at(101).with_expires_at(101);
+        let auction = AuctionBuilder::new().lots(2).closes_at(100).build();
+        let resolution = auction.resolve_bids_full(vec![on_time, late], 200);
+        assert_eq!(resolution.winners(), &vec![on_time]);
+        assert_eq!(resolution.rejected()[0].reason(), RejectReason::Late);
+        assert_eq!(resolution.sales().len(), 1);
+    }
+
+    #[test]
+    fn full_resolution_partitions_random_bids() {
+        for seed in 0..50 {
+            let mut bids = generators::large_quantities(seed, 100, 49, 4);
+            for (index, bid) in bids.iter_mut().enumerate() {
+                if index % 5 == 0 {
+                    *bid = bid.with_expires_at(seed);
+                }
+            }
+            for strategy in [
+                AuctionStrategy::SinglePrice,
+                AuctionStrategy::MultiPrice,
+                AuctionStrategy::AllPay,
+                AuctionStrategy::GeneralizedSecondPrice,
+            ] {
+                let auction = AuctionBuilder::new()
+                    .strategy(strategy)
+                    .lots(seed as usize * 2 + 1)
+                    .reserve_price(10)
+                    .build();
+                let resolution = auction.resolve_bids_full(bids.clone(), 25);
+                assert_partitioned(&bids, &resolution);
+            }
+        }
+    }
+
+    #[test]
+    fn explained_other_strategies_have_no_outcomes() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .build();
+        let bids: Bids = vec![bid![10, 1], bid![5, 1]];
+        let (sales, outcomes) = auction.resolve_explained(bids.clone());
+        assert_eq!(sales, auction.resolve_bids(bids));
+        assert!(outcomes.is_empty());
+    }
+}
@@ -0,0 +1,313 @@
+//! Module containing the seller's fees and what they leave the seller with.
+use alloc::vec::Vec;
+
+use crate::{invoice, Invoice, Sale};
+
+/// The basis points in a whole.
+const BASIS_POINTS: i128 = 10_000;
+
+/// The fees charged to the seller on each sale.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeSchedule {
+    /// The commission on each sale's total, in basis points.
+    commission_bps: i64,
+    /// The flat fee per unit sold, in cents.
+    per_lot_fee: i64,
+    /// The least fee charged on any one sale, in cents.
+    minimum_fee: i64,
+    /// The premium buyers pay on top of their sales, in basis points.
+    #[cfg_attr(feature = "serde", serde(default))]
+    buyers_premium_bps: i64,
+}
+
+impl FeeSchedule {
+    /// Creates a schedule charging no fees.
+    pub const fn new() -> Self {
+        Self {
+            commission_bps: 0,
+            per_lot_fee: 0,
+            minimum_fee: 0,
+            buyers_premium_bps: 0,
+        }
+    }
+
+    /// Set the commission on each sale's total, in basis points.
+    pub const fn with_commission_bps(mut self, commission_bps: i64) -> Self {
+        self.commission_bps = commission_bps;
+        self
+    }
+
+    /// Set the flat fee per unit sold, in cents.
+    pub const fn with_per_lot_fee(mut self, per_lot_fee: i64) -> Self {
+        self.per_lot_fee = per_lot_fee;
+        self
+    }
+
+    /// Set the least fee charged on any one sale, in cents.
+    pub const fn with_minimum_fee(mut self, minimum_fee: i64) -> Self {
+        self.minimum_fee = minimum_fee;
+        self
+    }
+
+    /// Set the premium buyers pay on top of their sales, in basis points.
+    pub const fn with_buyers_premium_bps(mut self, buyers_premium_bps: i64) -> Self {
+        self.buyers_premium_bps = buyers_premium_bps;
+        self
+    }
+
+    /// The commission on each sale's total, in basis points.
+    pub const fn commission_bps(&self) -> i64 {
+        self.commission_bps
+    }
+
+    /// The flat fee per unit sold, in cents.
+    pub const fn per_lot_fee(&self) -> i64 {
+        self.per_lot_fee
+    }
+
+    /// The least fee charged on any one sale, in cents.
+    pub const fn minimum_fee(&self) -> i64 {
+        self.minimum_fee
+    }
+
+    /// The premium buyers pay on top of their sales, in basis points.
+    pub const fn buyers_premium_bps(&self) -> i64 {
+        self.buyers_premium_bps
+    }
+
+    /// The fees on one sale, None if any total overflows.
+    ///
+    /// The commission is rounded half up to the cent. When the commission
+    /// and lot fees come to less than the minimum fee the difference is added
+    /// as a top up. The fees may come to more than the sale's total. Totals
+    /// are `i128` like [`Sale::total`], while the schedule's rates stay `i64`.
+    pub fn fees_for(&self, sale: &Sale) -> Option<SaleFees> {
+        let gross = sale.total();
+        let commission = basis_points_of(gross, self.commission_bps)?;
+        let lot_fees = i128::from(self.per_lot_fee) * sale.quantity as i128;
+        let charged = commission.checked_add(lot_fees)?;
+        let minimum_top_up = (i128::from(self.minimum_fee) - charged).max(0);
+        let total_fees = charged.checked_add(minimum_top_up)?;
+        Some(SaleFees {
+            sale: *sale,
+            gross,
+            commission,
+            lot_fees,
+            minimum_top_up,
+            total_fees,
+            net: gross.checked_sub(total_fees)?,
+        })
+    }
+}
+
+/// The share of the amount given in basis points, rounded half up to the
+/// cent, None if it overflows an `i128`.
+pub fn basis_points_of(amount: i128, basis_points: i64) -> Option<i128> {
+    let share = amount.checked_mul(i128::from(basis_points))?;
+    Some(
+        share
+            .checked_add(BASIS_POINTS / 2)?
+            .div_euclid(BASIS_POINTS),
+    )
+}
+
+/// The fees charged on one sale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SaleFees {
+    sale: Sale,
+    gross: i128,
+    commission: i128,
+    lot_fees: i128,
+    minimum_top_up: i128,
+    total_fees: i128,
+    net: i128,
+}
+
+impl SaleFees {
+    /// The sale the fees are charged on.
+    pub const fn sale(&self) -> &Sale {
+        &self.sale
+    }
+
+    /// The sale's total in cents.
+    pub const fn gross(&self) -> i128 {
+        self.gross
+    }
+
+    /// The commission in cents, rounded half up.
+    pub const fn commission(&self) -> i128 {
+        self.commission
+    }
+
+    /// The flat fees for the units sold in cents.
+    pub const fn lot_fees(&self) -> i128 {
+        self.lot_fees
+    }
+
+    /// What was added to bring the fees up to the minimum fee, in cents.
+    pub const fn minimum_top_up(&self) -> i128 {
+        self.minimum_top_up
+    }
+
+    /// Every fee on the sale in cents.
+    pub const fn total_fees(&self) -> i128 {
+        self.total_fees
+    }
+
+    /// What the seller receives from the sale in cents.
+    pub const fn net(&self) -> i128 {
+        self.net
+    }
+}
+
+/// What the seller receives from a set of sales once fees are taken.
+///
+/// The totals are accumulated in `i128`, so settling sales whose revenue is
+/// beyond `i64` succeeds.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settlement {
+    gross_revenue: i128,
+    total_fees: i128,
+    net_proceeds: i128,
+    sales: Vec<SaleFees>,
+    buyers_premium_bps: i64,
+}
+
+impl Settlement {
+    /// The total charged across the sales in cents.
+    pub const fn gross_revenue(&self) -> i128 {
+        self.gross_revenue
+    }
+
+    /// The fees across the sales in cents.
+    pub const fn total_fees(&self) -> i128 {
+        self.total_fees
+    }
+
+    /// What the seller receives in cents.
+    pub const fn net_proceeds(&self) -> i128 {
+        self.net_proceeds
+    }
+
+    /// The fees on each sale, in the order of the sales.
+    pub fn sales(&self) -> &[SaleFees] {
+        &self.sales
+    }
+
+    /// One invoice per bidder, ordered by bidder id, None if a buyer's
+    /// premium or invoice total overflows.
+    pub fn invoices(&self) -> Option<Vec<Invoice>> {
+        invoice::invoices(&self.sales, self.buyers_premium_bps)
+    }
+}
+
+/// Settles sales against a fee schedule, available on
+/// [`Sales`](crate::Sales) and any slice of sales.
+pub trait SalesSettlement {
+    /// What the seller receives once the fees are taken, None if any total
+    /// overflows.
+    fn settle(&self, fees: &FeeSchedule) -> Option<Settlement>;
+}
+
+impl SalesSettlement for [Sale] {
+    fn settle(&self, fees: &FeeSchedule) -> Option<Settlement> {
+        let mut settlement = Settlement {
+            buyers_premium_bps: fees.buyers_premium_bps,
+            ..Settlement::default()
+        };
+        for sale in self {
+            let sale_fees = fees.fees_for(sale)?;
+            settlement.gross_revenue = settlement.gross_revenue.checked_add(sale_fees.gross)?;
+            settlement.total_fees = settlement.total_fees.checked_add(sale_fees.total_fees)?;
+            settlement.net_proceeds = settlement.net_proceeds.checked_add(sale_fees.net)?;
+            settlement.sales.push(sale_fees);
+        }
+        Some(settlement)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::*;
+
+    #[test]
+    fn fees_round_commission_half_up() {
+        let fees = FeeSchedule::new().with_commission_bps(250);
+        let commission = |amount| {
+            fees.fees_for(&Sale::new(Uuid::new_v4(), amount, 1))
+                .unwrap()
+                .commission()
+        };
+        assert_eq!(commission(1_999), 50);
+        assert_eq!(commission(1_980), 50);
+        assert_eq!(commission(1_940), 49);
+        assert_eq!(commission(1_939), 48);
+        assert_eq!(commission(19), 0);
+        assert_eq!(commission(20), 1);
+    }
+
+    #[test]
+    fn fees_settle_sales() {
+        let fees = FeeSchedule::new()
+            .with_commission_bps(250)
+            .with_per_lot_fee(15)
+            .with_minimum_fee(100);
+        let sales: Sales = vec![
+            Sale::new(Uuid::new_v4(), 3_333, 3),
+            Sale::new(Uuid::new_v4(), 999, 1),
+        ];
+        let settlement = sales.settle(&fees).unwrap();
+        let first = settlement.sales()[0];
+        assert_eq!(first.gross(), 9_999);
+        assert_eq!(first.commission(), 250);
+        assert_eq!(first.lot_fees(), 45);
+        assert_eq!(first.minimum_top_up(), 0);
+        assert_eq!(first.net(), 9_704);
+        let second = settlement.sales()[1];
+        assert_eq!(second.commission(), 25);
+        assert_eq!(second.lot_fees(), 15);
+        assert_eq!(second.minimum_top_up(), 60);
+        assert_eq!(second.net(), 899);
+        assert_eq!(settlement.gross_revenue(), 10_998);
+        assert_eq!(settlement.total_fees(), 395);
+        assert_eq!(settlement.net_proceeds(), 10_603);
+    }
+
+    #[test]
+    fn fees_settle_revenue_beyond_i64() {
+        let fees = FeeSchedule::new()
+            .with_commission_bps(100)
+            .with_per_lot_fee(i64::MAX);
+        let sales: Sales = vec![
+            Sale::new(Uuid::new_v4(), i64::MAX, 2),
+            Sale::new(Uuid::new_v4(), i64::MAX, 1),
+        ];
+        let settlement = sales.settle(&fees).unwrap();
+        let max = i128::from(i64::MAX);
+        assert_eq!(settlement.gross_revenue(), max * 3);
+        assert_eq!(settlement.sales()[0].lot_fees(), max * 2);
+        let commission = (max * 2 + 50) / 100 + (max + 50) / 100;
+        assert_eq!(settlement.total_fees(), commission + max * 3);
+        assert_eq!(settlement.net_proceeds(), -commission);
+    }
+
+    #[test]
+    fn fees_overflow_is_none() {
+        let fees = FeeSchedule::new().with_commission_bps(i64::MAX);
+        let sales: Sales = vec![Sale::new(Uuid::new_v4(), i64::MAX, usize::MAX)];
+        assert_eq!(sales.settle(&fees), None);
+    }
+
+    #[test]
+    fn fees_of_no_sales_are_zero() {
+        let sales: Sales = Vec::new();
+        let settlement = sales.settle(&FeeSchedule::new()).unwrap();
+        assert_eq!(settlement.net_proceeds(), 0);
+        assert!(settlement.sales().is_empty());
+    }
+}
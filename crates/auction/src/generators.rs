@@ -0,0 +1,90 @@
+//! Module of synthetic bid generators for benchmarks, tests and fuzzing.
+//!
+//! Every generator is seeded, so the same seed always gives the same bids.
+pub use crate::rng::BidRng;
+use crate::{Bid, Bids};
+
+/// Bids for one unit with amounts spread evenly from zero to the maximum.
+pub fn uniform(seed: u64, count: usize, max_amount: i64) -> Bids {
+    let mut rng = BidRng::new(seed);
+    (0..count)
+        .map(|_| Bid::new(random_amount(&mut rng, max_amount), 1))
+        .collect()
+}
+
+/// Bids for one unit whose amounts follow a power law, most are small and few
+/// approach the maximum. Larger exponents skew the amounts lower.
+pub fn power_law(seed: u64, count: usize, max_amount: i64, exponent: f64) -> Bids {
+    let mut rng = BidRng::new(seed);
+    (0..count)
+        .map(|_| {
+            let amount = (rng.unit().powf(exponent) * max_amount as f64) as i64;
+            Bid::new(amount, 1)
+        })
+        .collect()
+}
+
+/// Bids for one unit sharing only a few distinct amounts, so most bids tie.
+pub fn heavy_ties(seed: u64, count: usize, levels: usize, max_amount: i64) -> Bids {
+    let mut rng = BidRng::new(seed);
+    let step = max_amount / levels.max(1) as i64;
+    (0..count)
+        .map(|_| {
+            let level = rng.up_to(levels.saturating_sub(1) as u64) as i64;
+            Bid::new(level * step, 1)
+        })
+        .collect()
+}
+
+/// Bids with amounts spread evenly up to the maximum, each for between one
+/// and the maximum quantity of units.
+pub fn large_quantities(seed: u64, count: usize, max_amount: i64, max_quantity: usize) -> Bids {
+    let mut rng = BidRng::new(seed);
+    (0..count)
+        .map(|_| {
+            let amount = random_amount(&mut rng, max_amount);
+            let quantity = rng.up_to(max_quantity.saturating_sub(1) as u64) as usize + 1;
+            Bid::new(amount, quantity)
+        })
+        .collect()
+}
+
+fn random_amount(rng: &mut BidRng, max_amount: i64) -> i64 {
+    rng.up_to(max_amount.max(0) as u64) as i64
+}
+
+#[cfg(test)]
+mod test {
+    use crate::generators::*;
+
+    #[test]
+    fn generators_are_deterministic() {
+        let first: Vec<i64> = uniform(7, 50, 100).iter().map(|bid| bid.amount()).collect();
+        let again: Vec<i64> = uniform(7, 50, 100).iter().map(|bid| bid.amount()).collect();
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn generators_stay_in_bounds() {
+        assert!(uniform(1, 1000, 100)
+            .iter()
+            .all(|bid| (0..=100).contains(&bid.amount()) && bid.quantity() == 1));
+        assert!(power_law(1, 1000, 100, 3.0)
+            .iter()
+            .all(|bid| (0..=100).contains(&bid.amount())));
+        assert!(large_quantities(1, 1000, 100, 50)
+            .iter()
+            .all(|bid| (1..=50).contains(&bid.quantity())));
+    }
+
+    #[test]
+    fn heavy_ties_uses_few_levels() {
+        let mut amounts: Vec<i64> = heavy_ties(3, 1000, 4, 100)
+            .iter()
+            .map(|bid| bid.amount())
+            .collect();
+        amounts.sort_unstable();
+        amounts.dedup();
+        assert_eq!(amounts, vec![0, 25, 50, 75]);
+    }
+}
@@ -0,0 +1,199 @@
+//! Module containing the invoices sent to each winning bidder.
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+use uuid::Uuid;
+
+use crate::{fees::basis_points_of, SaleFees};
+
+/// One fill on an invoice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InvoiceLine {
+    quantity: usize,
+    unit_price: i64,
+    line_total: i128,
+}
+
+impl InvoiceLine {
+    /// The units filled, zero for a losing all-pay bid.
+    pub const fn quantity(&self) -> usize {
+        self.quantity
+    }
+
+    /// The price of each unit in cents.
+    pub const fn unit_price(&self) -> i64 {
+        self.unit_price
+    }
+
+    /// The total owed for the fill in cents.
+    pub const fn line_total(&self) -> i128 {
+        self.line_total
+    }
+}
+
+/// What one bidder owes across all of their sales.
+///
+/// Totals are `i128`, like [`Sale::total`](crate::Sale::total).
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Invoice {
+    bidder_id: Uuid,
+    /// The bidder's fills, in the order of the sales.
+    lines: Vec<InvoiceLine>,
+    subtotal: i128,
+    /// None when no buyer's premium is configured.
+    buyers_premium: Option<i128>,
+    total: i128,
+}
+
+impl Invoice {
+    /// The bidder the invoice is for.
+    pub const fn bidder_id(&self) -> Uuid {
+        self.bidder_id
+    }
+
+    /// The bidder's fills, in the order of the sales.
+    pub fn lines(&self) -> &[InvoiceLine] {
+        &self.lines
+    }
+
+    /// The sum of the line totals in cents.
+    pub const fn subtotal(&self) -> i128 {
+        self.subtotal
+    }
+
+    /// The buyer's premium on the subtotal in cents, rounded half up. None
+    /// when no buyer's premium is configured.
+    pub const fn buyers_premium(&self) -> Option<i128> {
+        self.buyers_premium
+    }
+
+    /// The subtotal plus any buyer's premium in cents.
+    pub const fn total(&self) -> i128 {
+        self.total
+    }
+}
+
+impl fmt::Display for Invoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Invoice for bidder {}", self.bidder_id)?;
+        for line in &self.lines {
+            writeln!(
+                f,
+                "  {} x {} = {}",
+                line.quantity,
+                Cents(line.unit_price.into()),
+                Cents(line.line_total)
+            )?;
+        }
+        writeln!(f, "Subtotal: {}", Cents(self.subtotal))?;
+        if let Some(premium) = self.buyers_premium {
+            writeln!(f, "Buyer's premium: {}", Cents(premium))?;
+        }
+        write!(f, "Total: {}", Cents(self.total))
+    }
+}
+
+/// Displays an amount in cents as whole units and two decimal places.
+pub struct Cents(pub i128);
+
+impl fmt::Display for Cents {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let cents = self.0.unsigned_abs();
+        write!(f, "{sign}{}.{:02}", cents / 100, cents % 100)
+    }
+}
+
+/// Gathers each bidder's sales into an invoice, ordered by bidder id.
+pub fn invoices(sales: &[SaleFees], buyers_premium_bps: i64) -> Option<Vec<Invoice>> {
+    let mut lines: BTreeMap<Uuid, Vec<InvoiceLine>> = BTreeMap::new();
+    for sale_fees in sales {
+        let sale = sale_fees.sale();
+        lines.entry(sale.bidder_id).or_default().push(InvoiceLine {
+            quantity: sale.quantity,
+            unit_price: sale.amount,
+            line_total: sale_fees.gross(),
+        });
+    }
+    lines
+        .into_iter()
+        .map(|(bidder_id, lines)| {
+            let subtotal = lines
+                .iter()
+                .try_fold(0_i128, |total, line| total.checked_add(line.line_total))?;
+            let buyers_premium = match buyers_premium_bps {
+                0 => None,
+                bps => Some(basis_points_of(subtotal, bps)?),
+            };
+            Some(Invoice {
+                bidder_id,
+                lines,
+                subtotal,
+                buyers_premium,
+                total: subtotal.checked_add(buyers_premium.unwrap_or_default())?,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::*;
+
+    #[test]
+    fn invoice_collapses_bidder_fills() {
+        let (bidder, other) = (Uuid::from_u128(2), Uuid::from_u128(1));
+        let sales: Sales = vec![
+            Sale::new(bidder, 1_250, 2),
+            Sale::new(other, 999, 1),
+            Sale::new(bidder, 1_250, 1),
+            Sale::new(bidder, 1_005, 3),
+        ];
+        let fees = FeeSchedule::new().with_buyers_premium_bps(1_250);
+        let invoices = sales.settle(&fees).unwrap().invoices().unwrap();
+        assert_eq!(invoices.len(), 2);
+        assert_eq!(invoices[0].bidder_id(), other);
+        let invoice = &invoices[1];
+        assert_eq!(invoice.bidder_id(), bidder);
+        let lines: Vec<(usize, i64, i128)> = invoice
+            .lines()
+            .iter()
+            .map(|line| (line.quantity(), line.unit_price(), line.line_total()))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![(2, 1_250, 2_500), (1, 1_250, 1_250), (3, 1_005, 3_015)]
+        );
+        assert_eq!(invoice.subtotal(), 6_765);
+        assert_eq!(invoice.buyers_premium(), Some(846));
+        assert_eq!(invoice.total(), 7_611);
+        assert_eq!(
+            invoice.to_string(),
+            format!(
+                "Invoice for bidder {bidder}\n  2 x 12.50 = 25.00\n  1 x 12.50 = 12.50\n  \
+                 3 x 10.05 = 30.15\nSubtotal: 67.65\nBuyer's premium: 8.46\nTotal: 76.11"
+            )
+        );
+    }
+
+    #[test]
+    fn invoice_without_premium_omits_it() {
+        let bidder = Uuid::from_u128(7);
+        let sales: Sales = vec![Sale::new(bidder, 5, 1)];
+        let invoices = sales
+            .settle(&FeeSchedule::new())
+            .unwrap()
+            .invoices()
+            .unwrap();
+        assert_eq!(invoices[0].buyers_premium(), None);
+        assert_eq!(invoices[0].total(), 5);
+        assert_eq!(
+            invoices[0].to_string(),
+            format!("Invoice for bidder {bidder}\n  1 x 0.05 = 0.05\nSubtotal: 0.05\nTotal: 0.05")
+        );
+    }
+}
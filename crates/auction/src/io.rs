@@ -0,0 +1,386 @@
+//! Module for importing bids and exporting sales as CSV, or as JSON Lines with
+//! the `json` feature.
+//!
+//! Fields are separated by commas and surrounding whitespace is ignored, quoted
+//! fields are not supported. The first row is a header naming the columns.
+//!
+//! ```
+//! use auction::{io, AuctionBuilder};
+//!
+//! let csv = "bidder_id,amount,quantity\n\
+//!            67e55044-10b1-426f-9247-bb680e5fe0c8,150,2\n\
+//!            a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8,120,1\n";
+//! let bids = io::read_bids_csv(csv.as_bytes()).unwrap();
+//!
+//! let auction = AuctionBuilder::new().lots(2).build();
+//! let sales = auction.resolve_bids(bids);
+//!
+//! let mut out = Vec::new();
+//! io::write_sales_csv(&mut out, &sales).unwrap();
+//! assert_eq!(
+//!     String::from_utf8(out).unwrap(),
+//!     "bidder_id,amount,quantity\n\
+//!      67e55044-10b1-426f-9247-bb680e5fe0c8,150,2\n"
+//! );
+//! ```
+//!
+//! JSON Lines are read one bid at a time with `BidStream`, so the whole input is
+//! never held in memory.
+use std::io::{BufRead, BufReader, Read, Write};
+
+use uuid::Uuid;
+
+use crate::{parse, Bid, BidParseError, BidParseErrorKind, Bids, Sales};
+
+/// The positions of the known columns in the header.
+struct Columns {
+    bid_id: Option<usize>,
+    bidder_id: usize,
+    amount: usize,
+    quantity: usize,
+}
+
+impl Columns {
+    fn from_header(header: &str) -> Result<Self, BidParseError> {
+        let names: Vec<&str> = header.split(',').map(str::trim).collect();
+        let find = |name: &'static str| {
+            names
+                .iter()
+                .position(|column| *column == name)
+                .ok_or_else(|| BidParseError::new(1, None, BidParseErrorKind::MissingColumn(name)))
+        };
+        Ok(Self {
+            bid_id: find("bid_id").ok(),
+            bidder_id: find("bidder_id")?,
+            amount: find("amount")?,
+            quantity: find("quantity")?,
+        })
+    }
+}
+
+/// Reads bids from CSV with `bidder_id`, `amount` and `quantity` columns.
+///
+/// An optional `bid_id` column sets each bid's id, otherwise a new one is made.
+/// Blank lines are skipped and an empty input has no bids.
+///
+/// # Arguments
+/// * `reader` - The CSV to read.
+///
+/// # Returns
+/// The bids, or the line and column of the first invalid field.
+///
+pub fn read_bids_csv(reader: impl Read) -> Result<Bids, BidParseError> {
+    let lines = BufReader::new(reader).lines().enumerate();
+    let mut columns = None;
+    let mut bids = Vec::new();
+    for (index, line) in lines {
+        let number = index + 1;
+        let line = line.map_err(|error| {
+            BidParseError::new(number, None, BidParseErrorKind::Io(error.to_string()))
+        })?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match &columns {
+            None => columns = Some(Columns::from_header(&line)?),
+            Some(columns) => bids.push(parse_row(columns, number, &line)?),
+        }
+    }
+    Ok(bids)
+}
+
+fn parse_row(columns: &Columns, line: usize, row: &str) -> Result<Bid, BidParseError> {
+    let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+    let field = |index: usize, name: &'static str| match fields.get(index) {
+        Some(value) if !value.is_empty() => Ok(*value),
+        _ => Err(BidParseError::new(
+            line,
+            Some(index + 1),
+            BidParseErrorKind::MissingField(name),
+        )),
+    };
+    let error = |index: usize| move |kind| BidParseError::new(line, Some(index + 1), kind);
+
+    let bidder_id =
+        parse::id(field(columns.bidder_id, "bidder_id")?).map_err(error(columns.bidder_id))?;
+    let amount = parse::amount(field(columns.amount, "amount")?).map_err(error(columns.amount))?;
+    let quantity =
+        parse::quantity(field(columns.quantity, "quantity")?).map_err(error(columns.quantity))?;
+
+    let mut bid = Bid::new(amount, quantity).with_bidder_id(bidder_id);
+    if let Some(index) = columns.bid_id {
+        bid.id = parse::id(field(index, "bid_id")?).map_err(error(index))?;
+    }
+    Ok(bid)
+}
+
+/// Writes sales as CSV with `bidder_id`, `amount` and `quantity` columns.
+///
+/// # Arguments
+/// * `writer` - Where to write the CSV.
+/// * `sales` - The sales to write.
+///
+pub fn write_sales_csv(mut writer: impl Write, sales: &Sales) -> std::io::Result<()> {
+    writeln!(writer, "bidder_id,amount,quantity")?;
+    for sale in sales {
+        writeln!(
+            writer,
+            "{},{},{}",
+            sale.bidder_id, sale.amount, sale.quantity
+        )?;
+    }
+    Ok(())
+}
+
+/// Iterator over bids read from JSON Lines, one bid object per line.
+///
+/// Blank lines are skipped. Each invalid line yields an error carrying its line
+/// number and reading carries on with the next line, so rejects can be counted.
+#[cfg(feature = "json")]
+#[derive(Debug)]
+pub struct BidStream<R> {
+    reader: R,
+    line: usize,
+    buffer: String,
+    done: bool,
+}
+
+#[cfg(feature = "json")]
+impl<R: BufRead> BidStream<R> {
+    /// Streams bids from the given reader.
+    pub const fn from_reader(reader: R) -> Self {
+        Self {
+            reader,
+            line: 0,
+            buffer: String::new(),
+            done: false,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl<R: BufRead> Iterator for BidStream<R> {
+    type Item = Result<Bid, BidParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buffer.clear();
+            self.line += 1;
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => self.done = true,
+                Ok(_) if self.buffer.trim().is_empty() => continue,
+                Ok(_) => return Some(parse_json_line(self.line, &self.buffer)),
+                Err(error) => {
+                    self.done = true;
+                    let kind = BidParseErrorKind::Io(error.to_string());
+                    return Some(Err(BidParseError::new(self.line, None, kind)));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(feature = "json")]
+fn parse_json_line(line: usize, json: &str) -> Result<Bid, BidParseError> {
+    let bid: Bid = serde_json::from_str(json).map_err(|error| {
+        let kind = BidParseErrorKind::InvalidJson(error.to_string());
+        BidParseError::new(line, Some(error.column()), kind)
+    })?;
+    if bid.amount < 0 {
+        let kind = BidParseErrorKind::NegativeAmount(bid.amount);
+        return Err(BidParseError::new(line, None, kind));
+    }
+    Ok(bid)
+}
+
+/// Writes sales as JSON Lines, one sale object per line.
+///
+/// # Arguments
+/// * `writer` - Where to write the JSON Lines.
+/// * `sales` - The sales to write.
+///
+#[cfg(feature = "json")]
+pub fn write_sales_json_lines(mut writer: impl Write, sales: &Sales) -> std::io::Result<()> {
+    for sale in sales {
+        serde_json::to_writer(&mut writer, sale)?;
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::*;
+
+    const ALICE: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+    const BOB: &str = "a1a2a3a4-b1b2-c1c2-d1d2-d3d4d5d6d7d8";
+
+    #[test]
+    fn read_bids_csv_well_formed() {
+        let csv = format!("bidder_id,amount,quantity\n{ALICE},150,2\n\n{BOB}, 120 ,1\n");
+        let bids = read_bids_csv(csv.as_bytes()).unwrap();
+        assert_eq!(bids.len(), 2);
+        assert_eq!(bids[0].bidder_id, Uuid::parse_str(ALICE).unwrap());
+        assert_eq!(bids[0].amount, 150);
+        assert_eq!(bids[0].quantity, 2);
+        assert_eq!(bids[1].amount, 120);
+    }
+
+    #[test]
+    fn read_bids_csv_reorders_columns_and_reads_bid_id() {
+        let csv = format!("quantity,amount,bidder_id,bid_id\n3,90,{ALICE},{BOB}\n");
+        let bids = read_bids_csv(csv.as_bytes()).unwrap();
+        assert_eq!(bids[0].id, Uuid::parse_str(BOB).unwrap());
+        assert_eq!(bids[0].bidder_id, Uuid::parse_str(ALICE).unwrap());
+        assert_eq!(bids[0].quantity, 3);
+    }
+
+    #[test]
+    fn read_bids_csv_empty_file() {
+        assert!(read_bids_csv(&b""[..]).unwrap().is_empty());
+        assert!(read_bids_csv(&b"bidder_id,amount,quantity\n"[..])
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn read_bids_csv_malformed_row_in_middle() {
+        let csv = format!("bidder_id,amount,quantity\n{ALICE},150,2\n{BOB},abc,1\n{ALICE},1,1\n");
+        let error = read_bids_csv(csv.as_bytes()).unwrap_err();
+        assert_eq!(error.line(), 3);
+        assert_eq!(error.column(), Some(2));
+        assert_eq!(
+            error.kind(),
+            &BidParseErrorKind::InvalidAmount("abc".into())
+        );
+        assert_eq!(error.to_string(), "line 3, column 2: invalid amount `abc`");
+    }
+
+    #[test]
+    fn read_bids_csv_bad_uuid() {
+        let csv = "bidder_id,amount,quantity\nnot-a-uuid,150,2\n";
+        let error = read_bids_csv(csv.as_bytes()).unwrap_err();
+        assert_eq!(error.line(), 2);
+        assert_eq!(error.column(), Some(1));
+        assert!(matches!(error.kind(), BidParseErrorKind::InvalidUuid(_)));
+    }
+
+    #[test]
+    fn read_bids_csv_negative_amount() {
+        let csv = format!("bidder_id,amount,quantity\n{ALICE},-5,2\n");
+        let error = read_bids_csv(csv.as_bytes()).unwrap_err();
+        assert_eq!(error.kind(), &BidParseErrorKind::NegativeAmount(-5));
+    }
+
+    #[test]
+    fn read_bids_csv_shares_the_bid_parser() {
+        for (amount, quantity) in [("-5", "2"), ("1.5", "2"), ("150", "lots")] {
+            let csv = format!("bidder_id,amount,quantity\n{ALICE},{amount},{quantity}\n");
+            let from_csv = read_bids_csv(csv.as_bytes()).unwrap_err();
+            let from_str = format!("{amount}x{quantity}").parse::<Bid>().unwrap_err();
+            assert_eq!(from_csv.kind(), from_str.kind());
+        }
+        let csv = format!("bid_id,bidder_id,amount,quantity\n{BOB},{BOB},150,3\n");
+        let from_csv = read_bids_csv(csv.as_bytes()).unwrap();
+        let from_str: Bid = format!("{BOB}:150x3").parse().unwrap();
+        assert_eq!(
+            (from_csv[0].id, from_csv[0].bidder_id, from_csv[0].quantity),
+            (from_str.id, from_str.bidder_id, from_str.quantity)
+        );
+    }
+
+    #[test]
+    fn read_bids_csv_missing_field() {
+        let csv = format!("bidder_id,amount,quantity\n{ALICE},150\n");
+        let error = read_bids_csv(csv.as_bytes()).unwrap_err();
+        assert_eq!(error.column(), Some(3));
+        assert_eq!(error.kind(), &BidParseErrorKind::MissingField("quantity"));
+    }
+
+    #[test]
+    fn read_bids_csv_missing_column() {
+        let error = read_bids_csv(&b"bidder_id,amount\n"[..]).unwrap_err();
+        assert_eq!(error.line(), 1);
+        assert_eq!(error.kind(), &BidParseErrorKind::MissingColumn("quantity"));
+    }
+
+    #[test]
+    fn write_sales_csv_stable_columns() {
+        let id = Uuid::parse_str(ALICE).unwrap();
+        let sales = vec![Sale::new(id, 150, 2)];
+        let mut out = Vec::new();
+        write_sales_csv(&mut out, &sales).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!("bidder_id,amount,quantity\n{ALICE},150,2\n")
+        );
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn bid_stream_reports_line_numbers_and_continues() {
+        let json = format!(
+            "{{\"bidder_id\":\"{ALICE}\",\"amount\":150,\"quantity\":2}}\n\n\
+             not json\n\
+             {{\"amount\":-1,\"quantity\":1}}\n\
+             {{\"amount\":90,\"quantity\":1}}\n"
+        );
+        let results: Vec<_> = BidStream::from_reader(json.as_bytes()).collect();
+        assert_eq!(results.len(), 4);
+        let first = results[0].as_ref().unwrap();
+        assert_eq!(first.bidder_id, Uuid::parse_str(ALICE).unwrap());
+        assert_eq!(first.amount, 150);
+        let error = results[1].as_ref().unwrap_err();
+        assert_eq!(error.line(), 3);
+        assert!(matches!(error.kind(), BidParseErrorKind::InvalidJson(_)));
+        let error = results[2].as_ref().unwrap_err();
+        assert_eq!(error.line(), 4);
+        assert_eq!(error.kind(), &BidParseErrorKind::NegativeAmount(-1));
+        assert_eq!(results[3].as_ref().unwrap().amount, 90);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn bid_stream_handles_many_lines() {
+        let mut json = String::new();
+        for i in 0..300_000 {
+            if i % 1000 == 999 {
+                json.push_str("{\"amount\":\"oops\"}\n");
+            } else {
+                json.push_str(&format!("{{\"amount\":{},\"quantity\":1}}\n", i % 500));
+            }
+        }
+        let (mut accepted, mut rejected) = (0, 0);
+        for result in BidStream::from_reader(std::io::Cursor::new(json)) {
+            match result {
+                Ok(_) => accepted += 1,
+                Err(error) => {
+                    assert_eq!(error.line() % 1000, 0);
+                    rejected += 1;
+                }
+            }
+        }
+        assert_eq!(accepted, 299_700);
+        assert_eq!(rejected, 300);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn write_sales_json_lines_one_object_per_sale() {
+        let id = Uuid::parse_str(ALICE).unwrap();
+        let sales = vec![Sale::new(id, 150, 2), Sale::new(id, 120, 1)];
+        let mut out = Vec::new();
+        write_sales_json_lines(&mut out, &sales).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            format!(
+                "{{\"bidder_id\":\"{ALICE}\",\"amount\":150,\"quantity\":2}}\n\
+                 {{\"bidder_id\":\"{ALICE}\",\"amount\":120,\"quantity\":1}}\n"
+            )
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Module containing demand curves bid as a ladder of price steps.
+use alloc::{string::ToString, vec::Vec};
+
+use uuid::Uuid;
+
+use crate::{Bid, BidError, Bids};
+
+/// A bidder's demand curve, as steps of an amount and the further units
+/// wanted at it, best first.
+///
+/// Each step is resolved as its own bid attributed to the bidder, so per
+/// bidder caps and budgets apply across the whole ladder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LadderBid {
+    bidder_id: Uuid,
+    steps: Vec<(i64, usize)>,
+}
+
+impl LadderBid {
+    /// Creates a ladder, rejecting it unless it has steps, each step is a
+    /// valid bid and the amounts strictly decrease.
+    pub fn try_new(bidder_id: Uuid, steps: Vec<(i64, usize)>) -> Result<Self, BidError> {
+        if steps.is_empty() {
+            return Err(BidError::EmptyLadder);
+        }
+        for (step, &(amount, quantity)) in steps.iter().enumerate() {
+            Bid::with_id(Uuid::nil(), amount, quantity).validate()?;
+            if step > 0 && amount >= steps[step - 1].0 {
+                return Err(BidError::NonDecreasingLadder { step });
+            }
+        }
+        Ok(Self { bidder_id, steps })
+    }
+
+    /// The bidder making the ladder.
+    pub const fn bidder_id(&self) -> Uuid {
+        self.bidder_id
+    }
+
+    /// The steps, as pairs of an amount and the units wanted at it.
+    pub fn steps(&self) -> &[(i64, usize)] {
+        &self.steps
+    }
+
+    /// The id of the bid for the given step, derived from the bidder and the
+    /// step so it is the same every time.
+    pub fn step_id(&self, step: usize) -> Uuid {
+        Uuid::new_v5(&self.bidder_id, step.to_string().as_bytes())
+    }
+
+    /// The bids for each step, best first, all attributed to the bidder.
+    pub fn bids(&self) -> Bids {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(step, &(amount, quantity))| {
+                Bid::with_id(self.step_id(step), amount, quantity).with_bidder_id(self.bidder_id)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::*;
+
+    fn ladder(steps: Vec<(i64, usize)>) -> LadderBid {
+        LadderBid::try_new(Uuid::new_v4(), steps).unwrap()
+    }
+
+    #[test]
+    fn ladder_rejects_non_decreasing_amounts() {
+        let bidder = Uuid::new_v4();
+        assert_eq!(
+            LadderBid::try_new(bidder, vec![(120, 5), (100, 10), (100, 20)]),
+            Err(BidError::NonDecreasingLadder { step: 2 })
+        );
+        assert_eq!(
+            LadderBid::try_new(bidder, vec![(100, 5), (120, 10)]),
+            Err(BidError::NonDecreasingLadder { step: 1 })
+        );
+    }
+
+    #[test]
+    fn ladder_rejects_invalid_steps() {
+        let bidder = Uuid::new_v4();
+        assert_eq!(
+            LadderBid::try_new(bidder, Vec::new()),
+            Err(BidError::EmptyLadder)
+        );
+        assert_eq!(
+            LadderBid::try_new(bidder, vec![(120, 5), (100, 0)]),
+            Err(BidError::ZeroQuantity)
+        );
+    }
+
+    #[test]
+    fn ladder_bids_are_attributed_to_bidder() {
+        let ladder = ladder(vec![(120, 5), (100, 10)]);
+        let bids = ladder.bids();
+        assert_eq!(bids.len(), 2);
+        assert!(bids.iter().all(|bid| bid.bidder_id == ladder.bidder_id()));
+        assert_eq!(bids[1].id, ladder.step_id(1));
+        assert_ne!(bids[0].id, bids[1].id);
+        assert_eq!(ladder.bids(), bids);
+    }
+
+    #[test]
+    fn ladder_partially_filled_across_two_steps() {
+        let ladder = ladder(vec![(120, 5), (100, 10), (80, 20)]);
+        let rival = bid![110, 1];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(8)
+            .build();
+        let sales = auction.resolve_ladders(std::slice::from_ref(&ladder), vec![rival]);
+        let steps = ladder.bids();
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(ladder.bidder_id(), 120, 5).with_bid(&steps[0]),
+                Sale::new(rival.id, 110, 1).with_bid(&rival),
+                Sale::new(ladder.bidder_id(), 100, 2).with_bid(&steps[1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn ladder_respects_per_bidder_cap_across_steps() {
+        let ladder = ladder(vec![(120, 5), (100, 5)]);
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(10)
+            .max_lots_per_bidder(6)
+            .build();
+        let sales = auction.resolve_ladders(&[ladder], Vec::new());
+        assert_eq!(sales.total_quantity(), 6);
+    }
+}
@@ -0,0 +1,397 @@
+//! Module containing the double-entry journal of a set of sales.
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use uuid::Uuid;
+
+use crate::{fees::basis_points_of, FeeSchedule, Sale};
+
+/// An account a ledger entry is posted to.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LedgerAccount {
+    /// What a buyer owes, one account per bidder.
+    Buyer(Uuid),
+    /// An account named by the caller, such as the seller's or the fees'.
+    Named(String),
+}
+
+impl fmt::Display for LedgerAccount {
+    /// Writes buyer accounts as `buyer:` and the bidder id, and named accounts
+    /// as their name.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Buyer(bidder_id) => write!(f, "buyer:{bidder_id}"),
+            Self::Named(name) => f.write_str(name),
+        }
+    }
+}
+
+/// Enum representing which side of an account an entry is posted to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EntryDirection {
+    Debit,
+    Credit,
+}
+
+/// One posting of a journal, always for a positive amount.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LedgerEntry {
+    account: LedgerAccount,
+    direction: EntryDirection,
+    amount: i128,
+    /// The index of the sale posted, None for the rounding remainder.
+    sale: Option<usize>,
+    bidder_id: Option<Uuid>,
+    bid_id: Option<Uuid>,
+}
+
+impl LedgerEntry {
+    /// The account posted to.
+    pub const fn account(&self) -> &LedgerAccount {
+        &self.account
+    }
+
+    /// Whether the account is debited or credited.
+    pub const fn direction(&self) -> EntryDirection {
+        self.direction
+    }
+
+    /// The amount posted in cents, never negative.
+    pub const fn amount(&self) -> i128 {
+        self.amount
+    }
+
+    /// The amount with debits positive and credits negative, so a balanced
+    /// journal sums to zero.
+    pub const fn signed_amount(&self) -> i128 {
+        match self.direction {
+            EntryDirection::Debit => self.amount,
+            EntryDirection::Credit => -self.amount,
+        }
+    }
+
+    /// The index of the sale the entry posts among the sales journaled, None
+    /// for the rounding remainder.
+    pub const fn sale(&self) -> Option<usize> {
+        self.sale
+    }
+
+    /// The bidder of the sale the entry posts.
+    pub const fn bidder_id(&self) -> Option<Uuid> {
+        self.bidder_id
+    }
+
+    /// The id of the bid the sale filled, None for sales made without one.
+    pub const fn bid_id(&self) -> Option<Uuid> {
+        self.bid_id
+    }
+}
+
+/// Journals sales as balanced double entries, available on
+/// [`Sales`](crate::Sales) and any slice of sales.
+///
+/// Each sale debits the buyer and credits the seller with its total, then
+/// debits the seller and credits the fee account with its fees, as
+/// [`FeeSchedule::fees_for`] works them out. Entries for zero amounts are left
+/// out, and the buyer's premium is invoiced rather than journaled.
+pub trait SalesLedger {
+    /// The journal of the sales, keeping each sale's rounded commission on the
+    /// fee account. None if any total overflows.
+    fn to_ledger(
+        &self,
+        seller_account: &str,
+        fee_account: &str,
+        schedule: &FeeSchedule,
+    ) -> Option<Vec<LedgerEntry>>;
+
+    /// The journal of the sales, with the fee account credited the
+    /// commission on the sales' combined total.
+    ///
+    /// Rounding each sale's commission half up can charge the seller more or
+    /// less than the commission on the total, and that remainder is posted
+    /// between the fee account and the rounding account so the journal still
+    /// balances. None if any total overflows.
+    fn to_ledger_with_rounding(
+        &self,
+        seller_account: &str,
+        fee_account: &str,
+        rounding_account: &str,
+        schedule: &FeeSchedule,
+    ) -> Option<Vec<LedgerEntry>>;
+}
+
+impl SalesLedger for [Sale] {
+    fn to_ledger(
+        &self,
+        seller_account: &str,
+        fee_account: &str,
+        schedule: &FeeSchedule,
+    ) -> Option<Vec<LedgerEntry>> {
+        self.to_ledger_with_rounding(seller_account, fee_account, fee_account, schedule)
+    }
+
+    fn to_ledger_with_rounding(
+        &self,
+        seller_account: &str,
+        fee_account: &str,
+        rounding_account: &str,
+        schedule: &FeeSchedule,
+    ) -> Option<Vec<LedgerEntry>> {
+        let seller = LedgerAccount::Named(seller_account.to_string());
+        let fees = LedgerAccount::Named(fee_account.to_string());
+        let mut journal = Journal::default();
+        let mut gross: i128 = 0;
+        let mut commission: i128 = 0;
+        for (index, sale) in self.iter().enumerate() {
+            let sale_fees = schedule.fees_for(sale)?;
+            gross = gross.checked_add(sale_fees.gross())?;
+            commission = commission.checked_add(sale_fees.commission())?;
+            let posting = Posting {
+                sale: Some(index),
+                bidder_id: Some(sale.bidder_id),
+                bid_id: sale.bid_id,
+            };
+            journal.post(
+                &LedgerAccount::Buyer(sale.bidder_id),
+                &seller,
+                sale_fees.gross(),
+                posting,
+            )?;
+            journal.post(&seller, &fees, sale_fees.total_fees(), posting)?;
+        }
+        if rounding_account != fee_account {
+            let remainder =
+                basis_points_of(gross, schedule.commission_bps())?.checked_sub(commission)?;
+            let rounding = LedgerAccount::Named(rounding_account.to_string());
+            journal.post(&rounding, &fees, remainder, Posting::default())?;
+        }
+        Some(journal.entries)
+    }
+}
+
+/// What an entry refers back to.
+#[derive(Clone, Copy, Default)]
+struct Posting {
+    sale: Option<usize>,
+    bidder_id: Option<Uuid>,
+    bid_id: Option<Uuid>,
+}
+
+/// The entries journaled so far.
+#[derive(Default)]
+struct Journal {
+    entries: Vec<LedgerEntry>,
+}
+
+impl Journal {
+    /// Posts the amount from one account to another, a debit and a matching
+    /// credit, unless the amount is zero. A negative amount is posted the other
+    /// way round, None if it cannot be negated.
+    fn post(
+        &mut self,
+        debit: &LedgerAccount,
+        credit: &LedgerAccount,
+        amount: i128,
+        posting: Posting,
+    ) -> Option<()> {
+        if amount == 0 {
+            return Some(());
+        }
+        let (debit, credit, amount) = if amount < 0 {
+            (credit, debit, amount.checked_neg()?)
+        } else {
+            (debit, credit, amount)
+        };
+        for (account, direction) in [
+            (debit, EntryDirection::Debit),
+            (credit, EntryDirection::Credit),
+        ] {
+            self.entries.push(LedgerEntry {
+                account: account.clone(),
+                direction,
+                amount,
+                sale: posting.sale,
+                bidder_id: posting.bidder_id,
+                bid_id: posting.bid_id,
+            });
+        }
+        Some(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::vec::Vec;
+
+    use uuid::Uuid;
+
+    use crate::*;
+
+    /// Asserts the journal balances, overall and for each sale.
+    fn assert_balanced(entries: &[LedgerEntry]) {
+        let total: i128 = entries.iter().map(LedgerEntry::signed_amount).sum();
+        assert_eq!(total, 0, "journal does not balance");
+        let mut sales: Vec<Option<usize>> = entries.iter().map(LedgerEntry::sale).collect();
+        sales.dedup();
+        for sale in sales {
+            let balance: i128 = entries
+                .iter()
+                .filter(|entry| entry.sale() == sale)
+                .map(LedgerEntry::signed_amount)
+                .sum();
+            assert_eq!(balance, 0, "sale {sale:?} does not balance");
+        }
+        assert!(entries.iter().all(|entry| entry.amount() > 0));
+    }
+
+    /// The net of every entry posted to the account.
+    fn balance_of(entries: &[LedgerEntry], account: &LedgerAccount) -> i128 {
+        entries
+            .iter()
+            .filter(|entry| entry.account() == account)
+            .map(LedgerEntry::signed_amount)
+            .sum()
+    }
+
+    fn named(name: &str) -> LedgerAccount {
+        LedgerAccount::Named(name.into())
+    }
+
+    #[test]
+    fn ledger_posts_each_sale() {
+        let bid = bid![1_000, 2];
+        let sales = AuctionBuilder::new()
+            .lots(2)
+            .build()
+            .resolve_bids(vec![bid]);
+        let fees = FeeSchedule::new()
+            .with_commission_bps(1_000)
+            .with_per_lot_fee(50);
+        let entries = sales.to_ledger("seller", "fees", &fees).unwrap();
+        assert_balanced(&entries);
+        assert_eq!(entries.len(), 4);
+        assert_eq!(entries[0].account(), &LedgerAccount::Buyer(bid.bidder_id()));
+        assert_eq!(entries[0].direction(), EntryDirection::Debit);
+        assert_eq!(entries[0].amount(), 2_000);
+        assert!(entries
+            .iter()
+            .all(|entry| entry.sale() == Some(0) && entry.bid_id() == Some(bid.id())));
+        assert_eq!(balance_of(&entries, &named("seller")), -1_700);
+        assert_eq!(balance_of(&entries, &named("fees")), -300);
+    }
+
+    #[test]
+    fn ledger_leaves_out_zero_fees() {
+        let sales: Sales = vec![Sale::new(Uuid::new_v4(), 10, 1)];
+        let entries = sales
+            .to_ledger("seller", "fees", &FeeSchedule::new())
+            .unwrap();
+        assert_balanced(&entries);
+        assert_eq!(entries.len(), 2);
+        assert!(sales[..0]
+            .to_ledger("seller", "fees", &FeeSchedule::new())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn ledger_posts_rounding_remainder() {
+        let sales: Sales = (0..3).map(|_| Sale::new(Uuid::new_v4(), 5, 1)).collect();
+        let fees = FeeSchedule::new().with_commission_bps(1_000);
+        let entries = sales
+            .to_ledger_with_rounding("seller", "fees", "rounding", &fees)
+            .unwrap();
+        assert_balanced(&entries);
+        // Each sale's 0.5 cent commission rounds up to a cent, where the
+        // commission on the 15 cent total rounds up to 2.
+        assert_eq!(balance_of(&entries, &named("seller")), -12);
+        assert_eq!(balance_of(&entries, &named("fees")), -2);
+        assert_eq!(balance_of(&entries, &named("rounding")), -1);
+        let remainder: Vec<&LedgerEntry> = entries
+            .iter()
+            .filter(|entry| entry.sale().is_none())
+            .collect();
+        assert_eq!(remainder.len(), 2);
+        assert_eq!(remainder[0].bidder_id(), None);
+    }
+
+    #[test]
+    fn ledger_debits_rounding_when_sales_round_down() {
+        let sales: Sales = (0..3).map(|_| Sale::new(Uuid::new_v4(), 4, 1)).collect();
+        let fees = FeeSchedule::new().with_commission_bps(1_000);
+        let entries = sales
+            .to_ledger_with_rounding("seller", "fees", "rounding", &fees)
+            .unwrap();
+        assert_balanced(&entries);
+        assert_eq!(balance_of(&entries, &named("fees")), -1);
+        assert_eq!(balance_of(&entries, &named("rounding")), 1);
+    }
+
+    #[test]
+    fn ledger_keeps_rounding_on_the_fee_account() {
+        let sales: Sales = (0..3).map(|_| Sale::new(Uuid::new_v4(), 5, 1)).collect();
+        let fees = FeeSchedule::new().with_commission_bps(1_000);
+        let entries = sales.to_ledger("seller", "fees", &fees).unwrap();
+        assert_balanced(&entries);
+        assert_eq!(balance_of(&entries, &named("fees")), -3);
+        assert!(entries.iter().all(|entry| entry.sale().is_some()));
+    }
+
+    #[test]
+    fn ledger_balances_generated_sales() {
+        let fees = FeeSchedule::new()
+            .with_commission_bps(333)
+            .with_per_lot_fee(7)
+            .with_minimum_fee(25);
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(40)
+            .build();
+        let sales = auction.resolve_bids(generators::large_quantities(3, 60, 5_000, 4));
+        let entries = sales
+            .to_ledger_with_rounding("seller", "fees", "rounding", &fees)
+            .unwrap();
+        assert_balanced(&entries);
+        let settlement = sales.settle(&fees).unwrap();
+        assert_eq!(
+            balance_of(&entries, &named("seller")),
+            -settlement.net_proceeds()
+        );
+    }
+
+    #[test]
+    fn ledger_total_beyond_i64_fits() {
+        let sales: Sales = vec![Sale::new(Uuid::new_v4(), i64::MAX, 4)];
+        let entries = sales
+            .to_ledger("seller", "fees", &FeeSchedule::new())
+            .unwrap();
+        assert_balanced(&entries);
+        assert_eq!(entries[0].amount(), i128::from(i64::MAX) * 4);
+    }
+
+    #[test]
+    fn ledger_overflow_is_none() {
+        let sales: Sales = vec![
+            Sale::new(Uuid::new_v4(), i64::MAX, usize::MAX),
+            Sale::new(Uuid::new_v4(), i64::MAX, usize::MAX),
+        ];
+        let fees = FeeSchedule::new();
+        assert_eq!(sales.to_ledger("seller", "fees", &fees), None);
+    }
+
+    #[test]
+    fn ledger_account_display() {
+        let bidder = Uuid::nil();
+        assert_eq!(
+            LedgerAccount::Buyer(bidder).to_string(),
+            "buyer:00000000-0000-0000-0000-000000000000"
+        );
+        assert_eq!(named("fees").to_string(), "fees");
+    }
+}
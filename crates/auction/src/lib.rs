@@ -1,68 +1,546 @@
 //! Resolve auctions using a variety of algorithms.
+//!
+//! The `std` feature is on by default. Without it the crate only needs
+//! `alloc`, so the core types and strategies run where `std` is unavailable.
+//! Generating random bid ids, reading and writing files, serialization,
+//! simulation and the `Error` impls then go away.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![allow(unused)]
 #![warn(clippy::all, clippy::nursery)]
-use uuid::Uuid;
+extern crate alloc;
 
-mod strategies;
+use alloc::{borrow::Cow, boxed::Box, sync::Arc, vec::Vec};
+use core::{cmp::Ordering, fmt};
+
+pub use uuid::Uuid;
+
+use crate::collections::{HashMap, HashSet};
+
+mod anglo_dutch;
+#[cfg(any(test, feature = "proptest"))]
+pub mod arbitrary;
+mod batch;
+mod bid_cap;
+mod candle;
+mod collections;
+mod compare;
+mod core_selecting;
+mod currency;
+mod double;
+mod error;
+mod events;
+mod explain;
+mod fees;
+#[cfg(any(test, feature = "generators"))]
+pub mod generators;
+mod invoice;
+#[cfg(feature = "std")]
+pub mod io;
+mod ladder;
+mod ledger;
+#[cfg(feature = "metadata")]
+mod metadata;
+mod money;
+mod observer;
+mod open;
+mod order_book;
+mod package;
+#[cfg(feature = "std")]
+mod parse;
+pub mod prelude;
+mod reserve;
+mod result;
+mod rng;
+mod rounds;
+mod scoring;
+mod session;
+#[cfg(feature = "std")]
+pub mod simulation;
+pub mod strategies;
+mod summary;
+#[cfg(feature = "metrics")]
+mod telemetry;
+mod tick;
+#[cfg(feature = "tracing")]
+mod trace;
+mod verify;
+
+pub use anglo_dutch::{AngloDutch, AngloDutchViolation};
+pub use batch::resolve_batch;
+pub use candle::{CandleAuction, CandleResult};
+pub use compare::{compare_strategies, StrategyOutcome};
+pub use currency::Currency;
+pub use double::{ClearingRule, DoubleAuction, DoubleAuctionResult};
+pub use error::{
+    AuctionConfigError, AuctionError, BidError, BidParseError, BidParseErrorKind, ParseBidError,
+};
+pub use events::{replay_events, AuctionEvent, EventKind};
+pub use explain::{BidOutcome, FullResolution, Outcome, RejectReason, RejectedBid};
+pub use fees::{FeeSchedule, SaleFees, SalesSettlement, Settlement};
+pub use invoice::{Invoice, InvoiceLine};
+pub use ladder::LadderBid;
+pub use ledger::{EntryDirection, LedgerAccount, LedgerEntry, SalesLedger};
+#[cfg(feature = "metadata")]
+pub use metadata::BidMetadata;
+pub use money::Money;
+pub use observer::AuctionObserver;
+pub use open::OpenAuction;
+pub use order_book::{OrderBook, Side, Trade};
+pub use package::{PackageAuction, PackageBid, PaymentRule, MAX_PACKAGE_BIDS};
+pub use reserve::{DynamicReserve, ReserveSweep};
+pub use result::{AuctionResult, ReserveStatus};
+pub use rounds::{MultiRoundAuction, RoundResult};
+pub use session::{
+    AmendError, AmendmentTime, AuctionSession, BidRejection, Receipt, SessionSnapshot,
+    WithdrawError,
+};
+pub use strategies::Strategy;
+pub use summary::{BidderSettlement, SalesSummary, SalesTotals};
+pub use verify::{verify, Violation};
+
+/// A point in time, in units chosen by the caller such as seconds since the
+/// Unix epoch.
+pub type Timestamp = u64;
+
+/// The score weight of a bid with neither an advantage nor a penalty, in basis
+/// points.
+pub const SCORE_WEIGHT_UNIT: i64 = 10_000;
 
 /// The Bid type.
-#[derive(Debug, Clone, Copy)]
+///
+/// Bids are equal when every field is, so two bids with the same amount are
+/// only equal if they are the same bid. Strategies rank bids by amount with
+/// explicit comparators, see [`Bid::cmp_amount`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "BidRecord", into = "BidRecord")
+)]
 pub struct Bid {
     /// The bids unique identifier.
     id: Uuid,
+    /// The identifier of the bidder, shared by all of their bids.
+    bidder_id: Uuid,
     /// The bid in cents.
     amount: i64,
     /// The amount of desired units being bid on. Typically one.
     quantity: usize,
+    /// When the bid was submitted, used to break ties between equal bids.
+    submitted_at: Option<Timestamp>,
+    /// Whether the bid refuses to be partially filled.
+    all_or_nothing: bool,
+    /// The fewest units the bid will accept, zero accepts any fill.
+    min_quantity: usize,
+    /// When the bid lapses, it can no longer win from this time onwards.
+    expires_at: Option<Timestamp>,
+    /// Whether the amount is a maximum the engine bids up to on the bidder's
+    /// behalf, rather than a fixed amount.
+    proxy: bool,
+    /// The quality weight scored auctions rank the bid by, in basis points.
+    score_weight: i64,
+    /// The currency the amount is in, None when untagged.
+    currency: Option<Currency>,
+    /// The priority tier the bid is ranked in before its amount, lower tiers
+    /// first, None for the lowest priority of all.
+    priority: Option<u8>,
 }
 
-impl Ord for Bid {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.amount.cmp(&other.amount)
+impl fmt::Display for Bid {
+    /// Writes the bid as `amount x quantity [id]`, such as
+    /// `150x3 [67e55044-10b1-426f-9247-bb680e5fe0c8]`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{} [{}]", self.amount, self.quantity, self.id)
     }
 }
 
-impl PartialOrd for Bid {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
+impl Bid {
+    /// Creates a new bid with a random id.
+    ///
+    /// The bid is not validated, see [`Bid::try_new`].
+    #[cfg(any(feature = "std", test))]
+    pub fn new(amount: i64, quantity: usize) -> Self {
+        Self::with_id(Uuid::new_v4(), amount, quantity)
+    }
+
+    /// Creates a new bid with a random id, rejecting a zero quantity or a
+    /// negative amount.
+    #[cfg(any(feature = "std", test))]
+    pub fn try_new(amount: i64, quantity: usize) -> Result<Self, BidError> {
+        let bid = Self::new(amount, quantity);
+        bid.validate()?;
+        Ok(bid)
+    }
+
+    /// Creates a new bid with the given id, which is also its bidder id.
+    pub const fn with_id(id: Uuid, amount: i64, quantity: usize) -> Self {
+        Self {
+            id,
+            bidder_id: id,
+            amount,
+            quantity,
+            submitted_at: None,
+            all_or_nothing: false,
+            min_quantity: 0,
+            expires_at: None,
+            proxy: false,
+            score_weight: SCORE_WEIGHT_UNIT,
+            currency: None,
+            priority: None,
+        }
+    }
+
+    /// Creates a new bid with an id derived from a name, the same namespace and
+    /// name always give the same id.
+    pub fn with_name(namespace: &Uuid, name: &str, amount: i64, quantity: usize) -> Self {
+        Self::with_id(Uuid::new_v5(namespace, name.as_bytes()), amount, quantity)
+    }
+
+    /// Creates a new bid from a money amount, held in minor units at the given
+    /// scale, validated as by [`Bid::try_new`].
+    #[cfg(any(feature = "std", test))]
+    pub fn from_money<M: Money>(amount: M, scale: u32, quantity: usize) -> Result<Self, BidError> {
+        let amount = amount
+            .to_minor_units(scale)
+            .ok_or(BidError::UnrepresentableAmount)?;
+        Self::try_new(amount, quantity)
+    }
+
+    /// Set the bidder placing the bid, by default each bid is its own bidder.
+    pub const fn with_bidder_id(mut self, bidder_id: Uuid) -> Self {
+        self.bidder_id = bidder_id;
+        self
+    }
+
+    /// Set when the bid was submitted.
+    pub const fn with_submitted_at(mut self, submitted_at: Timestamp) -> Self {
+        self.submitted_at = Some(submitted_at);
+        self
+    }
+
+    /// The bids unique identifier.
+    pub const fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// The identifier of the bidder placing the bid.
+    pub const fn bidder_id(&self) -> Uuid {
+        self.bidder_id
+    }
+
+    /// The bid in cents.
+    pub const fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    /// The amount of desired units being bid on.
+    pub const fn quantity(&self) -> usize {
+        self.quantity
+    }
+
+    /// Set whether the bid refuses to be partially filled.
+    pub const fn with_all_or_nothing(mut self, all_or_nothing: bool) -> Self {
+        self.all_or_nothing = all_or_nothing;
+        self
+    }
+
+    /// Set the fewest units the bid will accept being filled with.
+    pub const fn with_min_quantity(mut self, min_quantity: usize) -> Result<Self, BidError> {
+        if min_quantity > self.quantity {
+            return Err(BidError::MinQuantityExceedsQuantity {
+                min_quantity,
+                quantity: self.quantity,
+            });
+        }
+        self.min_quantity = min_quantity;
+        Ok(self)
+    }
+
+    /// Set when the bid lapses.
+    pub const fn with_expires_at(mut self, expires_at: Timestamp) -> Self {
+        self.expires_at = Some(expires_at);
+        self
+    }
+
+    /// Set the currency the amount is in.
+    pub const fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Set the quality weight scored auctions rank the bid by, in basis points
+    /// of [`SCORE_WEIGHT_UNIT`].
+    pub const fn with_score_weight(mut self, score_weight: i64) -> Self {
+        self.score_weight = score_weight;
+        self
+    }
+
+    /// Checks the bid asks for some units at an amount of at least zero, that
+    /// its minimum fill fits in its quantity and that its score weight is
+    /// positive.
+    pub const fn validate(&self) -> Result<(), BidError> {
+        if self.quantity == 0 {
+            return Err(BidError::ZeroQuantity);
+        }
+        if self.amount < 0 {
+            return Err(BidError::NegativeAmount(self.amount));
+        }
+        if self.min_quantity > self.quantity {
+            return Err(BidError::MinQuantityExceedsQuantity {
+                min_quantity: self.min_quantity,
+                quantity: self.quantity,
+            });
+        }
+        if self.score_weight <= 0 {
+            return Err(BidError::NonPositiveScoreWeight(self.score_weight));
+        }
+        Ok(())
+    }
+
+    /// Set whether the amount is a proxy maximum rather than a fixed amount.
+    pub const fn with_proxy(mut self, proxy: bool) -> Self {
+        self.proxy = proxy;
+        self
+    }
+
+    /// Set the priority tier, lower tiers winning lots before higher ones
+    /// whatever their amounts. Only single and multi price auctions rank by
+    /// tier.
+    pub const fn with_priority(mut self, priority: u8) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// When the bid was submitted, if known.
+    pub const fn submitted_at(&self) -> Option<Timestamp> {
+        self.submitted_at
+    }
+
+    /// Whether the bid refuses to be partially filled.
+    pub const fn all_or_nothing(&self) -> bool {
+        self.all_or_nothing
+    }
+
+    /// The fewest units the bid will accept, zero accepts any fill.
+    pub const fn min_quantity(&self) -> usize {
+        self.min_quantity
+    }
+
+    /// When the bid lapses, if ever.
+    pub const fn expires_at(&self) -> Option<Timestamp> {
+        self.expires_at
+    }
+
+    /// Whether the amount is a proxy maximum rather than a fixed amount.
+    pub const fn proxy(&self) -> bool {
+        self.proxy
+    }
+
+    /// The quality weight scored auctions rank the bid by, in basis points.
+    pub const fn score_weight(&self) -> i64 {
+        self.score_weight
+    }
+
+    /// The currency the amount is in, None when untagged.
+    pub const fn currency(&self) -> Option<Currency> {
+        self.currency
+    }
+
+    /// The priority tier, None for the lowest priority.
+    pub const fn priority(&self) -> Option<u8> {
+        self.priority
+    }
+
+    /// The rank of the bid's tier, untiered bids ranking after every tier.
+    pub(crate) const fn tier(&self) -> u16 {
+        match self.priority {
+            Some(priority) => priority as u16,
+            None => u8::MAX as u16 + 1,
+        }
+    }
+
+    /// Whether the bid has lapsed by the given time.
+    pub const fn is_expired(&self, now: Timestamp) -> bool {
+        match self.expires_at {
+            Some(expires_at) => expires_at <= now,
+            None => false,
+        }
+    }
+
+    /// The smallest quantity the bid will accept being filled with.
+    pub(crate) const fn min_fill(&self) -> usize {
+        if self.all_or_nothing {
+            self.quantity
+        } else if self.min_quantity > 1 {
+            self.min_quantity
+        } else {
+            1
+        }
+    }
+
+    /// Orders bids by amount alone, lowest first, the order strategies rank
+    /// bids in before breaking ties.
+    ///
+    /// ```
+    /// use auction::bid;
+    ///
+    /// let mut bids = vec![bid![20, 1], bid![10, 1], bid![20, 2]];
+    /// bids.sort_by(|a, b| b.cmp_amount(a));
+    /// assert_eq!(bids[0].quantity(), 1);
+    /// assert_eq!(bids[2].amount(), 10);
+    /// ```
+    pub fn cmp_amount(&self, other: &Self) -> Ordering {
+        self.amount.cmp(&other.amount)
+    }
+
+    /// Orders bids by submission time, earliest first and untimestamped last.
+    pub(crate) fn cmp_submitted(&self, other: &Self) -> Ordering {
+        match (self.submitted_at, other.submitted_at) {
+            (Some(a), Some(b)) => a.cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        }
+    }
+
+    /// Returns a copy of the bid for a different amount, keeping the same id.
+    pub(crate) const fn with_amount(self, amount: i64) -> Self {
+        Self { amount, ..self }
+    }
+
+    /// Returns a copy of the bid for a smaller quantity, keeping the same id.
+    pub(crate) const fn with_quantity(self, quantity: usize) -> Self {
+        Self { quantity, ..self }
     }
 }
 
-impl PartialEq for Bid {
-    fn eq(&self, other: &Self) -> bool {
-        self.amount == other.amount
+/// The serialized form of a bid, where everything but the amount and quantity
+/// may be left out.
+///
+/// Bids serialize through it too, so formats that are not self-describing
+/// read back what they wrote.
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct BidRecord {
+    id: Option<Uuid>,
+    bidder_id: Option<Uuid>,
+    amount: i64,
+    quantity: usize,
+    submitted_at: Option<Timestamp>,
+    #[serde(default)]
+    all_or_nothing: bool,
+    #[serde(default)]
+    min_quantity: usize,
+    expires_at: Option<Timestamp>,
+    #[serde(default)]
+    proxy: bool,
+    #[serde(default = "default_score_weight")]
+    score_weight: i64,
+    currency: Option<Currency>,
+    priority: Option<u8>,
+}
+
+#[cfg(feature = "serde")]
+impl From<Bid> for BidRecord {
+    fn from(bid: Bid) -> Self {
+        Self {
+            id: Some(bid.id),
+            bidder_id: Some(bid.bidder_id),
+            amount: bid.amount,
+            quantity: bid.quantity,
+            submitted_at: bid.submitted_at,
+            all_or_nothing: bid.all_or_nothing,
+            min_quantity: bid.min_quantity,
+            expires_at: bid.expires_at,
+            proxy: bid.proxy,
+            score_weight: bid.score_weight,
+            currency: bid.currency,
+            priority: bid.priority,
+        }
     }
 }
 
-impl Eq for Bid {}
+#[cfg(feature = "serde")]
+const fn default_score_weight() -> i64 {
+    SCORE_WEIGHT_UNIT
+}
 
-impl Bid {
-    /// Creates a new bid.
-    pub fn new(amount: i64, quantity: usize) -> Self {
-        let id = Uuid::new_v4();
+#[cfg(feature = "serde")]
+const fn default_allow_withdrawals() -> bool {
+    true
+}
+
+#[cfg(feature = "serde")]
+impl From<BidRecord> for Bid {
+    fn from(record: BidRecord) -> Self {
+        let id = record.id.unwrap_or_else(Uuid::new_v4);
         Self {
             id,
-            amount,
-            quantity,
+            bidder_id: record.bidder_id.unwrap_or(id),
+            amount: record.amount,
+            quantity: record.quantity,
+            submitted_at: record.submitted_at,
+            all_or_nothing: record.all_or_nothing,
+            min_quantity: record.min_quantity,
+            expires_at: record.expires_at,
+            proxy: record.proxy,
+            score_weight: record.score_weight,
+            currency: record.currency,
+            priority: record.priority,
         }
     }
 }
 
+/// Creates a bid from an amount and quantity, optionally prefixed by an id.
+///
+/// `bid![amount]` bids on a single unit, `bid![amount, quantity]` on several and
+/// `bid![id; amount, quantity]` uses the given id rather than a random one.
 #[macro_export]
 macro_rules! bid {
-    ($amount:literal, $quantity:literal) => {{
-        Bid::new($amount, $quantity)
+    ($id:expr; $amount:expr, $quantity:expr $(,)?) => {{
+        $crate::Bid::with_id($id, $amount, $quantity)
+    }};
+    ($amount:expr, $quantity:expr $(,)?) => {{
+        $crate::Bid::new($amount, $quantity)
+    }};
+    ($amount:expr $(,)?) => {{
+        $crate::Bid::new($amount, 1)
     }};
 }
 
 pub type Bids = Vec<Bid>;
 
 /// The Sale type.
-#[derive(Debug, Clone, Copy)]
+///
+/// A sale with zero quantity is a losing all-pay bid, charged its amount once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Sale {
     bidder_id: Uuid,
     amount: i64,
     quantity: usize,
+    /// The bid the sale fills, None for sales not made from a bid.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    bid_id: Option<Uuid>,
+    /// The quantity the bid asked for, None when the bid is not known.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    requested_quantity: Option<usize>,
+    /// The package bid that won, None outside package auctions.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    package_id: Option<Uuid>,
+    /// The currency of the auction, None when untagged.
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    currency: Option<Currency>,
 }
 
 impl Sale {
@@ -72,7 +550,116 @@ impl Sale {
             bidder_id,
             amount,
             quantity,
+            bid_id: None,
+            requested_quantity: None,
+            package_id: None,
+            currency: None,
+        }
+    }
+
+    /// Record the bid the sale fills and the quantity it asked for.
+    pub const fn with_bid(mut self, bid: &Bid) -> Self {
+        self.bid_id = Some(bid.id);
+        self.requested_quantity = Some(bid.quantity);
+        self
+    }
+
+    /// Record the package bid that won the sale.
+    pub const fn with_package_id(mut self, package_id: Uuid) -> Self {
+        self.package_id = Some(package_id);
+        self
+    }
+
+    /// Record the currency the sale is priced in.
+    pub const fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// The identifier of the winning bidder.
+    pub const fn bidder_id(&self) -> Uuid {
+        self.bidder_id
+    }
+
+    /// The currency the sale is priced in, None when untagged.
+    pub const fn currency(&self) -> Option<Currency> {
+        self.currency
+    }
+
+    /// The price paid per unit in cents.
+    pub const fn amount(&self) -> i64 {
+        self.amount
+    }
+
+    /// The number of units sold.
+    pub const fn quantity(&self) -> usize {
+        self.quantity
+    }
+
+    /// The bid the sale fills, None for sales not made from a bid, such as
+    /// package sales.
+    pub const fn bid_id(&self) -> Option<Uuid> {
+        self.bid_id
+    }
+
+    /// The quantity the sale's bid asked for, None when the bid is not known.
+    ///
+    /// A bid may be filled over several sales, so it is the sum of their
+    /// quantities that this bounds.
+    pub const fn requested_quantity(&self) -> Option<usize> {
+        self.requested_quantity
+    }
+
+    /// The package bid that won the sale, None outside package auctions.
+    pub const fn package_id(&self) -> Option<Uuid> {
+        self.package_id
+    }
+
+    /// The amount charged for the sale.
+    ///
+    /// This is the amount multiplied by the quantity, or just the amount for a
+    /// losing all-pay sale. The total is an `i128` so it holds any `i64`
+    /// amount times any quantity without overflowing.
+    pub fn total(&self) -> i128 {
+        if self.quantity == 0 {
+            return i128::from(self.amount);
         }
+        i128::from(self.amount) * self.quantity as i128
+    }
+
+    /// The amount as money, reading the minor units at the given scale.
+    pub fn amount_in<M: Money>(&self, scale: u32) -> Option<M> {
+        M::from_minor_units(self.amount, scale)
+    }
+
+    /// The total as money, reading the minor units at the given scale, None
+    /// if the total does not fit in an `i64`.
+    pub fn total_in<M: Money>(&self, scale: u32) -> Option<M> {
+        M::from_minor_units(i64::try_from(self.total()).ok()?, scale)
+    }
+}
+
+impl From<&Bid> for Sale {
+    /// A sale filling the whole bid at its own amount.
+    fn from(bid: &Bid) -> Self {
+        Self::new(bid.bidder_id, bid.amount, bid.quantity).with_bid(bid)
+    }
+}
+
+impl fmt::Display for Sale {
+    /// Writes the sale as `bidder… pays amount for quantity`, the bidder
+    /// shortened to the first eight digits of their id, such as
+    /// `67e55044… pays 150 for 3`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut buffer = Uuid::encode_buffer();
+        let bidder = self.bidder_id.simple().encode_lower(&mut buffer);
+        write!(
+            f,
+            "{}… pays {} for {}",
+            &bidder[..8],
+            self.amount,
+            self.quantity
+        )
     }
 }
 
@@ -80,71 +667,2490 @@ pub type Sales = Vec<Sale>;
 
 /// Enum representing valid auction strategies.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AuctionStrategy {
     SinglePrice,
     MultiPrice,
+    /// A descending clock starting at `start_price`, dropping by `decrement`.
+    Dutch {
+        start_price: i64,
+        decrement: i64,
+    },
+    /// Every bidder pays their bid, only the best bids receive lots.
+    AllPay,
+    /// Ranked slots where each winner pays the next highest bid.
+    GeneralizedSecondPrice,
+    /// A single lot where proxy bids pay just enough to beat the next bid.
+    Proxy,
+    /// Allocated as multi price, each winner paying the value their bids
+    /// displace from the other bidders.
+    Vcg,
+    /// Winners drawn at random from the bids meeting the reserve, each paying
+    /// the reserve price. The same seed always draws the same winners.
+    Lottery {
+        seed: u64,
+    },
+    /// Bids ranked by their amount weighted by their score weight, so a better
+    /// quality bid can beat a better priced one.
+    Scored,
+    /// An ascending clock starting at `start`, or the reserve price when None,
+    /// rising by `increment`. Bidders drop out once it passes their amount.
+    Japanese {
+        start: Option<i64>,
+        increment: i64,
+    },
+    /// A fixed price sale at the reserve price, first come first served.
+    PostedPrice,
+    /// A user supplied strategy, which cannot be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    Custom(Arc<dyn Strategy>),
+}
+
+/// Enum representing how lots are shared between equal bids that ask for more
+/// than the lots remaining.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AllocationPolicy {
+    /// Fill equal bids in the order they were submitted.
+    #[default]
+    Priority,
+    /// Share the lots in proportion to each bid's quantity, using the largest
+    /// remainder method so every remaining lot is allocated.
+    ProRata,
+}
+
+/// Enum representing the order resolved sales are returned in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SalesOrdering {
+    /// The order the strategy produced them in, the ranking of their bids
+    /// unless the strategy documents another.
+    #[default]
+    Ranked,
+    /// The highest price first.
+    PriceDesc,
+    /// Grouped by bidder, in ascending bidder id.
+    BidderId,
+    /// The largest quantity first.
+    QuantityDesc,
+    /// The order their bids were given in, sales not made from a given bid
+    /// last.
+    InputBidOrder,
+}
+
+impl SalesOrdering {
+    /// Sort the sales resolved from the bids. Sales the ordering does not
+    /// separate keep their order.
+    pub fn sort(self, sales: &mut [Sale], bids: &[Bid]) {
+        match self {
+            Self::Ranked => {}
+            Self::PriceDesc => sales.sort_by_key(|sale| core::cmp::Reverse(sale.amount)),
+            Self::BidderId => sales.sort_by_key(|sale| sale.bidder_id),
+            Self::QuantityDesc => sales.sort_by_key(|sale| core::cmp::Reverse(sale.quantity)),
+            Self::InputBidOrder => {
+                let positions: HashMap<Uuid, usize> = bids
+                    .iter()
+                    .enumerate()
+                    .map(|(position, bid)| (bid.id, position))
+                    .collect();
+                sales.sort_by_key(|sale| {
+                    sale.bid_id
+                        .and_then(|bid_id| positions.get(&bid_id).copied())
+                        .unwrap_or(usize::MAX)
+                });
+            }
+        }
+    }
+}
+
+/// Enum representing what happens to the marginal bid, the bid that asks for
+/// more than the lots remaining.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PartialFillPolicy {
+    /// Fill the marginal bid with the lots remaining.
+    #[default]
+    Split,
+    /// Pass over the marginal bid, offering the lots to the following bids.
+    Skip,
+    /// Stop filling at the marginal bid, leaving the lots remaining unsold.
+    LeaveUnsold,
+}
+
+/// Enum representing how bids with the same amount are ranked.
+///
+/// Unlike the tie break, this orders every price level rather than only the
+/// one competing for the last lots, so it also sets which bid of the marginal
+/// level is partially filled when the tie break keeps the ranked order. Bids
+/// the key does not separate keep their input order.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SecondarySort {
+    /// Rank the earliest submitted bids first, untimestamped bids last.
+    #[default]
+    EarliestSubmitted,
+    /// Rank the bids asking for the most units first.
+    LargestQuantityFirst,
+    /// Rank the bids asking for the fewest units first.
+    SmallestQuantityFirst,
+    /// Rank the bids in the order they were given.
+    InputOrder,
+}
+
+/// Enum representing how equal bids competing for the last lots are ordered.
+///
+/// Levels of equal bids that fit in the remaining lots are filled as ranked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TieBreak {
+    /// Keep the ranked order, as the secondary sort leaves it.
+    #[default]
+    InputOrder,
+    /// Shuffle the tied bids, the same seed always gives the same order.
+    Random { seed: u64 },
+    /// Fill the bids asking for the most units first.
+    LargestQuantityFirst,
+    /// Fill the bids asking for the fewest units first.
+    SmallestQuantityFirst,
+}
+
+/// Enum representing the clearing price every winner of a single price auction
+/// pays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PricingRule {
+    /// The amount of the lowest winning bid.
+    #[default]
+    LowestAccepted,
+    /// The amount of the highest bid not completely filled, which may be lower.
+    HighestRejected,
+}
+
+/// Enum representing what the marginal bids of a multi price auction pay, the
+/// bids at the last price level cut short because every lot was sold.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MarginalPricing {
+    /// The marginal bids pay their own amount like every other winner.
+    #[default]
+    OwnAmount,
+    /// The marginal bids pay the clearing price, the best bid that won nothing
+    /// or the reserve price when every bid won.
+    ClearingPrice,
+}
+
+/// Enum representing what the winners of a scored auction pay.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ScoredPricing {
+    /// Each winner pays their own amount.
+    #[default]
+    OwnAmount,
+    /// Each winner pays the amount that would tie their score with the best
+    /// bid that won nothing.
+    SecondScore,
+}
+
+/// Enum representing each bid's chance of being drawn in a lottery.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LotteryWeighting {
+    /// Every bid has the same chance.
+    #[default]
+    Equal,
+    /// A bid's chance is in proportion to its quantity.
+    Quantity,
+}
+
+/// Enum representing what happens to bids whose amount is off the tick grid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TickPolicy {
+    /// Drop the bid.
+    #[default]
+    Reject,
+    /// Round the amount down to the grid.
+    RoundDown,
+}
+
+/// Enum representing what happens to bids asking for more lots than the
+/// maximum bid fraction allows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BidCapPolicy {
+    /// Drop the bid.
+    #[default]
+    Reject,
+    /// Reduce the quantity to the cap, dropping the bid if it will not accept
+    /// that fill.
+    Clamp,
+}
+
+/// Enum representing how a derived price that falls between whole cents, or
+/// between ticks of the grid, is rounded.
+///
+/// Derived prices are those a strategy computes rather than takes from a bid,
+/// such as a VCG payment spread over units or the midpoint of a double
+/// auction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RoundingPolicy {
+    /// Round up, in favour of whoever receives the payment.
+    TowardSeller,
+    /// Round down, in favour of whoever makes the payment.
+    #[default]
+    TowardBuyer,
+    /// Round to the nearest, halves rounding up.
+    Nearest,
+}
+
+impl RoundingPolicy {
+    /// Divides the numerator by the positive denominator, rounding by the
+    /// policy.
+    ///
+    /// Every derived price is rounded here so the strategies cannot diverge.
+    pub(crate) const fn divide(self, numerator: i128, denominator: i128) -> i128 {
+        let floor = numerator.div_euclid(denominator);
+        let remainder = numerator.rem_euclid(denominator);
+        let round_up = match self {
+            Self::TowardSeller => remainder > 0,
+            Self::TowardBuyer => false,
+            Self::Nearest => remainder * 2 >= denominator,
+        };
+        if round_up {
+            floor + 1
+        } else {
+            floor
+        }
+    }
+}
+
+/// Enum representing the price the tick grid is counted from.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum TickAnchor {
+    /// Amounts are multiples of the tick size.
+    #[default]
+    Zero,
+    /// Amounts are the reserve price plus a multiple of the tick size.
+    ReservePrice,
+}
+
+/// Enum representing what happens to a batch of bids when some are not in the
+/// auction's currency.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CurrencyMismatch {
+    /// Refuse the whole batch.
+    #[default]
+    Reject,
+    /// Drop the mismatched bids and resolve the rest.
+    Filter,
+}
+
+/// Enum representing whether bids without a submission time are accepted by
+/// an auction with a deadline.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum UntimedBids {
+    /// Treat the bid as on time.
+    #[default]
+    Accept,
+    /// Treat the bid as late.
+    Reject,
+}
+
+/// Enum representing which side of the auction is competing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AuctionDirection {
+    /// Buyers compete, the highest bids win and must meet the reserve price.
+    #[default]
+    Forward,
+    /// Suppliers compete, the lowest offers win and must not exceed the price
+    /// ceiling. Only the single and multi price strategies support this.
+    Reverse,
 }
 
 /// The auction type.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Auction {
     lots: usize,
     reserve_price: i64,
+    /// Reserve prices for consecutive runs of lots, empty for a flat reserve.
+    #[cfg_attr(feature = "serde", serde(default))]
+    reserve_schedule: Vec<(usize, i64)>,
+    /// How far below the reserve, in basis points of it, the best bid may fall
+    /// and still be reported as nearly meeting it.
+    #[cfg_attr(feature = "serde", serde(default))]
+    soft_reserve_tolerance_bps: Option<u32>,
+    /// The reserve worked out from the bids in place of the reserve price,
+    /// which cannot be serialized.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dynamic_reserve: Option<DynamicReserve>,
+    /// The price at which a bid wins its lots before the auction resolves.
+    #[cfg_attr(feature = "serde", serde(default))]
+    buy_now_price: Option<i64>,
+    /// The grid bid amounts must sit on, None for any amount.
+    #[cfg_attr(feature = "serde", serde(default))]
+    tick_size: Option<i64>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tick_policy: TickPolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tick_anchor: TickAnchor,
+    #[cfg_attr(feature = "serde", serde(default))]
+    rounding_policy: RoundingPolicy,
+    /// The largest share of the lots one bid may ask for, as a numerator and
+    /// denominator, None for no cap.
+    #[cfg_attr(feature = "serde", serde(default))]
+    max_bid_fraction: Option<(usize, usize)>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    bid_cap_policy: BidCapPolicy,
+    /// The last time a bid may be submitted, None for no deadline.
+    #[cfg_attr(feature = "serde", serde(default))]
+    closes_at: Option<Timestamp>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    untimed_bids: UntimedBids,
+    /// The currency bids must be in, None for untagged bids only.
+    #[cfg_attr(feature = "serde", serde(default))]
+    currency: Option<Currency>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    currency_mismatch: CurrencyMismatch,
+    price_ceiling: Option<i64>,
     strategy: AuctionStrategy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    pricing_rule: PricingRule,
+    #[cfg_attr(feature = "serde", serde(default))]
+    marginal_pricing: MarginalPricing,
+    #[cfg_attr(feature = "serde", serde(default))]
+    lottery_weighting: LotteryWeighting,
+    #[cfg_attr(feature = "serde", serde(default))]
+    scored_pricing: ScoredPricing,
+    allocation: AllocationPolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    partial_fill: PartialFillPolicy,
+    #[cfg_attr(feature = "serde", serde(default))]
+    sales_ordering: SalesOrdering,
+    direction: AuctionDirection,
+    #[cfg_attr(feature = "serde", serde(default))]
+    tie_break: TieBreak,
+    #[cfg_attr(feature = "serde", serde(default))]
+    secondary_sort: SecondarySort,
+    /// The most lots any one bidder may win.
+    max_lots_per_bidder: Option<usize>,
+    /// Whether a bidder's bids at a price level are allocated as one.
+    #[cfg_attr(feature = "serde", serde(default))]
+    aggregate_bidders: bool,
+    /// How far a proxy bid must beat the next bid by.
+    bid_increment: i64,
+    /// Whether sessions let bidders withdraw accepted bids.
+    #[cfg_attr(feature = "serde", serde(default = "default_allow_withdrawals"))]
+    allow_withdrawals: bool,
+    /// The most each bidder may be charged, in cents.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    budgets: HashMap<Uuid, i64>,
+    /// The lots each bidder won before the strategy resolved the rest, which
+    /// count towards their lot cap.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    awarded: HashMap<Uuid, usize>,
+    /// The `auction_id` label of the auction's metrics, None for no label.
+    #[cfg(feature = "metrics")]
+    #[cfg_attr(feature = "serde", serde(default))]
+    metrics_label: Option<String>,
 }
 
 impl Auction {
-    /// Resolve the bids against the given auction
-    pub fn resolve_bids(&self, mut bids: Bids) -> Sales {
-        match self.strategy {
-            AuctionStrategy::SinglePrice => strategies::single_price(self, bids),
-            AuctionStrategy::MultiPrice => strategies::multi_price(self, bids),
+    /// The number of lots for sale.
+    pub const fn lots(&self) -> usize {
+        self.lots
+    }
+
+    /// The lowest amount a bid must offer to win.
+    pub const fn reserve_price(&self) -> i64 {
+        self.reserve_price
+    }
+
+    /// The reserve price of each run of lots, as pairs of the number of lots
+    /// and their reserve, empty when every lot shares the reserve price.
+    pub fn reserve_schedule(&self) -> &[(usize, i64)] {
+        &self.reserve_schedule
+    }
+
+    /// The price at which bids win their lots before the auction resolves.
+    pub const fn buy_now_price(&self) -> Option<i64> {
+        self.buy_now_price
+    }
+
+    /// The currency bids must be in, None for untagged bids only.
+    pub const fn currency(&self) -> Option<Currency> {
+        self.currency
+    }
+
+    /// The ids of the bids not in the auction's currency, in input order.
+    ///
+    /// Tagged bids do not match an untagged auction, nor untagged bids a
+    /// tagged one.
+    pub fn currency_mismatches(&self, bids: &[Bid]) -> Vec<Uuid> {
+        bids.iter()
+            .filter(|bid| bid.currency != self.currency)
+            .map(|bid| bid.id)
+            .collect()
+    }
+
+    /// The bids resolved under the currency policy, borrowed when every bid
+    /// is in the auction's currency.
+    fn in_currency<'a>(&self, bids: &'a [Bid]) -> Cow<'a, [Bid]> {
+        if bids.iter().all(|bid| bid.currency == self.currency) {
+            return Cow::Borrowed(bids);
+        }
+        match self.currency_mismatch {
+            CurrencyMismatch::Reject => Cow::Owned(Vec::new()),
+            CurrencyMismatch::Filter => Cow::Owned(
+                bids.iter()
+                    .filter(|bid| bid.currency == self.currency)
+                    .copied()
+                    .collect(),
+            ),
         }
     }
-}
 
-/// The AuctionBuilder type. Used to easily create Auctions.
-#[derive(Default)]
-pub struct AuctionBuilder {
-    lots: usize,
-    reserve_price: Option<i64>,
-    strategy: Option<AuctionStrategy>,
-}
+    /// The ids of the bids the currency policy leaves out of the resolution,
+    /// every bid when a mismatch rejects the batch.
+    fn currency_rejected(&self, bids: &[Bid]) -> HashSet<Uuid> {
+        if bids.iter().all(|bid| bid.currency == self.currency) {
+            return HashSet::default();
+        }
+        bids.iter()
+            .filter(|bid| {
+                self.currency_mismatch == CurrencyMismatch::Reject || bid.currency != self.currency
+            })
+            .map(|bid| bid.id)
+            .collect()
+    }
 
-impl AuctionBuilder {
-    /// Create a new builder.
-    pub const fn new() -> Self {
-        Self {
-            lots: 1,
-            reserve_price: None,
-            strategy: None,
+    /// Prices the sales in the auction's currency, if it has one.
+    fn tag_currency(&self, sales: &mut Sales) {
+        if let Some(currency) = self.currency {
+            for sale in sales {
+                *sale = sale.with_currency(currency);
+            }
         }
     }
 
-    /// Set the number of auction lots.
-    pub const fn lots(mut self, lots: usize) -> Self {
-        self.lots = lots;
-        self
+    /// The last time a bid may be submitted, None for no deadline.
+    pub const fn closes_at(&self) -> Option<Timestamp> {
+        self.closes_at
     }
 
-    /// Set the reserve price of the auction.
-    pub const fn reserve_price(mut self, reserve_price: i64) -> Self {
-        self.reserve_price = Some(reserve_price);
-        self
+    /// Whether the bid was submitted after the auction closed.
+    ///
+    /// Bids submitted exactly at the close are on time, and bids without a
+    /// submission time follow the auction's [`UntimedBids`] policy.
+    pub fn is_late(&self, bid: &Bid) -> bool {
+        self.closes_at.is_some_and(|closes_at| {
+            bid.submitted_at
+                .map_or(self.untimed_bids == UntimedBids::Reject, |submitted_at| {
+                    submitted_at > closes_at
+                })
+        })
     }
 
-    /// Set the strategy of the auction.
-    pub const fn strategy(mut self, strategy: AuctionStrategy) -> Self {
-        self.strategy = Some(strategy);
-        self
+    /// The strategy used to resolve bids.
+    pub const fn strategy(&self) -> &AuctionStrategy {
+        &self.strategy
     }
 
-    /// Build the auction.
-    pub fn build(self) -> Auction {
-        Auction {
-            lots: self.lots,
-            reserve_price: self.reserve_price.unwrap_or_default(),
-            strategy: self.strategy.unwrap_or(AuctionStrategy::SinglePrice),
+    /// Whether the auction resolves bids the same whatever order they are
+    /// given in, as long as no two equal bids share a submission time.
+    ///
+    /// A lottery draws from the bids in input order, and a custom strategy
+    /// promises nothing. Every secondary sort but
+    /// [`SecondarySort::EarliestSubmitted`] leaves some equal bids in input
+    /// order.
+    pub const fn is_order_independent(&self) -> bool {
+        !matches!(
+            self.strategy,
+            AuctionStrategy::Lottery { .. } | AuctionStrategy::Custom(_)
+        ) && matches!(self.secondary_sort, SecondarySort::EarliestSubmitted)
+    }
+
+    /// Resolve the bids against the given auction
+    ///
+    /// Resolution is deterministic, the same bids in the same order always
+    /// give the same sales in the same order. Bids are ranked best amount
+    /// first, then by the secondary sort, earliest submitted by default, then
+    /// in input order, and sales follow that ranking unless the strategy
+    /// documents another order. See [`Auction::is_order_independent`] for
+    /// when the input order does not matter.
+    pub fn resolve_bids(&self, bids: Bids) -> Sales {
+        self.resolve_bids_ref(&bids)
+    }
+
+    /// Resolve borrowed bids against the given auction, leaving the caller's
+    /// bids untouched so they can be resolved again.
+    ///
+    /// The built-in strategies rank references to the bids rather than the
+    /// bids themselves.
+    pub fn resolve_bids_ref(&self, bids: &[Bid]) -> Sales {
+        self.resolve_observed(bids, &mut ())
+    }
+
+    /// Resolve borrowed bids as [`Auction::resolve_bids_ref`] does, calling
+    /// the observer's hooks as each bid is rejected and each sale priced.
+    ///
+    /// Bids are rejected for their currency, tick and bid cap before the
+    /// strategy decides the rest. The `()` observer resolves exactly as a
+    /// plain resolution.
+    fn resolve_observed(&self, bids: &[Bid], observer: &mut impl AuctionObserver) -> Sales {
+        if self.dynamic_reserve.is_some() {
+            return reserve::apply_dynamic_reserve(self, bids).resolve_observed(bids, observer);
+        }
+        #[cfg(feature = "tracing")]
+        let _span = trace::resolve_span(self, bids).entered();
+        #[cfg(feature = "metrics")]
+        let started = std::time::Instant::now();
+        let in_currency = self.in_currency(bids);
+        let on_grid = tick::apply_tick_size(self, &in_currency);
+        let capped = bid_cap::apply_bid_cap(self, &on_grid);
+        if observer.enabled() {
+            let mismatched = self.currency_rejected(bids);
+            for bid in bids.iter().filter(|bid| mismatched.contains(&bid.id)) {
+                observer.on_bid_rejected(bid, RejectReason::CurrencyMismatch);
+            }
+            for bid in in_currency
+                .iter()
+                .filter(|bid| tick::is_rejected(self, bid))
+            {
+                observer.on_bid_rejected(bid, RejectReason::OffTick);
+            }
+            for bid in on_grid.iter().filter(|bid| bid_cap::is_rejected(self, bid)) {
+                observer.on_bid_rejected(bid, RejectReason::ExceedsBidCap);
+            }
         }
+        let mut sales = match self.buy_now_price {
+            Some(buy_now_price) => {
+                strategies::buy_now_observed(self, &capped, buy_now_price, observer)
+            }
+            None if observer.enabled() => {
+                self.strategy.resolve_observed_by(self, &capped, observer)
+            }
+            None => self.strategy.resolve(self, &capped),
+        };
+        strategies::record_requested(&mut sales, bids);
+        self.tag_currency(&mut sales);
+        self.sales_ordering.sort(&mut sales, bids);
+        #[cfg(feature = "tracing")]
+        trace::resolved(self, bids, &sales);
+        #[cfg(feature = "metrics")]
+        telemetry::resolved(self, bids, &sales, started.elapsed());
+        observer.on_complete(&sales);
+        sales
+    }
+
+    /// Resolve the bids, checking the auction and bids first and the sale
+    /// totals after.
+    ///
+    /// Fails if the auction or bids fail [`Auction::check_bids`], or a sale's
+    /// total or the revenue does not fit in an `i128` number of cents.
+    pub fn try_resolve_bids(&self, bids: Bids) -> Result<Sales, AuctionError> {
+        self.check_bids(&bids)?;
+        let sales = self.resolve_bids(bids);
+        sales.total_revenue().ok_or(AuctionError::AmountOverflow)?;
+        Ok(sales)
+    }
+
+    /// Check the auction and bids can be resolved, without resolving them.
+    ///
+    /// Fails if the auction is misconfigured, or a bid is invalid or needs a
+    /// strategy the auction does not use. Bids not in the auction's currency
+    /// fail the batch unless the auction filters them out.
+    pub fn check_bids(&self, bids: &[Bid]) -> Result<(), AuctionError> {
+        self.validate()?;
+        if self.currency_mismatch == CurrencyMismatch::Reject {
+            let bid_ids = self.currency_mismatches(bids);
+            if !bid_ids.is_empty() {
+                return Err(AuctionError::CurrencyMismatch { bid_ids });
+            }
+        }
+        for bid in bids {
+            bid.validate().map_err(|error| AuctionError::InvalidBid {
+                bid_id: bid.id,
+                error,
+            })?;
+            if bid.proxy && !matches!(self.strategy, AuctionStrategy::Proxy) {
+                return Err(AuctionError::StrategyMismatch {
+                    bid_id: bid.id,
+                    reason: "proxy bids need a proxy auction",
+                });
+            }
+            if bid.priority.is_some()
+                && !matches!(
+                    self.strategy,
+                    AuctionStrategy::SinglePrice | AuctionStrategy::MultiPrice
+                )
+            {
+                return Err(AuctionError::StrategyMismatch {
+                    bid_id: bid.id,
+                    reason: "priority tiers need a single or multi price auction",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve the bids, recording why each bid won, lost or was trimmed.
+    ///
+    /// The outcomes follow the order the bids were ranked in. Only single and
+    /// multi price auctions are explained, other strategies resolve as usual
+    /// with no outcomes.
+    pub fn resolve_explained(&self, bids: Bids) -> (Sales, Vec<BidOutcome>) {
+        explain::resolve_explained(self, &bids)
+            .unwrap_or_else(|| (self.resolve_bids_ref(&bids), Vec::new()))
+    }
+
+    /// Resolve the bids ranked by the scorer, larger scores first, rather than
+    /// by amount.
+    ///
+    /// Only single and multi price auctions without a buy-now price are
+    /// scored, other auctions resolve as usual. Multi price winners pay their
+    /// own amount. Single price winners pay the amount of the lowest scoring
+    /// winner, whatever the pricing rule, but never more than their own amount,
+    /// or less in a reverse auction.
+    pub fn resolve_scored(&self, bids: Bids, scorer: impl Fn(&Bid) -> i64) -> Sales {
+        scoring::resolve_scored(self, &bids, scorer).unwrap_or_else(|| self.resolve_bids(bids))
+    }
+
+    /// Resolve the bids against the given auction, yielding the sales lazily.
+    ///
+    /// Multi price auctions emit each price level's sales as they are reached,
+    /// without collecting every sale first, when the sales are returned in
+    /// ranked order. Every other strategy needs the
+    /// whole outcome before charging anything, single price must know the
+    /// clearing price for instance, so their sales are resolved up front and
+    /// then yielded.
+    pub fn resolve_iter(&self, bids: Bids) -> impl Iterator<Item = Sale> + '_ {
+        let sales: Box<dyn Iterator<Item = Sale>> = match self.strategy {
+            AuctionStrategy::MultiPrice
+                if self.buy_now_price.is_none()
+                    && self.dynamic_reserve.is_none()
+                    && self.marginal_pricing == MarginalPricing::OwnAmount
+                    && self.sales_ordering == SalesOrdering::Ranked
+                    && self.max_bid_fraction.is_none()
+                    && self.currency.is_none()
+                    && self.currency_mismatches(&bids).is_empty() =>
+            {
+                let requested: HashMap<Uuid, usize> =
+                    bids.iter().map(|bid| (bid.id, bid.quantity)).collect();
+                let bids = match tick::apply_tick_size(self, &bids) {
+                    Cow::Borrowed(_) => bids,
+                    Cow::Owned(on_grid) => on_grid,
+                };
+                Box::new(
+                    strategies::multi_price_iter(self, bids).map(move |mut sale| {
+                        sale.requested_quantity = sale
+                            .bid_id
+                            .and_then(|bid_id| requested.get(&bid_id).copied());
+                        sale
+                    }),
+                )
+            }
+            _ => Box::new(self.resolve_bids(bids).into_iter()),
+        };
+        sales
+    }
+
+    /// Resolve the bids against the given auction, summarising the outcome.
+    ///
+    /// Fails if the revenue does not fit in an `i128` number of cents.
+    pub fn resolve(&self, bids: Bids) -> Result<AuctionResult, AuctionError> {
+        let auction = reserve::apply_dynamic_reserve(self, &bids);
+        let reserve_status = auction.reserve_status(&bids);
+        Ok(AuctionResult::new(&auction, auction.resolve_bids(bids))?
+            .with_reserve_status(reserve_status))
+    }
+
+    /// The flat reserve price that would have earned the most revenue from the
+    /// bids, with the revenue and lots sold at every candidate.
+    ///
+    /// Each distinct valid bid amount is tried as the reserve, in place of the
+    /// auction's reserve, reserve schedule or dynamic reserve, and the bids are
+    /// resolved as [`Auction::resolve_bids`] would.
+    pub fn optimal_reserve(&self, bids: &[Bid]) -> ReserveSweep {
+        reserve::sweep_reserves(self, bids)
+    }
+
+    /// Resolve the bids, keeping each bidder's charges within their budget.
+    ///
+    /// Bids are filled in rank order, so a bidder keeps their best bids and
+    /// the fill that would take them over budget is trimmed to the units they
+    /// can still afford, dropping their later fills. The freed lots pass to
+    /// the following bids. Budgets are checked against each bid's own amount,
+    /// which is the most any built-in strategy charges for it. Bidders without
+    /// a budget are unconstrained.
+    pub fn resolve_with_budgets(&self, bids: Bids, budgets: HashMap<Uuid, i64>) -> Sales {
+        let auction = Self {
+            budgets,
+            ..self.clone()
+        };
+        auction.resolve_bids(bids)
+    }
+
+    /// Resolve the bids, drawing the order of bids tied for the last lots at
+    /// random from the seed.
+    ///
+    /// Only the tied group at the margin is shuffled, so the same seed and bids
+    /// always give the same sales and the draw can be replayed for an audit.
+    /// Overrides the auction's own tie break.
+    pub fn resolve_bids_seeded(&self, bids: Bids, seed: u64) -> Sales {
+        let auction = Self {
+            tie_break: TieBreak::Random { seed },
+            ..self.clone()
+        };
+        auction.resolve_bids(bids)
+    }
+
+    /// Resolve ladders of price steps alongside single bids, each filled step
+    /// selling to the ladder's bidder.
+    pub fn resolve_ladders(&self, ladders: &[LadderBid], bids: Bids) -> Sales {
+        let steps = ladders.iter().flat_map(LadderBid::bids);
+        self.resolve_bids(steps.chain(bids).collect())
+    }
+
+    /// Resolve the live bids at the given time, returning the sales and the
+    /// bids that had expired or were submitted after the auction closed.
+    ///
+    /// [`Auction::is_late`] tells the late bids apart from the expired ones.
+    pub fn resolve_bids_at(&self, bids: Bids, now: Timestamp) -> (Sales, Bids) {
+        let (refused, live) = bids
+            .into_iter()
+            .partition(|bid| bid.is_expired(now) || self.is_late(bid));
+        (self.resolve_bids(live), refused)
+    }
+
+    /// Resolve the live bids at the given time, reporting every bid that won
+    /// nothing with the reason why.
+    ///
+    /// Each input bid appears exactly once, as a winner or a rejection.
+    pub fn resolve_bids_full(&self, bids: Bids, now: Timestamp) -> FullResolution {
+        explain::resolve_full(self, bids, now)
+    }
+
+    /// Resolve the bids, logging each step as an event.
+    ///
+    /// The sales are those [`Auction::resolve_bids`] makes, and
+    /// [`replay_events`] rebuilds them exactly from the events. Every bid is
+    /// logged once, as accepted or rejected, as the resolution decides it.
+    pub fn resolve_with_events(&self, bids: Bids) -> (Sales, Vec<AuctionEvent>) {
+        events::resolve_with_events(self, &bids)
+    }
+
+    /// Resolve the bids, calling the observer's hooks for each rejection and
+    /// allocation in turn and once the sales are complete.
+    ///
+    /// The sales are those [`Auction::resolve_bids`] makes, which calls no
+    /// hooks at all.
+    pub fn resolve_with_observer(&self, bids: Bids, observer: &mut impl AuctionObserver) -> Sales {
+        observer::resolve_with_observer(self, &bids, observer)
+    }
+
+    /// Resolve the live bids at the given time, summarising the outcome and
+    /// reporting the bids that had expired or were submitted after the close.
+    pub fn resolve_at(&self, bids: Bids, now: Timestamp) -> Result<AuctionResult, AuctionError> {
+        let (late, on_time): (Bids, Bids) = bids.into_iter().partition(|bid| self.is_late(bid));
+        let (expired, live): (Bids, Bids) =
+            on_time.into_iter().partition(|bid| bid.is_expired(now));
+        let auction = reserve::apply_dynamic_reserve(self, &live);
+        let reserve_status = auction.reserve_status(&live);
+        Ok(AuctionResult::new(&auction, auction.resolve_bids(live))?
+            .with_expired(expired)
+            .with_late(late)
+            .with_reserve_status(reserve_status))
+    }
+
+    /// Checks the configuration makes sense.
+    fn validate(&self) -> Result<(), AuctionConfigError> {
+        if self.lots == 0 {
+            return Err(AuctionConfigError::ZeroLots);
+        }
+        if self.reserve_price < 0 {
+            return Err(AuctionConfigError::NegativeReservePrice(self.reserve_price));
+        }
+        if let Some(tick_size) = self.tick_size.filter(|&tick_size| tick_size <= 0) {
+            return Err(AuctionConfigError::NonPositiveTickSize(tick_size));
+        }
+        if self
+            .max_bid_fraction
+            .is_some_and(|(_, denominator)| denominator == 0)
+        {
+            return Err(AuctionConfigError::ZeroBidFractionDenominator);
+        }
+        if let Some(buy_now_price) = self.buy_now_price {
+            if self.direction == AuctionDirection::Forward && buy_now_price < self.reserve_price {
+                return Err(AuctionConfigError::BuyNowBelowReserve {
+                    buy_now_price,
+                    reserve_price: self.reserve_price,
+                });
+            }
+        }
+        if self
+            .reserve_schedule
+            .windows(2)
+            .any(|pair| pair[1].1 < pair[0].1)
+        {
+            return Err(AuctionConfigError::DecreasingReserveSchedule);
+        }
+        if !self.reserve_schedule.is_empty() {
+            if self.direction == AuctionDirection::Reverse {
+                return Err(AuctionConfigError::StrategyMismatch(
+                    "reserve schedules only apply to forward auctions",
+                ));
+            }
+            if !matches!(
+                self.strategy,
+                AuctionStrategy::SinglePrice | AuctionStrategy::MultiPrice
+            ) {
+                return Err(AuctionConfigError::StrategyMismatch(
+                    "reserve schedules need a single or multi price auction",
+                ));
+            }
+        }
+        if self.pricing_rule != PricingRule::LowestAccepted
+            && !matches!(self.strategy, AuctionStrategy::SinglePrice)
+        {
+            return Err(AuctionConfigError::StrategyMismatch(
+                "pricing rules only apply to single price auctions",
+            ));
+        }
+        if self.marginal_pricing != MarginalPricing::OwnAmount
+            && !matches!(self.strategy, AuctionStrategy::MultiPrice)
+        {
+            return Err(AuctionConfigError::StrategyMismatch(
+                "marginal pricing only applies to multi price auctions",
+            ));
+        }
+        if self.lottery_weighting != LotteryWeighting::Equal
+            && !matches!(self.strategy, AuctionStrategy::Lottery { .. })
+        {
+            return Err(AuctionConfigError::StrategyMismatch(
+                "lottery weighting only applies to lotteries",
+            ));
+        }
+        if self.scored_pricing != ScoredPricing::OwnAmount
+            && !matches!(self.strategy, AuctionStrategy::Scored)
+        {
+            return Err(AuctionConfigError::StrategyMismatch(
+                "scored pricing only applies to scored auctions",
+            ));
+        }
+        if self.partial_fill != PartialFillPolicy::Split {
+            if !matches!(
+                self.strategy,
+                AuctionStrategy::SinglePrice | AuctionStrategy::MultiPrice
+            ) {
+                return Err(AuctionConfigError::StrategyMismatch(
+                    "partial fill policies need a single or multi price auction",
+                ));
+            }
+            if self.allocation == AllocationPolicy::ProRata {
+                return Err(AuctionConfigError::StrategyMismatch(
+                    "pro rata allocation always splits the marginal bids",
+                ));
+            }
+        }
+        match self.strategy {
+            AuctionStrategy::Dutch { decrement, .. } if decrement <= 0 => {
+                return Err(AuctionConfigError::NonPositiveDecrement(decrement));
+            }
+            AuctionStrategy::Japanese { increment, .. } if increment <= 0 => {
+                return Err(AuctionConfigError::NonPositiveIncrement(increment));
+            }
+            AuctionStrategy::Proxy if self.lots > 1 => {
+                return Err(AuctionConfigError::StrategyMismatch(
+                    "proxy bidding only supports a single lot",
+                ));
+            }
+            AuctionStrategy::SinglePrice
+            | AuctionStrategy::MultiPrice
+            | AuctionStrategy::Scored
+            | AuctionStrategy::Custom(_) => {}
+            _ if self.direction == AuctionDirection::Reverse => {
+                return Err(AuctionConfigError::StrategyMismatch(
+                    "only single, multi price and scored auctions can run in reverse",
+                ));
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The reserve price of the lot at the given index, counting from zero.
+    ///
+    /// Lots past the end of the schedule keep its last reserve.
+    pub(crate) fn reserve_at(&self, lot: usize) -> i64 {
+        let mut end: usize = 0;
+        for &(lots, reserve) in &self.reserve_schedule {
+            end = end.saturating_add(lots);
+            if lot < end {
+                return reserve;
+            }
+        }
+        self.reserve_schedule
+            .last()
+            .map_or(self.reserve_price, |&(_, reserve)| reserve)
+    }
+
+    /// How many of the lots after those already sold a bid of the given
+    /// amount meets the reserve of, stopping at the first it does not.
+    pub(crate) fn lots_within_reserve(&self, sold: usize, amount: i64) -> usize {
+        if self.reserve_schedule.is_empty() || self.direction == AuctionDirection::Reverse {
+            return usize::MAX;
+        }
+        let mut within: usize = 0;
+        let mut start: usize = 0;
+        for &(lots, reserve) in &self.reserve_schedule {
+            let end = start.saturating_add(lots);
+            if end > sold {
+                if amount < reserve {
+                    return within;
+                }
+                within = within.saturating_add(end - start.max(sold));
+            }
+            start = end;
+        }
+        usize::MAX
+    }
+
+    /// How close the best bid came to the soft reserve, None without one.
+    fn reserve_status(&self, bids: &[Bid]) -> Option<ReserveStatus> {
+        let tolerance_bps = self.soft_reserve_tolerance_bps?;
+        let best_bid = bids
+            .iter()
+            .filter(|bid| bid.quantity > 0)
+            .map(|bid| bid.amount)
+            .max();
+        Some(ReserveStatus::assess(
+            self.reserve_price,
+            tolerance_bps,
+            best_bid,
+        ))
+    }
+
+    /// Whether a bid of the given amount may win, going by the direction.
+    pub(crate) fn qualifies(&self, amount: i64) -> bool {
+        match self.direction {
+            AuctionDirection::Forward => amount >= self.reserve_price,
+            AuctionDirection::Reverse => self.price_ceiling.is_none_or(|ceiling| amount <= ceiling),
+        }
+    }
+}
+
+/// The AuctionBuilder type. Used to easily create Auctions.
+///
+/// Fields missing when deserializing take the values [`AuctionBuilder::new`]
+/// gives them, so a configuration need only name what it changes.
+#[derive(Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default = "AuctionBuilder::new"))]
+pub struct AuctionBuilder {
+    lots: usize,
+    reserve_price: Option<i64>,
+    reserve_schedule: Vec<(usize, i64)>,
+    soft_reserve_tolerance_bps: Option<u32>,
+    buy_now_price: Option<i64>,
+    tick_size: Option<i64>,
+    tick_policy: TickPolicy,
+    tick_anchor: TickAnchor,
+    rounding_policy: RoundingPolicy,
+    max_bid_fraction: Option<(usize, usize)>,
+    bid_cap_policy: BidCapPolicy,
+    closes_at: Option<Timestamp>,
+    untimed_bids: UntimedBids,
+    currency: Option<Currency>,
+    currency_mismatch: CurrencyMismatch,
+    price_ceiling: Option<i64>,
+    strategy: Option<AuctionStrategy>,
+    pricing_rule: PricingRule,
+    marginal_pricing: MarginalPricing,
+    lottery_weighting: LotteryWeighting,
+    scored_pricing: ScoredPricing,
+    allocation: AllocationPolicy,
+    partial_fill: PartialFillPolicy,
+    sales_ordering: SalesOrdering,
+    direction: AuctionDirection,
+    tie_break: TieBreak,
+    secondary_sort: SecondarySort,
+    max_lots_per_bidder: Option<usize>,
+    aggregate_bidders: bool,
+    bid_increment: i64,
+    allow_withdrawals: bool,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    dynamic_reserve: Option<DynamicReserve>,
+    #[cfg(feature = "metrics")]
+    metrics_label: Option<String>,
+}
+
+impl AuctionBuilder {
+    /// Create a new builder.
+    pub const fn new() -> Self {
+        Self {
+            lots: 1,
+            reserve_price: None,
+            reserve_schedule: Vec::new(),
+            soft_reserve_tolerance_bps: None,
+            buy_now_price: None,
+            tick_size: None,
+            tick_policy: TickPolicy::Reject,
+            tick_anchor: TickAnchor::Zero,
+            rounding_policy: RoundingPolicy::TowardBuyer,
+            max_bid_fraction: None,
+            bid_cap_policy: BidCapPolicy::Reject,
+            closes_at: None,
+            untimed_bids: UntimedBids::Accept,
+            currency: None,
+            currency_mismatch: CurrencyMismatch::Reject,
+            price_ceiling: None,
+            strategy: None,
+            pricing_rule: PricingRule::LowestAccepted,
+            marginal_pricing: MarginalPricing::OwnAmount,
+            lottery_weighting: LotteryWeighting::Equal,
+            scored_pricing: ScoredPricing::OwnAmount,
+            allocation: AllocationPolicy::Priority,
+            partial_fill: PartialFillPolicy::Split,
+            sales_ordering: SalesOrdering::Ranked,
+            direction: AuctionDirection::Forward,
+            tie_break: TieBreak::InputOrder,
+            secondary_sort: SecondarySort::EarliestSubmitted,
+            max_lots_per_bidder: None,
+            aggregate_bidders: false,
+            bid_increment: 1,
+            allow_withdrawals: true,
+            dynamic_reserve: None,
+            #[cfg(feature = "metrics")]
+            metrics_label: None,
+        }
+    }
+
+    /// Set the number of auction lots.
+    pub const fn lots(mut self, lots: usize) -> Self {
+        self.lots = lots;
+        self
+    }
+
+    /// Set the reserve price of the auction.
+    pub const fn reserve_price(mut self, reserve_price: i64) -> Self {
+        self.reserve_price = Some(reserve_price);
+        self
+    }
+
+    /// Set the reserve price of consecutive runs of lots, as pairs of the number
+    /// of lots and their reserve, replacing the single reserve price.
+    ///
+    /// The reserves must not decrease, and lots past the end of the schedule
+    /// keep its last reserve. A bid must meet the reserve of every lot it
+    /// takes, so it is only filled with the lots it qualifies for.
+    pub fn reserve_schedule(mut self, reserve_schedule: Vec<(usize, i64)>) -> Self {
+        self.reserve_schedule = reserve_schedule;
+        self
+    }
+
+    /// Set a reserve price that also reports when the best bid falls short of
+    /// it by no more than the tolerance, in basis points of the reserve.
+    ///
+    /// Sales still respect the reserve, only the result's
+    /// [`AuctionResult::reserve_status`] changes. Soft reserves apply to
+    /// forward auctions.
+    pub const fn soft_reserve(mut self, reserve_price: i64, tolerance_bps: u32) -> Self {
+        self.reserve_price = Some(reserve_price);
+        self.soft_reserve_tolerance_bps = Some(tolerance_bps);
+        self
+    }
+
+    /// Set a price at which bids win their lots immediately, paying the buy-now
+    /// price, before the remaining lots are resolved with the strategy.
+    ///
+    /// Reverse auctions accept offers at or below the price.
+    pub const fn buy_now_price(mut self, buy_now_price: i64) -> Self {
+        self.buy_now_price = Some(buy_now_price);
+        self
+    }
+
+    /// Set the tick size bid amounts must be a multiple of, counted from the
+    /// tick anchor.
+    pub const fn tick_size(mut self, tick_size: i64) -> Self {
+        self.tick_size = Some(tick_size);
+        self
+    }
+
+    /// Set whether off-grid bids are rejected or rounded down to the grid.
+    pub const fn tick_policy(mut self, tick_policy: TickPolicy) -> Self {
+        self.tick_policy = tick_policy;
+        self
+    }
+
+    /// Set the price the tick grid is counted from.
+    pub const fn tick_anchor(mut self, tick_anchor: TickAnchor) -> Self {
+        self.tick_anchor = tick_anchor;
+        self
+    }
+
+    /// Set how derived prices between cents or ticks are rounded.
+    pub const fn rounding_policy(mut self, rounding_policy: RoundingPolicy) -> Self {
+        self.rounding_policy = rounding_policy;
+        self
+    }
+
+    /// Set the largest share of the lots one bid may ask for, as
+    /// `numerator / denominator` of them rounded down.
+    pub const fn max_bid_fraction(mut self, numerator: usize, denominator: usize) -> Self {
+        self.max_bid_fraction = Some((numerator, denominator));
+        self
+    }
+
+    /// Set whether bids over the bid cap are rejected or clamped to it.
+    pub const fn bid_cap_policy(mut self, bid_cap_policy: BidCapPolicy) -> Self {
+        self.bid_cap_policy = bid_cap_policy;
+        self
+    }
+
+    /// Set the last time a bid may be submitted. Bids submitted later are
+    /// refused when resolving at a time.
+    pub const fn closes_at(mut self, closes_at: Timestamp) -> Self {
+        self.closes_at = Some(closes_at);
+        self
+    }
+
+    /// Set the currency bids must be in, and sales are priced in.
+    pub const fn currency(mut self, currency: Currency) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Set whether a batch with bids in another currency is refused or has
+    /// those bids dropped.
+    pub const fn currency_mismatch(mut self, currency_mismatch: CurrencyMismatch) -> Self {
+        self.currency_mismatch = currency_mismatch;
+        self
+    }
+
+    /// Set whether bids without a submission time are accepted once the
+    /// auction has a deadline.
+    pub const fn untimed_bids(mut self, untimed_bids: UntimedBids) -> Self {
+        self.untimed_bids = untimed_bids;
+        self
+    }
+
+    /// Set the highest offer a reverse auction will accept.
+    pub const fn price_ceiling(mut self, price_ceiling: i64) -> Self {
+        self.price_ceiling = Some(price_ceiling);
+        self
+    }
+
+    /// Set the strategy of the auction.
+    pub fn strategy(mut self, strategy: AuctionStrategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Set the clearing price a single price auction charges.
+    pub const fn pricing_rule(mut self, pricing_rule: PricingRule) -> Self {
+        self.pricing_rule = pricing_rule;
+        self
+    }
+
+    /// Set what the marginal bids of a multi price auction pay.
+    pub const fn marginal_pricing(mut self, marginal_pricing: MarginalPricing) -> Self {
+        self.marginal_pricing = marginal_pricing;
+        self
+    }
+
+    /// Set each bid's chance of being drawn in a lottery.
+    pub const fn lottery_weighting(mut self, lottery_weighting: LotteryWeighting) -> Self {
+        self.lottery_weighting = lottery_weighting;
+        self
+    }
+
+    /// Set what the winners of a scored auction pay.
+    pub const fn scored_pricing(mut self, scored_pricing: ScoredPricing) -> Self {
+        self.scored_pricing = scored_pricing;
+        self
+    }
+
+    /// Set a reserve worked out from the bids, which overrides the reserve
+    /// price and any reserve schedule.
+    ///
+    /// It is evaluated once over the valid bids before they are allocated, and
+    /// a negative reserve is taken as zero. The evaluated reserve is reported
+    /// by [`AuctionResult::reserve_price`].
+    pub fn dynamic_reserve(
+        mut self,
+        reserve: impl Fn(&[Bid]) -> i64 + Send + Sync + 'static,
+    ) -> Self {
+        self.dynamic_reserve = Some(DynamicReserve::new(reserve));
+        self
+    }
+
+    /// Set a user supplied strategy for the auction.
+    pub fn custom_strategy(self, strategy: Box<dyn Strategy>) -> Self {
+        self.strategy(AuctionStrategy::Custom(Arc::from(strategy)))
+    }
+
+    /// Set how lots are shared between equal bids at the margin.
+    pub const fn allocation(mut self, allocation: AllocationPolicy) -> Self {
+        self.allocation = allocation;
+        self
+    }
+
+    /// Set what happens to the bid that asks for more than the lots remaining.
+    pub const fn partial_fill(mut self, partial_fill: PartialFillPolicy) -> Self {
+        self.partial_fill = partial_fill;
+        self
+    }
+
+    /// Set the order resolved sales are returned in.
+    pub const fn sales_ordering(mut self, sales_ordering: SalesOrdering) -> Self {
+        self.sales_ordering = sales_ordering;
+        self
+    }
+
+    /// Set which side of the auction is competing.
+    pub const fn direction(mut self, direction: AuctionDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Set how equal bids competing for the last lots are ordered.
+    pub const fn tie_break(mut self, tie_break: TieBreak) -> Self {
+        self.tie_break = tie_break;
+        self
+    }
+
+    /// Set how bids with the same amount are ranked.
+    pub const fn secondary_sort(mut self, secondary_sort: SecondarySort) -> Self {
+        self.secondary_sort = secondary_sort;
+        self
+    }
+
+    /// Set the `auction_id` label the auction's metrics are recorded under,
+    /// behind the `metrics` feature.
+    #[cfg(feature = "metrics")]
+    pub fn metrics_label(mut self, auction_id: impl Into<String>) -> Self {
+        self.metrics_label = Some(auction_id.into());
+        self
+    }
+
+    /// Set the most lots any one bidder may win across all of their bids.
+    pub const fn max_lots_per_bidder(mut self, max_lots_per_bidder: usize) -> Self {
+        self.max_lots_per_bidder = Some(max_lots_per_bidder);
+        self
+    }
+
+    /// Set whether each bidder's bids form one demand schedule.
+    ///
+    /// Every bid still competes at its own amount, so a bidder's best price
+    /// does not lift their other bids. Where a price level is contended, the
+    /// bidder's bids there are filled together, in the place of whichever of
+    /// them the tie break puts first, and pro rata shares are worked out per
+    /// bidder before being handed to their bids in ranked order. Lot caps and
+    /// budgets always apply per bidder.
+    pub const fn aggregate_bidders(mut self, aggregate_bidders: bool) -> Self {
+        self.aggregate_bidders = aggregate_bidders;
+        self
+    }
+
+    /// Set how far a proxy bid must beat the next bid by.
+    pub const fn bid_increment(mut self, bid_increment: i64) -> Self {
+        self.bid_increment = bid_increment;
+        self
+    }
+
+    /// Set whether sessions let bidders withdraw accepted bids, as they do by
+    /// default.
+    pub const fn allow_withdrawals(mut self, allow_withdrawals: bool) -> Self {
+        self.allow_withdrawals = allow_withdrawals;
+        self
+    }
+
+    /// Build the auction, checking the configuration makes sense.
+    pub fn try_build(self) -> Result<Auction, AuctionConfigError> {
+        let auction = self.build();
+        auction.validate()?;
+        Ok(auction)
+    }
+
+    /// Build the auction without checking the configuration.
+    pub fn build(self) -> Auction {
+        Auction {
+            lots: self.lots,
+            reserve_price: self
+                .reserve_schedule
+                .first()
+                .map(|&(_, reserve)| reserve)
+                .or(self.reserve_price)
+                .unwrap_or_default(),
+            reserve_schedule: self.reserve_schedule,
+            soft_reserve_tolerance_bps: self.soft_reserve_tolerance_bps,
+            buy_now_price: self.buy_now_price,
+            tick_size: self.tick_size,
+            tick_policy: self.tick_policy,
+            tick_anchor: self.tick_anchor,
+            rounding_policy: self.rounding_policy,
+            max_bid_fraction: self.max_bid_fraction,
+            bid_cap_policy: self.bid_cap_policy,
+            closes_at: self.closes_at,
+            untimed_bids: self.untimed_bids,
+            currency: self.currency,
+            currency_mismatch: self.currency_mismatch,
+            price_ceiling: self.price_ceiling,
+            strategy: self.strategy.unwrap_or(AuctionStrategy::SinglePrice),
+            pricing_rule: self.pricing_rule,
+            marginal_pricing: self.marginal_pricing,
+            lottery_weighting: self.lottery_weighting,
+            scored_pricing: self.scored_pricing,
+            allocation: self.allocation,
+            partial_fill: self.partial_fill,
+            sales_ordering: self.sales_ordering,
+            direction: self.direction,
+            tie_break: self.tie_break,
+            secondary_sort: self.secondary_sort,
+            max_lots_per_bidder: self.max_lots_per_bidder,
+            aggregate_bidders: self.aggregate_bidders,
+            bid_increment: self.bid_increment,
+            allow_withdrawals: self.allow_withdrawals,
+            dynamic_reserve: self.dynamic_reserve,
+            budgets: HashMap::new(),
+            awarded: HashMap::new(),
+            #[cfg(feature = "metrics")]
+            metrics_label: self.metrics_label,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    /// Sells a lot to each bid at or above the reserve, in submission order.
+    #[derive(Debug)]
+    struct FirstComeFirstServed;
+
+    impl Strategy for FirstComeFirstServed {
+        fn resolve(&self, auction: &Auction, bids: &[Bid]) -> Sales {
+            bids.iter()
+                .filter(|bid| bid.amount >= auction.reserve_price)
+                .take(auction.lots)
+                .map(|bid| Sale::new(bid.id, auction.reserve_price, 1))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn custom_strategy_resolves_bids() {
+        let bids: Bids = vec![bid![10, 1], bid![5, 1], bid![30, 1], bid![20, 1]];
+        let ids = [bids[0].id, bids[2].id];
+        let auction = AuctionBuilder::new()
+            .custom_strategy(Box::new(FirstComeFirstServed))
+            .lots(2)
+            .reserve_price(8)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].bidder_id, ids[0]);
+        assert_eq!(sales[1].bidder_id, ids[1]);
+        assert!(sales.iter().all(|sale| sale.amount == 8));
+    }
+
+    #[test]
+    fn resolve_bids_ref_leaves_bids_for_another_strategy() {
+        let bids = [bid![10, 1], bid![30, 1], bid![20, 1]];
+        let ids: Vec<Uuid> = bids.iter().map(|bid| bid.id).collect();
+        let single = AuctionBuilder::new().lots(2).build();
+        let multi = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+
+        let single_sales = single.resolve_bids_ref(&bids);
+        let multi_sales = multi.resolve_bids_ref(&bids);
+
+        let after: Vec<Uuid> = bids.iter().map(|bid| bid.id).collect();
+        assert_eq!(after, ids);
+        assert!(single_sales.iter().all(|sale| sale.amount == 20));
+        assert_eq!(multi_sales[0].amount, 30);
+        assert_eq!(multi_sales[1].amount, 20);
+        assert_eq!(single.resolve_bids(bids.to_vec()), single_sales);
+    }
+
+    #[test]
+    fn resolve_iter_matches_resolve_bids() {
+        for seed in 0..20 {
+            let bids = generators::large_quantities(seed, 200, 49, 4);
+            for allocation in [AllocationPolicy::Priority, AllocationPolicy::ProRata] {
+                for strategy in [AuctionStrategy::MultiPrice, AuctionStrategy::SinglePrice] {
+                    let auction = AuctionBuilder::new()
+                        .strategy(strategy)
+                        .allocation(allocation)
+                        .lots(seed as usize * 10 + 1)
+                        .reserve_price(10)
+                        .build();
+                    let streamed: Sales = auction.resolve_iter(bids.clone()).collect();
+                    assert_eq!(streamed, auction.resolve_bids(bids.clone()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_iter_stops_early() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .build();
+        let mut sales = auction.resolve_iter(vec![bid![30, 1], bid![20, 1], bid![10, 1]]);
+        assert_eq!(sales.next().map(|sale| sale.amount), Some(30));
+    }
+
+    #[test]
+    fn built_in_strategies_implement_strategy() {
+        let auction = AuctionBuilder::new().lots(2).build();
+        let bids: Bids = vec![bid![10, 1], bid![20, 1]];
+        let sales = AuctionStrategy::MultiPrice.resolve(&auction, &bids);
+        assert_eq!(sales[0].amount, 20);
+        assert_eq!(sales[1].amount, 10);
+    }
+
+    #[test]
+    fn try_build_accepts_valid_config() {
+        let auction = AuctionBuilder::new().lots(3).reserve_price(10).try_build();
+        assert!(auction.is_ok());
+    }
+
+    #[test]
+    fn try_build_rejects_zero_lots() {
+        let error = AuctionBuilder::new().lots(0).try_build().unwrap_err();
+        assert_eq!(error, AuctionConfigError::ZeroLots);
+    }
+
+    #[test]
+    fn try_build_rejects_negative_reserve() {
+        let error = AuctionBuilder::new()
+            .reserve_price(-1)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(error, AuctionConfigError::NegativeReservePrice(-1));
+    }
+
+    #[test]
+    fn try_build_rejects_non_positive_decrement() {
+        let error = AuctionBuilder::new()
+            .strategy(AuctionStrategy::Dutch {
+                start_price: 100,
+                decrement: 0,
+            })
+            .try_build()
+            .unwrap_err();
+        assert_eq!(error, AuctionConfigError::NonPositiveDecrement(0));
+    }
+
+    #[test]
+    fn reserve_schedule_replaces_reserve_price() {
+        let auction = AuctionBuilder::new()
+            .reserve_price(50)
+            .reserve_schedule(vec![(2, 100), (2, 120)])
+            .build();
+        assert_eq!(auction.reserve_price(), 100);
+        assert_eq!(auction.reserve_schedule(), &[(2, 100), (2, 120)]);
+        assert_eq!(auction.reserve_at(1), 100);
+        assert_eq!(auction.reserve_at(2), 120);
+        assert_eq!(auction.reserve_at(10), 120);
+    }
+
+    #[test]
+    fn lots_within_reserve_counts_from_lots_sold() {
+        let auction = AuctionBuilder::new()
+            .lots(6)
+            .reserve_schedule(vec![(2, 100), (2, 120), (2, 150)])
+            .build();
+        assert_eq!(auction.lots_within_reserve(0, 99), 0);
+        assert_eq!(auction.lots_within_reserve(0, 130), 4);
+        assert_eq!(auction.lots_within_reserve(1, 130), 3);
+        assert_eq!(auction.lots_within_reserve(4, 130), 0);
+        assert_eq!(auction.lots_within_reserve(3, 150), usize::MAX);
+    }
+
+    #[test]
+    fn try_build_rejects_non_positive_tick_size() {
+        let error = AuctionBuilder::new().tick_size(0).try_build().unwrap_err();
+        assert_eq!(error, AuctionConfigError::NonPositiveTickSize(0));
+    }
+
+    #[test]
+    fn try_build_rejects_buy_now_below_reserve() {
+        let error = AuctionBuilder::new()
+            .reserve_price(100)
+            .buy_now_price(50)
+            .try_build()
+            .unwrap_err();
+        assert_eq!(
+            error,
+            AuctionConfigError::BuyNowBelowReserve {
+                buy_now_price: 50,
+                reserve_price: 100
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_iter_includes_buy_now_sales() {
+        let bids: Bids = vec![bid![150, 1], bid![40, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .buy_now_price(100)
+            .build();
+        let streamed: Sales = auction.resolve_iter(bids.clone()).collect();
+        assert_eq!(streamed, auction.resolve_bids(bids));
+    }
+
+    #[test]
+    fn try_build_rejects_decreasing_reserve_schedule() {
+        let error = AuctionBuilder::new()
+            .lots(4)
+            .reserve_schedule(vec![(2, 120), (2, 100)])
+            .try_build()
+            .unwrap_err();
+        assert_eq!(error, AuctionConfigError::DecreasingReserveSchedule);
+    }
+
+    #[test]
+    fn try_build_rejects_reserve_schedule_for_other_strategies() {
+        let error = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .reserve_schedule(vec![(1, 100)])
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(error, AuctionConfigError::StrategyMismatch(_)));
+
+        let error = AuctionBuilder::new()
+            .direction(AuctionDirection::Reverse)
+            .reserve_schedule(vec![(1, 100)])
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(error, AuctionConfigError::StrategyMismatch(_)));
+    }
+
+    #[test]
+    fn try_build_rejects_reverse_unsupported_strategy() {
+        let error = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .direction(AuctionDirection::Reverse)
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(error, AuctionConfigError::StrategyMismatch(_)));
+    }
+
+    #[test]
+    fn try_resolve_bids_resolves_valid_bids() {
+        let auction = AuctionBuilder::new().lots(2).build();
+        let bids: Bids = vec![bid![10, 1], bid![20, 1], bid![5, 1]];
+        let sales = auction.try_resolve_bids(bids.clone()).unwrap();
+        assert_eq!(sales, auction.resolve_bids(bids));
+    }
+
+    #[test]
+    fn try_resolve_bids_rejects_misconfigured_auction() {
+        let auction = AuctionBuilder::new().lots(0).build();
+        let error = auction.try_resolve_bids(vec![bid![10, 1]]).unwrap_err();
+        assert_eq!(error, AuctionError::Config(AuctionConfigError::ZeroLots));
+    }
+
+    #[test]
+    fn try_resolve_bids_rejects_invalid_bid() {
+        let auction = AuctionBuilder::new().lots(1).build();
+        let bad_bid = bid![-5, 1];
+        let error = auction
+            .try_resolve_bids(vec![bid![10, 1], bad_bid])
+            .unwrap_err();
+        assert_eq!(
+            error,
+            AuctionError::InvalidBid {
+                bid_id: bad_bid.id,
+                error: BidError::NegativeAmount(-5)
+            }
+        );
+    }
+
+    #[test]
+    fn check_bids_matches_try_resolve_bids() {
+        let auction = AuctionBuilder::new().lots(1).build();
+        let bids = vec![bid![10, 1], bid![20, 0]];
+        assert_eq!(
+            auction.check_bids(&bids),
+            auction.try_resolve_bids(bids.clone()).map(|_| ())
+        );
+        assert!(auction.check_bids(&bids[..1]).is_ok());
+    }
+
+    #[test]
+    fn try_resolve_bids_accepts_total_beyond_i64() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+        let sales = auction.try_resolve_bids(vec![bid![i64::MAX, 2]]).unwrap();
+        assert_eq!(sales.total_revenue(), Some(i128::from(i64::MAX) * 2));
+    }
+
+    #[test]
+    fn try_resolve_bids_rejects_overflowing_revenue() {
+        let mut bids = vec![bid![i64::MAX, usize::MAX]];
+        bids.extend((0..8).map(|_| bid![i64::MAX - 1, 1]));
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .lots(usize::MAX)
+            .build();
+        let error = auction.try_resolve_bids(bids).unwrap_err();
+        assert_eq!(error, AuctionError::AmountOverflow);
+    }
+
+    #[test]
+    fn try_resolve_bids_rejects_proxy_bid_without_proxy_strategy() {
+        let auction = AuctionBuilder::new().lots(1).build();
+        let proxy_bid = bid![10, 1].with_proxy(true);
+        let error = auction.try_resolve_bids(vec![proxy_bid]).unwrap_err();
+        assert!(matches!(
+            error,
+            AuctionError::StrategyMismatch { bid_id, .. } if bid_id == proxy_bid.id
+        ));
+    }
+
+    #[test]
+    fn try_resolve_bids_rejects_tiered_bid_without_ranked_strategy() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::Vcg)
+            .lots(1)
+            .build();
+        let tiered = bid![10, 1].with_priority(0);
+        let error = auction.try_resolve_bids(vec![tiered]).unwrap_err();
+        assert!(matches!(
+            error,
+            AuctionError::StrategyMismatch { bid_id, .. } if bid_id == tiered.id
+        ));
+        assert!(AuctionBuilder::new()
+            .lots(1)
+            .build()
+            .try_resolve_bids(vec![tiered])
+            .is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn auction_error_exposes_source() {
+        use std::error::Error;
+
+        let error = AuctionError::from(AuctionConfigError::ZeroLots);
+        assert_eq!(
+            error.to_string(),
+            "invalid auction: auction must have at least one lot"
+        );
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn bid_validate_rejects_zero_quantity() {
+        assert_eq!(bid![10, 0].validate(), Err(BidError::ZeroQuantity));
+    }
+
+    #[test]
+    fn seeded_resolution_is_reproducible() {
+        let bids: Bids = (0..100).map(|_| bid![10, 1]).collect();
+        let auction = AuctionBuilder::new().lots(10).build();
+        assert_eq!(
+            auction.resolve_bids_seeded(bids.clone(), 42),
+            auction.resolve_bids_seeded(bids, 42)
+        );
+    }
+
+    #[test]
+    fn seeded_resolution_differs_between_seeds() {
+        let bids: Bids = (0..100).map(|_| bid![10, 1]).collect();
+        let auction = AuctionBuilder::new().lots(10).build();
+        assert_ne!(
+            auction.resolve_bids_seeded(bids.clone(), 1),
+            auction.resolve_bids_seeded(bids, 2)
+        );
+    }
+
+    #[test]
+    fn seeded_resolution_only_shuffles_the_margin() {
+        let mut bids: Bids = vec![bid![30, 1], bid![20, 1], bid![20, 1]];
+        bids.extend((0..50).map(|_| bid![10, 1]));
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(5)
+            .build();
+        for seed in 0..20 {
+            let sales = auction.resolve_bids_seeded(bids.clone(), seed);
+            let leaders: Vec<_> = sales[..3].iter().map(|sale| sale.bidder_id).collect();
+            assert_eq!(leaders, vec![bids[0].id, bids[1].id, bids[2].id]);
+            assert!(sales[3..].iter().all(|sale| sale.amount == 10));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn config_error_is_descriptive() {
+        let error: Box<dyn std::error::Error> = Box::new(AuctionConfigError::ZeroLots);
+        assert_eq!(error.to_string(), "auction must have at least one lot");
+    }
+
+    #[test]
+    fn sale_total_multiplies_quantity() {
+        assert_eq!(Sale::new(Uuid::new_v4(), 15, 3).total(), 45);
+    }
+
+    #[test]
+    fn sale_total_beyond_i64_fits() {
+        assert_eq!(
+            Sale::new(Uuid::new_v4(), i64::MAX, 2).total(),
+            i128::from(i64::MAX) * 2
+        );
+        assert_eq!(
+            Sale::new(Uuid::new_v4(), 2, usize::MAX).total(),
+            2 * usize::MAX as i128
+        );
+    }
+
+    #[test]
+    fn sale_from_bid_fills_whole_bid() {
+        let bid = bid![25, 4];
+        let sale = Sale::from(&bid);
+        assert_eq!(sale.bidder_id(), bid.id());
+        assert_eq!(sale.amount(), 25);
+        assert_eq!(sale.quantity(), 4);
+        assert_eq!(sale.bid_id(), Some(bid.id()));
+        assert_eq!(sale.requested_quantity(), Some(4));
+    }
+
+    #[test]
+    fn sale_references_partially_filled_bid() {
+        let bidder = Uuid::new_v4();
+        let bids: Bids = vec![
+            bid![30, 2].with_bidder_id(bidder),
+            bid![20, 5].with_bidder_id(bidder),
+        ];
+        for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+            let auction = AuctionBuilder::new().strategy(strategy).lots(4).build();
+            let sales = auction.resolve_bids_ref(&bids);
+            let fills: Vec<_> = sales
+                .iter()
+                .map(|sale| (sale.bid_id(), sale.quantity(), sale.requested_quantity()))
+                .collect();
+            assert_eq!(
+                fills,
+                vec![
+                    (Some(bids[0].id), 2, Some(2)),
+                    (Some(bids[1].id), 2, Some(5))
+                ]
+            );
+        }
+    }
+
+    #[test]
+    fn resolve_iter_references_partially_filled_bid() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 5]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(4)
+            .build();
+        let last = auction.resolve_iter(bids.clone()).last().unwrap();
+        assert_eq!(last.bid_id(), Some(bids[1].id));
+        assert_eq!(last.quantity(), 2);
+        assert_eq!(last.requested_quantity(), Some(5));
+    }
+
+    #[test]
+    fn min_quantity_defaults_to_zero() {
+        assert_eq!(bid![10, 3].min_quantity(), 0);
+    }
+
+    #[test]
+    fn min_quantity_above_quantity_rejected() {
+        let error = bid![10, 3].with_min_quantity(4).unwrap_err();
+        assert_eq!(
+            error,
+            BidError::MinQuantityExceedsQuantity {
+                min_quantity: 4,
+                quantity: 3
+            }
+        );
+    }
+
+    #[test]
+    fn min_quantity_equal_to_quantity_accepted() {
+        let bid = bid![10, 3].with_min_quantity(3).unwrap();
+        assert_eq!(bid.min_quantity(), 3);
+    }
+
+    #[test]
+    fn bid_expires_at_its_expiry_time() {
+        let bid = bid![10, 1].with_expires_at(100);
+        assert!(!bid.is_expired(99));
+        assert!(bid.is_expired(100));
+        assert!(!bid![10, 1].is_expired(u64::MAX));
+    }
+
+    #[test]
+    fn resolve_bids_at_filters_expired_bids() {
+        let expired = bid![30, 1].with_expires_at(50);
+        let live = bid![20, 1].with_expires_at(500);
+        let untimed = bid![10, 1];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .build();
+        let (sales, lapsed) = auction.resolve_bids_at(vec![expired, live, untimed], 100);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].bidder_id, live.id);
+        assert_eq!(sales[1].bidder_id, untimed.id);
+        assert_eq!(lapsed.len(), 1);
+        assert_eq!(lapsed[0].id, expired.id);
+    }
+
+    #[test]
+    fn resolve_bids_at_every_bid_expired() {
+        let bids: Bids = vec![
+            bid![30, 1].with_expires_at(50),
+            bid![20, 1].with_expires_at(60),
+        ];
+        let auction = AuctionBuilder::new().lots(2).build();
+        let result = auction.resolve_at(bids, 60).unwrap();
+        assert!(result.sales().is_empty());
+        assert_eq!(result.expired().len(), 2);
+        assert_eq!(result.lots_unsold(), 2);
+    }
+
+    fn deadline_auction(untimed_bids: UntimedBids) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .closes_at(100)
+            .untimed_bids(untimed_bids)
+            .build()
+    }
+
+    #[test]
+    fn resolve_bids_at_refuses_late_bids() {
+        let on_time = bid![20, 1].with_submitted_at(100);
+        let late = bid![30, 1].with_submitted_at(101);
+        let untimed = bid![10, 1];
+        let auction = deadline_auction(UntimedBids::Accept);
+        let (sales, refused) = auction.resolve_bids_at(vec![on_time, late, untimed], 200);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].bidder_id, on_time.id);
+        assert_eq!(sales[1].bidder_id, untimed.id);
+        assert_eq!(refused.len(), 1);
+        assert_eq!(refused[0].id, late.id);
+        assert!(auction.is_late(&refused[0]));
+    }
+
+    #[test]
+    fn resolve_bids_at_can_refuse_untimed_bids() {
+        let on_time = bid![20, 1].with_submitted_at(50);
+        let late = bid![30, 1].with_submitted_at(150);
+        let untimed = bid![10, 1];
+        let auction = deadline_auction(UntimedBids::Reject);
+        let (sales, refused) = auction.resolve_bids_at(vec![on_time, late, untimed], 200);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].bidder_id, on_time.id);
+        let refused: Vec<_> = refused.iter().map(|bid| bid.id).collect();
+        assert_eq!(refused, vec![late.id, untimed.id]);
+    }
+
+    #[test]
+    fn resolve_at_reports_late_bids_apart_from_expired() {
+        let late = bid![30, 1].with_submitted_at(101);
+        let expired = bid![25, 1].with_submitted_at(90).with_expires_at(150);
+        let live = bid![20, 1].with_submitted_at(90);
+        let auction = deadline_auction(UntimedBids::Accept);
+        let result = auction.resolve_at(vec![late, expired, live], 200).unwrap();
+        assert_eq!(result.sales().len(), 1);
+        assert_eq!(result.late().len(), 1);
+        assert_eq!(result.late()[0].id, late.id);
+        assert_eq!(result.expired().len(), 1);
+        assert_eq!(result.expired()[0].id, expired.id);
+    }
+
+    #[test]
+    fn auction_without_deadline_has_no_late_bids() {
+        let auction = AuctionBuilder::new()
+            .untimed_bids(UntimedBids::Reject)
+            .build();
+        assert!(!auction.is_late(&bid![10, 1]));
+        assert!(!auction.is_late(&bid![10, 1].with_submitted_at(u64::MAX)));
+    }
+
+    #[test]
+    fn bidder_id_defaults_to_bid_id() {
+        let bid = bid![10, 1];
+        assert_eq!(bid.bidder_id(), bid.id());
+    }
+
+    #[test]
+    fn sales_carry_bidder_id() {
+        let bidder = Uuid::new_v4();
+        let bid = bid![10, 1].with_bidder_id(bidder);
+        let sales = AuctionBuilder::new().build().resolve_bids(vec![bid]);
+        assert_eq!(sales[0].bidder_id(), bidder);
+    }
+
+    const PRICE: i64 = 25;
+
+    fn quantity() -> usize {
+        3
+    }
+
+    #[test]
+    fn bid_macro_accepts_expressions() {
+        let amount = 10;
+        let bid = bid![amount * 2, quantity()];
+        assert_eq!(bid.amount, 20);
+        assert_eq!(bid.quantity, 3);
+        let bid = bid![PRICE + 1];
+        assert_eq!(bid.amount, 26);
+        assert_eq!(bid.quantity, 1);
+    }
+
+    #[test]
+    fn bid_macro_accepts_id() {
+        let id = Uuid::new_v4();
+        let bid = bid![id; PRICE, 2];
+        assert_eq!(bid.id, id);
+        assert_eq!(bid.amount, PRICE);
+        assert_eq!(bid.quantity, 2);
+    }
+
+    #[test]
+    fn try_new_accepts_valid_bid() {
+        let bid = Bid::try_new(10, 2).unwrap();
+        assert_eq!(bid.amount, 10);
+        assert_eq!(bid.quantity, 2);
+    }
+
+    #[test]
+    fn try_new_rejects_zero_quantity() {
+        assert_eq!(Bid::try_new(100, 0).unwrap_err(), BidError::ZeroQuantity);
+    }
+
+    #[test]
+    fn try_new_rejects_negative_amount() {
+        assert_eq!(
+            Bid::try_new(-1, 1).unwrap_err(),
+            BidError::NegativeAmount(-1)
+        );
+    }
+
+    #[test]
+    fn try_new_zero_amount_wins_at_zero_reserve() {
+        let bids: Bids = vec![Bid::try_new(0, 1).unwrap()];
+        let sales = AuctionBuilder::new()
+            .reserve_price(0)
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].amount, 0);
+    }
+
+    #[test]
+    fn zero_quantity_bid_never_sells() {
+        let sales = AuctionBuilder::new()
+            .reserve_price(0)
+            .build()
+            .resolve_bids(vec![bid![100, 0], bid![5, 1]]);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].quantity, 1);
+    }
+
+    #[test]
+    fn with_id_keeps_the_given_id() {
+        let id = Uuid::new_v4();
+        let bid = Bid::with_id(id, 10, 2);
+        assert_eq!(bid.id(), id);
+        assert_eq!(bid.bidder_id(), id);
+    }
+
+    #[test]
+    fn with_name_is_deterministic() {
+        let namespace = Uuid::NAMESPACE_OID;
+        let first = Bid::with_name(&namespace, "order-1", 10, 1);
+        let again = Bid::with_name(&namespace, "order-1", 20, 3);
+        let other = Bid::with_name(&namespace, "order-2", 10, 1);
+        assert_eq!(first.id(), again.id());
+        assert_ne!(first.id(), other.id());
+    }
+
+    #[test]
+    fn bids_with_the_same_amount_are_not_equal() {
+        let bid = bid![10, 1];
+        assert_ne!(bid, bid![10, 1]);
+        assert_eq!(bid, bid);
+        assert_ne!(bid, bid.with_amount(11));
+        assert_ne!(bid, bid.with_priority(0));
+        assert_eq!(bid.cmp_amount(&bid![10, 5]), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn bids_deduplicate_in_a_hash_set() {
+        let id = Uuid::from_u128(1);
+        let bids = [
+            bid![id; 10, 1],
+            bid![id; 10, 1],
+            bid![id; 10, 2],
+            bid![Uuid::from_u128(2); 10, 1],
+        ];
+        let unique: std::collections::HashSet<Bid> = bids.into_iter().collect();
+        assert_eq!(unique.len(), 3);
+    }
+
+    #[test]
+    fn partial_fills_keep_given_ids() {
+        let ids = [Uuid::new_v4(), Uuid::new_v4()];
+        let bids: Bids = vec![Bid::with_id(ids[0], 20, 2), Bid::with_id(ids[1], 10, 5)];
+        for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+            let sales = AuctionBuilder::new()
+                .lots(4)
+                .strategy(strategy)
+                .build()
+                .resolve_bids(bids.clone());
+            assert_eq!(sales[0].bidder_id, ids[0]);
+            assert_eq!(sales[1].bidder_id, ids[1]);
+            assert_eq!(sales[1].quantity, 2);
+        }
+        let sales = AuctionBuilder::new()
+            .lots(3)
+            .allocation(AllocationPolicy::ProRata)
+            .build()
+            .resolve_bids(vec![
+                Bid::with_id(ids[0], 10, 2),
+                Bid::with_id(ids[1], 10, 4),
+            ]);
+        let sold: Vec<Uuid> = sales.iter().map(|sale| sale.bidder_id).collect();
+        assert_eq!(sold, ids);
+    }
+
+    fn budget_auction(lots: usize) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(lots)
+            .build()
+    }
+
+    #[test]
+    fn budget_trims_latest_fill() {
+        let bidder = Uuid::new_v4();
+        let bids: Bids = vec![
+            bid![30, 2].with_bidder_id(bidder),
+            bid![20, 3].with_bidder_id(bidder),
+            bid![10, 5],
+        ];
+        let budgets = HashMap::from([(bidder, 100)]);
+        let sales = budget_auction(5).resolve_with_budgets(bids, budgets);
+        assert_eq!(sales.len(), 3);
+        assert_eq!(sales[0].quantity, 2);
+        assert_eq!(sales[1].amount, 20);
+        assert_eq!(sales[1].quantity, 2);
+        assert_eq!(sales[2].amount, 10);
+        assert_eq!(sales[2].quantity, 1);
+        let spent: i128 = sales[..2].iter().map(Sale::total).sum();
+        assert!(spent <= 100);
+    }
+
+    #[test]
+    fn budget_exhausted_drops_later_bids() {
+        let bidder = Uuid::new_v4();
+        let bids: Bids = vec![
+            bid![30, 1].with_bidder_id(bidder),
+            bid![25, 1].with_bidder_id(bidder),
+            bid![10, 1],
+        ];
+        let budgets = HashMap::from([(bidder, 40)]);
+        let sales = budget_auction(2).resolve_with_budgets(bids, budgets);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 30);
+        assert_eq!(sales[1].amount, 10);
+    }
+
+    #[test]
+    fn bidders_without_budget_are_unconstrained() {
+        let bids: Bids = vec![bid![30, 3], bid![20, 3]];
+        let budgets = HashMap::from([(Uuid::new_v4(), 1)]);
+        let sales = budget_auction(6).resolve_with_budgets(bids, budgets);
+        let sold: usize = sales.iter().map(|sale| sale.quantity).sum();
+        assert_eq!(sold, 6);
+    }
+
+    #[test]
+    fn budget_respected_under_pro_rata() {
+        let bidder = Uuid::new_v4();
+        let bids: Bids = vec![bid![10, 4].with_bidder_id(bidder), bid![10, 4]];
+        let auction = AuctionBuilder::new()
+            .allocation(AllocationPolicy::ProRata)
+            .lots(4)
+            .build();
+        let budgets = HashMap::from([(bidder, 10)]);
+        let sales = auction.resolve_with_budgets(bids, budgets);
+        assert_eq!(sales[0].bidder_id, bidder);
+        assert_eq!(sales[0].quantity, 1);
+        assert_eq!(sales[1].quantity, 3);
+    }
+
+    #[test]
+    fn max_lots_per_bidder_cascades_to_smaller_bidders() {
+        let dominant = Uuid::new_v4();
+        let bids: Bids = vec![
+            bid![50, 6].with_bidder_id(dominant),
+            bid![45, 4].with_bidder_id(dominant),
+            bid![40, 2],
+            bid![35, 2],
+            bid![30, 2],
+        ];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(10)
+            .max_lots_per_bidder(4)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        let won = |bidder| -> usize {
+            sales
+                .iter()
+                .filter(|sale| sale.bidder_id == bidder)
+                .map(|sale| sale.quantity)
+                .sum()
+        };
+        assert_eq!(won(dominant), 4);
+        assert_eq!(sales.len(), 4);
+        assert_eq!(sales[0].quantity, 4);
+        assert!(sales[1..].iter().all(|sale| sale.quantity == 2));
+        assert_eq!(sales[3].amount, 30);
+    }
+
+    #[test]
+    fn max_lots_per_bidder_trims_single_bid() {
+        let auction = AuctionBuilder::new().lots(5).max_lots_per_bidder(2).build();
+        let result = auction.resolve(vec![bid![10, 5]]).unwrap();
+        assert_eq!(result.lots_sold(), 2);
+        assert_eq!(result.lots_unsold(), 3);
+    }
+
+    #[test]
+    fn try_build_rejects_multi_lot_proxy() {
+        let error = AuctionBuilder::new()
+            .strategy(AuctionStrategy::Proxy)
+            .lots(2)
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(error, AuctionConfigError::StrategyMismatch(_)));
+    }
+
+    fn partial_fill_auction(strategy: AuctionStrategy, policy: PartialFillPolicy) -> Auction {
+        AuctionBuilder::new()
+            .strategy(strategy)
+            .lots(3)
+            .partial_fill(policy)
+            .build()
+    }
+
+    #[test]
+    fn partial_fill_split_fills_marginal_bid() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 3], bid![10, 1]];
+        let auction = partial_fill_auction(AuctionStrategy::SinglePrice, PartialFillPolicy::Split);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bids[0].id, 20, 2).with_bid(&bids[0]),
+                Sale::new(bids[1].id, 20, 1).with_bid(&bids[1]),
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_fill_skip_lowers_single_price_clearing_level() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 3], bid![10, 1]];
+        let auction = partial_fill_auction(AuctionStrategy::SinglePrice, PartialFillPolicy::Skip);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bids[0].id, 10, 2).with_bid(&bids[0]),
+                Sale::new(bids[2].id, 10, 1).with_bid(&bids[2]),
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_fill_skip_under_highest_rejected_keeps_last_winner_price() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 3], bid![10, 1], bid![5, 1]];
+        let auction = AuctionBuilder::new()
+            .lots(3)
+            .pricing_rule(PricingRule::HighestRejected)
+            .partial_fill(PartialFillPolicy::Skip)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert!(sales.iter().all(|sale| sale.amount == 10));
+        assert_eq!(sales.total_quantity(), 3);
+    }
+
+    #[test]
+    fn partial_fill_skip_multi_price_charges_own_amounts() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 3], bid![10, 1]];
+        let auction = partial_fill_auction(AuctionStrategy::MultiPrice, PartialFillPolicy::Skip);
+        let amounts: Vec<_> = auction
+            .resolve_bids(bids)
+            .iter()
+            .map(|sale| (sale.amount, sale.quantity))
+            .collect();
+        assert_eq!(amounts, vec![(30, 2), (10, 1)]);
+    }
+
+    #[test]
+    fn partial_fill_skip_passes_over_tied_bid() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 3], bid![20, 1], bid![10, 1]];
+        let auction = partial_fill_auction(AuctionStrategy::SinglePrice, PartialFillPolicy::Skip);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(sales[1].bid_id(), Some(bids[2].id));
+        assert!(sales.iter().all(|sale| sale.amount == 20));
+    }
+
+    #[test]
+    fn partial_fill_skip_never_splits_a_bid() {
+        for (seed, lots) in [(1, 10), (2, 100)] {
+            let bids = generators::large_quantities(seed, 300, 49, 4);
+            for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+                let auction = AuctionBuilder::new()
+                    .strategy(strategy)
+                    .lots(lots)
+                    .partial_fill(PartialFillPolicy::Skip)
+                    .build();
+                let sales = auction.resolve_bids_ref(&bids);
+                assert!(sales
+                    .iter()
+                    .all(|sale| sale.requested_quantity() == Some(sale.quantity)));
+                assert_eq!(verify(&auction, &bids, &sales), Ok(()));
+            }
+        }
+    }
+
+    #[test]
+    fn partial_fill_leave_unsold_reports_unsold_lots() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 3], bid![10, 1]];
+        for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+            let auction = partial_fill_auction(strategy, PartialFillPolicy::LeaveUnsold);
+            let result = auction.resolve(bids.clone()).unwrap();
+            assert_eq!(
+                result.sales(),
+                &vec![Sale::new(bids[0].id, 30, 2).with_bid(&bids[0])]
+            );
+            assert_eq!(result.lots_sold(), 2);
+            assert_eq!(result.lots_unsold(), 1);
+            assert_eq!(result.clearing_price(), Some(30));
+        }
+    }
+
+    #[test]
+    fn partial_fill_leave_unsold_stops_lazy_resolution() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 3], bid![10, 1]];
+        let auction =
+            partial_fill_auction(AuctionStrategy::MultiPrice, PartialFillPolicy::LeaveUnsold);
+        let sales: Sales = auction.resolve_iter(bids.clone()).collect();
+        assert_eq!(sales, auction.resolve_bids(bids));
+    }
+
+    #[test]
+    fn try_build_rejects_partial_fill_policy_mismatch() {
+        let error = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .partial_fill(PartialFillPolicy::Skip)
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(error, AuctionConfigError::StrategyMismatch(_)));
+
+        let error = AuctionBuilder::new()
+            .allocation(AllocationPolicy::ProRata)
+            .partial_fill(PartialFillPolicy::LeaveUnsold)
+            .try_build()
+            .unwrap_err();
+        assert!(matches!(error, AuctionConfigError::StrategyMismatch(_)));
+    }
+
+    /// Many bids over a few amounts, each submitted at a distinct time.
+    fn tie_heavy_bids() -> Bids {
+        let mut rng = rng::BidRng::new(7);
+        (0..2000)
+            .map(|index| {
+                let amount = 10 + rng.up_to(4) as i64;
+                let quantity = 1 + rng.up_to(2) as usize;
+                bid![amount, quantity].with_submitted_at(index)
+            })
+            .collect()
+    }
+
+    fn shuffled(mut bids: Bids, seed: u64) -> Bids {
+        let mut rng = rng::BidRng::new(seed);
+        for index in (1..bids.len()).rev() {
+            bids.swap(index, rng.up_to(index as u64) as usize);
+        }
+        bids
+    }
+
+    #[test]
+    fn resolution_order_is_deterministic() {
+        let strategies = [
+            (AuctionStrategy::SinglePrice, 50),
+            (AuctionStrategy::MultiPrice, 50),
+            (AuctionStrategy::AllPay, 50),
+            (AuctionStrategy::GeneralizedSecondPrice, 50),
+            (AuctionStrategy::Proxy, 1),
+            (AuctionStrategy::Vcg, 50),
+            (AuctionStrategy::Scored, 50),
+            (AuctionStrategy::PostedPrice, 50),
+            (
+                AuctionStrategy::Dutch {
+                    start_price: 20,
+                    decrement: 1,
+                },
+                50,
+            ),
+            (
+                AuctionStrategy::Japanese {
+                    start: None,
+                    increment: 1,
+                },
+                1000,
+            ),
+        ];
+        let bids = tie_heavy_bids();
+        for (strategy, lots) in strategies {
+            let auction = AuctionBuilder::new()
+                .strategy(strategy.clone())
+                .lots(lots)
+                .reserve_price(10)
+                .build();
+            assert!(auction.is_order_independent());
+            let sales = auction.resolve_bids_ref(&bids);
+            assert!(!sales.is_empty(), "{strategy:?}");
+            assert_eq!(auction.resolve_bids_ref(&bids), sales);
+            for seed in 0..3 {
+                assert_eq!(auction.resolve_bids(shuffled(bids.clone(), seed)), sales);
+            }
+        }
+    }
+
+    #[test]
+    fn lottery_is_deterministic_but_order_dependent() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::Lottery { seed: 3 })
+            .lots(50)
+            .build();
+        assert!(!auction.is_order_independent());
+        let bids = tie_heavy_bids();
+        let sales = auction.resolve_bids_ref(&bids);
+        assert_eq!(auction.resolve_bids_ref(&bids), sales);
+        assert_ne!(auction.resolve_bids(shuffled(bids, 0)), sales);
+    }
+
+    #[test]
+    fn secondary_sorts_but_submission_time_are_order_dependent() {
+        let bids = tie_heavy_bids();
+        for secondary_sort in [
+            SecondarySort::LargestQuantityFirst,
+            SecondarySort::SmallestQuantityFirst,
+            SecondarySort::InputOrder,
+        ] {
+            let auction = AuctionBuilder::new()
+                .strategy(AuctionStrategy::MultiPrice)
+                .lots(50)
+                .secondary_sort(secondary_sort)
+                .build();
+            assert!(!auction.is_order_independent());
+            let sales = auction.resolve_bids_ref(&bids);
+            assert_eq!(auction.resolve_bids_ref(&bids), sales);
+            assert_ne!(auction.resolve_bids(shuffled(bids.clone(), 0)), sales);
+        }
+    }
+
+    fn ordered_sales(ordering: SalesOrdering, bids: &[Bid]) -> Sales {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(6)
+            .sales_ordering(ordering)
+            .build()
+            .resolve_bids_ref(bids)
+    }
+
+    fn ordering_bids() -> Bids {
+        vec![bid![20, 1], bid![40, 2], bid![30, 3], bid![10, 1]]
+    }
+
+    #[test]
+    fn sales_ordering_defaults_to_ranked() {
+        let sales = ordered_sales(SalesOrdering::default(), &ordering_bids());
+        let amounts: Vec<_> = sales.iter().map(Sale::amount).collect();
+        assert_eq!(amounts, vec![40, 30, 20]);
+    }
+
+    #[test]
+    fn sales_ordering_by_price_descending() {
+        let sales = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .direction(AuctionDirection::Reverse)
+            .lots(6)
+            .sales_ordering(SalesOrdering::PriceDesc)
+            .build()
+            .resolve_bids_ref(&ordering_bids());
+        let amounts: Vec<_> = sales.iter().map(Sale::amount).collect();
+        assert_eq!(amounts, vec![40, 30, 20, 10]);
+    }
+
+    #[test]
+    fn sales_ordering_by_bidder_id() {
+        let bidder = Uuid::new_v4();
+        let mut bids = ordering_bids();
+        bids[2] = bids[2].with_bidder_id(bidder);
+        bids[0] = bids[0].with_bidder_id(bidder);
+        let sales = ordered_sales(SalesOrdering::BidderId, &bids);
+        let bidders: Vec<_> = sales.iter().map(Sale::bidder_id).collect();
+        let mut sorted = bidders.clone();
+        sorted.sort();
+        assert_eq!(bidders, sorted);
+        let bidders_sales: Vec<_> = sales
+            .iter()
+            .filter(|sale| sale.bidder_id() == bidder)
+            .map(Sale::amount)
+            .collect();
+        assert_eq!(bidders_sales, vec![30, 20]);
+    }
+
+    #[test]
+    fn sales_ordering_by_quantity_descending() {
+        let sales = ordered_sales(SalesOrdering::QuantityDesc, &ordering_bids());
+        let quantities: Vec<_> = sales.iter().map(Sale::quantity).collect();
+        assert_eq!(quantities, vec![3, 2, 1]);
+    }
+
+    #[test]
+    fn sales_ordering_by_input_bid_order() {
+        let bids = ordering_bids();
+        let sales = ordered_sales(SalesOrdering::InputBidOrder, &bids);
+        let bid_ids: Vec<_> = sales.iter().map(Sale::bid_id).collect();
+        assert_eq!(
+            bid_ids,
+            vec![Some(bids[0].id), Some(bids[1].id), Some(bids[2].id)]
+        );
+        let unknown = Sale::new(Uuid::new_v4(), 50, 1);
+        let mut sales = vec![unknown, sales[2], sales[0]];
+        SalesOrdering::InputBidOrder.sort(&mut sales, &bids);
+        assert_eq!(sales[2], unknown);
+        assert_eq!(sales[0].bid_id(), Some(bids[0].id));
     }
 }
@@ -0,0 +1,98 @@
+//! Module for the attributes attached to bids, such as delivery time or rating.
+use alloc::{collections::BTreeMap, string::String};
+
+use uuid::Uuid;
+
+use crate::collections::HashMap;
+
+/// Named attributes of bids, keyed by bid id.
+///
+/// The attributes are kept apart from the bids so that [`crate::Bid`] stays
+/// `Copy`. Scorers passed to [`crate::Auction::resolve_scored`] can capture them
+/// to rank bids on more than their amount.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BidMetadata {
+    attributes: HashMap<Uuid, BTreeMap<String, i64>>,
+}
+
+impl BidMetadata {
+    /// Creates metadata with no attributes.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set an attribute of the bid, returning the metadata.
+    pub fn with_attribute(mut self, bid_id: Uuid, name: impl Into<String>, value: i64) -> Self {
+        self.insert(bid_id, name, value);
+        self
+    }
+
+    /// Set an attribute of the bid, returning its previous value.
+    pub fn insert(&mut self, bid_id: Uuid, name: impl Into<String>, value: i64) -> Option<i64> {
+        self.attributes
+            .entry(bid_id)
+            .or_default()
+            .insert(name.into(), value)
+    }
+
+    /// The value of the bid's attribute, if set.
+    pub fn get(&self, bid_id: Uuid, name: &str) -> Option<i64> {
+        self.attributes.get(&bid_id)?.get(name).copied()
+    }
+
+    /// Every attribute of the bid, ordered by name.
+    pub fn attributes(&self, bid_id: Uuid) -> impl Iterator<Item = (&str, i64)> {
+        self.attributes
+            .get(&bid_id)
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Remove every attribute of the bid, returning them.
+    pub fn remove(&mut self, bid_id: Uuid) -> Option<BTreeMap<String, i64>> {
+        self.attributes.remove(&bid_id)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn metadata_stores_attributes_per_bid() {
+        let (first, second) = (bid![10, 1], bid![20, 1]);
+        let mut metadata = BidMetadata::new()
+            .with_attribute(first.id, "rating", 4)
+            .with_attribute(first.id, "delivery_days", 3);
+        assert_eq!(metadata.insert(first.id, "rating", 5), Some(4));
+        assert_eq!(metadata.get(first.id, "rating"), Some(5));
+        assert_eq!(metadata.get(second.id, "rating"), None);
+        assert_eq!(
+            metadata.attributes(first.id).collect::<Vec<_>>(),
+            vec![("delivery_days", 3), ("rating", 5)]
+        );
+        assert!(metadata.remove(first.id).is_some());
+        assert_eq!(metadata.attributes(first.id).count(), 0);
+    }
+
+    #[test]
+    fn metadata_scorer_ranks_on_attributes() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .direction(AuctionDirection::Reverse)
+            .lots(1)
+            .build();
+        let (cheap, fast) = (bid![100, 1], bid![110, 1]);
+        let metadata = BidMetadata::new()
+            .with_attribute(cheap.id, "delivery_days", 10)
+            .with_attribute(fast.id, "delivery_days", 2);
+        let scorer = |bid: &Bid| {
+            let days = metadata.get(bid.id(), "delivery_days").unwrap_or(30);
+            -(bid.amount() + days * 5)
+        };
+        let sales = auction.resolve_scored(vec![cheap, fast], scorer);
+        assert_eq!(sales, vec![Sale::new(fast.id, 110, 1).with_bid(&fast)]);
+    }
+}
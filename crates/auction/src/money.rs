@@ -0,0 +1,111 @@
+//! Module for converting between caller money types and the minor units used
+//! to resolve auctions.
+//!
+//! Auctions work in whole `i64` minor units. A scale says how many decimal
+//! places of a money value those units carry, so a scale of 2 makes them cents
+//! and a scale of 6 keeps fractions of a cent for ad or FX markets.
+#[cfg(feature = "decimal")]
+use rust_decimal::Decimal;
+
+/// A money type that can be converted to and from minor units.
+pub trait Money: Copy + Ord {
+    /// The value in minor units at the given scale, None if it has more
+    /// decimal places than the scale or does not fit in an `i64`.
+    fn to_minor_units(self, scale: u32) -> Option<i64>;
+
+    /// The value of the given minor units at the given scale, None if the type
+    /// cannot represent it exactly.
+    fn from_minor_units(units: i64, scale: u32) -> Option<Self>;
+}
+
+impl Money for i64 {
+    fn to_minor_units(self, scale: u32) -> Option<i64> {
+        self.checked_mul(10_i64.checked_pow(scale)?)
+    }
+
+    fn from_minor_units(units: i64, scale: u32) -> Option<Self> {
+        let factor = 10_i64.checked_pow(scale)?;
+        (units % factor == 0).then_some(units / factor)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl Money for Decimal {
+    fn to_minor_units(self, scale: u32) -> Option<i64> {
+        let value = self.normalize();
+        let places = scale.checked_sub(value.scale())?;
+        let units = value.mantissa().checked_mul(10_i128.checked_pow(places)?)?;
+        i64::try_from(units).ok()
+    }
+
+    fn from_minor_units(units: i64, scale: u32) -> Option<Self> {
+        Self::try_new(units, scale).ok()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn money_i64_round_trips_at_scale() {
+        assert_eq!(12_i64.to_minor_units(2), Some(1200));
+        assert_eq!(i64::from_minor_units(1200, 2), Some(12));
+        assert_eq!(i64::from_minor_units(1250, 2), None);
+        assert_eq!(i64::MAX.to_minor_units(1), None);
+    }
+
+    #[test]
+    fn money_i64_resolves_and_converts_back() {
+        let amounts = [12_i64, 15, 9];
+        let bids: Bids = amounts
+            .iter()
+            .map(|amount| Bid::from_money(*amount, 2, 1).unwrap())
+            .collect();
+        let sales = AuctionBuilder::new()
+            .lots(2)
+            .reserve_price(10_i64.to_minor_units(2).unwrap())
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount_in::<i64>(2), Some(12));
+        assert_eq!(sales[0].total_in::<i64>(2), Some(12));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn money_decimal_keeps_fractional_cents() {
+        use rust_decimal::Decimal;
+
+        let price = Decimal::new(123_456, 4);
+        assert_eq!(price.to_minor_units(4), Some(123_456));
+        assert_eq!(price.to_minor_units(6), Some(12_345_600));
+        assert_eq!(price.to_minor_units(2), None);
+        assert_eq!(Decimal::from_minor_units(123_456, 4), Some(price));
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    fn money_decimal_resolves_and_converts_back() {
+        use rust_decimal::Decimal;
+
+        let bids: Bids = vec![
+            Bid::from_money(Decimal::new(10_0025, 4), 4, 2).unwrap(),
+            Bid::from_money(Decimal::new(10_0050, 4), 4, 1).unwrap(),
+            Bid::from_money(Decimal::new(9_9999, 4), 4, 1).unwrap(),
+        ];
+        let sales = AuctionBuilder::new()
+            .lots(3)
+            .strategy(AuctionStrategy::MultiPrice)
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(sales[0].amount_in(4), Some(Decimal::new(10_0050, 4)));
+        assert_eq!(sales[1].total_in(4), Some(Decimal::new(20_0050, 4)));
+    }
+
+    #[test]
+    fn money_rejects_unrepresentable_bid() {
+        let error = Bid::from_money(i64::MAX, 2, 1).unwrap_err();
+        assert_eq!(error, BidError::UnrepresentableAmount);
+    }
+}
@@ -0,0 +1,377 @@
+//! Module for observing the decisions of a resolution as they are made.
+use uuid::Uuid;
+
+use crate::{
+    collections::{HashMap, HashSet},
+    strategies::fill,
+    Auction, Bid, RejectReason, Sale, Sales,
+};
+
+/// Hooks called as a resolution makes each decision, each doing nothing
+/// unless implemented.
+///
+/// ```
+/// use auction::{bid, AuctionBuilder, AuctionObserver, Bid};
+///
+/// #[derive(Default)]
+/// struct Allocated(usize);
+///
+/// impl AuctionObserver for Allocated {
+///     fn on_allocation(&mut self, _bid: &Bid, quantity: usize, _price: i64) {
+///         self.0 += quantity;
+///     }
+/// }
+///
+/// let mut allocated = Allocated::default();
+/// let auction = AuctionBuilder::new().lots(2).build();
+/// auction.resolve_with_observer(vec![bid![30, 1], bid![20, 3]], &mut allocated);
+/// assert_eq!(allocated.0, 2);
+/// ```
+pub trait AuctionObserver {
+    /// Called for each bid that wins nothing, when it is left out.
+    fn on_bid_rejected(&mut self, _bid: &Bid, _reason: RejectReason) {}
+
+    /// Called for each sale as it is priced, with the bid it fills.
+    ///
+    /// A losing all-pay bid is allocated no units at the amount it pays.
+    fn on_allocation(&mut self, _bid: &Bid, _quantity: usize, _price: i64) {}
+
+    /// Called once with every sale, in their final order, after the other
+    /// hooks.
+    fn on_complete(&mut self, _sales: &Sales) {}
+
+    /// Whether the observer uses its hooks. Resolutions skip working out what
+    /// to tell an observer that does not, such as `()`.
+    fn enabled(&self) -> bool {
+        true
+    }
+}
+
+/// The observer that ignores every hook, which plain resolutions use.
+impl AuctionObserver for () {
+    fn enabled(&self) -> bool {
+        false
+    }
+}
+
+impl<O: AuctionObserver + ?Sized> AuctionObserver for &mut O {
+    fn on_bid_rejected(&mut self, bid: &Bid, reason: RejectReason) {
+        (**self).on_bid_rejected(bid, reason);
+    }
+
+    fn on_allocation(&mut self, bid: &Bid, quantity: usize, price: i64) {
+        (**self).on_allocation(bid, quantity, price);
+    }
+
+    fn on_complete(&mut self, sales: &Sales) {
+        (**self).on_complete(sales);
+    }
+
+    fn enabled(&self) -> bool {
+        (**self).enabled()
+    }
+}
+
+/// Resolves the bids as [`Auction::resolve_bids_ref`] does, calling the
+/// observer's hooks as each bid is rejected and each sale priced, then once
+/// with the sales.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+/// * `observer` - The hooks to call.
+///
+/// # Returns
+/// The sales for the bids.
+///
+pub fn resolve_with_observer(
+    auction: &Auction,
+    bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    auction.resolve_observed(bids, observer)
+}
+
+/// Tells the observer about the sales of a strategy that calls no hooks
+/// itself: each bid without a sale is rejected, in input order, then each
+/// sale made from a bid is allocated.
+///
+/// Bids that do not qualify are rejected as below the reserve, the rest as
+/// outbid.
+pub fn observe_sales(
+    auction: &Auction,
+    bids: &[Bid],
+    sales: &[Sale],
+    observer: &mut dyn AuctionObserver,
+) {
+    let by_id: HashMap<Uuid, &Bid> = bids.iter().map(|bid| (bid.id, bid)).collect();
+    let sold: HashSet<Uuid> = sales.iter().filter_map(|sale| sale.bid_id).collect();
+    for bid in bids.iter().filter(|bid| !sold.contains(&bid.id)) {
+        observer.on_bid_rejected(bid, fill::reject_reason(auction, bid.amount));
+    }
+    for sale in sales {
+        if let Some(bid) = sale.bid_id.and_then(|bid_id| by_id.get(&bid_id)) {
+            observer.on_allocation(bid, sale.quantity, sale.amount);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use alloc::{collections::BTreeMap, vec::Vec};
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use uuid::Uuid;
+
+    use crate::*;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Call {
+        Rejected(Uuid, RejectReason),
+        Allocation(Uuid, usize, i64),
+        Complete(usize),
+    }
+
+    #[derive(Default)]
+    struct Recorder(Vec<Call>);
+
+    impl AuctionObserver for Recorder {
+        fn on_bid_rejected(&mut self, bid: &Bid, reason: RejectReason) {
+            self.0.push(Call::Rejected(bid.id, reason));
+        }
+
+        fn on_allocation(&mut self, bid: &Bid, quantity: usize, price: i64) {
+            self.0.push(Call::Allocation(bid.id, quantity, price));
+        }
+
+        fn on_complete(&mut self, sales: &Sales) {
+            self.0.push(Call::Complete(sales.len()));
+        }
+    }
+
+    #[test]
+    fn observer_sees_each_decision_in_order() {
+        let bids: Bids = vec![bid![20, 2], bid![5, 1], bid![30, 1], bid![15, 2]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(4)
+            .reserve_price(10)
+            .build();
+        let mut recorder = Recorder::default();
+        let sales = auction.resolve_with_observer(bids.clone(), &mut recorder);
+        assert_eq!(sales, auction.resolve_bids_ref(&bids));
+        assert_eq!(
+            recorder.0,
+            vec![
+                Call::Rejected(bids[1].id, RejectReason::BelowReserve),
+                Call::Allocation(bids[2].id, 1, 30),
+                Call::Allocation(bids[0].id, 2, 20),
+                Call::Allocation(bids[3].id, 1, 15),
+                Call::Complete(3),
+            ]
+        );
+    }
+
+    #[test]
+    fn observer_sees_all_pay_losers_allocated_nothing() {
+        let bids: Bids = vec![bid![30, 1], bid![10, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .build();
+        let mut recorder = Recorder::default();
+        auction.resolve_with_observer(bids.clone(), &mut recorder);
+        assert_eq!(
+            recorder.0,
+            vec![
+                Call::Allocation(bids[0].id, 1, 30),
+                Call::Allocation(bids[1].id, 0, 10),
+                Call::Complete(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn observer_completes_with_no_bids() {
+        let mut recorder = Recorder::default();
+        let sales = AuctionBuilder::new()
+            .build()
+            .resolve_with_observer(Vec::new(), &mut recorder);
+        assert!(sales.is_empty());
+        assert_eq!(recorder.0, vec![Call::Complete(0)]);
+    }
+
+    #[test]
+    fn unit_observer_resolves_as_usual() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 1]];
+        let auction = AuctionBuilder::new().build();
+        assert_eq!(
+            auction.resolve_with_observer(bids.clone(), &mut ()),
+            auction.resolve_bids(bids)
+        );
+    }
+
+    #[test]
+    fn observer_hears_of_a_refill_below_the_best_bids() {
+        let bids: Bids = vec![
+            bid![30, 3].with_all_or_nothing(true),
+            bid![20, 1],
+            bid![5, 1],
+            bid![10, 1],
+        ];
+        let auction = AuctionBuilder::new().lots(2).reserve_price(8).build();
+        let mut recorder = Recorder::default();
+        let sales = auction.resolve_with_observer(bids.clone(), &mut recorder);
+        assert_eq!(sales, auction.resolve_bids_ref(&bids));
+        assert_eq!(
+            recorder.0,
+            vec![
+                Call::Rejected(bids[0].id, RejectReason::NoLotsRemaining),
+                Call::Rejected(bids[2].id, RejectReason::BelowReserve),
+                Call::Allocation(bids[1].id, 1, 10),
+                Call::Allocation(bids[3].id, 1, 10),
+                Call::Complete(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn observer_hears_of_bids_left_out_before_the_strategy() {
+        let usd = Currency::USD;
+        let bids: Bids = vec![
+            bid![25, 1].with_currency(usd),
+            bid![30, 1].with_currency(Currency::EUR),
+            bid![40, 1].with_currency(usd),
+        ];
+        let auction = AuctionBuilder::new()
+            .currency(usd)
+            .currency_mismatch(CurrencyMismatch::Filter)
+            .tick_size(10)
+            .tick_policy(TickPolicy::Reject)
+            .build();
+        let mut recorder = Recorder::default();
+        auction.resolve_with_observer(bids.clone(), &mut recorder);
+        assert_eq!(
+            recorder.0,
+            vec![
+                Call::Rejected(bids[1].id, RejectReason::CurrencyMismatch),
+                Call::Rejected(bids[0].id, RejectReason::OffTick),
+                Call::Allocation(bids[2].id, 1, 40),
+                Call::Complete(1),
+            ]
+        );
+    }
+
+    /// Counts the hooks called for each bid, and the allocations made.
+    #[derive(Default)]
+    struct Tally {
+        mentions: BTreeMap<Uuid, usize>,
+        allocations: Vec<(Uuid, usize, i64)>,
+    }
+
+    impl AuctionObserver for Tally {
+        fn on_bid_rejected(&mut self, bid: &Bid, _reason: RejectReason) {
+            *self.mentions.entry(bid.id).or_default() += 1;
+        }
+
+        fn on_allocation(&mut self, bid: &Bid, quantity: usize, price: i64) {
+            *self.mentions.entry(bid.id).or_default() += 1;
+            self.allocations.push((bid.id, quantity, price));
+        }
+    }
+
+    #[test]
+    fn observer_hears_of_every_bid_once() {
+        let strategies = [
+            AuctionStrategy::SinglePrice,
+            AuctionStrategy::MultiPrice,
+            AuctionStrategy::Dutch {
+                start_price: 1_000,
+                decrement: 7,
+            },
+            AuctionStrategy::AllPay,
+            AuctionStrategy::GeneralizedSecondPrice,
+            AuctionStrategy::Proxy,
+            AuctionStrategy::Vcg,
+            AuctionStrategy::Lottery { seed: 3 },
+            AuctionStrategy::Scored,
+            AuctionStrategy::Japanese {
+                start: None,
+                increment: 5,
+            },
+            AuctionStrategy::PostedPrice,
+        ];
+        for seed in 0..10 {
+            let mut bids = generators::large_quantities(seed, 40, 1_000, 5);
+            for (index, bid) in bids.iter_mut().enumerate() {
+                match index % 7 {
+                    0 => *bid = bid.with_currency(Currency::EUR),
+                    1 => *bid = bid.with_all_or_nothing(true),
+                    2 => *bid = bid.with_bidder_id(Uuid::from_u128(7)),
+                    _ => {}
+                }
+            }
+            for strategy in &strategies {
+                for buy_now in [false, true] {
+                    let mut builder = AuctionBuilder::new()
+                        .strategy(strategy.clone())
+                        .lots(seed as usize + 8)
+                        .reserve_price(100)
+                        .tick_size(5)
+                        .tick_policy(TickPolicy::Reject)
+                        .max_bid_fraction(1, 4)
+                        .currency_mismatch(CurrencyMismatch::Filter)
+                        .max_lots_per_bidder(3);
+                    if buy_now {
+                        builder = builder.buy_now_price(900);
+                    }
+                    let auction = builder.build();
+                    let mut tally = Tally::default();
+                    let sales = auction.resolve_with_observer(bids.clone(), &mut tally);
+                    assert_eq!(sales, auction.resolve_bids_ref(&bids), "{strategy:?}");
+                    assert_eq!(tally.mentions.len(), bids.len(), "{strategy:?}");
+                    assert!(tally.mentions.values().all(|&count| count == 1));
+                    let mut sold: Vec<(Uuid, usize, i64)> = sales
+                        .iter()
+                        .map(|sale| (sale.bid_id.unwrap(), sale.quantity, sale.amount))
+                        .collect();
+                    sold.sort_unstable();
+                    tally.allocations.sort_unstable();
+                    assert_eq!(tally.allocations, sold, "{strategy:?}");
+                }
+            }
+        }
+    }
+
+    /// Resolves as single price, counting how often it is asked to.
+    #[derive(Debug, Default)]
+    struct Counted(AtomicUsize);
+
+    impl Strategy for Counted {
+        fn resolve(&self, auction: &Auction, bids: &[Bid]) -> Sales {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            strategies::single_price(auction, bids)
+        }
+    }
+
+    #[test]
+    fn observer_resolves_a_custom_strategy_once() {
+        let counted = Arc::new(Counted::default());
+        let bids: Bids = vec![bid![30, 1], bid![5, 1], bid![20, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::Custom(counted.clone()))
+            .reserve_price(10)
+            .build();
+        let mut recorder = Recorder::default();
+        auction.resolve_with_observer(bids.clone(), &mut recorder);
+        assert_eq!(counted.0.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            recorder.0,
+            vec![
+                Call::Rejected(bids[1].id, RejectReason::BelowReserve),
+                Call::Rejected(bids[2].id, RejectReason::OutbidAtClearing),
+                Call::Allocation(bids[0].id, 1, 30),
+                Call::Complete(1),
+            ]
+        );
+    }
+}
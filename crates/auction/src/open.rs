@@ -0,0 +1,165 @@
+//! Module containing the open ascending (English) auction.
+use alloc::vec::Vec;
+
+use crate::{strategies::fill::fill_lots, Auction, Bid, Bids, Sales};
+
+/// An open ascending auction that accepts bids over time.
+///
+/// The bids covering the auction's lots are provisionally winning. Once every
+/// lot is claimed a new bid must beat the lowest provisionally winning bid by
+/// at least the minimum increment. Closing resolves the accepted bids with the
+/// wrapped auction's strategy.
+#[derive(Debug, Clone)]
+pub struct OpenAuction {
+    auction: Auction,
+    min_increment: i64,
+    /// The accepted bids, best first.
+    bids: Bids,
+}
+
+impl OpenAuction {
+    /// Open a new auction.
+    pub const fn new(auction: Auction, min_increment: i64) -> Self {
+        Self {
+            auction,
+            min_increment,
+            bids: Vec::new(),
+        }
+    }
+
+    /// Submit a bid, returning whether it is currently leading.
+    ///
+    /// Bids below the minimum acceptable amount are rejected and never lead.
+    pub fn submit(&mut self, bid: Bid) -> bool {
+        if bid.amount < self.minimum_bid() {
+            return false;
+        }
+        let position = self
+            .bids
+            .partition_point(|other| other.amount >= bid.amount);
+        self.bids.insert(position, bid);
+        self.leading().iter().any(|leader| leader.id == bid.id)
+    }
+
+    /// The standing high bid, None before any bid is accepted.
+    pub fn current_price(&self) -> Option<i64> {
+        self.bids.first().map(|bid| bid.amount)
+    }
+
+    /// The lowest amount the next bid must offer to be accepted.
+    pub fn minimum_bid(&self) -> i64 {
+        let leading = self.leading();
+        let claimed: usize = leading.iter().map(|bid| bid.quantity).sum();
+        match leading.last() {
+            Some(lowest) if claimed >= self.auction.lots => {
+                lowest.amount.saturating_add(self.min_increment)
+            }
+            _ => self.auction.reserve_price,
+        }
+    }
+
+    /// The provisionally winning bids, best first.
+    pub fn leading(&self) -> Bids {
+        fill_lots(
+            &self.auction,
+            &self.bids,
+            |bid| bid.amount >= self.auction.reserve_price,
+            &mut (),
+        )
+    }
+
+    /// Close the auction, resolving the accepted bids.
+    pub fn close(self) -> Sales {
+        self.auction.resolve_bids(self.bids)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn open_auction(lots: usize, reserve_price: i64) -> OpenAuction {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(lots)
+            .reserve_price(reserve_price)
+            .build();
+        OpenAuction::new(auction, 5)
+    }
+
+    #[test]
+    fn open_auction_starts_without_price() {
+        let open = open_auction(1, 10);
+        assert_eq!(open.current_price(), None);
+        assert_eq!(open.minimum_bid(), 10);
+        assert!(open.close().is_empty());
+    }
+
+    #[test]
+    fn open_auction_rejects_bids_below_reserve() {
+        let mut open = open_auction(1, 10);
+        assert!(!open.submit(bid![9, 1]));
+        assert_eq!(open.current_price(), None);
+    }
+
+    #[test]
+    fn open_auction_enforces_minimum_increment() {
+        let mut open = open_auction(1, 10);
+        assert!(open.submit(bid![10, 1]));
+        assert!(!open.submit(bid![14, 1]));
+        assert_eq!(open.current_price(), Some(10));
+        assert!(open.submit(bid![15, 1]));
+        assert_eq!(open.current_price(), Some(15));
+        assert_eq!(open.minimum_bid(), 20);
+    }
+
+    #[test]
+    fn open_auction_rounds_of_competing_bids() {
+        let mut open = open_auction(1, 10);
+        let alice = bid![10, 1];
+        let bob = bid![20, 1];
+        let alice_again = bid![30, 1];
+        assert!(open.submit(alice));
+        assert!(open.submit(bob));
+        assert!(open.submit(alice_again));
+        assert!(!open.submit(bid![32, 1]));
+
+        let sales = open.close();
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].bidder_id, alice_again.id);
+        assert_eq!(sales[0].amount, 30);
+    }
+
+    #[test]
+    fn open_auction_multi_lot_top_bids_lead() {
+        let mut open = open_auction(2, 10);
+        let first = bid![10, 1];
+        let second = bid![12, 1];
+        assert!(open.submit(first));
+        assert!(open.submit(second));
+        assert_eq!(open.minimum_bid(), 15);
+
+        let third = bid![15, 1];
+        assert!(open.submit(third));
+        let leading: Vec<_> = open.leading().iter().map(|bid| bid.id).collect();
+        assert_eq!(leading, vec![third.id, second.id]);
+        assert_eq!(open.minimum_bid(), 17);
+
+        let sales = open.close();
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 15);
+        assert_eq!(sales[1].amount, 12);
+    }
+
+    #[test]
+    fn open_auction_close_uses_auction_strategy() {
+        let auction = AuctionBuilder::new().lots(2).build();
+        let mut open = OpenAuction::new(auction, 1);
+        open.submit(bid![10, 1]);
+        open.submit(bid![20, 1]);
+        let sales = open.close();
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 10);
+        assert_eq!(sales[1].amount, 10);
+    }
+}
@@ -0,0 +1,304 @@
+//! Module containing a continuous order book matching engine.
+use alloc::{
+    collections::{BTreeMap, VecDeque},
+    vec::Vec,
+};
+
+use uuid::Uuid;
+
+use crate::{collections::HashMap, Bid, Sale};
+
+/// Enum representing which side of the book an order rests on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Buy,
+    Sell,
+}
+
+/// A trade between a buy order and a sell order.
+#[derive(Debug, Clone, Copy)]
+pub struct Trade {
+    /// The sale to the buyer.
+    buy: Sale,
+    /// The sale by the seller.
+    sell: Sale,
+}
+
+impl Trade {
+    /// The sale to the buyer.
+    pub const fn buy(&self) -> &Sale {
+        &self.buy
+    }
+
+    /// The sale by the seller.
+    pub const fn sell(&self) -> &Sale {
+        &self.sell
+    }
+
+    /// The price the trade happened at.
+    pub const fn price(&self) -> i64 {
+        self.buy.amount
+    }
+
+    /// The quantity traded.
+    pub const fn quantity(&self) -> usize {
+        self.buy.quantity
+    }
+}
+
+type Levels = BTreeMap<i64, VecDeque<Bid>>;
+
+/// A continuous order book matching orders by price-time priority.
+///
+/// Incoming orders trade against the best resting orders on the other side,
+/// oldest first within a price level, at the resting order's price. Whatever
+/// is left of an incoming order rests on the book.
+#[derive(Debug, Clone, Default)]
+pub struct OrderBook {
+    buys: Levels,
+    sells: Levels,
+    /// The side and price of every resting order.
+    orders: HashMap<Uuid, (Side, i64)>,
+}
+
+impl OrderBook {
+    /// Create an empty order book.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Submit an order to buy, returning the trades it caused.
+    pub fn buy(&mut self, bid: Bid) -> Vec<Trade> {
+        self.submit(Side::Buy, bid)
+    }
+
+    /// Submit an order to sell, returning the trades it caused.
+    pub fn sell(&mut self, bid: Bid) -> Vec<Trade> {
+        self.submit(Side::Sell, bid)
+    }
+
+    /// Submit an order to the given side, returning the trades it caused.
+    pub fn submit(&mut self, side: Side, mut bid: Bid) -> Vec<Trade> {
+        let mut trades = Vec::new();
+        while bid.quantity > 0 {
+            let best = match side {
+                Side::Buy => self.sells.first_entry(),
+                Side::Sell => self.buys.last_entry(),
+            };
+            let Some(mut level) = best else {
+                break;
+            };
+            let price = *level.key();
+            let crosses = match side {
+                Side::Buy => bid.amount >= price,
+                Side::Sell => bid.amount <= price,
+            };
+            if !crosses {
+                break;
+            }
+
+            let resting = level.get_mut().front_mut().expect("levels are never empty");
+            let quantity = bid.quantity.min(resting.quantity);
+            let (buyer, seller) = match side {
+                Side::Buy => (bid.bidder_id, resting.bidder_id),
+                Side::Sell => (resting.bidder_id, bid.bidder_id),
+            };
+            trades.push(Trade {
+                buy: Sale::new(buyer, price, quantity),
+                sell: Sale::new(seller, price, quantity),
+            });
+
+            bid.quantity -= quantity;
+            resting.quantity -= quantity;
+            if resting.quantity == 0 {
+                let filled = level.get_mut().pop_front().expect("levels are never empty");
+                self.orders.remove(&filled.id);
+                if level.get().is_empty() {
+                    level.remove();
+                }
+            }
+        }
+
+        if bid.quantity > 0 {
+            self.orders.insert(bid.id, (side, bid.amount));
+            self.levels_mut(side)
+                .entry(bid.amount)
+                .or_default()
+                .push_back(bid);
+        }
+        trades
+    }
+
+    /// Cancel a resting order, returning what was left of it.
+    pub fn cancel(&mut self, id: Uuid) -> Option<Bid> {
+        let (side, price) = self.orders.remove(&id)?;
+        let levels = self.levels_mut(side);
+        let level = levels.get_mut(&price)?;
+        let position = level.iter().position(|bid| bid.id == id)?;
+        let cancelled = level.remove(position);
+        if level.is_empty() {
+            levels.remove(&price);
+        }
+        cancelled
+    }
+
+    /// The highest resting buy price.
+    pub fn best_bid(&self) -> Option<i64> {
+        self.buys.keys().next_back().copied()
+    }
+
+    /// The lowest resting sell price.
+    pub fn best_ask(&self) -> Option<i64> {
+        self.sells.keys().next().copied()
+    }
+
+    /// The resting quantity at the best price levels of a side, best first.
+    pub fn depth(&self, side: Side, levels: usize) -> Vec<(i64, usize)> {
+        let quantity = |(price, orders): (&i64, &VecDeque<Bid>)| {
+            (*price, orders.iter().map(|bid| bid.quantity).sum())
+        };
+        match side {
+            Side::Buy => self.buys.iter().rev().take(levels).map(quantity).collect(),
+            Side::Sell => self.sells.iter().take(levels).map(quantity).collect(),
+        }
+    }
+
+    /// Whether the book has no resting orders.
+    pub fn is_empty(&self) -> bool {
+        self.orders.is_empty()
+    }
+
+    const fn levels_mut(&mut self, side: Side) -> &mut Levels {
+        match side {
+            Side::Buy => &mut self.buys,
+            Side::Sell => &mut self.sells,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn order_book_rests_non_crossing_orders() {
+        let mut book = OrderBook::new();
+        assert!(book.buy(bid![10, 1]).is_empty());
+        assert!(book.sell(bid![12, 1]).is_empty());
+        assert_eq!(book.best_bid(), Some(10));
+        assert_eq!(book.best_ask(), Some(12));
+    }
+
+    #[test]
+    fn order_book_empty_has_no_prices() {
+        let book = OrderBook::new();
+        assert!(book.is_empty());
+        assert_eq!(book.best_bid(), None);
+        assert_eq!(book.best_ask(), None);
+        assert!(book.depth(Side::Buy, 5).is_empty());
+    }
+
+    #[test]
+    fn order_book_crossing_order_trades_at_resting_price() {
+        let mut book = OrderBook::new();
+        let seller = bid![12, 1];
+        book.sell(seller);
+        let buyer = bid![15, 1];
+        let trades = book.buy(buyer);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].price(), 12);
+        assert_eq!(trades[0].quantity(), 1);
+        assert_eq!(trades[0].buy().bidder_id, buyer.id);
+        assert_eq!(trades[0].sell().bidder_id, seller.id);
+        assert!(book.is_empty());
+    }
+
+    #[test]
+    fn order_book_incoming_sell_trades_at_resting_buy_price() {
+        let mut book = OrderBook::new();
+        book.buy(bid![15, 2]);
+        let trades = book.sell(bid![11, 1]);
+        assert_eq!(trades[0].price(), 15);
+        assert_eq!(book.depth(Side::Buy, 1), vec![(15, 1)]);
+    }
+
+    #[test]
+    fn order_book_partial_match_rests_remainder() {
+        let mut book = OrderBook::new();
+        book.sell(bid![12, 2]);
+        let trades = book.buy(bid![12, 5]);
+        assert_eq!(trades.len(), 1);
+        assert_eq!(trades[0].quantity(), 2);
+        assert_eq!(book.best_ask(), None);
+        assert_eq!(book.depth(Side::Buy, 1), vec![(12, 3)]);
+    }
+
+    #[test]
+    fn order_book_sweeps_several_levels() {
+        let mut book = OrderBook::new();
+        book.sell(bid![10, 1]);
+        book.sell(bid![11, 2]);
+        book.sell(bid![13, 1]);
+        let trades = book.buy(bid![12, 4]);
+        let prices: Vec<_> = trades.iter().map(Trade::price).collect();
+        assert_eq!(prices, vec![10, 11]);
+        assert_eq!(trades[1].quantity(), 2);
+        assert_eq!(book.best_ask(), Some(13));
+        assert_eq!(book.best_bid(), Some(12));
+    }
+
+    #[test]
+    fn order_book_time_priority_within_level() {
+        let mut book = OrderBook::new();
+        let first = bid![10, 1];
+        let second = bid![10, 1];
+        book.sell(first);
+        book.sell(second);
+        let trades = book.buy(bid![10, 1]);
+        assert_eq!(trades[0].sell().bidder_id, first.id);
+        assert_eq!(book.depth(Side::Sell, 1), vec![(10, 1)]);
+    }
+
+    #[test]
+    fn order_book_depth_aggregates_levels() {
+        let mut book = OrderBook::new();
+        book.buy(bid![10, 1]);
+        book.buy(bid![10, 2]);
+        book.buy(bid![9, 4]);
+        book.buy(bid![8, 1]);
+        assert_eq!(book.depth(Side::Buy, 2), vec![(10, 3), (9, 4)]);
+    }
+
+    #[test]
+    fn order_book_cancel_removes_order() {
+        let mut book = OrderBook::new();
+        let order = bid![10, 1];
+        book.buy(order);
+        let cancelled = book.cancel(order.id).unwrap();
+        assert_eq!(cancelled.id, order.id);
+        assert!(book.is_empty());
+        assert_eq!(book.best_bid(), None);
+        assert!(book.cancel(order.id).is_none());
+    }
+
+    #[test]
+    fn order_book_cancel_partially_filled_order() {
+        let mut book = OrderBook::new();
+        let order = bid![10, 5];
+        book.sell(order);
+        book.buy(bid![10, 2]);
+        let cancelled = book.cancel(order.id).unwrap();
+        assert_eq!(cancelled.quantity, 3);
+        assert!(book.sell(bid![10, 1]).is_empty());
+        assert_eq!(book.depth(Side::Sell, 1), vec![(10, 1)]);
+    }
+
+    #[test]
+    fn order_book_fully_filled_order_cannot_be_cancelled() {
+        let mut book = OrderBook::new();
+        let order = bid![10, 1];
+        book.sell(order);
+        book.buy(bid![10, 1]);
+        assert!(book.cancel(order.id).is_none());
+    }
+}
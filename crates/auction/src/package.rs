@@ -0,0 +1,457 @@
+//! Module containing the combinatorial auction of package bids over named lots.
+use alloc::{collections::BTreeSet, string::String, vec, vec::Vec};
+
+use uuid::Uuid;
+
+use crate::{collections::HashMap, core_selecting, AuctionError, BidError, Sale, Sales};
+
+/// The most package bids winner determination will search, as every subset
+/// may need checking.
+pub const MAX_PACKAGE_BIDS: usize = 20;
+
+/// A bid for a bundle of named lots, won whole or not at all.
+///
+/// A bidder's package bids are exclusive, at most one of them wins.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackageBid {
+    id: Uuid,
+    bidder_id: Uuid,
+    lots: BTreeSet<String>,
+    /// The amount offered for the whole package, in cents.
+    amount: i64,
+}
+
+impl PackageBid {
+    /// Creates a new package bid with a random id, which is also its bidder id.
+    #[cfg(any(feature = "std", test))]
+    pub fn new<I, S>(lots: I, amount: i64) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self::with_id(Uuid::new_v4(), lots, amount)
+    }
+
+    /// Creates a new package bid with the given id, which is also its bidder
+    /// id.
+    pub fn with_id<I, S>(id: Uuid, lots: I, amount: i64) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            id,
+            bidder_id: id,
+            lots: lots.into_iter().map(Into::into).collect(),
+            amount,
+        }
+    }
+
+    /// Set the bidder making the package bid.
+    pub const fn with_bidder_id(mut self, bidder_id: Uuid) -> Self {
+        self.bidder_id = bidder_id;
+        self
+    }
+
+    /// The identifier of the package bid.
+    pub const fn id(&self) -> Uuid {
+        self.id
+    }
+
+    /// The identifier of the bidder.
+    pub const fn bidder_id(&self) -> Uuid {
+        self.bidder_id
+    }
+
+    /// The names of the lots in the package.
+    pub const fn lots(&self) -> &BTreeSet<String> {
+        &self.lots
+    }
+
+    /// The amount offered for the whole package, in cents.
+    pub const fn amount(&self) -> i64 {
+        self.amount
+    }
+}
+
+/// Enum representing how winning package bids are priced.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PaymentRule {
+    /// Winners pay their bid.
+    #[default]
+    PayAsBid,
+    /// Winners pay the value their package displaces from the other bidders.
+    Vcg,
+    /// Winners pay the least total no group of bidders could beat, shared out
+    /// as close to the VCG payments as possible.
+    CoreSelecting,
+}
+
+/// An auction of distinct named lots sold to package bids.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackageAuction {
+    lots: BTreeSet<String>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    payment_rule: PaymentRule,
+}
+
+impl PackageAuction {
+    /// Create an auction selling the named lots.
+    pub fn new<I, S>(lots: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            lots: lots.into_iter().map(Into::into).collect(),
+            payment_rule: PaymentRule::PayAsBid,
+        }
+    }
+
+    /// The names of the lots for sale.
+    pub const fn lots(&self) -> &BTreeSet<String> {
+        &self.lots
+    }
+
+    /// Set how winning packages are priced.
+    pub const fn with_payment_rule(mut self, payment_rule: PaymentRule) -> Self {
+        self.payment_rule = payment_rule;
+        self
+    }
+
+    /// Resolve the package bids, selling the non-overlapping set of packages
+    /// that raises the most bid value, with at most one package per bidder.
+    ///
+    /// Winners pay as the auction's [`PaymentRule`] sets. Every subset of
+    /// packages may be searched, so at most [`MAX_PACKAGE_BIDS`] package bids
+    /// are accepted. When several sets raise the same value the one favouring
+    /// the highest bids is chosen.
+    ///
+    /// # Arguments
+    /// * `packages` - The package bids.
+    ///
+    /// # Returns
+    /// A sale of one package for each winner, in input order, referencing the
+    /// winning package.
+    ///
+    pub fn resolve(&self, packages: &[PackageBid]) -> Result<Sales, AuctionError> {
+        if packages.len() > MAX_PACKAGE_BIDS {
+            return Err(AuctionError::TooManyPackageBids {
+                count: packages.len(),
+                limit: MAX_PACKAGE_BIDS,
+            });
+        }
+        let problem = self.problem(packages)?;
+        let (welfare, mut winners) = problem.solve(|candidate| candidate.amount);
+        winners.sort_unstable();
+        let payments = match self.payment_rule {
+            PaymentRule::PayAsBid => winners
+                .iter()
+                .map(|&winner| problem.candidates[winner].amount)
+                .collect(),
+            PaymentRule::Vcg => vcg_payments(&problem, welfare, &winners),
+            PaymentRule::CoreSelecting => {
+                let vcg = vcg_payments(&problem, welfare, &winners);
+                core_selecting::core_payments(&problem, &winners, &vcg)
+            }
+        };
+        Ok(winners
+            .into_iter()
+            .zip(payments)
+            .map(|(winner, payment)| {
+                let package = &packages[winner];
+                Sale::new(package.bidder_id, payment, 1).with_package_id(package.id)
+            })
+            .collect())
+    }
+
+    /// Check the package bids, indexing their lots and bidders.
+    fn problem(&self, packages: &[PackageBid]) -> Result<Problem, AuctionError> {
+        let lot_index: HashMap<&str, usize> = self
+            .lots
+            .iter()
+            .enumerate()
+            .map(|(index, lot)| (lot.as_str(), index))
+            .collect();
+        let mut bidders = HashMap::new();
+        let mut candidates = Vec::with_capacity(packages.len());
+        for package in packages {
+            let invalid = |error| AuctionError::InvalidBid {
+                bid_id: package.id,
+                error,
+            };
+            if package.lots.is_empty() {
+                return Err(invalid(BidError::EmptyPackage));
+            }
+            if package.amount < 0 {
+                return Err(invalid(BidError::NegativeAmount(package.amount)));
+            }
+            let lots = package
+                .lots
+                .iter()
+                .map(|lot| {
+                    lot_index
+                        .get(lot.as_str())
+                        .copied()
+                        .ok_or_else(|| AuctionError::UnknownLot {
+                            bid_id: package.id,
+                            lot: lot.clone(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let next_bidder = bidders.len();
+            let bidder = *bidders.entry(package.bidder_id).or_insert(next_bidder);
+            candidates.push(Candidate {
+                bidder,
+                lots,
+                amount: package.amount,
+            });
+        }
+        Ok(Problem {
+            candidates,
+            lot_count: self.lots.len(),
+            bidder_count: bidders.len(),
+        })
+    }
+}
+
+/// The VCG payment of each winner: the value the other bidders would win
+/// without the winner, less the value they win with them.
+fn vcg_payments(problem: &Problem, welfare: i128, winners: &[usize]) -> Vec<i64> {
+    winners
+        .iter()
+        .map(|&winner| {
+            let winning = &problem.candidates[winner];
+            let (without, _) = problem.solve(|candidate| {
+                if candidate.bidder == winning.bidder {
+                    0
+                } else {
+                    candidate.amount
+                }
+            });
+            let others = welfare - i128::from(winning.amount);
+            i64::try_from(without - others)
+                .map_or(winning.amount, |payment| payment.clamp(0, winning.amount))
+        })
+        .collect()
+}
+
+/// A package bid with its lots and bidder as indices.
+pub struct Candidate {
+    pub bidder: usize,
+    pub lots: Vec<usize>,
+    pub amount: i64,
+}
+
+/// The package bids of an auction, ready for winner determination.
+pub struct Problem {
+    pub candidates: Vec<Candidate>,
+    lot_count: usize,
+    bidder_count: usize,
+}
+
+impl Problem {
+    /// The highest total value of non-overlapping candidates, at most one per
+    /// bidder, valuing each candidate as given, and the candidates chosen.
+    ///
+    /// Candidates valued at zero or less are never chosen.
+    pub fn solve(&self, value: impl Fn(&Candidate) -> i64) -> (i128, Vec<usize>) {
+        let values: Vec<i64> = self.candidates.iter().map(value).collect();
+        let mut order: Vec<usize> = (0..values.len()).filter(|&i| values[i] > 0).collect();
+        order.sort_by_key(|&i| core::cmp::Reverse(values[i]));
+        let mut remaining = vec![0; order.len() + 1];
+        for (index, &candidate) in order.iter().enumerate().rev() {
+            remaining[index] = remaining[index + 1] + i128::from(values[candidate]);
+        }
+        let mut search = Search {
+            problem: self,
+            values: &values,
+            order: &order,
+            remaining,
+            lots_taken: vec![false; self.lot_count],
+            bidders_taken: vec![false; self.bidder_count],
+            chosen: Vec::new(),
+            best: Vec::new(),
+            best_value: 0,
+        };
+        search.run(0, 0);
+        (search.best_value, search.best)
+    }
+}
+
+/// A depth first branch and bound search over subsets of the candidates, in
+/// order of value, the remaining value bounding what the rest can add.
+struct Search<'a> {
+    problem: &'a Problem,
+    values: &'a [i64],
+    order: &'a [usize],
+    remaining: Vec<i128>,
+    lots_taken: Vec<bool>,
+    bidders_taken: Vec<bool>,
+    chosen: Vec<usize>,
+    best: Vec<usize>,
+    best_value: i128,
+}
+
+impl Search<'_> {
+    fn run(&mut self, index: usize, value: i128) {
+        if value > self.best_value {
+            self.best_value = value;
+            self.best.clone_from(&self.chosen);
+        }
+        if index == self.order.len() || value + self.remaining[index] <= self.best_value {
+            return;
+        }
+        let candidate = self.order[index];
+        let fits = {
+            let candidate = &self.problem.candidates[candidate];
+            !self.bidders_taken[candidate.bidder]
+                && candidate.lots.iter().all(|&lot| !self.lots_taken[lot])
+        };
+        if fits {
+            self.take(candidate, true);
+            self.chosen.push(candidate);
+            self.run(index + 1, value + i128::from(self.values[candidate]));
+            self.chosen.pop();
+            self.take(candidate, false);
+        }
+        self.run(index + 1, value);
+    }
+
+    fn take(&mut self, candidate: usize, taken: bool) {
+        let candidate = &self.problem.candidates[candidate];
+        self.bidders_taken[candidate.bidder] = taken;
+        for &lot in &candidate.lots {
+            self.lots_taken[lot] = taken;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::*;
+
+    fn auction() -> PackageAuction {
+        PackageAuction::new(["A", "B"])
+    }
+
+    fn won(sales: &Sales) -> Vec<Uuid> {
+        sales.iter().filter_map(Sale::package_id).collect()
+    }
+
+    #[test]
+    fn package_beats_greedy_choice() {
+        let bundle = PackageBid::new(["A", "B"], 500);
+        let a = PackageBid::new(["A"], 300);
+        let b = PackageBid::new(["B"], 250);
+        let sales = auction().resolve(&[bundle, a.clone(), b.clone()]).unwrap();
+        assert_eq!(won(&sales), vec![a.id(), b.id()]);
+        assert_eq!(sales.total_revenue(), Some(550));
+        assert_eq!(
+            sales[0],
+            Sale::new(a.bidder_id(), 300, 1).with_package_id(a.id())
+        );
+    }
+
+    #[test]
+    fn package_bundle_wins_when_worth_more() {
+        let bundle = PackageBid::new(["A", "B"], 600);
+        let a = PackageBid::new(["A"], 300);
+        let b = PackageBid::new(["B"], 250);
+        let sales = auction().resolve(&[a, bundle.clone(), b]).unwrap();
+        assert_eq!(won(&sales), vec![bundle.id()]);
+    }
+
+    #[test]
+    fn package_bids_from_one_bidder_are_exclusive() {
+        let bidder = Uuid::new_v4();
+        let a = PackageBid::new(["A"], 300).with_bidder_id(bidder);
+        let b = PackageBid::new(["B"], 250).with_bidder_id(bidder);
+        let rival = PackageBid::new(["B"], 100);
+        let sales = auction().resolve(&[a.clone(), b, rival.clone()]).unwrap();
+        assert_eq!(won(&sales), vec![a.id(), rival.id()]);
+    }
+
+    #[test]
+    fn package_without_bids_sells_nothing() {
+        assert!(auction().resolve(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn package_rejects_unknown_lot() {
+        let package = PackageBid::new(["A", "C"], 300);
+        assert_eq!(
+            auction().resolve(std::slice::from_ref(&package)),
+            Err(AuctionError::UnknownLot {
+                bid_id: package.id(),
+                lot: "C".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn package_rejects_invalid_bids() {
+        let empty = PackageBid::new(Vec::<String>::new(), 300);
+        assert_eq!(
+            auction().resolve(std::slice::from_ref(&empty)),
+            Err(AuctionError::InvalidBid {
+                bid_id: empty.id(),
+                error: BidError::EmptyPackage,
+            })
+        );
+    }
+
+    #[test]
+    fn package_rejects_too_many_bids() {
+        let packages: Vec<_> = (0..=MAX_PACKAGE_BIDS)
+            .map(|amount| PackageBid::new(["A"], amount as i64))
+            .collect();
+        assert_eq!(
+            auction().resolve(&packages),
+            Err(AuctionError::TooManyPackageBids {
+                count: MAX_PACKAGE_BIDS + 1,
+                limit: MAX_PACKAGE_BIDS,
+            })
+        );
+    }
+
+    #[test]
+    fn package_search_matches_brute_force() {
+        let lots = ["A", "B", "C", "D"];
+        let auction = PackageAuction::new(lots);
+        for seed in 0..30_u64 {
+            let mut rng = generators::BidRng::new(seed);
+            let packages: Vec<_> = (0..12)
+                .map(|_| {
+                    let mask = rng.next_u64() % 15 + 1;
+                    let lots = lots
+                        .iter()
+                        .enumerate()
+                        .filter(|(index, _)| mask & (1 << index) != 0)
+                        .map(|(_, lot)| *lot);
+                    PackageBid::new(lots, (rng.next_u64() % 100) as i64)
+                })
+                .collect();
+            let best = (0_u32..1 << packages.len())
+                .filter_map(|subset| {
+                    let chosen: Vec<_> = (0..packages.len())
+                        .filter(|index| subset & (1 << index) != 0)
+                        .map(|index| &packages[index])
+                        .collect();
+                    let lots: usize = chosen.iter().map(|package| package.lots().len()).sum();
+                    let distinct: std::collections::BTreeSet<_> =
+                        chosen.iter().flat_map(|package| package.lots()).collect();
+                    (lots == distinct.len())
+                        .then(|| chosen.iter().map(|package| package.amount()).sum::<i64>())
+                })
+                .max();
+            let sales = auction.resolve(&packages).unwrap();
+            assert_eq!(sales.total_revenue(), best.map(i128::from));
+        }
+    }
+}
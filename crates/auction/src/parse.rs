@@ -0,0 +1,187 @@
+//! Module for parsing bids from short text such as `150x3`.
+//!
+//! The field parsers are shared with the CSV reader, so a value rejected in
+//! one is rejected the same way in the other.
+use core::str::FromStr;
+
+use uuid::Uuid;
+
+use crate::{Bid, BidParseErrorKind, ParseBidError};
+
+/// Parse an amount in cents, rejecting amounts below zero.
+pub fn amount(value: &str) -> Result<i64, BidParseErrorKind> {
+    let amount: i64 = value
+        .parse()
+        .map_err(|_| BidParseErrorKind::InvalidAmount(value.into()))?;
+    if amount < 0 {
+        return Err(BidParseErrorKind::NegativeAmount(amount));
+    }
+    Ok(amount)
+}
+
+/// Parse a quantity of units.
+pub fn quantity(value: &str) -> Result<usize, BidParseErrorKind> {
+    value
+        .parse()
+        .map_err(|_| BidParseErrorKind::InvalidQuantity(value.into()))
+}
+
+/// Parse an id in any of the forms a uuid is written in.
+pub fn id(value: &str) -> Result<Uuid, BidParseErrorKind> {
+    Uuid::parse_str(value).map_err(|_| BidParseErrorKind::InvalidUuid(value.into()))
+}
+
+/// A part of the text trimmed of whitespace, with the byte offset it starts
+/// at.
+struct Part<'a> {
+    value: &'a str,
+    offset: usize,
+}
+
+impl<'a> Part<'a> {
+    /// The part of `text` starting `start` bytes in.
+    fn new(text: &str, start: usize, value: &'a str) -> Self {
+        let trimmed = value.trim_start();
+        Self {
+            value: trimmed.trim_end(),
+            offset: start + value.len() - trimmed.len(),
+        }
+    }
+
+    /// Parse the part, failing at its offset, or as missing when it is
+    /// empty.
+    fn parse<T>(
+        &self,
+        name: &'static str,
+        parse: impl FnOnce(&str) -> Result<T, BidParseErrorKind>,
+    ) -> Result<T, ParseBidError> {
+        if self.value.is_empty() {
+            return Err(ParseBidError::new(
+                self.offset,
+                BidParseErrorKind::MissingField(name),
+            ));
+        }
+        parse(self.value).map_err(|kind| ParseBidError::new(self.offset, kind))
+    }
+}
+
+impl FromStr for Bid {
+    type Err = ParseBidError;
+
+    /// Parses `amount`, `amount x quantity`, `id:amount x quantity` or
+    /// `amount x quantity [id]` as written by the bid's `Display`. The
+    /// quantity is one unless given, and the bid gets a random id unless one
+    /// is given.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let (id_part, terms) = if let Some((id, terms)) = text.split_once(':') {
+            (
+                Some(Part::new(text, 0, id)),
+                Part::new(text, id.len() + 1, terms),
+            )
+        } else if let Some((terms, id)) = text
+            .trim_end()
+            .strip_suffix(']')
+            .and_then(|rest| rest.rsplit_once('['))
+        {
+            (
+                Some(Part::new(text, terms.len() + 1, id)),
+                Part::new(text, 0, terms),
+            )
+        } else {
+            (None, Part::new(text, 0, text))
+        };
+        let (amount_part, quantity_part) = match terms.value.split_once('x') {
+            Some((amount, quantity)) => (
+                Part::new(text, terms.offset, amount),
+                Some(Part::new(text, terms.offset + amount.len() + 1, quantity)),
+            ),
+            None => (Part::new(text, terms.offset, terms.value), None),
+        };
+        let amount = amount_part.parse("amount", amount)?;
+        let quantity = match quantity_part {
+            Some(part) => part.parse("quantity", quantity)?,
+            None => 1,
+        };
+        let id = match id_part {
+            Some(part) => part.parse("id", id)?,
+            None => Uuid::new_v4(),
+        };
+        Ok(Self::with_id(id, amount, quantity))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    const ID: &str = "67e55044-10b1-426f-9247-bb680e5fe0c8";
+
+    fn terms(bid: Bid) -> (Uuid, Uuid, i64, usize) {
+        (bid.id, bid.bidder_id, bid.amount, bid.quantity)
+    }
+
+    #[test]
+    fn parse_amount_and_quantity() {
+        let bid: Bid = "150x3".parse().unwrap();
+        assert_eq!((bid.amount, bid.quantity), (150, 3));
+        let bid: Bid = " 150 x 3 ".parse().unwrap();
+        assert_eq!((bid.amount, bid.quantity), (150, 3));
+        let bid: Bid = "150".parse().unwrap();
+        assert_eq!((bid.amount, bid.quantity), (150, 1));
+    }
+
+    #[test]
+    fn parse_with_id() {
+        let id = Uuid::parse_str(ID).unwrap();
+        let bid: Bid = format!("{ID}:150x3").parse().unwrap();
+        assert_eq!(terms(bid), (id, id, 150, 3));
+        let bid: Bid = format!("150x3 [{ID}]").parse().unwrap();
+        assert_eq!(terms(bid), (id, id, 150, 3));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let bid = Bid::with_id(Uuid::parse_str(ID).unwrap(), 150, 3);
+        assert_eq!(bid.to_string(), format!("150x3 [{ID}]"));
+        let parsed: Bid = bid.to_string().parse().unwrap();
+        assert_eq!(terms(parsed), terms(bid));
+        for bid in generators::large_quantities(1, 20, 1_000, 5) {
+            let parsed: Bid = bid.to_string().parse().unwrap();
+            assert_eq!(terms(parsed), terms(bid));
+        }
+    }
+
+    #[test]
+    fn sale_display() {
+        let sale = Sale::new(Uuid::parse_str(ID).unwrap(), 150, 3);
+        assert_eq!(sale.to_string(), "67e55044… pays 150 for 3");
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_failure() {
+        let error = |text: &str| text.parse::<Bid>().unwrap_err();
+        assert_eq!(
+            error("150xlots"),
+            ParseBidError::new(4, BidParseErrorKind::InvalidQuantity("lots".into()))
+        );
+        assert_eq!(
+            error("  -5x1"),
+            ParseBidError::new(2, BidParseErrorKind::NegativeAmount(-5))
+        );
+        assert_eq!(
+            error("nope:150x3"),
+            ParseBidError::new(0, BidParseErrorKind::InvalidUuid("nope".into()))
+        );
+        assert_eq!(
+            error("150x3 [nope]"),
+            ParseBidError::new(7, BidParseErrorKind::InvalidUuid("nope".into()))
+        );
+        assert_eq!(
+            error("x3"),
+            ParseBidError::new(0, BidParseErrorKind::MissingField("amount"))
+        );
+        assert_eq!(error("150x").offset(), 4);
+        assert_eq!(error("").kind(), &BidParseErrorKind::MissingField("amount"));
+        assert_eq!(error("1.5x2").to_string(), "byte 0: invalid amount `1.5`");
+    }
+}
@@ -0,0 +1,17 @@
+//! The types most uses of the crate need, for importing all at once.
+//!
+//! ```
+//! use auction::prelude::*;
+//!
+//! let auction = AuctionBuilder::new()
+//!     .strategy(AuctionStrategy::MultiPrice)
+//!     .lots(2)
+//!     .build();
+//! let id = Uuid::from_u128(1);
+//! let sales: Sales = auction.resolve_bids(vec![bid![id; 30, 2], bid![id; 20, 1]]);
+//! assert_eq!(sales.total_quantity(), 2);
+//! ```
+pub use crate::{
+    bid, Auction, AuctionBuilder, AuctionDirection, AuctionError, AuctionResult, AuctionStrategy,
+    Bid, Bids, Sale, Sales, SalesSummary, SalesTotals, Strategy, Uuid,
+};
@@ -0,0 +1,212 @@
+//! Module for reserve prices set from the bids an auction receives.
+use alloc::{borrow::Cow, sync::Arc, vec::Vec};
+use core::fmt;
+
+use crate::{Auction, Bid, Bids, SalesSummary};
+
+/// A function working out the reserve price from the bids.
+type ReserveFn = dyn Fn(&[Bid]) -> i64 + Send + Sync;
+
+/// A reserve price worked out from the valid bids before they are allocated.
+#[derive(Clone)]
+pub struct DynamicReserve(Arc<ReserveFn>);
+
+impl DynamicReserve {
+    /// Wrap the function working out the reserve from the bids.
+    pub fn new(reserve: impl Fn(&[Bid]) -> i64 + Send + Sync + 'static) -> Self {
+        Self(Arc::new(reserve))
+    }
+
+    /// The reserve for the bids, never below zero.
+    pub fn evaluate(&self, bids: &[Bid]) -> i64 {
+        (self.0)(bids).max(0)
+    }
+}
+
+impl fmt::Debug for DynamicReserve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DynamicReserve")
+    }
+}
+
+/// The auction with its dynamic reserve, evaluated once over the valid bids,
+/// as its flat reserve price. Borrowed untouched without a dynamic reserve.
+pub fn apply_dynamic_reserve<'a>(auction: &'a Auction, bids: &[Bid]) -> Cow<'a, Auction> {
+    let Some(dynamic_reserve) = &auction.dynamic_reserve else {
+        return Cow::Borrowed(auction);
+    };
+    let valid: Bids = bids
+        .iter()
+        .filter(|bid| bid.validate().is_ok())
+        .copied()
+        .collect();
+    Cow::Owned(Auction {
+        reserve_price: dynamic_reserve.evaluate(&valid),
+        reserve_schedule: Vec::new(),
+        dynamic_reserve: None,
+        ..auction.clone()
+    })
+}
+
+/// The revenue an auction would have made at each candidate reserve price.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReserveSweep {
+    best_reserve: Option<i64>,
+    revenue: i128,
+    curve: Vec<(i64, i128, usize)>,
+}
+
+impl ReserveSweep {
+    /// The reserve with the most revenue, the lowest of any tied, None when
+    /// there were no valid bids.
+    pub const fn best_reserve(&self) -> Option<i64> {
+        self.best_reserve
+    }
+
+    /// The revenue at the best reserve in cents.
+    pub const fn revenue(&self) -> i128 {
+        self.revenue
+    }
+
+    /// The reserve, revenue and lots sold at each candidate, lowest reserve
+    /// first.
+    pub fn curve(&self) -> &[(i64, i128, usize)] {
+        &self.curve
+    }
+}
+
+/// Resolve the bids at every distinct valid bid amount as a flat reserve.
+pub fn sweep_reserves(auction: &Auction, bids: &[Bid]) -> ReserveSweep {
+    let mut candidates: Vec<i64> = bids
+        .iter()
+        .filter(|bid| bid.validate().is_ok())
+        .map(|bid| bid.amount)
+        .collect();
+    candidates.sort_unstable();
+    candidates.dedup();
+    let curve: Vec<(i64, i128, usize)> = candidates
+        .into_iter()
+        .map(|reserve_price| {
+            let sales = Auction {
+                reserve_price,
+                reserve_schedule: Vec::new(),
+                dynamic_reserve: None,
+                ..auction.clone()
+            }
+            .resolve_bids_ref(bids);
+            let revenue = sales.total_revenue().unwrap_or(i128::MAX);
+            (reserve_price, revenue, sales.total_quantity())
+        })
+        .collect();
+    let best = curve.iter().rev().max_by_key(|&&(_, revenue, _)| revenue);
+    ReserveSweep {
+        best_reserve: best.map(|&(reserve, _, _)| reserve),
+        revenue: best.map_or(0, |&(_, revenue, _)| revenue),
+        curve,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    /// The amount a quarter of the way up the sorted bid amounts.
+    fn lower_quartile(bids: &[Bid]) -> i64 {
+        let mut amounts: Vec<i64> = bids.iter().map(|bid| bid.amount).collect();
+        amounts.sort_unstable();
+        amounts.get(amounts.len() / 4).copied().unwrap_or_default()
+    }
+
+    #[test]
+    fn percentile_reserve_overrides_static_reserve() {
+        let bids: Bids = [40, 60, 80, 100, 120, 140, 160, 180]
+            .into_iter()
+            .map(|amount| bid![amount, 1])
+            .collect();
+        let auction = AuctionBuilder::new()
+            .lots(8)
+            .reserve_price(10)
+            .dynamic_reserve(|bids| lower_quartile(bids).max(50))
+            .build();
+        let result = auction.resolve(bids.clone()).unwrap();
+        assert_eq!(result.reserve_price(), 80);
+        assert_eq!(result.lots_sold(), 6);
+        assert_eq!(result.clearing_price(), Some(80));
+        assert_eq!(auction.resolve_bids(bids).len(), 6);
+        assert_eq!(auction.reserve_price(), 10);
+    }
+
+    #[test]
+    fn percentile_reserve_is_floored() {
+        let bids: Bids = vec![bid![20, 1], bid![30, 1], bid![60, 1]];
+        let auction = AuctionBuilder::new()
+            .lots(3)
+            .dynamic_reserve(|bids| lower_quartile(bids).max(50))
+            .build();
+        let result = auction.resolve(bids).unwrap();
+        assert_eq!(result.reserve_price(), 50);
+        assert_eq!(result.lots_sold(), 1);
+    }
+
+    #[test]
+    fn reserve_above_every_bid_sells_nothing() {
+        let bids: Bids = vec![bid![20, 1], bid![30, 2]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .dynamic_reserve(|bids| bids.iter().map(|bid| bid.amount).max().unwrap_or(0) + 1)
+            .build();
+        let result = auction.resolve(bids.clone()).unwrap();
+        assert_eq!(result.reserve_price(), 31);
+        assert!(result.sales().is_empty());
+        assert_eq!(result.lots_unsold(), 3);
+        assert_eq!(auction.resolve_iter(bids).count(), 0);
+    }
+
+    #[test]
+    fn optimal_reserve_beats_selling_everything() {
+        // Selling every lot clears at 30 for 90, holding out for the 100 bid
+        // sells one lot for 100.
+        let bids: Bids = vec![bid![100, 1], bid![30, 1], bid![30, 1]];
+        let auction = AuctionBuilder::new().lots(3).build();
+        let sweep = auction.optimal_reserve(&bids);
+        assert_eq!(sweep.curve(), &[(30, 90, 3), (100, 100, 1)]);
+        assert_eq!(sweep.best_reserve(), Some(100));
+        assert_eq!(sweep.revenue(), 100);
+        let sales = AuctionBuilder::new()
+            .lots(3)
+            .reserve_price(100)
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(sales.total_revenue(), Some(sweep.revenue()));
+    }
+
+    #[test]
+    fn optimal_reserve_ties_keep_the_lowest() {
+        let bids: Bids = vec![bid![40, 1], bid![20, 2]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(1)
+            .build();
+        let sweep = auction.optimal_reserve(&bids);
+        assert_eq!(sweep.curve(), &[(20, 40, 1), (40, 40, 1)]);
+        assert_eq!(sweep.best_reserve(), Some(20));
+    }
+
+    #[test]
+    fn optimal_reserve_without_bids() {
+        let sweep = AuctionBuilder::new().build().optimal_reserve(&[]);
+        assert_eq!(sweep.best_reserve(), None);
+        assert_eq!(sweep.revenue(), 0);
+        assert!(sweep.curve().is_empty());
+    }
+
+    #[test]
+    fn reserve_sees_only_valid_bids() {
+        let bids: Bids = vec![bid![-5, 1], bid![20, 1]];
+        let auction = AuctionBuilder::new()
+            .dynamic_reserve(|bids| bids.iter().map(|bid| bid.amount).min().unwrap_or(0))
+            .build();
+        assert_eq!(auction.resolve(bids).unwrap().reserve_price(), 20);
+    }
+}
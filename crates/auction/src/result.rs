@@ -0,0 +1,376 @@
+//! Module containing the summary of a resolved auction.
+use alloc::vec::Vec;
+
+use crate::{Auction, AuctionDirection, AuctionError, Bids, Sale, Sales};
+
+/// Enum representing how close the best bid came to a soft reserve.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ReserveStatus {
+    /// The best bid meets the reserve.
+    Met,
+    /// The best bid is short of the reserve by no more than the tolerance.
+    NearlyMet { shortfall: i64 },
+    /// The best bid is short of the reserve by more than the tolerance, or
+    /// there were no bids.
+    NotMet,
+}
+
+impl ReserveStatus {
+    /// Assess the best bid against the reserve, the tolerance being a share of
+    /// the reserve in basis points. A shortfall of exactly the tolerance is
+    /// nearly met.
+    pub(crate) fn assess(reserve_price: i64, tolerance_bps: u32, best_bid: Option<i64>) -> Self {
+        let Some(best_bid) = best_bid else {
+            return Self::NotMet;
+        };
+        if best_bid >= reserve_price {
+            return Self::Met;
+        }
+        let shortfall = i128::from(reserve_price) - i128::from(best_bid);
+        let tolerance = i128::from(reserve_price) * i128::from(tolerance_bps);
+        match i64::try_from(shortfall) {
+            Ok(shortfall) if i128::from(shortfall) * 10_000 <= tolerance => {
+                Self::NearlyMet { shortfall }
+            }
+            _ => Self::NotMet,
+        }
+    }
+}
+
+/// The outcome of resolving bids against an auction.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AuctionResult {
+    /// The sales produced by the auction.
+    sales: Sales,
+    /// The marginal price lots sold at, None when nothing sold.
+    clearing_price: Option<i64>,
+    /// The number of lots sold.
+    lots_sold: usize,
+    /// The number of lots left unsold.
+    lots_unsold: usize,
+    /// The total revenue in cents.
+    revenue: i128,
+    /// The bids that expired before the auction was resolved.
+    expired: Bids,
+    /// The bids submitted after the auction closed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    late: Bids,
+    /// The bids withdrawn from the session before it closed.
+    #[cfg_attr(feature = "serde", serde(default))]
+    withdrawn: Bids,
+    /// The reserve price the bids were resolved against.
+    #[cfg_attr(feature = "serde", serde(default))]
+    reserve_price: i64,
+    /// How close the best bid came to the soft reserve, None without one.
+    reserve_status: Option<ReserveStatus>,
+}
+
+impl AuctionResult {
+    /// Summarise the sales produced by the given auction, failing if the
+    /// revenue overflows.
+    pub(crate) fn new(auction: &Auction, sales: Sales) -> Result<Self, AuctionError> {
+        let prices = sales
+            .iter()
+            .filter(|sale| sale.quantity > 0)
+            .map(|sale| sale.amount);
+        let clearing_price = match auction.direction {
+            AuctionDirection::Forward => prices.min(),
+            AuctionDirection::Reverse => prices.max(),
+        };
+        let lots_sold = sales.iter().map(|sale| sale.quantity).sum();
+        let revenue = sales.iter().try_fold(0_i128, |revenue, sale| {
+            revenue
+                .checked_add(sale.total())
+                .ok_or(AuctionError::AmountOverflow)
+        })?;
+        Ok(Self {
+            sales,
+            clearing_price,
+            lots_sold,
+            lots_unsold: auction.lots.saturating_sub(lots_sold),
+            revenue,
+            expired: Vec::new(),
+            late: Vec::new(),
+            withdrawn: Vec::new(),
+            reserve_price: auction.reserve_price,
+            reserve_status: None,
+        })
+    }
+
+    /// Record the bids that expired before resolution.
+    pub(crate) fn with_expired(mut self, expired: Bids) -> Self {
+        self.expired = expired;
+        self
+    }
+
+    /// Record the bids submitted after the auction closed.
+    pub(crate) fn with_late(mut self, late: Bids) -> Self {
+        self.late = late;
+        self
+    }
+
+    /// Record the bids withdrawn before the auction closed.
+    pub(crate) fn with_withdrawn(mut self, withdrawn: Bids) -> Self {
+        self.withdrawn = withdrawn;
+        self
+    }
+
+    /// Record how close the best bid came to the soft reserve.
+    pub(crate) const fn with_reserve_status(
+        mut self,
+        reserve_status: Option<ReserveStatus>,
+    ) -> Self {
+        self.reserve_status = reserve_status;
+        self
+    }
+
+    /// The sales produced by the auction.
+    pub const fn sales(&self) -> &Sales {
+        &self.sales
+    }
+
+    /// Consume the result, returning the sales.
+    pub fn into_sales(self) -> Sales {
+        self.sales
+    }
+
+    /// The reserve price the bids were resolved against, the evaluated reserve
+    /// when the auction has a dynamic one.
+    pub const fn reserve_price(&self) -> i64 {
+        self.reserve_price
+    }
+
+    /// The marginal price lots sold at, None when nothing sold.
+    ///
+    /// This is the lowest sale price, or the highest for reverse auctions.
+    pub const fn clearing_price(&self) -> Option<i64> {
+        self.clearing_price
+    }
+
+    /// The number of lots sold.
+    pub const fn lots_sold(&self) -> usize {
+        self.lots_sold
+    }
+
+    /// The number of lots left unsold.
+    pub const fn lots_unsold(&self) -> usize {
+        self.lots_unsold
+    }
+
+    /// The total revenue in cents, an `i128` so that revenue beyond `i64`
+    /// is kept.
+    pub const fn revenue(&self) -> i128 {
+        self.revenue
+    }
+
+    /// The bids that expired before the auction was resolved.
+    pub const fn expired(&self) -> &Bids {
+        &self.expired
+    }
+
+    /// The bids submitted after the auction closed.
+    pub const fn late(&self) -> &Bids {
+        &self.late
+    }
+
+    /// The bids withdrawn from the session before it closed.
+    pub const fn withdrawn(&self) -> &Bids {
+        &self.withdrawn
+    }
+
+    /// How close the best bid came to the soft reserve, None when the auction
+    /// has no soft reserve.
+    pub const fn reserve_status(&self) -> Option<ReserveStatus> {
+        self.reserve_status
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn result_for_no_bids() {
+        let auction = AuctionBuilder::new().lots(3).build();
+        let result = auction.resolve(vec![]).unwrap();
+        assert!(result.sales().is_empty());
+        assert_eq!(result.clearing_price(), None);
+        assert_eq!(result.lots_sold(), 0);
+        assert_eq!(result.lots_unsold(), 3);
+        assert_eq!(result.revenue(), 0);
+    }
+
+    #[test]
+    fn result_for_fully_sold_auction() {
+        let bids: Bids = vec![bid![10, 2], bid![20, 1], bid![5, 1]];
+        let auction = AuctionBuilder::new().lots(3).build();
+        let result = auction.resolve(bids).unwrap();
+        assert_eq!(result.sales().len(), 2);
+        assert_eq!(result.clearing_price(), Some(10));
+        assert_eq!(result.lots_sold(), 3);
+        assert_eq!(result.lots_unsold(), 0);
+        assert_eq!(result.revenue(), 30);
+    }
+
+    #[test]
+    fn result_for_partially_sold_auction() {
+        let bids: Bids = vec![bid![10, 1], bid![20, 1], bid![5, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(5)
+            .reserve_price(8)
+            .build();
+        let result = auction.resolve(bids).unwrap();
+        assert_eq!(result.sales().len(), 2);
+        assert_eq!(result.clearing_price(), Some(10));
+        assert_eq!(result.lots_sold(), 2);
+        assert_eq!(result.lots_unsold(), 3);
+        assert_eq!(result.revenue(), 30);
+    }
+
+    #[test]
+    fn result_for_reverse_auction_uses_highest_price() {
+        let bids: Bids = vec![bid![10, 1], bid![20, 1], bid![30, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .direction(AuctionDirection::Reverse)
+            .lots(2)
+            .build();
+        let result = auction.resolve(bids).unwrap();
+        assert_eq!(result.clearing_price(), Some(20));
+        assert_eq!(result.revenue(), 30);
+    }
+
+    #[test]
+    fn result_reports_expired_bids() {
+        let expired = bid![30, 1].with_expires_at(100);
+        let bids: Bids = vec![expired, bid![20, 1].with_expires_at(200), bid![10, 1]];
+        let auction = AuctionBuilder::new().lots(2).build();
+        let result = auction.resolve_at(bids, 150).unwrap();
+        assert_eq!(result.sales().len(), 2);
+        assert_eq!(result.clearing_price(), Some(10));
+        assert_eq!(result.expired().len(), 1);
+        assert_eq!(result.expired()[0].id, expired.id);
+    }
+
+    fn soft_reserve_auction() -> Auction {
+        AuctionBuilder::new()
+            .lots(1)
+            .soft_reserve(10_000, 500)
+            .build()
+    }
+
+    #[test]
+    fn result_soft_reserve_met() {
+        let result = soft_reserve_auction()
+            .resolve(vec![bid![10_000, 1]])
+            .unwrap();
+        assert_eq!(result.reserve_status(), Some(ReserveStatus::Met));
+        assert_eq!(result.sales().len(), 1);
+    }
+
+    #[test]
+    fn result_soft_reserve_nearly_met_at_exact_tolerance() {
+        let result = soft_reserve_auction()
+            .resolve(vec![bid![9_500, 1], bid![9_000, 1]])
+            .unwrap();
+        assert_eq!(
+            result.reserve_status(),
+            Some(ReserveStatus::NearlyMet { shortfall: 500 })
+        );
+        assert!(result.sales().is_empty());
+    }
+
+    #[test]
+    fn result_soft_reserve_not_met_just_past_tolerance() {
+        let result = soft_reserve_auction()
+            .resolve(vec![bid![9_499, 1]])
+            .unwrap();
+        assert_eq!(result.reserve_status(), Some(ReserveStatus::NotMet));
+    }
+
+    #[test]
+    fn result_soft_reserve_not_met_without_bids() {
+        let result = soft_reserve_auction().resolve(vec![]).unwrap();
+        assert_eq!(result.reserve_status(), Some(ReserveStatus::NotMet));
+    }
+
+    #[test]
+    fn result_soft_reserve_ignores_expired_bids() {
+        let bids: Bids = vec![bid![10_000, 1].with_expires_at(100), bid![9_600, 1]];
+        let result = soft_reserve_auction().resolve_at(bids, 100).unwrap();
+        assert_eq!(
+            result.reserve_status(),
+            Some(ReserveStatus::NearlyMet { shortfall: 400 })
+        );
+    }
+
+    #[test]
+    fn result_without_soft_reserve_has_no_status() {
+        let auction = AuctionBuilder::new().lots(1).reserve_price(10).build();
+        assert_eq!(
+            auction.resolve(vec![bid![5, 1]]).unwrap().reserve_status(),
+            None
+        );
+    }
+
+    #[test]
+    fn soft_reserve_fractional_tolerance_is_not_rounded_up() {
+        assert_eq!(
+            ReserveStatus::assess(99, 100, Some(98)),
+            ReserveStatus::NotMet
+        );
+        assert_eq!(
+            ReserveStatus::assess(100, 100, Some(99)),
+            ReserveStatus::NearlyMet { shortfall: 1 }
+        );
+    }
+
+    #[test]
+    fn soft_reserve_assessment_does_not_overflow() {
+        assert_eq!(
+            ReserveStatus::assess(i64::MAX, 10_000, Some(0)),
+            ReserveStatus::NearlyMet {
+                shortfall: i64::MAX
+            }
+        );
+        assert_eq!(
+            ReserveStatus::assess(0, 0, Some(i64::MIN)),
+            ReserveStatus::NotMet
+        );
+    }
+
+    #[test]
+    fn result_sale_total_beyond_i64_fits() {
+        let auction = AuctionBuilder::new().lots(2).build();
+        let result = auction.resolve(vec![bid![i64::MAX, 2]]).unwrap();
+        assert_eq!(result.revenue(), i128::from(i64::MAX) * 2);
+    }
+
+    #[test]
+    fn result_revenue_beyond_i64_fits() {
+        let bids: Bids = vec![bid![i64::MAX - 1, 1], bid![i64::MAX, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+        assert_eq!(
+            auction.resolve(bids).unwrap().revenue(),
+            i128::from(i64::MAX) * 2 - 1
+        );
+    }
+
+    #[test]
+    fn result_reports_overflowing_revenue() {
+        let mut bids = vec![bid![i64::MAX, usize::MAX]];
+        bids.extend((0..8).map(|_| bid![i64::MAX - 1, 1]));
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .lots(usize::MAX)
+            .build();
+        let result = auction.resolve(bids);
+        assert_eq!(result.unwrap_err(), AuctionError::AmountOverflow);
+    }
+}
@@ -0,0 +1,36 @@
+//! Module containing the random number generator behind seeded draws.
+
+/// A small deterministic random number generator, SplitMix64.
+#[derive(Debug, Clone)]
+pub struct BidRng {
+    state: u64,
+}
+
+impl BidRng {
+    /// Creates a generator from a seed.
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// The next random number.
+    pub const fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A random number from zero up to and including the bound.
+    pub const fn up_to(&mut self, bound: u64) -> u64 {
+        match bound.checked_add(1) {
+            Some(modulus) => self.next_u64() % modulus,
+            None => self.next_u64(),
+        }
+    }
+
+    /// A random number from zero up to but excluding one.
+    pub fn unit(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1_u64 << 53) as f64
+    }
+}
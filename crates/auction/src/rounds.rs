@@ -0,0 +1,216 @@
+//! Module containing the sealed auction run over several rounds.
+use alloc::{collections::VecDeque, vec::Vec};
+
+use crate::{Auction, AuctionError, AuctionResult, Bids, Sales};
+
+/// The outcome of one round of a multi-round auction.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RoundResult {
+    /// The round number, starting from one.
+    round: usize,
+    reserve_price: i64,
+    /// The lots offered in the round, those left unsold by earlier rounds.
+    lots_offered: usize,
+    result: AuctionResult,
+}
+
+impl RoundResult {
+    /// The round number, starting from one.
+    pub const fn round(&self) -> usize {
+        self.round
+    }
+
+    /// The reserve price of the round.
+    pub const fn reserve_price(&self) -> i64 {
+        self.reserve_price
+    }
+
+    /// The lots offered in the round.
+    pub const fn lots_offered(&self) -> usize {
+        self.lots_offered
+    }
+
+    /// The marginal price lots sold at in the round, None when nothing sold.
+    pub const fn clearing_price(&self) -> Option<i64> {
+        self.result.clearing_price()
+    }
+
+    /// The sales made in the round.
+    pub const fn sales(&self) -> &Sales {
+        self.result.sales()
+    }
+
+    /// The lots left unsold after the round, carried into the next.
+    pub const fn lots_remaining(&self) -> usize {
+        self.result.lots_unsold()
+    }
+
+    /// The summary of the round's resolution.
+    pub const fn result(&self) -> &AuctionResult {
+        &self.result
+    }
+}
+
+/// A sealed auction run over rounds, each offering the lots left unsold by the
+/// rounds before it at the round's own reserve price.
+///
+/// Every round resolves its bids with the wrapped auction's settings.
+#[derive(Debug, Clone)]
+pub struct MultiRoundAuction {
+    auction: Auction,
+    /// The reserve prices of the rounds still to run.
+    rounds: VecDeque<i64>,
+    lots_remaining: usize,
+    history: Vec<RoundResult>,
+}
+
+impl MultiRoundAuction {
+    /// Start a multi-round auction selling the auction's lots.
+    pub const fn new(auction: Auction) -> Self {
+        Self {
+            lots_remaining: auction.lots,
+            auction,
+            rounds: VecDeque::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Queue a round with the given reserve price.
+    pub fn add_round(&mut self, reserve_price: i64) -> &mut Self {
+        self.rounds.push_back(reserve_price);
+        self
+    }
+
+    /// Run the next queued round, offering the lots still unsold.
+    ///
+    /// Fails if every lot has been sold, no round is queued, or the round's
+    /// revenue overflows.
+    pub fn run_round(&mut self, bids: Bids) -> Result<&RoundResult, AuctionError> {
+        if self.lots_remaining == 0 {
+            return Err(AuctionError::AllLotsSold);
+        }
+        let reserve_price = self
+            .rounds
+            .front()
+            .copied()
+            .ok_or(AuctionError::NoRoundsLeft)?;
+        let round_auction = Auction {
+            lots: self.lots_remaining,
+            reserve_price,
+            reserve_schedule: Vec::new(),
+            ..self.auction.clone()
+        };
+        let result = round_auction.resolve(bids)?;
+        self.rounds.pop_front();
+        self.lots_remaining = result.lots_unsold();
+        self.history.push(RoundResult {
+            round: self.history.len() + 1,
+            reserve_price,
+            lots_offered: round_auction.lots,
+            result,
+        });
+        Ok(&self.history[self.history.len() - 1])
+    }
+
+    /// The lots not yet sold.
+    pub const fn lots_remaining(&self) -> usize {
+        self.lots_remaining
+    }
+
+    /// The rounds run so far, in order.
+    pub fn history(&self) -> &[RoundResult] {
+        &self.history
+    }
+
+    /// Every sale made across the rounds, in the order they were made.
+    pub fn sales(&self) -> Sales {
+        self.history
+            .iter()
+            .flat_map(|round| round.sales().iter().copied())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn three_rounds(lots: usize) -> MultiRoundAuction {
+        let mut auction = MultiRoundAuction::new(AuctionBuilder::new().lots(lots).build());
+        auction.add_round(100).add_round(80).add_round(60);
+        auction
+    }
+
+    #[test]
+    fn rounds_carry_unsold_lots_with_descending_reserves() {
+        let mut auction = three_rounds(10);
+
+        let first = auction.run_round(vec![bid![120, 3], bid![90, 2]]).unwrap();
+        assert_eq!(first.round(), 1);
+        assert_eq!(first.lots_offered(), 10);
+        assert_eq!(first.clearing_price(), Some(120));
+        assert_eq!(first.lots_remaining(), 7);
+
+        let second = auction.run_round(vec![bid![90, 2], bid![70, 4]]).unwrap();
+        assert_eq!(second.round(), 2);
+        assert_eq!(second.reserve_price(), 80);
+        assert_eq!(second.lots_offered(), 7);
+        assert_eq!(second.lots_remaining(), 5);
+
+        let third = auction.run_round(vec![bid![70, 4], bid![65, 3]]).unwrap();
+        assert_eq!(third.lots_offered(), 5);
+        assert_eq!(third.clearing_price(), Some(65));
+        assert_eq!(third.lots_remaining(), 0);
+
+        assert_eq!(auction.lots_remaining(), 0);
+        let prices: Vec<_> = auction
+            .history()
+            .iter()
+            .map(RoundResult::clearing_price)
+            .collect();
+        assert_eq!(prices, vec![Some(120), Some(90), Some(65)]);
+        let sold: usize = auction.sales().iter().map(Sale::quantity).sum();
+        assert_eq!(sold, 10);
+    }
+
+    #[test]
+    fn rounds_refuse_to_run_once_sold_out() {
+        let mut auction = three_rounds(2);
+        auction.run_round(vec![bid![150, 2]]).unwrap();
+        assert_eq!(
+            auction.run_round(vec![bid![90, 1]]).unwrap_err(),
+            AuctionError::AllLotsSold
+        );
+        assert_eq!(auction.history().len(), 1);
+    }
+
+    #[test]
+    fn rounds_refuse_to_run_without_a_queued_round() {
+        let mut auction = MultiRoundAuction::new(AuctionBuilder::new().lots(2).build());
+        auction.add_round(50);
+        auction.run_round(vec![]).unwrap();
+        assert_eq!(
+            auction.run_round(vec![bid![90, 1]]).unwrap_err(),
+            AuctionError::NoRoundsLeft
+        );
+    }
+
+    #[test]
+    fn rounds_keep_an_overflowing_round_queued() {
+        let mut bids = vec![bid![i64::MAX, usize::MAX]];
+        bids.extend((0..8).map(|_| bid![i64::MAX - 1, 1]));
+        let mut auction = MultiRoundAuction::new(
+            AuctionBuilder::new()
+                .strategy(AuctionStrategy::AllPay)
+                .lots(usize::MAX)
+                .build(),
+        );
+        auction.add_round(0);
+        assert_eq!(
+            auction.run_round(bids).unwrap_err(),
+            AuctionError::AmountOverflow
+        );
+        assert!(auction.run_round(vec![bid![10, 1]]).is_ok());
+    }
+}
@@ -0,0 +1,172 @@
+//! Module for resolving bids ranked by a caller supplied score.
+use alloc::vec::Vec;
+
+use crate::{
+    bid_cap, reserve,
+    strategies::{
+        fill::{rank_bids, LevelFiller},
+        record_requested,
+    },
+    tick, Auction, AuctionDirection, AuctionStrategy, Bid, Sale, Sales,
+};
+
+/// Resolves the bids ranked by the scorer, larger scores first, for single
+/// and multi price auctions. None for every other strategy and for auctions
+/// with a buy-now price.
+///
+/// Bids of equal score keep the usual ranking between them. Multi price
+/// winners pay their own amount. Single price winners pay the amount of the
+/// lowest scoring winner, which may be more than a better scoring bid offered,
+/// so nobody pays more than their own amount, or less in a reverse auction.
+pub fn resolve_scored(
+    auction: &Auction,
+    bids: &[Bid],
+    scorer: impl Fn(&Bid) -> i64,
+) -> Option<Sales> {
+    let single_price = match auction.strategy {
+        AuctionStrategy::SinglePrice => true,
+        AuctionStrategy::MultiPrice => false,
+        _ => return None,
+    };
+    if auction.buy_now_price.is_some() {
+        return None;
+    }
+    let auction = reserve::apply_dynamic_reserve(auction, bids);
+    let auction = &*auction;
+    let on_grid = tick::apply_tick_size(auction, bids);
+    let capped = bid_cap::apply_bid_cap(auction, &on_grid);
+    let mut qualifying: Vec<&Bid> = capped
+        .iter()
+        .filter(|bid| auction.qualifies(bid.amount))
+        .collect();
+    rank_bids(auction, &mut qualifying);
+    let mut ranked: Vec<(&Bid, i64)> = qualifying
+        .into_iter()
+        .map(|bid| (bid, scorer(bid)))
+        .collect();
+    ranked.sort_by_key(|&(_, score)| core::cmp::Reverse(score));
+
+    let mut filler = LevelFiller::new(auction);
+    let mut winning_bids = Vec::new();
+    for level in
+        ranked.chunk_by(|(a, a_score), (b, b_score)| a_score == b_score && a.amount == b.amount)
+    {
+        if filler.is_full() {
+            break;
+        }
+        let level: Vec<&Bid> = level.iter().map(|&(bid, _)| bid).collect();
+        winning_bids.extend(filler.fill_level(&level, &mut ()));
+    }
+
+    let clearing_price = winning_bids.last().map(|bid| bid.amount);
+    let mut sales: Sales = winning_bids
+        .iter()
+        .map(|bid| {
+            let amount = match (single_price, clearing_price, auction.direction) {
+                (true, Some(price), AuctionDirection::Forward) => price.min(bid.amount),
+                (true, Some(price), AuctionDirection::Reverse) => price.max(bid.amount),
+                _ => bid.amount,
+            };
+            Sale::new(bid.bidder_id, amount, bid.quantity).with_bid(bid)
+        })
+        .collect();
+    record_requested(&mut sales, bids);
+    auction.sales_ordering.sort(&mut sales, bids);
+    Some(sales)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    #[test]
+    fn scoring_by_amount_matches_resolve_bids() {
+        for seed in 0..20 {
+            let bids = generators::large_quantities(seed, 60, 49, 4);
+            for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+                let forward = AuctionBuilder::new()
+                    .strategy(strategy.clone())
+                    .lots(12)
+                    .reserve_price(10)
+                    .build();
+                assert_eq!(
+                    forward.resolve_scored(bids.clone(), Bid::amount),
+                    forward.resolve_bids_ref(&bids)
+                );
+                let reverse = AuctionBuilder::new()
+                    .strategy(strategy)
+                    .direction(AuctionDirection::Reverse)
+                    .lots(12)
+                    .build();
+                assert_eq!(
+                    reverse.resolve_scored(bids.clone(), |bid| -bid.amount()),
+                    reverse.resolve_bids_ref(&bids)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scoring_single_price_charges_lowest_scoring_winner() {
+        let auction = AuctionBuilder::new().lots(3).build();
+        let fast = bid![40, 1];
+        let cheap = bid![30, 1];
+        let slow = bid![60, 1];
+        let loser = bid![80, 1];
+        let rank = [fast.id, slow.id, cheap.id, loser.id];
+        let scorer = |bid: &Bid| -(rank.iter().position(|id| *id == bid.id()).unwrap() as i64);
+        let sales = auction.resolve_scored(vec![loser, slow, cheap, fast], scorer);
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(fast.id, 30, 1).with_bid(&fast),
+                Sale::new(slow.id, 30, 1).with_bid(&slow),
+                Sale::new(cheap.id, 30, 1).with_bid(&cheap),
+            ]
+        );
+    }
+
+    #[test]
+    fn scoring_single_price_never_charges_more_than_own_amount() {
+        let auction = AuctionBuilder::new().lots(2).build();
+        let preferred = bid![20, 1];
+        let other = bid![50, 1];
+        let scorer = |bid: &Bid| i64::from(bid.id() == preferred.id);
+        let sales = auction.resolve_scored(vec![other, preferred], scorer);
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(preferred.id, 20, 1).with_bid(&preferred),
+                Sale::new(other.id, 50, 1).with_bid(&other)
+            ]
+        );
+    }
+
+    #[test]
+    fn scoring_multi_price_charges_own_amount() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(1)
+            .build();
+        let preferred = bid![20, 1];
+        let scorer = |bid: &Bid| i64::from(bid.id() == preferred.id);
+        let sales = auction.resolve_scored(vec![bid![50, 1], preferred], scorer);
+        assert_eq!(
+            sales,
+            vec![Sale::new(preferred.id, 20, 1).with_bid(&preferred)]
+        );
+    }
+
+    #[test]
+    fn scoring_other_strategies_resolve_as_usual() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .lots(1)
+            .build();
+        let bids: Bids = vec![bid![20, 1], bid![50, 1]];
+        assert_eq!(
+            auction.resolve_scored(bids.clone(), |bid| -bid.amount()),
+            auction.resolve_bids(bids)
+        );
+    }
+}
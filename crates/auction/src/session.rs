@@ -0,0 +1,881 @@
+//! Module containing the live session collecting bids until the auction closes.
+use alloc::vec::Vec;
+use core::fmt;
+#[cfg(feature = "std")]
+use std::error::Error;
+
+use uuid::Uuid;
+
+#[cfg(feature = "metrics")]
+use crate::telemetry;
+use crate::{
+    collections::HashSet, tick, Auction, AuctionDirection, AuctionError, AuctionResult, Bid,
+    BidError, Bids, Sales, Timestamp,
+};
+
+/// Enum representing why a session refused a bid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BidRejection {
+    /// The bid is malformed.
+    Invalid(BidError),
+    /// The bid does not meet the reserve price, or the price ceiling of a
+    /// reverse auction.
+    BelowReserve(i64),
+    /// The bid's amount is off the tick grid.
+    OffTick(i64),
+    /// The bid had expired when it was submitted.
+    Expired,
+    /// A bid with the same id has already been accepted.
+    DuplicateBidId(Uuid),
+    /// No accepted bid has the id.
+    UnknownBidId(Uuid),
+    /// The session has closed, or the bid arrived after its deadline.
+    Closed,
+}
+
+impl fmt::Display for BidRejection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid(error) => write!(f, "invalid bid: {error}"),
+            Self::BelowReserve(amount) => {
+                write!(f, "bid amount {amount} does not meet the reserve")
+            }
+            Self::OffTick(amount) => write!(f, "bid amount {amount} is off the tick grid"),
+            Self::Expired => write!(f, "bid had expired when submitted"),
+            Self::DuplicateBidId(id) => write!(f, "bid {id} has already been submitted"),
+            Self::UnknownBidId(id) => write!(f, "no accepted bid {id}"),
+            Self::Closed => write!(f, "the auction has closed"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for BidRejection {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Invalid(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+/// Enum representing why a session refused to amend a bid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AmendError {
+    /// The amended bid would have been refused, or no accepted bid has the
+    /// id, or the session has closed.
+    Rejected(BidRejection),
+    /// The amendment lowers a forward bid or raises a reverse offer, and the
+    /// session only accepts improvements.
+    WorseAmount { amount: i64, amended: i64 },
+}
+
+impl fmt::Display for AmendError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Rejected(rejection) => write!(f, "amendment refused: {rejection}"),
+            Self::WorseAmount { amount, amended } => {
+                write!(f, "amended amount {amended} is worse than {amount}")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for AmendError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Rejected(rejection) => Some(rejection),
+            Self::WorseAmount { .. } => None,
+        }
+    }
+}
+
+impl From<BidRejection> for AmendError {
+    fn from(rejection: BidRejection) -> Self {
+        Self::Rejected(rejection)
+    }
+}
+
+/// Enum representing why a session refused to withdraw a bid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WithdrawError {
+    /// The auction does not allow withdrawals.
+    Disabled,
+    /// No accepted bid has the id, or the session has closed.
+    Rejected(BidRejection),
+}
+
+impl fmt::Display for WithdrawError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "the auction does not allow withdrawals"),
+            Self::Rejected(rejection) => write!(f, "withdrawal refused: {rejection}"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Error for WithdrawError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Disabled => None,
+            Self::Rejected(rejection) => Some(rejection),
+        }
+    }
+}
+
+impl From<BidRejection> for WithdrawError {
+    fn from(rejection: BidRejection) -> Self {
+        Self::Rejected(rejection)
+    }
+}
+
+/// Enum representing when an amended bid counts as submitted for breaking
+/// ties.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum AmendmentTime {
+    /// The amended bid moves behind every other bid, as if newly submitted.
+    #[default]
+    Reset,
+    /// The amended bid keeps its place and original submission time.
+    KeepOriginal,
+}
+
+/// Proof that a session accepted a bid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipt {
+    bid_id: Uuid,
+    /// The position of the bid among every bid the session accepted,
+    /// starting from zero.
+    sequence: usize,
+}
+
+impl Receipt {
+    /// The id of the accepted bid.
+    pub const fn bid_id(&self) -> Uuid {
+        self.bid_id
+    }
+
+    /// The position of the bid among every bid the session accepted.
+    pub const fn sequence(&self) -> usize {
+        self.sequence
+    }
+}
+
+/// The state of a session, from which [`AuctionSession::restore`] continues
+/// it.
+///
+/// Bidder budgets are kept in memory but are not serialized.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SessionSnapshot {
+    auction: Auction,
+    bids: Bids,
+    withdrawals: Bids,
+    accepted: usize,
+    closed: bool,
+    closes_at: Option<Timestamp>,
+    extension_window: Timestamp,
+    extension_amount: Timestamp,
+    latest_close: Option<Timestamp>,
+    worse_amendments: bool,
+    amendment_time: AmendmentTime,
+}
+
+/// An auction taking bids as they arrive, validating each immediately and
+/// resolving them all when it closes.
+///
+/// A timed session has a deadline. Bids accepted within the extension window
+/// before the deadline push it back by the extension amount, up to the
+/// latest close, so that late bids cannot snipe the auction.
+#[derive(Debug, Clone)]
+pub struct AuctionSession {
+    auction: Auction,
+    /// The accepted bids, in submission order.
+    bids: Bids,
+    /// The withdrawn bids, in withdrawal order.
+    withdrawals: Bids,
+    ids: HashSet<Uuid>,
+    accepted: usize,
+    closed: bool,
+    closes_at: Option<Timestamp>,
+    extension_window: Timestamp,
+    extension_amount: Timestamp,
+    latest_close: Option<Timestamp>,
+    worse_amendments: bool,
+    amendment_time: AmendmentTime,
+}
+
+impl AuctionSession {
+    /// Open a session for the auction.
+    pub fn new(auction: Auction) -> Self {
+        Self {
+            auction,
+            bids: Vec::new(),
+            withdrawals: Vec::new(),
+            ids: HashSet::default(),
+            accepted: 0,
+            closed: false,
+            closes_at: None,
+            extension_window: 0,
+            extension_amount: 0,
+            latest_close: None,
+            worse_amendments: false,
+            amendment_time: AmendmentTime::Reset,
+        }
+    }
+
+    /// Restore a session from a snapshot, as it was when taken.
+    pub fn restore(snapshot: SessionSnapshot) -> Self {
+        Self {
+            ids: snapshot.bids.iter().map(|bid| bid.id).collect(),
+            auction: snapshot.auction,
+            bids: snapshot.bids,
+            withdrawals: snapshot.withdrawals,
+            accepted: snapshot.accepted,
+            closed: snapshot.closed,
+            closes_at: snapshot.closes_at,
+            extension_window: snapshot.extension_window,
+            extension_amount: snapshot.extension_amount,
+            latest_close: snapshot.latest_close,
+            worse_amendments: snapshot.worse_amendments,
+            amendment_time: snapshot.amendment_time,
+        }
+    }
+
+    /// Take a snapshot of the session's state.
+    pub fn snapshot(&self) -> SessionSnapshot {
+        SessionSnapshot {
+            auction: self.auction.clone(),
+            bids: self.bids.clone(),
+            withdrawals: self.withdrawals.clone(),
+            accepted: self.accepted,
+            closed: self.closed,
+            closes_at: self.closes_at,
+            extension_window: self.extension_window,
+            extension_amount: self.extension_amount,
+            latest_close: self.latest_close,
+            worse_amendments: self.worse_amendments,
+            amendment_time: self.amendment_time,
+        }
+    }
+
+    /// Set the deadline after which timed submissions are refused.
+    pub const fn with_closes_at(mut self, closes_at: Timestamp) -> Self {
+        self.closes_at = Some(closes_at);
+        self
+    }
+
+    /// Extend the deadline by `amount` whenever a bid is accepted at most
+    /// `window` before it, never beyond `latest_close`.
+    pub const fn with_extension(
+        mut self,
+        window: Timestamp,
+        amount: Timestamp,
+        latest_close: Timestamp,
+    ) -> Self {
+        self.extension_window = window;
+        self.extension_amount = amount;
+        self.latest_close = Some(latest_close);
+        self
+    }
+
+    /// Set whether amendments may lower a forward bid or raise a reverse
+    /// offer. Only improvements are accepted by default.
+    pub const fn with_worse_amendments(mut self, allowed: bool) -> Self {
+        self.worse_amendments = allowed;
+        self
+    }
+
+    /// Set when an amended bid counts as submitted for breaking ties.
+    pub const fn with_amendment_time(mut self, amendment_time: AmendmentTime) -> Self {
+        self.amendment_time = amendment_time;
+        self
+    }
+
+    /// Submit a bid, checking it is valid, meets the reserve and tick grid,
+    /// had not expired when submitted and reuses no accepted bid's id.
+    ///
+    /// A withdrawn bid resubmitted is a new bid without a submission time, so
+    /// it ranks behind every timed bid rather than keeping the withdrawn
+    /// bid's priority. [`AuctionSession::submit_at`] gives it a new time.
+    pub fn submit(&mut self, bid: Bid) -> Result<Receipt, BidRejection> {
+        let bid = if self.is_withdrawn(bid.id) {
+            Bid {
+                submitted_at: None,
+                ..bid
+            }
+        } else {
+            bid
+        };
+        self.record(bid)
+    }
+
+    /// Whether a bid with the id was withdrawn.
+    fn is_withdrawn(&self, bid_id: Uuid) -> bool {
+        self.withdrawals
+            .iter()
+            .any(|withdrawn| withdrawn.id == bid_id)
+    }
+
+    /// Accepts the bid, recording the submission or refusal.
+    fn record(&mut self, bid: Bid) -> Result<Receipt, BidRejection> {
+        let submitted = self.accept(bid);
+        #[cfg(feature = "metrics")]
+        match &submitted {
+            Ok(_) => telemetry::submitted(&self.auction),
+            Err(rejection) => telemetry::refused(&self.auction, rejection),
+        }
+        submitted
+    }
+
+    fn accept(&mut self, bid: Bid) -> Result<Receipt, BidRejection> {
+        if self.closed {
+            return Err(BidRejection::Closed);
+        }
+        bid.validate().map_err(BidRejection::Invalid)?;
+        if !self.auction.qualifies(bid.amount) {
+            return Err(BidRejection::BelowReserve(bid.amount));
+        }
+        if tick::is_rejected(&self.auction, &bid) {
+            return Err(BidRejection::OffTick(bid.amount));
+        }
+        if bid
+            .submitted_at
+            .is_some_and(|submitted_at| bid.is_expired(submitted_at))
+        {
+            return Err(BidRejection::Expired);
+        }
+        if !self.ids.insert(bid.id) {
+            return Err(BidRejection::DuplicateBidId(bid.id));
+        }
+        self.bids.push(bid);
+        let receipt = Receipt {
+            bid_id: bid.id,
+            sequence: self.accepted,
+        };
+        self.accepted += 1;
+        Ok(receipt)
+    }
+
+    /// Submit a bid arriving at `now`, refusing it once the deadline has
+    /// passed and extending the deadline if it lands in the extension window.
+    ///
+    /// A withdrawn bid resubmitted is a new bid, submitted at `now`.
+    pub fn submit_at(&mut self, bid: Bid, now: Timestamp) -> Result<Receipt, BidRejection> {
+        if self.closes_at.is_some_and(|closes_at| now >= closes_at) {
+            #[cfg(feature = "metrics")]
+            telemetry::refused(&self.auction, &BidRejection::Closed);
+            return Err(BidRejection::Closed);
+        }
+        let bid = if self.is_withdrawn(bid.id) {
+            bid.with_submitted_at(now)
+        } else {
+            bid
+        };
+        let receipt = self.record(bid)?;
+        self.extend_close(now);
+        Ok(receipt)
+    }
+
+    fn extend_close(&mut self, now: Timestamp) {
+        let Some(closes_at) = self.closes_at else {
+            return;
+        };
+        if closes_at - now <= self.extension_window {
+            let extended = closes_at.saturating_add(self.extension_amount);
+            let capped = self
+                .latest_close
+                .map_or(extended, |latest| extended.min(latest));
+            self.closes_at = Some(capped.max(closes_at));
+        }
+    }
+
+    /// Amend the amount and quantity of an accepted bid, checking the amended
+    /// bid as a submission would be.
+    ///
+    /// Under [`AmendmentTime::Reset`] the bid moves behind every other bid,
+    /// though a timed bid keeps its submission time, which
+    /// [`AuctionSession::amend_at`] resets.
+    pub fn amend(&mut self, bid_id: Uuid, amount: i64, quantity: usize) -> Result<(), AmendError> {
+        self.amend_bid(bid_id, amount, quantity, None)
+    }
+
+    /// Amend an accepted bid at `now`, refusing once the deadline has passed
+    /// and extending the deadline if it lands in the extension window.
+    ///
+    /// Under [`AmendmentTime::Reset`] the bid is resubmitted at `now`.
+    pub fn amend_at(
+        &mut self,
+        bid_id: Uuid,
+        amount: i64,
+        quantity: usize,
+        now: Timestamp,
+    ) -> Result<(), AmendError> {
+        if self.closes_at.is_some_and(|closes_at| now >= closes_at) {
+            return Err(BidRejection::Closed.into());
+        }
+        self.amend_bid(bid_id, amount, quantity, Some(now))?;
+        self.extend_close(now);
+        Ok(())
+    }
+
+    fn amend_bid(
+        &mut self,
+        bid_id: Uuid,
+        amount: i64,
+        quantity: usize,
+        now: Option<Timestamp>,
+    ) -> Result<(), AmendError> {
+        if self.closed {
+            return Err(BidRejection::Closed.into());
+        }
+        let position = self
+            .bids
+            .iter()
+            .position(|bid| bid.id == bid_id)
+            .ok_or(BidRejection::UnknownBidId(bid_id))?;
+        let current = self.bids[position];
+        let worse = match self.auction.direction {
+            AuctionDirection::Forward => amount < current.amount,
+            AuctionDirection::Reverse => amount > current.amount,
+        };
+        if worse && !self.worse_amendments {
+            return Err(AmendError::WorseAmount {
+                amount: current.amount,
+                amended: amount,
+            });
+        }
+        let mut amended = current.with_amount(amount).with_quantity(quantity);
+        amended.validate().map_err(BidRejection::Invalid)?;
+        if !self.auction.qualifies(amended.amount) {
+            return Err(BidRejection::BelowReserve(amended.amount).into());
+        }
+        if tick::is_rejected(&self.auction, &amended) {
+            return Err(BidRejection::OffTick(amended.amount).into());
+        }
+        if now.is_some_and(|now| amended.is_expired(now)) {
+            return Err(BidRejection::Expired.into());
+        }
+        match self.amendment_time {
+            AmendmentTime::Reset => {
+                if let Some(now) = now {
+                    amended = amended.with_submitted_at(now);
+                }
+                self.bids.remove(position);
+                self.bids.push(amended);
+            }
+            AmendmentTime::KeepOriginal => self.bids[position] = amended,
+        }
+        Ok(())
+    }
+
+    /// The current deadline, including any extensions. None for an untimed
+    /// session.
+    pub const fn closes_at(&self) -> Option<Timestamp> {
+        self.closes_at
+    }
+
+    /// Withdraw an accepted bid, returning it and recording the withdrawal.
+    ///
+    /// The id may be submitted again, as a new bid.
+    pub fn withdraw(&mut self, bid_id: Uuid) -> Result<Bid, WithdrawError> {
+        if !self.auction.allow_withdrawals {
+            return Err(WithdrawError::Disabled);
+        }
+        if self.closed {
+            return Err(BidRejection::Closed.into());
+        }
+        let position = self
+            .bids
+            .iter()
+            .position(|bid| bid.id == bid_id)
+            .ok_or(BidRejection::UnknownBidId(bid_id))?;
+        self.ids.remove(&bid_id);
+        let bid = self.bids.remove(position);
+        self.withdrawals.push(bid);
+        #[cfg(feature = "metrics")]
+        telemetry::withdrawn(&self.auction);
+        Ok(bid)
+    }
+
+    /// The withdrawn bids, in withdrawal order.
+    pub fn withdrawals(&self) -> &[Bid] {
+        &self.withdrawals
+    }
+
+    /// The number of bids currently accepted.
+    pub const fn bid_count(&self) -> usize {
+        self.bids.len()
+    }
+
+    /// The accepted bids, in submission order.
+    pub fn bids(&self) -> &[Bid] {
+        &self.bids
+    }
+
+    /// Whether the session has closed.
+    pub const fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Close the session, resolving the accepted bids with the auction's
+    /// strategy. None if it had already closed.
+    pub fn close(&mut self) -> Option<Sales> {
+        if self.closed {
+            return None;
+        }
+        self.closed = true;
+        Some(self.auction.resolve_bids_ref(&self.bids))
+    }
+
+    /// Close the session, summarising the resolution of the accepted bids
+    /// along with the bids withdrawn. None if it had already closed.
+    pub fn close_with_result(&mut self) -> Option<Result<AuctionResult, AuctionError>> {
+        if self.closed {
+            return None;
+        }
+        self.closed = true;
+        Some(
+            self.auction
+                .resolve(self.bids.clone())
+                .map(|result| result.with_withdrawn(self.withdrawals.clone())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn session() -> AuctionSession {
+        AuctionSession::new(AuctionBuilder::new().lots(2).reserve_price(10).build())
+    }
+
+    #[test]
+    fn session_resolves_accepted_bids_on_close() {
+        let mut session = session();
+        let bids: Bids = vec![bid![30, 1], bid![20, 1], bid![15, 1]];
+        for (sequence, bid) in bids.iter().enumerate() {
+            let receipt = session.submit(*bid).unwrap();
+            assert_eq!(receipt.bid_id(), bid.id);
+            assert_eq!(receipt.sequence(), sequence);
+        }
+        assert_eq!(session.bid_count(), 3);
+        let auction = AuctionBuilder::new().lots(2).reserve_price(10).build();
+        assert_eq!(session.close(), Some(auction.resolve_bids(bids)));
+    }
+
+    #[test]
+    fn session_rejects_duplicate_bid_ids() {
+        let mut session = session();
+        let bid = bid![30, 1];
+        session.submit(bid).unwrap();
+        assert_eq!(
+            session.submit(bid),
+            Err(BidRejection::DuplicateBidId(bid.id))
+        );
+        assert_eq!(session.bid_count(), 1);
+    }
+
+    #[test]
+    fn session_rejects_bids_failing_validation() {
+        let mut session =
+            AuctionSession::new(AuctionBuilder::new().reserve_price(10).tick_size(5).build());
+        assert_eq!(
+            session.submit(bid![20, 0]),
+            Err(BidRejection::Invalid(BidError::ZeroQuantity))
+        );
+        assert_eq!(
+            session.submit(bid![5, 1]),
+            Err(BidRejection::BelowReserve(5))
+        );
+        assert_eq!(session.submit(bid![12, 1]), Err(BidRejection::OffTick(12)));
+        let expired = bid![20, 1].with_submitted_at(100).with_expires_at(100);
+        assert_eq!(session.submit(expired), Err(BidRejection::Expired));
+        assert_eq!(session.bid_count(), 0);
+    }
+
+    #[test]
+    fn session_withdraws_accepted_bid() {
+        let mut session = session();
+        let bid = bid![30, 1];
+        session.submit(bid).unwrap();
+        assert_eq!(session.withdraw(bid.id).unwrap().id, bid.id);
+        assert_eq!(session.bid_count(), 0);
+        assert_eq!(
+            session.withdraw(bid.id),
+            Err(WithdrawError::Rejected(BidRejection::UnknownBidId(bid.id)))
+        );
+        assert_eq!(session.submit(bid).unwrap().sequence(), 1);
+    }
+
+    #[test]
+    fn session_untimed_resubmission_loses_priority() {
+        let mut session = session();
+        let (withdrawn, rival) = (
+            bid![30, 2].with_submitted_at(10),
+            bid![30, 2].with_submitted_at(20),
+        );
+        session.submit(withdrawn).unwrap();
+        session.submit(rival).unwrap();
+        session.withdraw(withdrawn.id).unwrap();
+        session.submit(withdrawn).unwrap();
+        assert_eq!(session.bids()[1].submitted_at, None);
+        let sales = session.close().unwrap();
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].bid_id, Some(rival.id));
+    }
+
+    #[test]
+    fn session_withdraw_unknown_bid_fails() {
+        let mut session = session();
+        let id = uuid::Uuid::new_v4();
+        assert_eq!(
+            session.withdraw(id),
+            Err(WithdrawError::Rejected(BidRejection::UnknownBidId(id)))
+        );
+    }
+
+    #[test]
+    fn session_resubmitted_bid_replaces_withdrawn_bid() {
+        let mut session = timed_session();
+        let (withdrawn, rival) = (bid![30, 1].with_submitted_at(10), bid![30, 1]);
+        session.submit_at(withdrawn, 10).unwrap();
+        session.submit_at(rival, 20).unwrap();
+        session.withdraw(withdrawn.id).unwrap();
+        assert_eq!(session.withdrawals(), &[withdrawn]);
+
+        let receipt = session.submit_at(withdrawn.with_amount(20), 30).unwrap();
+        assert_eq!(receipt.sequence(), 2);
+        assert_eq!(session.bids()[1].submitted_at, Some(30));
+        let result = session.close_with_result().unwrap().unwrap();
+        assert_eq!(result.withdrawn(), &vec![withdrawn]);
+        assert_eq!(result.sales().len(), 2);
+        assert!(result.sales().iter().all(|sale| sale.amount == 20));
+    }
+
+    #[test]
+    fn session_refuses_withdrawal_after_close() {
+        let mut session = session();
+        let bid = bid![30, 1];
+        session.submit(bid).unwrap();
+        session.close();
+        assert_eq!(
+            session.withdraw(bid.id),
+            Err(WithdrawError::Rejected(BidRejection::Closed))
+        );
+        assert!(session.withdrawals().is_empty());
+    }
+
+    #[test]
+    fn session_refuses_withdrawal_when_disabled() {
+        let mut session =
+            AuctionSession::new(AuctionBuilder::new().allow_withdrawals(false).build());
+        let bid = bid![30, 1];
+        session.submit(bid).unwrap();
+        assert_eq!(session.withdraw(bid.id), Err(WithdrawError::Disabled));
+        assert_eq!(session.bids(), &[bid]);
+    }
+
+    #[test]
+    fn session_refuses_bids_after_close() {
+        let mut session = session();
+        let bid = bid![30, 1];
+        session.submit(bid).unwrap();
+        assert!(session.close().is_some());
+        assert!(session.is_closed());
+        assert_eq!(session.submit(bid![40, 1]), Err(BidRejection::Closed));
+        assert_eq!(
+            session.withdraw(bid.id),
+            Err(WithdrawError::Rejected(BidRejection::Closed))
+        );
+        assert_eq!(session.close(), None);
+    }
+
+    fn timed_session() -> AuctionSession {
+        session().with_closes_at(100).with_extension(10, 5, 112)
+    }
+
+    #[test]
+    fn session_refuses_bids_after_deadline() {
+        let mut session = session().with_closes_at(100);
+        assert!(session.submit_at(bid![30, 1], 99).is_ok());
+        assert_eq!(
+            session.submit_at(bid![40, 1], 100),
+            Err(BidRejection::Closed)
+        );
+        assert_eq!(session.closes_at(), Some(100));
+        assert_eq!(session.bid_count(), 1);
+    }
+
+    #[test]
+    fn session_extends_deadline_on_window_boundary() {
+        let mut session = timed_session();
+        session.submit_at(bid![30, 1], 89).unwrap();
+        assert_eq!(session.closes_at(), Some(100));
+        session.submit_at(bid![30, 1], 90).unwrap();
+        assert_eq!(session.closes_at(), Some(105));
+    }
+
+    #[test]
+    fn session_chained_extensions_stop_at_cap() {
+        let mut session = timed_session();
+        session.submit_at(bid![30, 1], 95).unwrap();
+        assert_eq!(session.closes_at(), Some(105));
+        session.submit_at(bid![31, 1], 104).unwrap();
+        assert_eq!(session.closes_at(), Some(110));
+        session.submit_at(bid![32, 1], 109).unwrap();
+        assert_eq!(session.closes_at(), Some(112));
+        session.submit_at(bid![33, 1], 111).unwrap();
+        assert_eq!(session.closes_at(), Some(112));
+        assert_eq!(
+            session.submit_at(bid![34, 1], 112),
+            Err(BidRejection::Closed)
+        );
+        assert_eq!(session.bid_count(), 4);
+    }
+
+    #[test]
+    fn session_rejected_bid_does_not_extend_deadline() {
+        let mut session = timed_session();
+        assert_eq!(
+            session.submit_at(bid![5, 1], 95),
+            Err(BidRejection::BelowReserve(5))
+        );
+        assert_eq!(session.closes_at(), Some(100));
+    }
+
+    #[test]
+    fn session_amends_bid_amount_and_quantity() {
+        let mut session = session();
+        let bid = bid![20, 1];
+        session.submit(bid).unwrap();
+        session.amend(bid.id, 25, 2).unwrap();
+        assert_eq!(session.bids()[0].amount, 25);
+        assert_eq!(session.bids()[0].quantity, 2);
+        assert_eq!(session.bids()[0].id, bid.id);
+    }
+
+    #[test]
+    fn session_refuses_worse_amendments_by_default() {
+        let mut session = session();
+        let bid = bid![20, 1];
+        session.submit(bid).unwrap();
+        assert_eq!(
+            session.amend(bid.id, 15, 1),
+            Err(AmendError::WorseAmount {
+                amount: 20,
+                amended: 15
+            })
+        );
+        assert_eq!(session.bids()[0].amount, 20);
+
+        let mut session = session.with_worse_amendments(true);
+        session.amend(bid.id, 15, 1).unwrap();
+        assert_eq!(session.bids()[0].amount, 15);
+    }
+
+    #[test]
+    fn session_refuses_raised_reverse_offers() {
+        let mut session = AuctionSession::new(
+            AuctionBuilder::new()
+                .direction(AuctionDirection::Reverse)
+                .build(),
+        );
+        let bid = bid![20, 1];
+        session.submit(bid).unwrap();
+        assert_eq!(
+            session.amend(bid.id, 25, 1),
+            Err(AmendError::WorseAmount {
+                amount: 20,
+                amended: 25
+            })
+        );
+        session.amend(bid.id, 15, 1).unwrap();
+    }
+
+    #[test]
+    fn session_revalidates_amended_bids() {
+        let mut session =
+            AuctionSession::new(AuctionBuilder::new().reserve_price(10).tick_size(5).build())
+                .with_worse_amendments(true);
+        let bid = bid![20, 1];
+        session.submit(bid).unwrap();
+        assert_eq!(
+            session.amend(bid.id, 20, 0),
+            Err(AmendError::Rejected(BidRejection::Invalid(
+                BidError::ZeroQuantity
+            )))
+        );
+        assert_eq!(
+            session.amend(bid.id, 5, 1),
+            Err(AmendError::Rejected(BidRejection::BelowReserve(5)))
+        );
+        assert_eq!(
+            session.amend(bid.id, 22, 1),
+            Err(AmendError::Rejected(BidRejection::OffTick(22)))
+        );
+        assert_eq!(session.bids(), &[bid]);
+    }
+
+    #[test]
+    fn session_amend_unknown_bid_fails() {
+        let mut session = session();
+        let bid = bid![20, 1];
+        assert_eq!(
+            session.amend(bid.id, 25, 1),
+            Err(AmendError::Rejected(BidRejection::UnknownBidId(bid.id)))
+        );
+    }
+
+    #[test]
+    fn session_refuses_amendments_after_close() {
+        let mut session = session();
+        let bid = bid![20, 1];
+        session.submit(bid).unwrap();
+        session.close();
+        assert_eq!(
+            session.amend(bid.id, 25, 1),
+            Err(AmendError::Rejected(BidRejection::Closed))
+        );
+
+        let mut session = timed_session();
+        session.submit_at(bid, 10).unwrap();
+        assert_eq!(
+            session.amend_at(bid.id, 25, 1, 100),
+            Err(AmendError::Rejected(BidRejection::Closed))
+        );
+    }
+
+    #[test]
+    fn session_amendment_resets_tie_break_time() {
+        let first = bid![30, 1].with_submitted_at(10);
+        let second = bid![30, 1].with_submitted_at(20);
+        let mut session = timed_session();
+        session.submit_at(first, 10).unwrap();
+        session.submit_at(second, 20).unwrap();
+        session.amend_at(first.id, 30, 1, 30).unwrap();
+        assert_eq!(session.bids()[1].id, first.id);
+        assert_eq!(session.bids()[1].submitted_at, Some(30));
+
+        let mut session = timed_session().with_amendment_time(AmendmentTime::KeepOriginal);
+        session.submit_at(first, 10).unwrap();
+        session.submit_at(second, 20).unwrap();
+        session.amend_at(first.id, 30, 1, 30).unwrap();
+        assert_eq!(session.bids()[0].id, first.id);
+        assert_eq!(session.bids()[0].submitted_at, Some(10));
+    }
+
+    #[test]
+    fn session_amendment_extends_deadline() {
+        let mut session = timed_session();
+        let bid = bid![20, 1];
+        session.submit_at(bid, 10).unwrap();
+        session.amend_at(bid.id, 25, 1, 95).unwrap();
+        assert!(session.closes_at() > Some(100));
+    }
+}
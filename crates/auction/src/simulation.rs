@@ -0,0 +1,263 @@
+//! Module for estimating an auction's revenue from models of its bidders.
+//!
+//! Each iteration samples a bid from every modelled bidder and resolves the
+//! bids. Bidders bid their valuation, and the same seed always samples the
+//! same bids, so running each strategy from one seed compares them over the
+//! same bid sets.
+use uuid::Uuid;
+
+pub use crate::rng::BidRng;
+use crate::{Auction, Bid, Bids, SalesSummary};
+
+/// The percentiles of revenue every report includes.
+const PERCENTILES: [u8; 5] = [5, 25, 50, 75, 95];
+
+/// A way of sampling what one bidder bids.
+pub trait BidderModel {
+    /// The bidder's valuation of each unit in cents.
+    fn valuation(&self, rng: &mut BidRng) -> i64;
+
+    /// The number of units the bidder asks for, one unless overridden.
+    fn quantity(&self, _rng: &mut BidRng) -> usize {
+        1
+    }
+}
+
+/// Enum representing the built-in distributions of valuations, in cents and
+/// never below zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Valuation {
+    /// Spread evenly from `min` up to and including `max`.
+    Uniform { min: i64, max: i64 },
+    /// Normally distributed with the given mean and standard deviation.
+    Normal { mean: f64, std_dev: f64 },
+    /// Log-normally distributed, the logarithm having the given mean and
+    /// standard deviation.
+    LogNormal { mu: f64, sigma: f64 },
+}
+
+impl BidderModel for Valuation {
+    fn valuation(&self, rng: &mut BidRng) -> i64 {
+        match *self {
+            Self::Uniform { min, max } => {
+                let min = min.max(0);
+                let span = max.saturating_sub(min).max(0) as u64;
+                min + rng.up_to(span) as i64
+            }
+            Self::Normal { mean, std_dev } => {
+                std_dev.mul_add(standard_normal(rng), mean).round().max(0.0) as i64
+            }
+            Self::LogNormal { mu, sigma } => {
+                sigma.mul_add(standard_normal(rng), mu).exp().round() as i64
+            }
+        }
+    }
+}
+
+/// A standard normal sample, by the Box-Muller transform.
+fn standard_normal(rng: &mut BidRng) -> f64 {
+    let radius = (-2.0 * (1.0 - rng.unit()).ln()).sqrt();
+    radius * (std::f64::consts::TAU * rng.unit()).cos()
+}
+
+/// The distribution of revenue over the simulated auctions.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationReport {
+    /// The revenue of each iteration in cents, lowest first.
+    revenues: Vec<i128>,
+    mean: f64,
+    mean_lots_sold: f64,
+    /// The 5th, 25th, 50th, 75th and 95th percentiles of revenue.
+    percentiles: Vec<(u8, i128)>,
+}
+
+impl SimulationReport {
+    /// The number of auctions simulated.
+    pub const fn iterations(&self) -> usize {
+        self.revenues.len()
+    }
+
+    /// The revenue of each simulated auction in cents, lowest first.
+    pub fn revenues(&self) -> &[i128] {
+        &self.revenues
+    }
+
+    /// The mean revenue in cents, zero without iterations.
+    pub const fn mean(&self) -> f64 {
+        self.mean
+    }
+
+    /// The mean number of lots sold, zero without iterations.
+    pub const fn mean_lots_sold(&self) -> f64 {
+        self.mean_lots_sold
+    }
+
+    /// The 5th, 25th, 50th, 75th and 95th percentiles of revenue, as pairs
+    /// of the percentile and the revenue in cents, empty without iterations.
+    pub fn percentiles(&self) -> &[(u8, i128)] {
+        &self.percentiles
+    }
+
+    /// The revenue at the given percentile by the nearest rank method, None
+    /// without iterations.
+    pub fn percentile(&self, percent: u8) -> Option<i128> {
+        let rank = (usize::from(percent.min(100)) * self.revenues.len()).div_ceil(100);
+        self.revenues.get(rank.saturating_sub(1)).copied()
+    }
+
+    /// The median revenue in cents, None without iterations.
+    pub fn median(&self) -> Option<i128> {
+        self.percentile(50)
+    }
+}
+
+/// Resolves sampled bid sets against the auction, reporting the distribution
+/// of revenue.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve each bid set against.
+/// * `models` - Each bidder model paired with the number of bidders it models.
+/// * `iterations` - The number of bid sets to sample.
+/// * `seed` - The seed the bids are sampled from.
+///
+/// # Returns
+/// The revenue of every iteration, its mean and percentiles.
+///
+pub fn simulate(
+    auction: &Auction,
+    models: &[(&dyn BidderModel, usize)],
+    iterations: usize,
+    seed: u64,
+) -> SimulationReport {
+    let mut rng = BidRng::new(seed);
+    let mut lots_sold = 0;
+    let mut revenues: Vec<i128> = (0..iterations)
+        .map(|_| {
+            let bids = sample_bids(&mut rng, models);
+            let sales = auction.resolve_bids_ref(&bids);
+            lots_sold += sales.total_quantity();
+            sales.total_revenue().unwrap_or(i128::MAX)
+        })
+        .collect();
+    revenues.sort_unstable();
+    let (mean, mean_lots_sold) = match iterations {
+        0 => (0.0, 0.0),
+        n => (
+            revenues.iter().map(|&revenue| revenue as f64).sum::<f64>() / n as f64,
+            lots_sold as f64 / n as f64,
+        ),
+    };
+    let mut report = SimulationReport {
+        revenues,
+        mean,
+        mean_lots_sold,
+        percentiles: Vec::new(),
+    };
+    report.percentiles = PERCENTILES
+        .iter()
+        .filter_map(|&percent| Some((percent, report.percentile(percent)?)))
+        .collect();
+    report
+}
+
+/// One bid from every modelled bidder, with ids drawn from the generator so
+/// the bid set is reproducible.
+fn sample_bids(rng: &mut BidRng, models: &[(&dyn BidderModel, usize)]) -> Bids {
+    models
+        .iter()
+        .flat_map(|&(model, bidders)| std::iter::repeat_n(model, bidders))
+        .map(|model| {
+            let id = Uuid::from_u64_pair(rng.next_u64(), rng.next_u64());
+            let amount = model.valuation(rng);
+            Bid::with_id(id, amount, model.quantity(rng))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{simulation::*, *};
+
+    const UNIFORM: Valuation = Valuation::Uniform { min: 0, max: 100 };
+
+    #[test]
+    fn simulate_is_deterministic_from_the_seed() {
+        let auction = AuctionBuilder::new().lots(2).build();
+        let models: [(&dyn BidderModel, usize); 2] = [
+            (&UNIFORM, 3),
+            (
+                &Valuation::Normal {
+                    mean: 80.0,
+                    std_dev: 10.0,
+                },
+                2,
+            ),
+        ];
+        let report = simulate(&auction, &models, 200, 7);
+        assert_eq!(report, simulate(&auction, &models, 200, 7));
+        assert_ne!(
+            report.revenues(),
+            simulate(&auction, &models, 200, 8).revenues()
+        );
+        assert_eq!(report.iterations(), 200);
+        assert!(report.mean_lots_sold() <= 2.0);
+    }
+
+    #[test]
+    fn simulate_reports_percentiles_in_order() {
+        let auction = AuctionBuilder::new().lots(1).build();
+        let report = simulate(&auction, &[(&UNIFORM, 2)], 500, 1);
+        let revenues: Vec<i128> = report
+            .percentiles()
+            .iter()
+            .map(|&(_, revenue)| revenue)
+            .collect();
+        assert_eq!(report.percentiles().len(), 5);
+        assert!(revenues.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(report.median(), Some(revenues[2]));
+        // One lot sells at the higher of two uniform valuations, whose
+        // expected value is two thirds of the range.
+        assert!((report.mean() - 66.7).abs() < 5.0);
+    }
+
+    #[test]
+    fn simulate_compares_strategies_over_the_same_bids() {
+        let models: [(&dyn BidderModel, usize); 1] = [(&UNIFORM, 6)];
+        let report = |strategy| {
+            let auction = AuctionBuilder::new().strategy(strategy).lots(3).build();
+            simulate(&auction, &models, 100, 3)
+        };
+        let single = report(AuctionStrategy::SinglePrice);
+        let multi = report(AuctionStrategy::MultiPrice);
+        assert!(multi.mean() >= single.mean());
+        assert_eq!(single.mean_lots_sold(), multi.mean_lots_sold());
+    }
+
+    #[test]
+    fn valuations_are_never_negative() {
+        let mut rng = BidRng::new(0);
+        let wide = Valuation::Normal {
+            mean: 0.0,
+            std_dev: 50.0,
+        };
+        let log_normal = Valuation::LogNormal {
+            mu: 3.0,
+            sigma: 0.5,
+        };
+        for _ in 0..1000 {
+            assert!(wide.valuation(&mut rng) >= 0);
+            assert!(log_normal.valuation(&mut rng) >= 0);
+            assert!((0..=100).contains(&UNIFORM.valuation(&mut rng)));
+        }
+    }
+
+    #[test]
+    fn simulate_without_iterations() {
+        let report = simulate(&AuctionBuilder::new().build(), &[(&UNIFORM, 2)], 0, 0);
+        assert_eq!(report.mean(), 0.0);
+        assert_eq!(report.median(), None);
+        assert!(report.percentiles().is_empty());
+    }
+}
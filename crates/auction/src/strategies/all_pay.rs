@@ -0,0 +1,117 @@
+//! Module containing the all-pay auction algorithm.
+use alloc::vec::Vec;
+
+use crate::{
+    strategies::fill::{fill_lots, rank_highest_first},
+    Auction, AuctionObserver, Bid, RejectReason, Sale, Sales,
+};
+
+/// Resolves bids into sales using the all-pay algorithm.
+///
+/// Every bid at or above the reserve price pays its amount, but only the best
+/// bids covering the lots receive any quantity. Losing bids are returned as
+/// sales with zero quantity, which are charged their amount once.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// A sale for every participating bid, winners first.
+///
+pub fn all_pay(auction: &Auction, bids: &[Bid]) -> Sales {
+    all_pay_observed(auction, bids, &mut ())
+}
+
+/// Resolves bids as [`all_pay`] does, telling the observer about each bid
+/// below the reserve and each sale, losing or not.
+pub fn all_pay_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let mut ranked: Vec<&Bid> = bids.iter().collect();
+    rank_highest_first(&mut ranked, auction.secondary_sort);
+
+    let participating = ranked
+        .iter()
+        .take_while(|bid| bid.amount >= auction.reserve_price)
+        .count();
+    let mut winning_bids = fill_lots(auction, &ranked[..participating], |_| true, &mut ())
+        .into_iter()
+        .peekable();
+
+    let sales = ranked[..participating]
+        .iter()
+        .map(|bid| {
+            let quantity = winning_bids
+                .next_if(|won| won.id == bid.id)
+                .map_or(0, |won| won.quantity);
+            observer.on_allocation(bid, quantity, bid.amount);
+            Sale::new(bid.bidder_id, bid.amount, quantity).with_bid(bid)
+        })
+        .collect();
+    for bid in &ranked[participating..] {
+        observer.on_bid_rejected(bid, RejectReason::BelowReserve);
+    }
+    sales
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn all_pay_auction(lots: usize, reserve_price: i64) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .lots(lots)
+            .reserve_price(reserve_price)
+            .build()
+    }
+
+    #[test]
+    fn all_pay_returns_empty_for_no_bids() {
+        let sales = all_pay_auction(1, 0).resolve_bids(vec![]);
+        assert!(sales.is_empty());
+    }
+
+    #[test]
+    fn all_pay_every_bidder_pays() {
+        let bids: Bids = vec![bid![10, 1], bid![30, 1], bid![20, 1]];
+        let sales = all_pay_auction(1, 0).resolve_bids(bids);
+        assert_eq!(sales.len(), 3);
+        assert_eq!(sales[0].amount, 30);
+        assert_eq!(sales[0].quantity, 1);
+        assert_eq!(sales[1].amount, 20);
+        assert_eq!(sales[1].quantity, 0);
+        assert_eq!(sales[2].amount, 10);
+        assert_eq!(sales[2].quantity, 0);
+    }
+
+    #[test]
+    fn all_pay_partially_fills_marginal_winner() {
+        let bids: Bids = vec![bid![10, 3], bid![30, 1]];
+        let sales = all_pay_auction(2, 0).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].quantity, 1);
+        assert_eq!(sales[1].quantity, 1);
+        assert_eq!(sales[1].amount, 10);
+    }
+
+    #[test]
+    fn all_pay_reserve_is_participation_threshold() {
+        let bids: Bids = vec![bid![10, 1], bid![30, 1], bid![20, 1]];
+        let sales = all_pay_auction(1, 15).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.amount >= 15));
+    }
+
+    #[test]
+    fn all_pay_result_charges_losing_bids() {
+        let bids: Bids = vec![bid![10, 1], bid![30, 2], bid![20, 1]];
+        let result = all_pay_auction(2, 0).resolve(bids).unwrap();
+        assert_eq!(result.lots_sold(), 2);
+        assert_eq!(result.clearing_price(), Some(30));
+        assert_eq!(result.revenue(), 90);
+    }
+}
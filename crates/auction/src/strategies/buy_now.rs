@@ -0,0 +1,311 @@
+//! Module for the buy-now pre-pass that sells to bids meeting the buy-now
+//! price before the strategy runs.
+use alloc::vec::Vec;
+
+use crate::{
+    strategies::fill::{reject_reason, LevelFiller},
+    Auction, AuctionDirection, AuctionObserver, Bid, Bids, Sale, Sales,
+};
+
+/// Buy-now pre-pass, selling at the buy-now price to every bid that meets it
+/// before resolving the remaining lots as usual.
+///
+/// Bids meeting the buy-now price are filled earliest submitted first, with
+/// the auction's allocation policy and tie break sharing the lots if they ask
+/// for more than there are. Their sales keep the buy-now price and come first.
+/// The remaining bids, along with any that met the price but went unfilled,
+/// are then resolved by the auction's strategy for the lots left, so buy-now
+/// sales play no part in setting its clearing price. Lot caps and budgets
+/// apply across both passes, and the lots left keep their place in the
+/// reserve schedule.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+/// * `buy_now_price` - The price that wins lots immediately.
+///
+/// # Returns
+/// The buy-now sales followed by the strategy's sales.
+///
+pub fn buy_now(auction: &Auction, bids: &[Bid], buy_now_price: i64) -> Sales {
+    buy_now_observed(auction, bids, buy_now_price, &mut ())
+}
+
+/// Resolves bids as [`buy_now`] does, telling the observer about each
+/// buy-now sale and then the strategy's decisions.
+pub fn buy_now_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    buy_now_price: i64,
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let meets = |bid: &Bid| match auction.direction {
+        AuctionDirection::Forward => bid.amount >= buy_now_price,
+        AuctionDirection::Reverse => bid.amount <= buy_now_price,
+    };
+    let mut hits: Vec<&Bid> = bids
+        .iter()
+        .filter(|bid| meets(bid) && bid.quantity > 0)
+        .collect();
+    if hits.is_empty() {
+        return auction
+            .strategy
+            .resolve_observed_by(auction, bids, observer);
+    }
+    hits.sort_by(|a, b| a.cmp_submitted(b));
+
+    let filled = LevelFiller::new(auction).fill_level(&hits, &mut ());
+    let bought: usize = filled.iter().map(|bid| bid.quantity).sum();
+    let mut sales: Sales = filled
+        .iter()
+        .map(|bid| {
+            observer.on_allocation(bid, bid.quantity, buy_now_price);
+            Sale::new(bid.bidder_id, buy_now_price, bid.quantity).with_bid(bid)
+        })
+        .collect();
+
+    let rest: Bids = bids
+        .iter()
+        .filter(|bid| !filled.iter().any(|winner| winner.id == bid.id))
+        .copied()
+        .collect();
+    let remaining_lots = auction.lots - bought;
+    if remaining_lots == 0 {
+        for bid in &rest {
+            observer.on_bid_rejected(bid, reject_reason(auction, bid.amount));
+        }
+        return sales;
+    }
+    let rest_auction = remaining_auction(auction, &filled, buy_now_price);
+    sales.extend(
+        rest_auction
+            .strategy
+            .resolve_observed_by(&rest_auction, &rest, observer),
+    );
+    sales
+}
+
+/// The auction for the lots the buy-now bids left, with their bidders' buy-now
+/// fills counted against their lot caps and budgets and the reserve schedule
+/// starting from the first lot left.
+fn remaining_auction(auction: &Auction, filled: &[Bid], buy_now_price: i64) -> Auction {
+    let bought: usize = filled.iter().map(|bid| bid.quantity).sum();
+    let mut rest = Auction {
+        lots: auction.lots - bought,
+        buy_now_price: None,
+        reserve_schedule: schedule_after(&auction.reserve_schedule, bought),
+        ..auction.clone()
+    };
+    if let Some(&(_, reserve)) = rest
+        .reserve_schedule
+        .first()
+        .or_else(|| auction.reserve_schedule.last())
+    {
+        rest.reserve_price = reserve;
+    }
+    for bid in filled {
+        *rest.awarded.entry(bid.bidder_id).or_default() += bid.quantity;
+        if let Some(budget) = rest.budgets.get_mut(&bid.bidder_id) {
+            let cost = i128::from(buy_now_price) * bid.quantity as i128;
+            *budget = i64::try_from((i128::from(*budget) - cost).max(0)).unwrap_or(i64::MAX);
+        }
+    }
+    rest
+}
+
+/// The reserve schedule of the lots after those sold, empty once every run
+/// has been sold.
+fn schedule_after(schedule: &[(usize, i64)], sold: usize) -> Vec<(usize, i64)> {
+    let mut start: usize = 0;
+    let mut rest = Vec::new();
+    for &(lots, reserve) in schedule {
+        let end = start.saturating_add(lots);
+        if end > sold {
+            rest.push((end - start.max(sold), reserve));
+        }
+        start = end;
+    }
+    rest
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn buy_now_auction(strategy: AuctionStrategy, lots: usize) -> Auction {
+        AuctionBuilder::new()
+            .strategy(strategy)
+            .lots(lots)
+            .buy_now_price(100)
+            .build()
+    }
+
+    #[test]
+    fn buy_now_sells_at_buy_now_price() {
+        let bids: Bids = vec![bid![150, 1], bid![40, 1], bid![30, 1]];
+        let sales = buy_now_auction(AuctionStrategy::MultiPrice, 3).resolve_bids(bids.clone());
+        assert_eq!(sales[0], Sale::new(bids[0].id, 100, 1).with_bid(&bids[0]));
+        assert_eq!(sales[1].amount, 40);
+        assert_eq!(sales[2].amount, 30);
+    }
+
+    #[test]
+    fn buy_now_excluded_from_single_price_clearing() {
+        let bids: Bids = vec![bid![40, 1], bid![120, 2], bid![30, 1], bid![20, 1]];
+        let sales = buy_now_auction(AuctionStrategy::SinglePrice, 4).resolve_bids(bids.clone());
+        assert_eq!(sales.len(), 3);
+        assert_eq!(sales[0], Sale::new(bids[1].id, 100, 2).with_bid(&bids[1]));
+        assert!(sales[1..].iter().all(|sale| sale.amount == 30));
+    }
+
+    #[test]
+    fn buy_now_exactly_at_price_wins() {
+        let bids: Bids = vec![bid![100, 1], bid![99, 1]];
+        let sales = buy_now_auction(AuctionStrategy::SinglePrice, 1).resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![Sale::new(bids[0].id, 100, 1).with_bid(&bids[0])]
+        );
+    }
+
+    #[test]
+    fn buy_now_earliest_submitted_wins_when_oversubscribed() {
+        let later = bid![200, 2].with_submitted_at(200);
+        let earlier = bid![150, 2].with_submitted_at(100);
+        let auction = buy_now_auction(AuctionStrategy::SinglePrice, 3);
+        let sales = auction.resolve_bids(vec![later, earlier, bid![50, 1]]);
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(earlier.id, 100, 2).with_bid(&earlier),
+                Sale::new(later.id, 100, 1).with_bid(&later)
+            ]
+        );
+    }
+
+    #[test]
+    fn buy_now_uses_tie_break_when_oversubscribed() {
+        let bids: Bids = vec![bid![150, 1], bid![150, 3]];
+        let auction = AuctionBuilder::new()
+            .lots(3)
+            .buy_now_price(100)
+            .tie_break(TieBreak::LargestQuantityFirst)
+            .build();
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![Sale::new(bids[1].id, 100, 3).with_bid(&bids[1])]
+        );
+    }
+
+    #[test]
+    fn buy_now_applies_to_other_strategies() {
+        let bids: Bids = vec![bid![150, 1], bid![50, 1], bid![40, 1]];
+        let auction = buy_now_auction(AuctionStrategy::GeneralizedSecondPrice, 2);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(sales[0], Sale::new(bids[0].id, 100, 1).with_bid(&bids[0]));
+        assert_eq!(sales[1].bidder_id, bids[1].id);
+        assert_eq!(sales[1].amount, 40);
+    }
+
+    #[test]
+    fn buy_now_without_hits_resolves_as_usual() {
+        let bids: Bids = vec![bid![50, 1], bid![40, 1]];
+        let auction = buy_now_auction(AuctionStrategy::SinglePrice, 1);
+        let plain = AuctionBuilder::new().lots(1).build();
+        assert_eq!(auction.resolve_bids(bids.clone()), plain.resolve_bids(bids));
+    }
+
+    #[test]
+    fn buy_now_unfilled_hit_competes_for_remaining_lots() {
+        let all_or_nothing = bid![150, 3].with_all_or_nothing(true);
+        let bids: Bids = vec![all_or_nothing, bid![60, 1]];
+        let auction = buy_now_auction(AuctionStrategy::MultiPrice, 2);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(sales, vec![Sale::new(bids[1].id, 60, 1).with_bid(&bids[1])]);
+    }
+
+    #[test]
+    fn buy_now_in_reverse_accepts_offers_at_or_below_price() {
+        let bids: Bids = vec![bid![90, 1], bid![120, 1], bid![130, 1]];
+        let auction = AuctionBuilder::new()
+            .direction(AuctionDirection::Reverse)
+            .lots(2)
+            .buy_now_price(100)
+            .build();
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(sales[0], Sale::new(bids[0].id, 100, 1).with_bid(&bids[0]));
+        assert_eq!(sales[1].bidder_id, bids[1].id);
+    }
+
+    #[test]
+    fn buy_now_counts_towards_lot_cap() {
+        let bidder = Uuid::new_v4();
+        let bids: Bids = vec![
+            bid![150, 2].with_bidder_id(bidder),
+            bid![60, 2].with_bidder_id(bidder),
+            bid![50, 3],
+        ];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(4)
+            .buy_now_price(100)
+            .max_lots_per_bidder(2)
+            .build();
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bidder, 100, 2).with_bid(&bids[0]),
+                Sale::new(bids[2].id, 50, 2).with_bid(&bids[2])
+            ]
+        );
+    }
+
+    #[test]
+    fn buy_now_spends_from_budget() {
+        let bidder = Uuid::new_v4();
+        let bids: Bids = vec![
+            bid![150, 1].with_bidder_id(bidder),
+            bid![60, 2].with_bidder_id(bidder),
+            bid![50, 3],
+        ];
+        let auction = buy_now_auction(AuctionStrategy::MultiPrice, 4);
+        let sales = auction.resolve_with_budgets(bids.clone(), HashMap::from([(bidder, 200)]));
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bidder, 100, 1).with_bid(&bids[0]),
+                Sale::new(bidder, 60, 1).with_bid(&bids[1]),
+                Sale::new(bids[2].id, 50, 2).with_bid(&bids[2])
+            ]
+        );
+    }
+
+    #[test]
+    fn buy_now_keeps_reserve_schedule_place() {
+        let bids: Bids = vec![bid![300, 1], bid![150, 1]];
+        let auction = AuctionBuilder::new()
+            .lots(2)
+            .reserve_schedule(vec![(1, 100), (1, 200)])
+            .buy_now_price(300)
+            .build();
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![Sale::new(bids[0].id, 300, 1).with_bid(&bids[0])]
+        );
+    }
+
+    #[test]
+    fn buy_now_resolutions_verify() {
+        for seed in 0..20 {
+            let bids = generators::large_quantities(seed, 100, 120, 3);
+            for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+                let auction = buy_now_auction(strategy, seed as usize * 4 + 1);
+                let sales = auction.resolve_bids_ref(&bids);
+                assert_eq!(verify(&auction, &bids, &sales), Ok(()));
+            }
+        }
+    }
+}
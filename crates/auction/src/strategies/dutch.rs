@@ -0,0 +1,194 @@
+//! Module containing the Dutch (descending clock) auction algorithm.
+use alloc::vec::Vec;
+
+use crate::{
+    strategies::fill::{fill_lots, rank_highest_first},
+    Auction, AuctionObserver, Bid, RejectReason, Sale, Sales,
+};
+
+/// Resolves bids into sales using a descending price clock.
+///
+/// The clock starts at `start_price` and drops by `decrement` each tick. Each
+/// bid accepts once the clock falls to its amount, and the clock stops at the
+/// first price where the accepted quantity covers the lots. The clock never
+/// drops below the reserve price, if it reaches that floor the accepted bids
+/// win and the remaining lots go unsold.
+///
+/// Every winner pays the stop price. When the bids accepted at the stop price
+/// ask for more than the lots remaining, higher bids are filled first and
+/// equal bids are filled in the order they were submitted.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+/// * `start_price` - The opening price of the clock.
+/// * `decrement` - How far the clock drops each tick.
+///
+/// # Returns
+/// A list of sales for the bids.
+///
+pub fn dutch(auction: &Auction, bids: &[Bid], start_price: i64, decrement: i64) -> Sales {
+    dutch_observed(auction, bids, start_price, decrement, &mut ())
+}
+
+/// Resolves bids as [`dutch`] does, telling the observer about each
+/// rejection and allocation.
+pub fn dutch_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    start_price: i64,
+    decrement: i64,
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let Some(floor) = floor_price(start_price, decrement, auction.reserve_price) else {
+        for bid in bids {
+            observer.on_bid_rejected(bid, RejectReason::BelowReserve);
+        }
+        return Vec::new();
+    };
+    let mut ranked: Vec<&Bid> = bids.iter().collect();
+    rank_highest_first(&mut ranked, auction.secondary_sort);
+
+    let mut claimed = 0;
+    let mut stop_price = floor;
+    for bid in ranked.iter().take_while(|bid| bid.amount >= floor) {
+        claimed = bid.quantity.saturating_add(claimed);
+        if claimed >= auction.lots {
+            stop_price = clock_price(start_price, decrement, bid.amount);
+            break;
+        }
+    }
+
+    fill_lots(auction, &ranked, |bid| bid.amount >= stop_price, observer)
+        .iter()
+        .map(|bid| {
+            observer.on_allocation(bid, bid.quantity, stop_price);
+            Sale::new(bid.bidder_id, stop_price, bid.quantity).with_bid(bid)
+        })
+        .collect()
+}
+
+/// The highest clock price at or below the given amount.
+///
+/// Worked in `i128` so the gap between the start price and the amount cannot
+/// overflow, the result lies between the two and so fits back in an `i64`.
+const fn clock_price(start_price: i64, decrement: i64, amount: i64) -> i64 {
+    if amount >= start_price {
+        return start_price;
+    }
+    let (start, decrement) = (start_price as i128, decrement as i128);
+    let ticks = (start - amount as i128 + decrement - 1) / decrement;
+    (start - ticks * decrement) as i64
+}
+
+/// The lowest clock price at or above the reserve, None if the clock never
+/// reaches a price at or above the reserve.
+const fn floor_price(start_price: i64, decrement: i64, reserve_price: i64) -> Option<i64> {
+    if start_price < reserve_price {
+        return None;
+    }
+    if decrement <= 0 {
+        return Some(start_price);
+    }
+    let (start, decrement) = (start_price as i128, decrement as i128);
+    let ticks = (start - reserve_price as i128) / decrement;
+    Some((start - ticks * decrement) as i64)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn dutch_auction(lots: usize, reserve_price: i64) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::Dutch {
+                start_price: 100,
+                decrement: 10,
+            })
+            .lots(lots)
+            .reserve_price(reserve_price)
+            .build()
+    }
+
+    #[test]
+    fn dutch_returns_empty_for_no_bids() {
+        let auction = dutch_auction(10, 0);
+        let sales = auction.resolve_bids(vec![]);
+        assert!(sales.is_empty());
+    }
+
+    #[test]
+    fn dutch_stops_at_first_covering_price() {
+        let bids: Bids = vec![bid![75, 1], bid![55, 1], bid![92, 1]];
+        let auction = dutch_auction(2, 0);
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 70);
+        assert_eq!(sales[1].amount, 70);
+        assert_eq!(sales[0].quantity, 1);
+    }
+
+    #[test]
+    fn dutch_bids_above_start_pay_start_price() {
+        let bids: Bids = vec![bid![150, 1], bid![120, 1]];
+        let auction = dutch_auction(2, 0);
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 100);
+        assert_eq!(sales[1].amount, 100);
+    }
+
+    #[test]
+    fn dutch_partially_fills_marginal_bid() {
+        let bids: Bids = vec![bid![80, 2], bid![95, 1]];
+        let auction = dutch_auction(2, 0);
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 80);
+        assert_eq!(sales[0].quantity, 1);
+        assert_eq!(sales[1].amount, 80);
+        assert_eq!(sales[1].quantity, 1);
+    }
+
+    #[test]
+    fn dutch_ties_at_stop_price_favour_higher_then_earlier_bids() {
+        let bids: Bids = vec![bid![71, 1], bid![73, 1], bid![71, 1]];
+        let first_tied = bids[0].id;
+        let auction = dutch_auction(2, 0);
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 70);
+        assert_eq!(sales[0].quantity, 1);
+        assert_eq!(sales[1].bidder_id, first_tied);
+    }
+
+    #[test]
+    fn dutch_reserve_is_floor_with_lots_unsold() {
+        let bids: Bids = vec![bid![65, 1], bid![30, 1]];
+        let auction = dutch_auction(3, 45);
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].amount, 50);
+    }
+
+    #[test]
+    fn dutch_start_below_reserve_sells_nothing() {
+        let bids: Bids = vec![bid![150, 1]];
+        let auction = dutch_auction(1, 120);
+        let sales = auction.resolve_bids(bids);
+        assert!(sales.is_empty());
+    }
+
+    #[test]
+    fn dutch_clock_near_i64_max_does_not_overflow() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::Dutch {
+                start_price: i64::MAX,
+                decrement: i64::MAX,
+            })
+            .build();
+        let sales = auction.resolve_bids(vec![bid![5, usize::MAX]]);
+        assert_eq!(sales[0].amount, 0);
+        assert_eq!(sales[0].quantity, 1);
+    }
+}
@@ -0,0 +1,1034 @@
+//! Module containing the lot filling shared by the strategies.
+use alloc::{collections::BinaryHeap, vec::Vec};
+use core::{
+    borrow::Borrow,
+    cmp::{Ordering, Reverse},
+};
+
+use uuid::Uuid;
+
+use crate::{
+    collections::{HashMap, HashSet},
+    rng::BidRng,
+    AllocationPolicy, Auction, AuctionDirection, AuctionObserver, Bid, Bids, PartialFillPolicy,
+    RejectReason, SecondarySort, TieBreak,
+};
+
+/// Ranks the bids from best to worst for the auction's direction.
+///
+/// Forward auctions rank the highest bids first, reverse auctions the lowest
+/// offers first, and equal bids are ranked by the auction's secondary sort.
+pub fn rank_bids<B: Borrow<Bid>>(auction: &Auction, bids: &mut [B]) {
+    match auction.direction {
+        AuctionDirection::Forward => rank_highest_first(bids, auction.secondary_sort),
+        AuctionDirection::Reverse => rank_lowest_first(bids, auction.secondary_sort),
+    }
+}
+
+/// Ranks the bids as [`rank_bids`] does, then by their priority tier, lower
+/// tiers first, keeping every bid that does not qualify after those that do.
+///
+/// Bids without a tier rank after every tier, so untiered bids are ranked as
+/// [`rank_bids`] ranks them.
+pub fn rank_tiered<B: Borrow<Bid>>(auction: &Auction, bids: &mut [B]) {
+    rank_bids(auction, bids);
+    if bids.iter().any(|bid| bid.borrow().priority.is_some()) {
+        bids.sort_by_key(|bid| {
+            let bid = bid.borrow();
+            (!auction.qualifies(bid.amount), bid.tier())
+        });
+    }
+}
+
+/// Ranks the bids highest first, then by the secondary sort.
+///
+/// Equal bids the secondary sort does not separate keep their order.
+pub fn rank_highest_first<B: Borrow<Bid>>(bids: &mut [B], secondary: SecondarySort) {
+    bids.sort_by(|a, b| {
+        let (a, b) = (a.borrow(), b.borrow());
+        b.cmp_amount(a).then_with(|| cmp_secondary(secondary, a, b))
+    });
+}
+
+/// Ranks the bids lowest first, then by the secondary sort.
+///
+/// Equal bids the secondary sort does not separate keep their order.
+pub fn rank_lowest_first<B: Borrow<Bid>>(bids: &mut [B], secondary: SecondarySort) {
+    bids.sort_by(|a, b| {
+        let (a, b) = (a.borrow(), b.borrow());
+        a.cmp_amount(b).then_with(|| cmp_secondary(secondary, a, b))
+    });
+}
+
+/// Orders bids with the same amount by the secondary sort, better first.
+fn cmp_secondary(secondary: SecondarySort, a: &Bid, b: &Bid) -> Ordering {
+    match secondary {
+        SecondarySort::EarliestSubmitted => a.cmp_submitted(b),
+        SecondarySort::LargestQuantityFirst => b.quantity.cmp(&a.quantity),
+        SecondarySort::SmallestQuantityFirst => a.quantity.cmp(&b.quantity),
+        SecondarySort::InputOrder => Ordering::Equal,
+    }
+}
+
+/// Ranks only the qualifying bids needed to cover the lots, plus the rest of
+/// the marginal bid's price level, keeping at most about one heap entry per lot.
+///
+/// The ranked bids are the prefix a full [`rank_bids`] would put first, in the
+/// same order. If skipped or trimmed bids leave lots unfilled, the bids left out
+/// may still win, so the caller must fall back to ranking everything.
+///
+/// # Arguments
+/// * `auction` - The auction whose lots are being filled.
+/// * `bids` - The bids to select from, in submission order.
+///
+/// # Returns
+/// The best bids ranked, and whether they are all the qualifying bids.
+///
+pub fn rank_top_bids<'a>(auction: &Auction, bids: &'a [Bid]) -> (Vec<&'a Bid>, bool) {
+    let mut heap = BinaryHeap::new();
+    let mut covered: usize = 0;
+    for bid in bids.iter().filter(|bid| auction.qualifies(bid.amount)) {
+        let key = rank_key(auction, bid);
+        let worse = heap
+            .peek()
+            .is_some_and(|Reverse((lowest, _))| key < *lowest);
+        if covered >= auction.lots && worse {
+            continue;
+        }
+        heap.push(Reverse((key, bid.quantity)));
+        covered = covered.saturating_add(bid.quantity);
+        while let Some(&Reverse((_, quantity))) = heap.peek() {
+            if covered - quantity < auction.lots {
+                break;
+            }
+            heap.pop();
+            covered -= quantity;
+        }
+    }
+
+    let threshold = heap.peek().map(|Reverse((lowest, _))| *lowest);
+    let complete = covered < auction.lots;
+    rank_from_threshold(auction, bids, threshold, complete)
+}
+
+/// Ranks the same bids as [`rank_top_bids`], finding the marginal price level
+/// in expected linear time with a quantity weighted quickselect.
+///
+/// This suits many lots, where the heap would grow large. It copies the keys
+/// and quantities of the qualifying bids rather than reordering the bids.
+pub fn select_top_bids<'a>(auction: &Auction, bids: &'a [Bid]) -> (Vec<&'a Bid>, bool) {
+    let mut keys: Vec<(i128, usize)> = bids
+        .iter()
+        .filter(|bid| auction.qualifies(bid.amount))
+        .map(|bid| (rank_key(auction, bid), bid.quantity))
+        .collect();
+    let demand = keys.iter().fold(0_usize, |demand, (_, quantity)| {
+        demand.saturating_add(*quantity)
+    });
+    let complete = demand < auction.lots;
+    let threshold = if complete {
+        None
+    } else {
+        weighted_threshold(&mut keys, auction.lots)
+    };
+    rank_from_threshold(auction, bids, threshold, complete)
+}
+
+/// Keeps the ranked bids at or above the reserve price that ask for a lot,
+/// telling the observer about the rest.
+pub fn retain_fillable<'a>(
+    auction: &Auction,
+    ranked: Vec<&'a Bid>,
+    observer: &mut impl AuctionObserver,
+) -> Vec<&'a Bid> {
+    let (fillable, rest): (Vec<&Bid>, Vec<&Bid>) = ranked
+        .into_iter()
+        .partition(|bid| bid.amount >= auction.reserve_price && bid.quantity > 0);
+    for bid in rest {
+        let reason = if bid.amount < auction.reserve_price {
+            RejectReason::BelowReserve
+        } else {
+            RejectReason::NoLotsRemaining
+        };
+        observer.on_bid_rejected(bid, reason);
+    }
+    fillable
+}
+
+/// Ranks the qualifying bids worse than every one of the top bids, which
+/// follow them when every bid is ranked.
+pub fn rank_rest<'a>(auction: &Auction, bids: &'a [Bid], top: &[&Bid]) -> Vec<&'a Bid> {
+    let Some(threshold) = top.last().map(|bid| rank_key(auction, bid)) else {
+        return Vec::new();
+    };
+    let mut rest: Vec<&Bid> = bids
+        .iter()
+        .filter(|bid| auction.qualifies(bid.amount) && rank_key(auction, bid) < threshold)
+        .collect();
+    rank_bids(auction, &mut rest);
+    rest
+}
+
+/// A key that is larger the better the bid ranks for the auction's direction.
+fn rank_key(auction: &Auction, bid: &Bid) -> i128 {
+    match auction.direction {
+        AuctionDirection::Forward => i128::from(bid.amount),
+        AuctionDirection::Reverse => -i128::from(bid.amount),
+    }
+}
+
+/// The key of the bid whose quantity, added to every better bid's, covers the
+/// lots, None when the lots are zero or cannot be covered.
+fn weighted_threshold(mut keys: &mut [(i128, usize)], lots: usize) -> Option<i128> {
+    let mut covered: usize = 0;
+    while !keys.is_empty() {
+        let middle = keys.len() / 2;
+        let (better, &mut (key, quantity), worse) =
+            core::mem::take(&mut keys).select_nth_unstable_by(middle, |a, b| b.0.cmp(&a.0));
+        let better_quantity = better.iter().fold(0_usize, |total, (_, quantity)| {
+            total.saturating_add(*quantity)
+        });
+        if covered.saturating_add(better_quantity) >= lots {
+            keys = better;
+            continue;
+        }
+        covered = covered
+            .saturating_add(better_quantity)
+            .saturating_add(quantity);
+        if covered >= lots {
+            return Some(key);
+        }
+        keys = worse;
+    }
+    None
+}
+
+/// Ranks the qualifying bids at or above the threshold, or all of them when
+/// they are all needed.
+fn rank_from_threshold<'a>(
+    auction: &Auction,
+    bids: &'a [Bid],
+    threshold: Option<i128>,
+    complete: bool,
+) -> (Vec<&'a Bid>, bool) {
+    let mut ranked: Vec<&Bid> = bids
+        .iter()
+        .filter(|bid| auction.qualifies(bid.amount))
+        .filter(|bid| {
+            complete || threshold.is_some_and(|threshold| rank_key(auction, bid) >= threshold)
+        })
+        .collect();
+    rank_bids(auction, &mut ranked);
+    (ranked, complete)
+}
+
+/// Fills the auction's lots from the ranked bids, best first.
+///
+/// Whole price levels are filled while lots remain. A level that asks for more
+/// than the remaining lots is shared out according to the auction's allocation
+/// policy, and the bid the remaining lots cannot cover is split, skipped or
+/// ends the filling as its partial fill policy says. Bids are trimmed to what
+/// their bidder's budget and lot cap allow, and bids that refuse the fill they
+/// would receive are skipped, leaving their lots to the following bids.
+///
+/// # Arguments
+/// * `auction` - The auction whose lots are being filled.
+/// * `bids` - The bids to fill, already ranked from best to worst.
+/// * `qualifies` - Whether a bid may be filled, the ranked bids are filled
+///   until the first that does not qualify.
+/// * `observer` - Told about each bid that wins nothing, in ranked order.
+///
+/// # Returns
+/// The winning bids, with marginal bids reduced to the lots they received.
+///
+pub fn fill_lots<B: Borrow<Bid>>(
+    auction: &Auction,
+    bids: &[B],
+    qualifies: impl Fn(&Bid) -> bool,
+    observer: &mut impl AuctionObserver,
+) -> Bids {
+    let eligible = bids
+        .iter()
+        .take_while(|bid| qualifies((*bid).borrow()))
+        .count();
+    let winning_bids = LevelFiller::new(auction).fill_ranked(&bids[..eligible], observer);
+    for bid in bids[eligible..].iter().map(Borrow::borrow) {
+        observer.on_bid_rejected(bid, reject_reason(auction, bid.amount));
+    }
+    winning_bids
+}
+
+/// Why a bid at the amount that was never filled wins nothing: below the
+/// reserve if it does not qualify, outbid otherwise.
+pub fn reject_reason(auction: &Auction, amount: i64) -> RejectReason {
+    if auction.qualifies(amount) {
+        RejectReason::OutbidAtClearing
+    } else {
+        RejectReason::BelowReserve
+    }
+}
+
+/// Fills the auction's lots one price level at a time, as [`fill_lots`] does,
+/// so callers can hand out each level's winners before ranking the next.
+pub struct LevelFiller<'a> {
+    auction: &'a Auction,
+    remaining_lots: usize,
+    /// Whether a marginal bid left the remaining lots unsold.
+    stopped: bool,
+    limits: BidderLimits<'a>,
+    /// Draws the order of tied bids for the random tie break.
+    rng: BidRng,
+}
+
+impl<'a> LevelFiller<'a> {
+    pub fn new(auction: &'a Auction) -> Self {
+        let seed = match auction.tie_break {
+            TieBreak::Random { seed } => seed,
+            _ => 0,
+        };
+        Self {
+            auction,
+            remaining_lots: auction.lots,
+            stopped: false,
+            limits: BidderLimits::new(auction),
+            rng: BidRng::new(seed),
+        }
+    }
+
+    /// Whether no more lots can be filled, because every lot has been or a
+    /// marginal bid left the rest unsold.
+    pub const fn is_full(&self) -> bool {
+        self.remaining_lots == 0 || self.stopped
+    }
+
+    /// Fills the remaining lots from the ranked bids a price level at a time,
+    /// as [`fill_lots`] does for the bids that qualify.
+    pub fn fill_ranked<B: Borrow<Bid>>(
+        &mut self,
+        bids: &[B],
+        observer: &mut impl AuctionObserver,
+    ) -> Bids {
+        let mut winning_bids = Vec::new();
+        for level in bids.chunk_by(|a, b| {
+            let (a, b) = (a.borrow(), b.borrow());
+            a.amount == b.amount && a.priority == b.priority
+        }) {
+            if !self.is_full() {
+                winning_bids.extend(self.fill_level(level, observer));
+            } else if observer.enabled() {
+                self.reject(level, observer);
+            } else {
+                break;
+            }
+        }
+        winning_bids
+    }
+
+    /// Fills the remaining lots from a level of equal bids, breaking ties
+    /// when the level asks for more than the lots remaining.
+    ///
+    /// Under a reserve schedule the level may only take the lots whose reserve
+    /// it meets. The observer is told about each bid of the level that wins
+    /// nothing.
+    pub fn fill_level<B: Borrow<Bid>>(
+        &mut self,
+        level: &[B],
+        observer: &mut impl AuctionObserver,
+    ) -> Bids {
+        let Some(amount) = level.first().map(|bid| bid.borrow().amount) else {
+            return Vec::new();
+        };
+        if self.stopped {
+            self.reject(level, observer);
+            return Vec::new();
+        }
+        let sold = self.auction.lots - self.remaining_lots;
+        let lots = self
+            .remaining_lots
+            .min(self.auction.lots_within_reserve(sold, amount));
+        let demand = level.iter().fold(0_usize, |demand, bid| {
+            demand.saturating_add(bid.borrow().quantity)
+        });
+        let (filled, stopped) = if demand > lots {
+            let mut tied: Vec<&Bid> = level.iter().map(Borrow::borrow).collect();
+            break_ties(self.auction.tie_break, &mut tied, &mut self.rng);
+            if self.auction.aggregate_bidders {
+                group_by_bidder(&mut tied);
+            }
+            self.allocate(&tied, lots)
+        } else {
+            self.allocate(level, lots)
+        };
+        if observer.enabled() && filled.len() < level.len() {
+            let reason = self.rejection(amount);
+            let won: HashSet<Uuid> = filled.iter().map(|bid| bid.id).collect();
+            for bid in level.iter().map(Borrow::borrow) {
+                if !won.contains(&bid.id) {
+                    observer.on_bid_rejected(bid, reason);
+                }
+            }
+        }
+        self.remaining_lots -= filled.iter().map(|bid| bid.quantity).sum::<usize>();
+        self.stopped = stopped;
+        filled
+    }
+
+    /// Tells the observer why each bid wins nothing from the lots left, for
+    /// bids the filler will not reach.
+    pub fn reject<B: Borrow<Bid>>(&self, bids: &[B], observer: &mut impl AuctionObserver) {
+        for bid in bids.iter().map(Borrow::borrow) {
+            observer.on_bid_rejected(bid, self.rejection(bid.amount));
+        }
+    }
+
+    /// Why a bid at the amount wins nothing from the lots left: outbid once
+    /// every lot is sold, below the reserve if it does not qualify for the
+    /// next lot, and otherwise because the lots left could not fill it.
+    fn rejection(&self, amount: i64) -> RejectReason {
+        let sold = self.auction.lots - self.remaining_lots;
+        if !self.auction.qualifies(amount) {
+            RejectReason::BelowReserve
+        } else if self.remaining_lots == 0 {
+            RejectReason::OutbidAtClearing
+        } else if self.auction.lots_within_reserve(sold, amount) == 0 {
+            RejectReason::BelowReserve
+        } else {
+            RejectReason::NoLotsRemaining
+        }
+    }
+
+    /// Shares the lots between the level by the allocation policy, and
+    /// whether a marginal bid left the rest unsold.
+    fn allocate<B: Borrow<Bid>>(&mut self, level: &[B], lots: usize) -> (Bids, bool) {
+        match self.auction.allocation {
+            AllocationPolicy::Priority => {
+                fill_in_order(level, lots, &mut self.limits, self.auction.partial_fill)
+            }
+            AllocationPolicy::ProRata => (
+                fill_pro_rata(
+                    level,
+                    lots,
+                    &mut self.limits,
+                    self.auction.aggregate_bidders,
+                ),
+                false,
+            ),
+        }
+    }
+}
+
+/// Orders a level of tied bids by the tie break, keeping the ranked order
+/// between bids the tie break does not separate.
+fn break_ties(tie_break: TieBreak, tied: &mut [&Bid], rng: &mut BidRng) {
+    match tie_break {
+        TieBreak::InputOrder => {}
+        TieBreak::Random { .. } => {
+            for index in (1..tied.len()).rev() {
+                let other = rng.up_to(index as u64) as usize;
+                tied.swap(index, other);
+            }
+        }
+        TieBreak::LargestQuantityFirst => tied.sort_by_key(|bid| Reverse(bid.quantity)),
+        TieBreak::SmallestQuantityFirst => tied.sort_by_key(|bid| bid.quantity),
+    }
+}
+
+/// Moves each bidder's bids up to their first, keeping the order of the
+/// bidders and of each bidder's bids.
+fn group_by_bidder(tied: &mut [&Bid]) {
+    let mut first_seen: HashMap<Uuid, usize> = HashMap::new();
+    for (index, bid) in tied.iter().enumerate() {
+        first_seen.entry(bid.bidder_id).or_insert(index);
+    }
+    tied.sort_by_key(|bid| first_seen[&bid.bidder_id]);
+}
+
+/// Tracks what each bidder has been awarded so far.
+#[derive(Clone)]
+struct BidderLimits<'a> {
+    auction: &'a Auction,
+    spent: HashMap<Uuid, i128>,
+    awarded: HashMap<Uuid, usize>,
+}
+
+impl<'a> BidderLimits<'a> {
+    fn new(auction: &'a Auction) -> Self {
+        Self {
+            auction,
+            spent: HashMap::default(),
+            awarded: auction.awarded.clone(),
+        }
+    }
+
+    /// Trims the bid to what its bidder can still be awarded, None if the bid
+    /// would then fall short of its minimum fill.
+    fn trim(&self, bid: &Bid, lots: usize) -> Option<Bid> {
+        let mut quantity = bid.quantity.min(lots);
+        if let Some(cap) = self.auction.max_lots_per_bidder {
+            let awarded = self
+                .awarded
+                .get(&bid.bidder_id)
+                .copied()
+                .unwrap_or_default();
+            quantity = quantity.min(cap.saturating_sub(awarded));
+        }
+        if let Some(&budget) = self.auction.budgets.get(&bid.bidder_id) {
+            let spent = self.spent.get(&bid.bidder_id).copied().unwrap_or_default();
+            let left = (i128::from(budget) - spent).max(0);
+            if bid.amount > 0 {
+                let affordable = left / i128::from(bid.amount);
+                quantity = quantity.min(usize::try_from(affordable).unwrap_or(usize::MAX));
+            }
+        }
+        (quantity > 0 && quantity >= bid.min_fill()).then(|| bid.with_quantity(quantity))
+    }
+
+    /// Records that the bid has been filled.
+    fn record(&mut self, bid: &Bid) {
+        let cost = i128::from(bid.amount) * bid.quantity as i128;
+        let spent = self.spent.entry(bid.bidder_id).or_default();
+        *spent = spent.saturating_add(cost);
+        *self.awarded.entry(bid.bidder_id).or_default() += bid.quantity;
+    }
+}
+
+/// Fills the lots from the level in bid order, handling the bid the lots
+/// cannot cover by the partial fill policy.
+///
+/// Returns the filled bids, and whether the marginal bid left the rest of the
+/// lots unsold.
+fn fill_in_order<B: Borrow<Bid>>(
+    level: &[B],
+    mut lots: usize,
+    limits: &mut BidderLimits,
+    partial_fill: PartialFillPolicy,
+) -> (Bids, bool) {
+    let mut filled = Vec::new();
+    for bid in level.iter().map(Borrow::borrow) {
+        if lots == 0 {
+            break;
+        }
+        let marginal = || {
+            limits
+                .trim(bid, usize::MAX)
+                .is_some_and(|whole| whole.quantity > lots)
+        };
+        match partial_fill {
+            PartialFillPolicy::Skip if marginal() => continue,
+            PartialFillPolicy::LeaveUnsold if marginal() => return (filled, true),
+            _ => {}
+        }
+        if let Some(bid) = limits.trim(bid, lots) {
+            lots -= bid.quantity;
+            limits.record(&bid);
+            filled.push(bid);
+        }
+    }
+    (filled, false)
+}
+
+/// Shares the lots across the level in proportion to each bid's quantity.
+///
+/// Each bid receives the whole part of its share, then the lots left over go
+/// one each to the bids with the largest fractional remainders, earlier bids
+/// winning ties. Bids whose share falls short of their minimum fill are
+/// removed and the lots shared again between the rest.
+///
+/// Aggregated bidders are shared the lots by their total quantity instead,
+/// each bidder's share filling their bids in order.
+fn fill_pro_rata<B: Borrow<Bid>>(
+    level: &[B],
+    lots: usize,
+    limits: &mut BidderLimits,
+    aggregate_bidders: bool,
+) -> Bids {
+    let mut tentative = limits.clone();
+    let mut candidates: Bids = Vec::new();
+    for bid in level.iter().map(Borrow::borrow) {
+        if let Some(trimmed) = tentative.trim(bid, usize::MAX) {
+            tentative.record(&trimmed);
+            candidates.push(trimmed);
+        }
+    }
+
+    loop {
+        let shares = if aggregate_bidders {
+            bidder_shares(&candidates, lots)
+        } else {
+            pro_rata_shares(&candidates, lots)
+        };
+        let before = candidates.len();
+        let mut index = 0;
+        candidates.retain(|bid| {
+            let share = shares[index];
+            index += 1;
+            share == 0 || share >= bid.min_fill()
+        });
+        if candidates.len() == before {
+            let filled: Bids = candidates
+                .iter()
+                .zip(shares)
+                .filter(|(_, quantity)| *quantity > 0)
+                .map(|(bid, quantity)| bid.with_quantity(quantity))
+                .collect();
+            filled.iter().for_each(|bid| limits.record(bid));
+            return filled;
+        }
+    }
+}
+
+/// The largest remainder shares of the lots for each bidder, each handed to
+/// the bidder's bids in order.
+fn bidder_shares(bids: &[Bid], lots: usize) -> Vec<usize> {
+    let mut bidders: Bids = Vec::new();
+    let mut positions: HashMap<Uuid, usize> = HashMap::new();
+    let groups: Vec<usize> = bids
+        .iter()
+        .map(|bid| {
+            let position = *positions.entry(bid.bidder_id).or_insert_with(|| {
+                bidders.push(bid.with_quantity(0));
+                bidders.len() - 1
+            });
+            let demand = &mut bidders[position];
+            *demand = demand.with_quantity(demand.quantity.saturating_add(bid.quantity));
+            position
+        })
+        .collect();
+
+    let mut remaining = pro_rata_shares(&bidders, lots);
+    bids.iter()
+        .zip(groups)
+        .map(|(bid, group)| {
+            let share = bid.quantity.min(remaining[group]);
+            remaining[group] -= share;
+            share
+        })
+        .collect()
+}
+
+/// The largest remainder shares of the lots for each bid.
+fn pro_rata_shares(bids: &[Bid], lots: usize) -> Vec<usize> {
+    let demand: u128 = bids.iter().map(|bid| bid.quantity as u128).sum();
+    if demand <= lots as u128 {
+        return bids.iter().map(|bid| bid.quantity).collect();
+    }
+    let mut shares: Vec<(usize, u128)> = bids
+        .iter()
+        .map(|bid| {
+            let share = bid.quantity as u128 * lots as u128;
+            ((share / demand) as usize, share % demand)
+        })
+        .collect();
+
+    let allocated: usize = shares.iter().map(|(quantity, _)| quantity).sum();
+    let mut by_remainder: Vec<usize> = (0..bids.len()).collect();
+    by_remainder.sort_by(|&a, &b| shares[b].1.cmp(&shares[a].1));
+    for &index in by_remainder.iter().take(lots - allocated) {
+        shares[index].0 += 1;
+    }
+    shares.into_iter().map(|(quantity, _)| quantity).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        strategies::fill::{rank_highest_first, rank_lowest_first},
+        *,
+    };
+
+    fn pro_rata_auction(lots: usize) -> Auction {
+        AuctionBuilder::new()
+            .lots(lots)
+            .allocation(AllocationPolicy::ProRata)
+            .build()
+    }
+
+    #[test]
+    fn pro_rata_splits_odd_lots_across_equal_bids() {
+        let bids: Bids = vec![bid![10, 2], bid![10, 2]];
+        let first = bids[0].id;
+        let sales = pro_rata_auction(3).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].bidder_id, first);
+        assert_eq!(sales[0].quantity, 2);
+        assert_eq!(sales[1].quantity, 1);
+    }
+
+    #[test]
+    fn pro_rata_is_proportional_to_quantity() {
+        let bids: Bids = vec![bid![20, 1], bid![10, 2], bid![10, 6]];
+        let sales = pro_rata_auction(5).resolve_bids(bids);
+        assert_eq!(sales.len(), 3);
+        assert_eq!(sales[0].quantity, 1);
+        assert_eq!(sales[1].quantity, 1);
+        assert_eq!(sales[2].quantity, 3);
+        assert!(sales.iter().all(|sale| sale.amount == 10));
+    }
+
+    #[test]
+    fn pro_rata_largest_remainder_wins_leftover_lot() {
+        let bids: Bids = vec![bid![10, 1], bid![10, 4]];
+        let larger = bids[1].id;
+        let sales = pro_rata_auction(2).resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].bidder_id, larger);
+        assert_eq!(sales[0].quantity, 2);
+    }
+
+    #[test]
+    fn pro_rata_allocates_exactly_the_lots() {
+        let bids: Bids = vec![bid![10, 3], bid![10, 3], bid![10, 3]];
+        let sales = pro_rata_auction(7).resolve_bids(bids);
+        let sold: usize = sales.iter().map(|sale| sale.quantity).sum();
+        assert_eq!(sold, 7);
+        assert_eq!(sales[0].quantity, 3);
+        assert_eq!(sales[1].quantity, 2);
+        assert_eq!(sales[2].quantity, 2);
+    }
+
+    #[test]
+    fn priority_fills_tied_bids_in_order() {
+        let bids: Bids = vec![bid![10, 2], bid![10, 2]];
+        let auction = AuctionBuilder::new().lots(3).build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales[0].quantity, 2);
+        assert_eq!(sales[1].quantity, 1);
+    }
+
+    #[test]
+    fn earlier_timestamp_wins_tie() {
+        let later = bid![10, 1].with_submitted_at(200);
+        let earlier = bid![10, 1].with_submitted_at(100);
+        let auction = AuctionBuilder::new().lots(1).build();
+        let sales = auction.resolve_bids(vec![later, earlier]);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].bidder_id, earlier.id);
+    }
+
+    #[test]
+    fn earlier_timestamp_wins_tie_in_reverse() {
+        let later = bid![10, 1].with_submitted_at(200);
+        let earlier = bid![10, 1].with_submitted_at(100);
+        let auction = AuctionBuilder::new()
+            .direction(AuctionDirection::Reverse)
+            .lots(1)
+            .build();
+        let sales = auction.resolve_bids(vec![later, earlier]);
+        assert_eq!(sales[0].bidder_id, earlier.id);
+    }
+
+    #[test]
+    fn timestamp_does_not_outrank_amount() {
+        let higher = bid![20, 1].with_submitted_at(200);
+        let earlier = bid![10, 1].with_submitted_at(100);
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(1)
+            .build();
+        let sales = auction.resolve_bids(vec![earlier, higher]);
+        assert_eq!(sales[0].bidder_id, higher.id);
+    }
+
+    #[test]
+    fn untimestamped_ties_keep_input_order() {
+        let first = bid![10, 1];
+        let second = bid![10, 1];
+        let auction = AuctionBuilder::new().lots(1).build();
+        let sales = auction.resolve_bids(vec![first, second]);
+        assert_eq!(sales[0].bidder_id, first.id);
+    }
+
+    #[test]
+    fn timestamped_ties_beat_untimestamped() {
+        let untimestamped = bid![10, 1];
+        let timestamped = bid![10, 1].with_submitted_at(500);
+        let auction = AuctionBuilder::new().lots(1).build();
+        let sales = auction.resolve_bids(vec![untimestamped, timestamped]);
+        assert_eq!(sales[0].bidder_id, timestamped.id);
+    }
+
+    fn tie_break_auction(tie_break: TieBreak, lots: usize) -> Auction {
+        AuctionBuilder::new()
+            .lots(lots)
+            .tie_break(tie_break)
+            .build()
+    }
+
+    fn winners(auction: &Auction, bids: &[Bid]) -> Vec<(uuid::Uuid, usize)> {
+        auction
+            .resolve_bids_ref(bids)
+            .iter()
+            .map(|sale| (sale.bidder_id, sale.quantity))
+            .collect()
+    }
+
+    #[test]
+    fn tie_break_input_order_fills_ranked_order() {
+        let bids: Bids = vec![bid![10, 1], bid![10, 3], bid![10, 2]];
+        let auction = tie_break_auction(TieBreak::InputOrder, 3);
+        assert_eq!(
+            winners(&auction, &bids),
+            vec![(bids[0].id, 1), (bids[1].id, 2)]
+        );
+    }
+
+    #[test]
+    fn tie_break_largest_quantity_first() {
+        let bids: Bids = vec![bid![20, 1], bid![10, 1], bid![10, 3], bid![10, 2]];
+        let auction = tie_break_auction(TieBreak::LargestQuantityFirst, 4);
+        assert_eq!(
+            winners(&auction, &bids),
+            vec![(bids[0].id, 1), (bids[2].id, 3)]
+        );
+    }
+
+    #[test]
+    fn tie_break_smallest_quantity_first() {
+        let bids: Bids = vec![bid![10, 3], bid![10, 2], bid![10, 1]];
+        let auction = tie_break_auction(TieBreak::SmallestQuantityFirst, 4);
+        assert_eq!(
+            winners(&auction, &bids),
+            vec![(bids[2].id, 1), (bids[1].id, 2), (bids[0].id, 1)]
+        );
+    }
+
+    #[test]
+    fn tie_break_quantity_keeps_ranked_order_between_equal_quantities() {
+        let later = bid![10, 2].with_submitted_at(200);
+        let earlier = bid![10, 2].with_submitted_at(100);
+        let auction = tie_break_auction(TieBreak::LargestQuantityFirst, 2);
+        assert_eq!(winners(&auction, &[later, earlier]), vec![(earlier.id, 2)]);
+    }
+
+    #[test]
+    fn tie_break_random_is_reproducible_from_seed() {
+        let bids: Bids = (0..20).map(|_| bid![10, 1]).collect();
+        let auction = tie_break_auction(TieBreak::Random { seed: 7 }, 5);
+        let first = winners(&auction, &bids);
+        assert_eq!(first, winners(&auction, &bids));
+        assert_eq!(first.len(), 5);
+        assert_ne!(
+            first,
+            winners(&tie_break_auction(TieBreak::InputOrder, 5), &bids)
+        );
+    }
+
+    #[test]
+    fn tie_break_does_not_reorder_uncontested_levels() {
+        let bids: Bids = vec![bid![20, 2], bid![20, 1], bid![10, 1], bid![10, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(4)
+            .tie_break(TieBreak::SmallestQuantityFirst)
+            .build();
+        let bidders: Vec<_> = winners(&auction, &bids).iter().map(|(id, _)| *id).collect();
+        assert_eq!(&bidders[..2], &[bids[0].id, bids[1].id]);
+    }
+
+    #[test]
+    fn tie_break_applies_to_multi_price() {
+        let bids: Bids = vec![bid![10, 1], bid![10, 2]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .tie_break(TieBreak::LargestQuantityFirst)
+            .build();
+        assert_eq!(winners(&auction, &bids), vec![(bids[1].id, 2)]);
+    }
+
+    /// Sells five lots where the level at 80 asks for five units but only
+    /// three lots remain after the bid at 100.
+    fn secondary_sort_sales(
+        secondary_sort: SecondarySort,
+    ) -> (Bids, Vec<(Option<Uuid>, i64, usize)>) {
+        let bids: Bids = vec![bid![100, 2], bid![80, 4], bid![80, 1], bid![60, 3]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .marginal_pricing(MarginalPricing::ClearingPrice)
+            .secondary_sort(secondary_sort)
+            .lots(5)
+            .build();
+        let sales = auction
+            .resolve_bids_ref(&bids)
+            .iter()
+            .map(|sale| (sale.bid_id, sale.amount, sale.quantity))
+            .collect();
+        (bids, sales)
+    }
+
+    #[test]
+    fn secondary_sort_largest_quantity_first_clears_at_the_contested_level() {
+        let (bids, sales) = secondary_sort_sales(SecondarySort::LargestQuantityFirst);
+        assert_eq!(
+            sales,
+            vec![(Some(bids[0].id), 100, 2), (Some(bids[1].id), 80, 3)]
+        );
+    }
+
+    #[test]
+    fn secondary_sort_smallest_quantity_first_clears_below_the_contested_level() {
+        let (bids, sales) = secondary_sort_sales(SecondarySort::SmallestQuantityFirst);
+        assert_eq!(
+            sales,
+            vec![
+                (Some(bids[0].id), 100, 2),
+                (Some(bids[2].id), 80, 1),
+                (Some(bids[1].id), 60, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn secondary_sort_input_order_ignores_timestamps() {
+        let later = bid![10, 1].with_submitted_at(200);
+        let earlier = bid![10, 1].with_submitted_at(100);
+        let auction = AuctionBuilder::new()
+            .secondary_sort(SecondarySort::InputOrder)
+            .build();
+        let sales = auction.resolve_bids(vec![later, earlier]);
+        assert_eq!(sales[0].bidder_id, later.id);
+    }
+
+    #[test]
+    fn secondary_sort_applies_to_every_level() {
+        let bids: Bids = vec![bid![20, 1], bid![20, 2], bid![10, 1]];
+        let mut ranked: Vec<&Bid> = bids.iter().collect();
+        rank_highest_first(&mut ranked, SecondarySort::LargestQuantityFirst);
+        assert_eq!(ranked, [&bids[1], &bids[0], &bids[2]]);
+        rank_lowest_first(&mut ranked, SecondarySort::SmallestQuantityFirst);
+        assert_eq!(ranked, [&bids[2], &bids[0], &bids[1]]);
+    }
+
+    #[test]
+    fn tie_break_reorders_the_secondary_sort_at_the_margin() {
+        let bids: Bids = vec![bid![10, 1], bid![10, 3]];
+        let auction = AuctionBuilder::new()
+            .secondary_sort(SecondarySort::SmallestQuantityFirst)
+            .tie_break(TieBreak::LargestQuantityFirst)
+            .lots(3)
+            .build();
+        assert_eq!(winners(&auction, &bids), vec![(bids[1].id, 3)]);
+    }
+
+    #[test]
+    fn pro_rata_drops_short_all_or_nothing_bids() {
+        let all_or_nothing = bid![10, 4].with_all_or_nothing(true);
+        let bids: Bids = vec![all_or_nothing, bid![10, 4]];
+        let sales = pro_rata_auction(3).resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_ne!(sales[0].bidder_id, all_or_nothing.id);
+        assert_eq!(sales[0].quantity, 3);
+    }
+
+    #[test]
+    fn skipped_lots_pass_to_lower_levels() {
+        let bids: Bids = vec![bid![20, 3].with_all_or_nothing(true), bid![10, 2]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].amount, 10);
+        assert_eq!(sales[0].quantity, 2);
+    }
+
+    /// Two bidders with interleaved price levels, meeting at a contended
+    /// level where bidder b's bid sits between bidder a's.
+    fn interleaved_bids() -> (Uuid, Uuid, Bids) {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let bids = vec![
+            bid![30, 1].with_bidder_id(a).with_submitted_at(1),
+            bid![25, 1].with_bidder_id(b).with_submitted_at(2),
+            bid![20, 2].with_bidder_id(a).with_submitted_at(4),
+            bid![20, 2].with_bidder_id(b).with_submitted_at(5),
+            bid![20, 2].with_bidder_id(a).with_submitted_at(6),
+        ];
+        (a, b, bids)
+    }
+
+    fn filled_bids(auction: &Auction, bids: &[Bid]) -> Vec<(Option<Uuid>, usize)> {
+        auction
+            .resolve_bids_ref(bids)
+            .iter()
+            .map(|sale| (sale.bid_id, sale.quantity))
+            .collect()
+    }
+
+    fn aggregate_auction(aggregate: bool) -> AuctionBuilder {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(5)
+            .aggregate_bidders(aggregate)
+    }
+
+    #[test]
+    fn aggregated_bidder_fills_contended_level_together() {
+        let (_, _, bids) = interleaved_bids();
+        let separate = aggregate_auction(false).build();
+        assert_eq!(
+            filled_bids(&separate, &bids),
+            vec![
+                (Some(bids[0].id), 1),
+                (Some(bids[1].id), 1),
+                (Some(bids[2].id), 2),
+                (Some(bids[3].id), 1)
+            ]
+        );
+        let aggregated = aggregate_auction(true).build();
+        assert_eq!(
+            filled_bids(&aggregated, &bids),
+            vec![
+                (Some(bids[0].id), 1),
+                (Some(bids[1].id), 1),
+                (Some(bids[2].id), 2),
+                (Some(bids[4].id), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn aggregated_bidder_keeps_lot_cap_across_levels() {
+        let (a, _, bids) = interleaved_bids();
+        let auction = aggregate_auction(true).max_lots_per_bidder(2).build();
+        let sales = auction.resolve_bids(bids.clone());
+        let won_by_a: usize = sales
+            .iter()
+            .filter(|sale| sale.bidder_id == a)
+            .map(|sale| sale.quantity)
+            .sum();
+        assert_eq!(won_by_a, 2);
+        assert_eq!(
+            filled_bids(&auction, &bids),
+            vec![
+                (Some(bids[0].id), 1),
+                (Some(bids[1].id), 1),
+                (Some(bids[2].id), 1),
+                (Some(bids[3].id), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn aggregated_pro_rata_shares_by_bidder() {
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let bids: Bids = vec![
+            bid![10, 1].with_bidder_id(a),
+            bid![10, 1].with_bidder_id(a),
+            bid![10, 1].with_bidder_id(a),
+            bid![10, 3].with_bidder_id(b),
+        ];
+        let separate = pro_rata_auction(1).resolve_bids(bids.clone());
+        assert_eq!(separate.len(), 1);
+        assert_eq!(separate[0].bidder_id, b);
+
+        let aggregated = AuctionBuilder::new()
+            .lots(1)
+            .allocation(AllocationPolicy::ProRata)
+            .aggregate_bidders(true)
+            .build()
+            .resolve_bids(bids.clone());
+        assert_eq!(aggregated.len(), 1);
+        assert_eq!(aggregated[0].bid_id, Some(bids[0].id));
+    }
+}
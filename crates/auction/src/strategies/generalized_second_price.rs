@@ -0,0 +1,138 @@
+//! Module containing the generalized second price auction algorithm.
+use alloc::vec::Vec;
+
+use crate::{
+    strategies::fill::{rank_highest_first, retain_fillable},
+    Auction, AuctionObserver, Bid, RejectReason, Sale, Sales,
+};
+
+/// Resolves bids into sales using the generalized second price algorithm.
+///
+/// Each lot is a ranked slot and each bid wins at most one slot, regardless of
+/// its quantity. The i-th highest bid wins slot i and pays the next highest
+/// bid, the last winner pays the next bid at or above the reserve price, or
+/// the reserve price itself when there is none. Equal bids keep the order they
+/// were submitted in. Bids for no units take no slot.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// A list of sales ordered by slot, so the sale at index i won slot i.
+///
+pub fn generalized_second_price(auction: &Auction, bids: &[Bid]) -> Sales {
+    generalized_second_price_observed(auction, bids, &mut ())
+}
+
+/// Resolves bids as [`generalized_second_price`] does, telling the observer
+/// about each rejection and allocation.
+pub fn generalized_second_price_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let mut ranked: Vec<&Bid> = bids.iter().collect();
+    rank_highest_first(&mut ranked, auction.secondary_sort);
+    let ranked = retain_fillable(auction, ranked, observer);
+
+    let sales = ranked
+        .iter()
+        .take(auction.lots)
+        .enumerate()
+        .map(|(slot, bid)| {
+            let price = ranked
+                .get(slot + 1)
+                .map_or(auction.reserve_price, |next| next.amount);
+            observer.on_allocation(bid, 1, price);
+            Sale::new(bid.bidder_id, price, 1).with_bid(bid)
+        })
+        .collect();
+    for bid in ranked.iter().skip(auction.lots) {
+        observer.on_bid_rejected(bid, RejectReason::OutbidAtClearing);
+    }
+    sales
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn gsp_auction(lots: usize, reserve_price: i64) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::GeneralizedSecondPrice)
+            .lots(lots)
+            .reserve_price(reserve_price)
+            .build()
+    }
+
+    #[test]
+    fn gsp_returns_empty_for_no_bids() {
+        let sales = gsp_auction(3, 0).resolve_bids(vec![]);
+        assert!(sales.is_empty());
+    }
+
+    #[test]
+    fn gsp_winners_pay_next_bid() {
+        let bids: Bids = vec![bid![10, 1], bid![30, 1], bid![20, 1], bid![5, 1]];
+        let sales = gsp_auction(3, 0).resolve_bids(bids);
+        assert_eq!(sales.len(), 3);
+        assert_eq!(sales[0].amount, 20);
+        assert_eq!(sales[1].amount, 10);
+        assert_eq!(sales[2].amount, 5);
+    }
+
+    #[test]
+    fn gsp_slots_are_in_rank_order() {
+        let bids: Bids = vec![bid![10, 1], bid![30, 1], bid![20, 1]];
+        let ids = [bids[1].id, bids[2].id];
+        let sales = gsp_auction(2, 0).resolve_bids(bids);
+        assert_eq!(sales[0].bidder_id, ids[0]);
+        assert_eq!(sales[1].bidder_id, ids[1]);
+    }
+
+    #[test]
+    fn gsp_fewer_bids_than_lots_last_pays_reserve() {
+        let bids: Bids = vec![bid![10, 1], bid![30, 1]];
+        let sales = gsp_auction(5, 4).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 10);
+        assert_eq!(sales[1].amount, 4);
+    }
+
+    #[test]
+    fn gsp_each_bid_wins_a_single_slot() {
+        let bids: Bids = vec![bid![30, 3], bid![10, 1]];
+        let sales = gsp_auction(3, 0).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.quantity == 1));
+    }
+
+    #[test]
+    fn gsp_ties_pay_the_tied_amount() {
+        let bids: Bids = vec![bid![20, 1], bid![20, 1], bid![10, 1]];
+        let first = bids[0].id;
+        let sales = gsp_auction(2, 0).resolve_bids(bids);
+        assert_eq!(sales[0].bidder_id, first);
+        assert_eq!(sales[0].amount, 20);
+        assert_eq!(sales[1].amount, 10);
+    }
+
+    #[test]
+    fn gsp_ignores_bids_below_reserve() {
+        let bids: Bids = vec![bid![30, 1], bid![8, 1], bid![20, 1]];
+        let sales = gsp_auction(3, 15).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 20);
+        assert_eq!(sales[1].amount, 15);
+    }
+
+    #[test]
+    fn gsp_ignores_zero_quantity_bids() {
+        let bids: Bids = vec![bid![50, 0], bid![20, 1]];
+        let second = bids[1].id;
+        let sales = gsp_auction(2, 0).resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].bidder_id, second);
+    }
+}
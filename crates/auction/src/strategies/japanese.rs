@@ -0,0 +1,194 @@
+//! Module containing the Japanese (ascending clock) auction algorithm.
+use alloc::vec::Vec;
+
+use crate::{
+    strategies::fill::{fill_lots, rank_highest_first},
+    Auction, AuctionObserver, Bid, RejectReason, Sale, Sales,
+};
+
+/// Resolves bids into sales using an ascending price clock.
+///
+/// The clock starts at `start`, or the reserve price when it is lower, and
+/// rises by `increment` each tick. Each bid's amount is the most its bidder
+/// will pay: they stay in while the clock is at or below it and drop out as
+/// it rises past. The clock stops at the first price where the demand of the
+/// bidders still in fits within the lots, so it stops as soon as demand
+/// equals supply.
+///
+/// Every bidder still in wins their whole quantity at the stop price. When
+/// several bidders drop out at the same tick the lots they leave go unsold,
+/// rather than being shared between them as a sealed single price auction
+/// would.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+/// * `start` - The opening price of the clock, the reserve price when None.
+/// * `increment` - How far the clock rises each tick.
+///
+/// # Returns
+/// A list of sales for the bids.
+///
+pub fn japanese(auction: &Auction, bids: &[Bid], start: Option<i64>, increment: i64) -> Sales {
+    japanese_observed(auction, bids, start, increment, &mut ())
+}
+
+/// Resolves bids as [`japanese`] does, telling the observer about each
+/// rejection and allocation.
+pub fn japanese_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    start: Option<i64>,
+    increment: i64,
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let start = start.map_or(auction.reserve_price, |start| {
+        start.max(auction.reserve_price)
+    });
+    let (mut ranked, empty): (Vec<&Bid>, Vec<&Bid>) = bids.iter().partition(|bid| bid.quantity > 0);
+    for bid in empty {
+        observer.on_bid_rejected(bid, RejectReason::NoLotsRemaining);
+    }
+    rank_highest_first(&mut ranked, auction.secondary_sort);
+
+    let Some(stop_price) = stop_price(auction.lots, &ranked, start, increment) else {
+        for bid in ranked {
+            observer.on_bid_rejected(bid, RejectReason::OutbidAtClearing);
+        }
+        return Vec::new();
+    };
+    fill_lots(auction, &ranked, |bid| bid.amount >= stop_price, observer)
+        .iter()
+        .map(|bid| {
+            observer.on_allocation(bid, bid.quantity, stop_price);
+            Sale::new(bid.bidder_id, stop_price, bid.quantity).with_bid(bid)
+        })
+        .collect()
+}
+
+/// The first clock price at which the demand still in fits within the lots,
+/// None if it is beyond every amount a bid can hold.
+///
+/// Demand goes over the lots exactly while the clock is at or below the
+/// amount of the bid whose quantity, added to every higher bid's, first goes
+/// over them, so the clock stops at the first tick above that amount.
+fn stop_price(lots: usize, ranked: &[&Bid], start: i64, increment: i64) -> Option<i64> {
+    let mut demand: usize = 0;
+    let over_supply = ranked.iter().find(|bid| {
+        demand = demand.saturating_add(bid.quantity);
+        demand > lots
+    });
+    let Some(threshold) = over_supply.map(|bid| bid.amount) else {
+        return Some(start);
+    };
+    if threshold < start {
+        return Some(start);
+    }
+    let (start, increment) = (i128::from(start), i128::from(increment.max(1)));
+    let ticks = (i128::from(threshold) - start) / increment + 1;
+    i64::try_from(start + ticks * increment).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn japanese_auction(lots: usize, start: Option<i64>) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::Japanese {
+                start,
+                increment: 10,
+            })
+            .lots(lots)
+            .reserve_price(20)
+            .build()
+    }
+
+    #[test]
+    fn japanese_returns_empty_for_no_bids() {
+        let auction = japanese_auction(3, None);
+        assert!(auction.resolve_bids(vec![]).is_empty());
+    }
+
+    #[test]
+    fn japanese_demand_within_lots_sells_at_start() {
+        let bids: Bids = vec![bid![75, 1], bid![15, 1], bid![40, 1]];
+        let auction = japanese_auction(2, None);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bids[0].id, 20, 1).with_bid(&bids[0]),
+                Sale::new(bids[2].id, 20, 1).with_bid(&bids[2])
+            ]
+        );
+        let sales = japanese_auction(2, Some(35)).resolve_bids(bids);
+        assert_eq!(sales[0].amount, 35);
+        assert_eq!(sales.len(), 2);
+    }
+
+    #[test]
+    fn japanese_stops_when_demand_equals_supply() {
+        let bids: Bids = vec![bid![75, 1], bid![55, 1], bid![92, 1], bid![38, 1]];
+        let auction = japanese_auction(2, None);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bids[2].id, 60, 1).with_bid(&bids[2]),
+                Sale::new(bids[0].id, 60, 1).with_bid(&bids[0])
+            ]
+        );
+        let sealed = AuctionBuilder::new().lots(2).reserve_price(20).build();
+        assert_eq!(sealed.resolve_bids(bids)[0].amount, 75);
+    }
+
+    #[test]
+    fn japanese_bidder_at_clock_price_is_still_in() {
+        let bids: Bids = vec![bid![60, 1], bid![50, 1], bid![40, 1]];
+        let sales = japanese_auction(2, None).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 50);
+    }
+
+    #[test]
+    fn japanese_simultaneous_drop_outs_leave_lots_unsold() {
+        let bids: Bids = vec![bid![90, 1], bid![55, 1], bid![52, 1], bid![51, 1]];
+        let auction = japanese_auction(3, None);
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(sales, vec![Sale::new(bids[0].id, 60, 1).with_bid(&bids[0])]);
+        let sealed = AuctionBuilder::new().lots(3).reserve_price(20).build();
+        assert_eq!(sealed.resolve_bids(bids).len(), 3);
+    }
+
+    #[test]
+    fn japanese_whole_quantity_drop_out_leaves_lots_unsold() {
+        let bids: Bids = vec![bid![90, 1], bid![55, 3]];
+        let sales = japanese_auction(2, None).resolve_bids(bids.clone());
+        assert_eq!(sales, vec![Sale::new(bids[0].id, 60, 1).with_bid(&bids[0])]);
+    }
+
+    #[test]
+    fn japanese_clock_near_i64_max_does_not_overflow() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::Japanese {
+                start: None,
+                increment: i64::MAX,
+            })
+            .build();
+        let bids: Bids = vec![bid![i64::MAX, 1], bid![i64::MAX, 1]];
+        assert!(auction.resolve_bids(bids).is_empty());
+    }
+
+    #[test]
+    fn japanese_rejects_non_positive_increment() {
+        let error = AuctionBuilder::new()
+            .strategy(AuctionStrategy::Japanese {
+                start: None,
+                increment: 0,
+            })
+            .try_build()
+            .unwrap_err();
+        assert_eq!(error, AuctionConfigError::NonPositiveIncrement(0));
+    }
+}
@@ -0,0 +1,144 @@
+//! Module containing the lottery allocation algorithm.
+use alloc::vec::Vec;
+
+use crate::{
+    rng::BidRng,
+    strategies::fill::{reject_reason, LevelFiller},
+    Auction, AuctionObserver, Bid, LotteryWeighting, Sale, Sales,
+};
+
+/// Resolves bids into sales by drawing winners at random.
+///
+/// Every bid at or above the reserve price enters. Winners are drawn without
+/// replacement, each with an equal chance or a chance in proportion to its
+/// quantity, and filled from the lots remaining until none are left. Every
+/// winner pays the reserve price. The same seed always draws the same winners.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+/// * `seed` - The seed of the draw.
+///
+/// # Returns
+/// A list of sales in the order the winners were drawn.
+///
+pub fn lottery(auction: &Auction, bids: &[Bid], seed: u64) -> Sales {
+    lottery_observed(auction, bids, seed, &mut ())
+}
+
+/// Resolves bids as [`lottery`] does, telling the observer about each
+/// rejection and allocation as the draws are made.
+pub fn lottery_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    seed: u64,
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let (mut entrants, left_out): (Vec<&Bid>, Vec<&Bid>) = bids
+        .iter()
+        .partition(|bid| bid.quantity > 0 && auction.qualifies(bid.amount));
+    for bid in left_out {
+        observer.on_bid_rejected(bid, reject_reason(auction, bid.amount));
+    }
+    let weight = |bid: &Bid| match auction.lottery_weighting {
+        LotteryWeighting::Equal => 1,
+        LotteryWeighting::Quantity => bid.quantity as u64,
+    };
+    let mut total: u64 = entrants.iter().map(|bid| weight(bid)).sum();
+    let mut rng = BidRng::new(seed);
+    let mut filler = LevelFiller::new(auction);
+    let mut sales = Vec::new();
+    while !filler.is_full() && !entrants.is_empty() {
+        let mut ticket = rng.up_to(total - 1);
+        let mut drawn = entrants.len() - 1;
+        for (index, bid) in entrants.iter().enumerate() {
+            match ticket.checked_sub(weight(bid)) {
+                Some(rest) => ticket = rest,
+                None => {
+                    drawn = index;
+                    break;
+                }
+            }
+        }
+        let bid = entrants.swap_remove(drawn);
+        total -= weight(bid);
+        sales.extend(filler.fill_level(&[bid], observer).iter().map(|won| {
+            observer.on_allocation(won, won.quantity, auction.reserve_price);
+            Sale::new(won.bidder_id, auction.reserve_price, won.quantity).with_bid(won)
+        }));
+    }
+    filler.reject(&entrants, observer);
+    sales
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use crate::*;
+
+    fn lottery_auction(seed: u64, lots: usize, weighting: LotteryWeighting) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::Lottery { seed })
+            .lots(lots)
+            .reserve_price(10)
+            .lottery_weighting(weighting)
+            .build()
+    }
+
+    #[test]
+    fn lottery_is_deterministic_per_seed() {
+        let bids = generators::large_quantities(1, 50, 49, 4);
+        for weighting in [LotteryWeighting::Equal, LotteryWeighting::Quantity] {
+            let first = lottery_auction(7, 10, weighting).resolve_bids_ref(&bids);
+            let again = lottery_auction(7, 10, weighting).resolve_bids_ref(&bids);
+            assert_eq!(first, again);
+            let other = lottery_auction(8, 10, weighting).resolve_bids_ref(&bids);
+            assert_ne!(first, other);
+        }
+    }
+
+    #[test]
+    fn lottery_winners_pay_reserve_within_their_quantity() {
+        for seed in 0..30 {
+            let bids = generators::large_quantities(seed, 40, 49, 4);
+            let requested: HashMap<_, _> = bids.iter().map(|bid| (bid.id, bid)).collect();
+            let qualifying: usize = bids
+                .iter()
+                .filter(|bid| bid.amount >= 10)
+                .map(|bid| bid.quantity)
+                .sum();
+            for weighting in [LotteryWeighting::Equal, LotteryWeighting::Quantity] {
+                let lots = seed as usize * 3 + 1;
+                let sales = lottery_auction(seed, lots, weighting).resolve_bids_ref(&bids);
+                for sale in &sales {
+                    let bid = requested[&sale.bidder_id];
+                    assert!(bid.amount >= 10);
+                    assert!(sale.quantity <= bid.quantity);
+                    assert_eq!(sale.amount, 10);
+                }
+                assert_eq!(sales.total_quantity(), lots.min(qualifying));
+            }
+        }
+    }
+
+    #[test]
+    fn lottery_ignores_bids_below_reserve() {
+        let bids: Bids = vec![bid![5, 1], bid![50, 1]];
+        let sales = lottery_auction(3, 2, LotteryWeighting::Equal).resolve_bids(bids.clone());
+        assert_eq!(sales, vec![Sale::new(bids[1].id, 10, 1).with_bid(&bids[1])]);
+    }
+
+    #[test]
+    fn lottery_quantity_weighting_favours_large_bids() {
+        let bids: Bids = vec![bid![10, 1], bid![10, 99]];
+        let large_wins = (0..100)
+            .filter(|&seed| {
+                let sales =
+                    lottery_auction(seed, 1, LotteryWeighting::Quantity).resolve_bids_ref(&bids);
+                sales[0].bidder_id == bids[1].id
+            })
+            .count();
+        assert!(large_wins > 90);
+    }
+}
@@ -1,5 +1,159 @@
+//! The resolution algorithms behind [`AuctionStrategy`], callable directly.
+//!
+//! This is the lower-level API. Each function takes the [`Auction`] for its
+//! lots, reserve and policies, and resolves the bids exactly as given.
+//! [`Auction::resolve_bids`] does more around the strategy: it filters bids
+//! in another currency, applies the tick size, bid cap, dynamic reserve and
+//! buy-now price, records each sale's requested quantity and orders the
+//! sales. Call these to bypass the enum, such as from a custom [`Strategy`].
+//!
+//! ```
+//! use auction::{bid, strategies, AuctionBuilder};
+//!
+//! let auction = AuctionBuilder::new().lots(2).build();
+//! let sales = strategies::vcg(&auction, &[bid![30, 1], bid![20, 1], bid![10, 1]]);
+//! assert!(sales.iter().all(|sale| sale.amount() == 10));
+//! ```
+mod all_pay;
+mod buy_now;
+mod dutch;
+pub(crate) mod fill;
+mod generalized_second_price;
+mod japanese;
+mod lottery;
 mod multi_price;
+mod posted_price;
+mod proxy;
+mod scored;
 mod single_price;
+mod vcg;
 
+use core::fmt::Debug;
+
+use uuid::Uuid;
+
+use crate::{
+    collections::HashMap, observer, Auction, AuctionObserver, AuctionStrategy, Bid, Sale, Sales,
+};
+
+pub use all_pay::all_pay;
+pub use buy_now::buy_now;
+pub(crate) use buy_now::buy_now_observed;
+pub use dutch::dutch;
+pub use generalized_second_price::generalized_second_price;
+pub use japanese::japanese;
+pub use lottery::lottery;
 pub use multi_price::multi_price;
+pub(crate) use multi_price::{multi_price_iter, price_as_bid, price_at_own_amount};
+pub use posted_price::posted_price;
+pub use proxy::proxy;
+pub use scored::scored;
 pub use single_price::single_price;
+pub(crate) use single_price::{price_at_lowest_winner, price_uniformly};
+pub use vcg::vcg;
+
+/// Records the quantity each sale's bid asked for.
+///
+/// The strategies build sales from bids already trimmed to their fills, so
+/// the requested quantities are taken from the bids as they were resolved.
+pub(crate) fn record_requested(sales: &mut [Sale], bids: &[Bid]) {
+    if sales.iter().all(|sale| sale.bid_id.is_none()) {
+        return;
+    }
+    let requested: HashMap<Uuid, usize> = bids.iter().map(|bid| (bid.id, bid.quantity)).collect();
+    for sale in sales {
+        if let Some(&quantity) = sale.bid_id.and_then(|bid_id| requested.get(&bid_id)) {
+            sale.requested_quantity = Some(quantity);
+        }
+    }
+}
+
+/// A resolution algorithm turning bids into sales.
+pub trait Strategy: Debug + Send + Sync {
+    /// Resolves the bids into sales for the given auction.
+    fn resolve(&self, auction: &Auction, bids: &[Bid]) -> Sales;
+
+    /// Resolves the bids as [`Strategy::resolve`] does, telling the observer
+    /// about each bid rejected and each sale made.
+    ///
+    /// By default the bids are resolved first, then every bid without a sale
+    /// is rejected, as below the reserve or outbid, and every sale made from a
+    /// bid is allocated. Implement it to tell the observer more precisely, as
+    /// each decision is made.
+    fn resolve_observed(
+        &self,
+        auction: &Auction,
+        bids: &[Bid],
+        observer: &mut dyn AuctionObserver,
+    ) -> Sales {
+        let sales = self.resolve(auction, bids);
+        if observer.enabled() {
+            observer::observe_sales(auction, bids, &sales, observer);
+        }
+        sales
+    }
+}
+
+impl Strategy for AuctionStrategy {
+    fn resolve(&self, auction: &Auction, bids: &[Bid]) -> Sales {
+        match self {
+            Self::SinglePrice => single_price(auction, bids),
+            Self::MultiPrice => multi_price(auction, bids),
+            Self::Dutch {
+                start_price,
+                decrement,
+            } => dutch(auction, bids, *start_price, *decrement),
+            Self::AllPay => all_pay(auction, bids),
+            Self::GeneralizedSecondPrice => generalized_second_price(auction, bids),
+            Self::Proxy => proxy(auction, bids),
+            Self::Vcg => vcg(auction, bids),
+            Self::Lottery { seed } => lottery(auction, bids, *seed),
+            Self::Scored => scored(auction, bids),
+            Self::Japanese { start, increment } => japanese(auction, bids, *start, *increment),
+            Self::PostedPrice => posted_price(auction, bids),
+            Self::Custom(strategy) => strategy.resolve(auction, bids),
+        }
+    }
+
+    fn resolve_observed(
+        &self,
+        auction: &Auction,
+        bids: &[Bid],
+        mut observer: &mut dyn AuctionObserver,
+    ) -> Sales {
+        self.resolve_observed_by(auction, bids, &mut observer)
+    }
+}
+
+impl AuctionStrategy {
+    /// Resolves the bids as [`Strategy::resolve`] does, with the strategy
+    /// calling the observer's hooks as it decides.
+    pub(crate) fn resolve_observed_by(
+        &self,
+        auction: &Auction,
+        bids: &[Bid],
+        observer: &mut impl AuctionObserver,
+    ) -> Sales {
+        match self {
+            Self::SinglePrice => single_price::single_price_observed(auction, bids, observer),
+            Self::MultiPrice => multi_price::multi_price_observed(auction, bids, observer),
+            Self::Dutch {
+                start_price,
+                decrement,
+            } => dutch::dutch_observed(auction, bids, *start_price, *decrement, observer),
+            Self::AllPay => all_pay::all_pay_observed(auction, bids, observer),
+            Self::GeneralizedSecondPrice => {
+                generalized_second_price::generalized_second_price_observed(auction, bids, observer)
+            }
+            Self::Proxy => proxy::proxy_observed(auction, bids, observer),
+            Self::Vcg => vcg::vcg_observed(auction, bids, observer),
+            Self::Lottery { seed } => lottery::lottery_observed(auction, bids, *seed, observer),
+            Self::Scored => scored::scored_observed(auction, bids, observer),
+            Self::Japanese { start, increment } => {
+                japanese::japanese_observed(auction, bids, *start, *increment, observer)
+            }
+            Self::PostedPrice => posted_price::posted_price_observed(auction, bids, observer),
+            Self::Custom(strategy) => strategy.resolve_observed(auction, bids, observer),
+        }
+    }
+}
@@ -1,8 +1,18 @@
 //! Module containing the multi price auction algorithm.
-use crate::{Auction, Bid, Bids, Sale, Sales};
+use alloc::vec::Vec;
+
+use uuid::Uuid;
+
+use crate::{
+    collections::{HashMap, HashSet},
+    strategies::fill::{fill_lots, rank_tiered, LevelFiller},
+    Auction, AuctionDirection, AuctionObserver, Bid, Bids, MarginalPricing, Sale, Sales,
+};
 
 /// Resolves bids into sales using the multi price algorithm.
 ///
+/// Bids are ranked by priority tier before amount.
+///
 /// # Arguments
 /// * `auction` - The auction to resolve bids for.
 /// * `bids` - The bids to resolve.
@@ -10,37 +20,189 @@ use crate::{Auction, Bid, Bids, Sale, Sales};
 /// # Returns
 /// A list of sales for the bids.
 ///
-pub fn multi_price(auction: &Auction, mut bids: Bids) -> Sales {
-    bids.sort_by(|a, b| b.cmp(a));
-
-    let mut remaining_lots = auction.lots;
-    let mut winning_bids = Vec::new();
-    for bid in bids.iter() {
-        if bid.amount < auction.reserve_price {
-            break;
+pub fn multi_price(auction: &Auction, bids: &[Bid]) -> Sales {
+    multi_price_observed(auction, bids, &mut ())
+}
+
+/// Resolves bids as [`multi_price`] does, telling the observer about each
+/// rejection and allocation.
+pub fn multi_price_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let mut ranked: Vec<&Bid> = bids.iter().collect();
+    rank_tiered(auction, &mut ranked);
+
+    let winning_bids = fill_lots(
+        auction,
+        &ranked,
+        |bid| auction.qualifies(bid.amount),
+        observer,
+    );
+
+    price_as_bid(auction, bids, &winning_bids, observer)
+}
+
+/// Charges every winning bid its own amount, except the marginal bids when the
+/// auction charges them the clearing price instead, telling the observer
+/// about each sale.
+pub fn price_as_bid(
+    auction: &Auction,
+    bids: &[Bid],
+    winning_bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let mut sales = price_at_own_amount(winning_bids);
+    if auction.marginal_pricing == MarginalPricing::ClearingPrice {
+        let marginal = marginal_bids(auction, bids, winning_bids);
+        if let Some(&first) = marginal.first() {
+            let price = clearing_price(auction, bids, winning_bids, winning_bids[first].amount);
+            for index in marginal {
+                sales[index] = Sale {
+                    amount: price,
+                    ..sales[index]
+                };
+            }
         }
-        if bid.quantity <= remaining_lots {
-            remaining_lots -= bid.quantity;
-            winning_bids.push(*bid);
-        } else if remaining_lots > 0 {
-            let new_bid = Bid::new(bid.amount, remaining_lots);
-            winning_bids.push(new_bid);
-            remaining_lots = 0;
-        } else {
-            break;
+    }
+    for (bid, sale) in winning_bids.iter().zip(&sales) {
+        observer.on_allocation(bid, sale.quantity, sale.amount);
+    }
+    sales
+}
+
+/// The positions of the marginal bids: the winning bids at the last price
+/// level filled that were cut short because every lot was sold.
+///
+/// Bids trimmed while lots remain, by a budget or lot cap, are not marginal.
+fn marginal_bids(auction: &Auction, bids: &[Bid], winning_bids: &[Bid]) -> Vec<usize> {
+    let sold: usize = winning_bids.iter().map(|bid| bid.quantity).sum();
+    let Some(last) = winning_bids.last() else {
+        return Vec::new();
+    };
+    if sold < auction.lots {
+        return Vec::new();
+    }
+    let requested: HashMap<Uuid, usize> = bids.iter().map(|bid| (bid.id, bid.quantity)).collect();
+    winning_bids
+        .iter()
+        .enumerate()
+        .filter(|(_, bid)| {
+            bid.amount == last.amount
+                && bid.priority == last.priority
+                && bid.quantity < requested[&bid.id]
+        })
+        .map(|(index, _)| index)
+        .collect()
+}
+
+/// The lowest price at which the marginal bids still win their fill: the best
+/// qualifying bid that won nothing, or the reserve price of the last lot sold
+/// when there is none, but never worse for the bidder than their own amount.
+///
+/// Reverse auctions use the lowest offer that won nothing, or the price
+/// ceiling, and the marginal amount without either.
+fn clearing_price(auction: &Auction, bids: &[Bid], winning_bids: &[Bid], marginal: i64) -> i64 {
+    let won: HashSet<Uuid> = winning_bids.iter().map(|bid| bid.id).collect();
+    let rejected = bids
+        .iter()
+        .filter(|bid| bid.quantity > 0 && auction.qualifies(bid.amount) && !won.contains(&bid.id))
+        .map(|bid| bid.amount);
+    match auction.direction {
+        AuctionDirection::Forward => {
+            let sold: usize = winning_bids.iter().map(|bid| bid.quantity).sum();
+            let reserve_price = auction.reserve_at(sold.saturating_sub(1));
+            rejected
+                .max()
+                .unwrap_or(reserve_price)
+                .max(reserve_price)
+                .min(marginal)
         }
+        AuctionDirection::Reverse => rejected
+            .min()
+            .or(auction.price_ceiling)
+            .unwrap_or(marginal)
+            .max(marginal),
     }
+}
 
+/// Charges every winning bid its own amount.
+pub fn price_at_own_amount(winning_bids: &[Bid]) -> Sales {
     winning_bids
         .iter()
-        .map(|bid| Sale::new(bid.id, bid.amount, bid.quantity))
+        .map(|bid| Sale::new(bid.bidder_id, bid.amount, bid.quantity).with_bid(bid))
         .collect()
 }
 
+/// Lazily resolves bids into sales using the multi price algorithm.
+///
+/// The bids are ranked up front, then each price level is filled only when
+/// the sales before it have been taken, so the sales are never all held at
+/// once.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// An iterator over the sales for the bids, in the order `multi_price` returns them.
+///
+pub fn multi_price_iter(auction: &Auction, mut bids: Bids) -> impl Iterator<Item = Sale> + '_ {
+    rank_tiered(auction, &mut bids);
+    let mut filler = LevelFiller::new(auction);
+    let mut start = 0;
+    core::iter::from_fn(move || {
+        let (amount, priority) = bids.get(start).map(|bid| (bid.amount, bid.priority))?;
+        if filler.is_full() || !auction.qualifies(amount) {
+            return None;
+        }
+        let end = start
+            + bids[start..].partition_point(|bid| bid.amount == amount && bid.priority == priority);
+        let filled = filler.fill_level(&bids[start..end], &mut ());
+        start = end;
+        Some(filled)
+    })
+    .flatten()
+    .map(|bid| Sale::new(bid.bidder_id, bid.amount, bid.quantity).with_bid(&bid))
+}
+
 #[cfg(test)]
 mod test {
     use crate::*;
 
+    #[test]
+    fn multi_price_reserve_schedule_steps_up() {
+        let bids: Bids = vec![bid![150, 1], bid![110, 2], bid![125, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(4)
+            .reserve_schedule(vec![(2, 100), (2, 120)])
+            .build();
+        let sales = auction.resolve_bids(bids);
+        let filled: Vec<_> = sales
+            .iter()
+            .map(|sale| (sale.amount, sale.quantity))
+            .collect();
+        assert_eq!(filled, vec![(150, 1), (125, 1)]);
+    }
+
+    #[test]
+    fn multi_price_reserve_schedule_trims_marginal_bid() {
+        let bids: Bids = vec![bid![150, 1], bid![110, 2]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(4)
+            .reserve_schedule(vec![(2, 100), (2, 120)])
+            .build();
+        let sales = auction.resolve_bids(bids);
+        let filled: Vec<_> = sales
+            .iter()
+            .map(|sale| (sale.amount, sale.quantity))
+            .collect();
+        assert_eq!(filled, vec![(150, 1), (110, 1)]);
+    }
+
     #[test]
     fn multi_price_returns_empty_for_no_bids() {
         let bids: Bids = vec![];
@@ -119,4 +281,226 @@ mod test {
         assert_eq!(sales.len(), 1);
         assert_eq!(sales[0].amount, 55);
     }
+
+    #[test]
+    fn multi_price_partial_fill_keeps_bidder_id() {
+        let bids: Bids = vec![bid![10, 2], bid![20, 1]];
+        let partial_id = bids[0].id;
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales[1].quantity, 1);
+        assert_eq!(sales[1].bidder_id, partial_id);
+    }
+
+    #[test]
+    fn multi_price_sales_reference_input_bids() {
+        let bids: Bids = vec![bid![10, 3], bid![20, 2], bid![15, 4], bid![30, 1]];
+        let ids: Vec<_> = bids.iter().map(|bid| bid.id).collect();
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(5)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert!(!sales.is_empty());
+        assert!(sales.iter().all(|sale| ids.contains(&sale.bidder_id)));
+    }
+
+    fn reverse_auction(lots: usize) -> AuctionBuilder {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .direction(AuctionDirection::Reverse)
+            .lots(lots)
+    }
+
+    #[test]
+    fn multi_price_reverse_buys_cheapest_offers() {
+        let bids: Bids = vec![bid![25, 1], bid![10, 1], bid![40, 1]];
+        let sales = reverse_auction(2).build().resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 10);
+        assert_eq!(sales[1].amount, 25);
+    }
+
+    #[test]
+    fn multi_price_reverse_price_ceiling_applied() {
+        let bids: Bids = vec![bid![25, 1], bid![10, 1], bid![40, 1]];
+        let sales = reverse_auction(3)
+            .price_ceiling(30)
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.amount <= 30));
+    }
+
+    #[test]
+    fn multi_price_reverse_ignores_reserve_price() {
+        let bids: Bids = vec![bid![25, 1], bid![10, 1]];
+        let sales = reverse_auction(2)
+            .reserve_price(50)
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+    }
+
+    #[test]
+    fn multi_price_reverse_partially_fulfilled() {
+        let bids: Bids = vec![bid![15, 3], bid![10, 1]];
+        let sales = reverse_auction(2).build().resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 10);
+        assert_eq!(sales[1].amount, 15);
+        assert_eq!(sales[0].quantity, 1);
+        assert_eq!(sales[1].quantity, 1);
+    }
+
+    #[test]
+    fn multi_price_all_or_nothing_bid_skipped_for_smaller_bid() {
+        let all_or_nothing = bid![25, 3].with_all_or_nothing(true);
+        let bids: Bids = vec![bid![30, 1], all_or_nothing, bid![10, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.bidder_id != all_or_nothing.id));
+        assert_eq!(sales[0].amount, 30);
+        assert_eq!(sales[1].amount, 10);
+    }
+
+    #[test]
+    fn multi_price_all_or_nothing_bid_filled_when_it_fits() {
+        let all_or_nothing = bid![25, 2].with_all_or_nothing(true);
+        let bids: Bids = vec![bid![30, 1], all_or_nothing, bid![10, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[1].bidder_id, all_or_nothing.id);
+        assert_eq!(sales[1].quantity, 2);
+    }
+
+    #[test]
+    fn multi_price_all_or_nothing_can_leave_lots_unsold() {
+        let bids: Bids = vec![bid![30, 1], bid![25, 3].with_all_or_nothing(true)];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+        let result = auction.resolve(bids).unwrap();
+        assert_eq!(result.lots_sold(), 1);
+        assert_eq!(result.lots_unsold(), 1);
+    }
+
+    #[test]
+    fn multi_price_min_quantity_met_exactly_partially_fills() {
+        let minimum = bid![20, 5].with_min_quantity(2).unwrap();
+        let bids: Bids = vec![bid![30, 1], minimum, bid![10, 2]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[1].bidder_id, minimum.id);
+        assert_eq!(sales[1].quantity, 2);
+    }
+
+    #[test]
+    fn multi_price_min_quantity_not_met_skips_bid() {
+        let minimum = bid![20, 5].with_min_quantity(3).unwrap();
+        let bids: Bids = vec![bid![30, 1], minimum, bid![10, 2]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.bidder_id != minimum.id));
+        assert_eq!(sales[1].amount, 10);
+        assert_eq!(sales[1].quantity, 2);
+    }
+
+    fn marginal_auction(lots: usize) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(lots)
+            .reserve_price(5)
+            .marginal_pricing(MarginalPricing::ClearingPrice)
+            .build()
+    }
+
+    fn filled(sales: &Sales) -> Vec<(i64, usize)> {
+        sales
+            .iter()
+            .map(|sale| (sale.amount, sale.quantity))
+            .collect()
+    }
+
+    #[test]
+    fn multi_price_exactly_filled_marginal_pays_own_amount() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 2], bid![10, 1]];
+        let sales = marginal_auction(3).resolve_bids(bids);
+        assert_eq!(filled(&sales), vec![(30, 1), (20, 2)]);
+    }
+
+    #[test]
+    fn multi_price_partially_filled_marginal_pays_clearing_price() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 3], bid![10, 1]];
+        let sales = marginal_auction(3).resolve_bids(bids.clone());
+        assert_eq!(filled(&sales), vec![(30, 1), (10, 2)]);
+        let own = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(filled(&own), vec![(30, 1), (20, 2)]);
+    }
+
+    #[test]
+    fn multi_price_marginal_clearing_price_falls_back_to_reserve() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 3], bid![3, 1]];
+        let sales = marginal_auction(3).resolve_bids(bids);
+        assert_eq!(filled(&sales), vec![(30, 1), (5, 2)]);
+    }
+
+    #[test]
+    fn multi_price_capped_bid_is_not_marginal() {
+        let bidder = uuid::Uuid::new_v4();
+        let bids: Bids = vec![bid![30, 3].with_bidder_id(bidder), bid![20, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(5)
+            .max_lots_per_bidder(2)
+            .marginal_pricing(MarginalPricing::ClearingPrice)
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(filled(&sales), vec![(30, 2), (20, 1)]);
+    }
+
+    #[test]
+    fn multi_price_ranks_tiers_before_amount() {
+        let bids: Bids = vec![bid![150, 2], bid![100, 1].with_priority(1), bid![90, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(2)
+            .build();
+        let sales = auction.resolve_bids(bids.clone());
+        assert_eq!(filled(&sales), vec![(100, 1), (150, 1)]);
+        let streamed: Sales = auction.resolve_iter(bids).collect();
+        assert_eq!(streamed, sales);
+    }
+
+    #[test]
+    fn multi_price_marginal_pricing_streams_the_same() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 3], bid![10, 1]];
+        let auction = marginal_auction(3);
+        let streamed: Sales = auction.resolve_iter(bids.clone()).collect();
+        assert_eq!(streamed, auction.resolve_bids(bids));
+    }
 }
@@ -0,0 +1,121 @@
+//! Module containing the posted price sale.
+use alloc::vec::Vec;
+
+use crate::{
+    strategies::fill::LevelFiller, Auction, AuctionObserver, Bid, RejectReason, Sale, Sales,
+};
+
+/// Resolves bids into sales at a fixed price, first come first served.
+///
+/// The reserve price is the posted price. Bids at or above it are filled in
+/// submission order, earliest first with untimestamped bids after in input
+/// order, until the lots run out. Every sale is at exactly the posted price,
+/// whatever the bid offered. The bid that reaches the last lot is partially
+/// filled unless it refuses to be.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// A list of sales in the order the bids were filled.
+///
+pub fn posted_price(auction: &Auction, bids: &[Bid]) -> Sales {
+    posted_price_observed(auction, bids, &mut ())
+}
+
+/// Resolves bids as [`posted_price`] does, telling the observer about each
+/// rejection and allocation in the order the bids are served.
+pub fn posted_price_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let (qualifying, below): (Vec<&Bid>, Vec<&Bid>) =
+        bids.iter().partition(|bid| auction.qualifies(bid.amount));
+    for bid in below {
+        observer.on_bid_rejected(bid, RejectReason::BelowReserve);
+    }
+    let mut queue: Vec<Bid> = qualifying
+        .into_iter()
+        .map(|bid| bid.with_amount(auction.reserve_price))
+        .collect();
+    queue.sort_by(Bid::cmp_submitted);
+
+    let mut filler = LevelFiller::new(auction);
+    let mut sales = Vec::new();
+    for (served, bid) in queue.iter().enumerate() {
+        if filler.is_full() {
+            filler.reject(&queue[served..], observer);
+            break;
+        }
+        sales.extend(
+            filler
+                .fill_level(core::slice::from_ref(bid), observer)
+                .iter()
+                .map(|won| {
+                    observer.on_allocation(won, won.quantity, won.amount);
+                    Sale::new(won.bidder_id, won.amount, won.quantity).with_bid(won)
+                }),
+        );
+    }
+    sales
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn posted_auction(lots: usize) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::PostedPrice)
+            .lots(lots)
+            .reserve_price(25)
+            .build()
+    }
+
+    #[test]
+    fn posted_price_charges_posted_price_not_amount() {
+        let bids: Bids = vec![bid![25, 1], bid![90, 2]];
+        let sales = posted_auction(5).resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bids[0].id, 25, 1).with_bid(&bids[0]),
+                Sale::new(bids[1].id, 25, 2).with_bid(&bids[1])
+            ]
+        );
+    }
+
+    #[test]
+    fn posted_price_oversubscribed_fills_earliest_first() {
+        let late = bid![100, 2].with_submitted_at(30);
+        let early = bid![30, 2].with_submitted_at(10);
+        let middle = bid![25, 2].with_submitted_at(20);
+        let untimed = bid![50, 1];
+        let sales = posted_auction(3).resolve_bids(vec![untimed, late, early, middle]);
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(early.id, 25, 2).with_bid(&early),
+                Sale::new(middle.id, 25, 1).with_bid(&middle)
+            ]
+        );
+    }
+
+    #[test]
+    fn posted_price_skips_all_or_nothing_bid_it_cannot_fill() {
+        let whole = bid![30, 3].with_all_or_nothing(true);
+        let next = bid![30, 1];
+        let sales = posted_auction(2).resolve_bids(vec![bid![40, 1], whole, next]);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[1], Sale::new(next.id, 25, 1).with_bid(&next));
+    }
+
+    #[test]
+    fn posted_price_ignores_bid_below_posted_price() {
+        let bids: Bids = vec![bid![24, 1], bid![26, 1]];
+        let sales = posted_auction(2).resolve_bids(bids.clone());
+        assert_eq!(sales, vec![Sale::new(bids[1].id, 25, 1).with_bid(&bids[1])]);
+    }
+}
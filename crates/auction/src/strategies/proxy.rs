@@ -0,0 +1,142 @@
+//! Module containing the proxy bidding auction algorithm.
+use alloc::{vec, vec::Vec};
+
+use crate::{
+    strategies::fill::{rank_highest_first, retain_fillable},
+    Auction, AuctionObserver, Bid, RejectReason, Sale, Sales,
+};
+
+/// Resolves bids into a sale of a single lot using proxy bidding.
+///
+/// A proxy bid's amount is the most its bidder will pay, and the engine bids
+/// on their behalf only as far as needed to beat the next highest bid by the
+/// auction's bid increment, never beyond that maximum. A lone proxy bid pays
+/// the reserve price. Fixed bids pay their own amount when they win. Equal
+/// maximums are won by the earliest bid.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// The sale of the lot, empty if no bid meets the reserve price.
+///
+pub fn proxy(auction: &Auction, bids: &[Bid]) -> Sales {
+    proxy_observed(auction, bids, &mut ())
+}
+
+/// Resolves bids as [`proxy`] does, telling the observer about each
+/// rejection and the sale.
+pub fn proxy_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let mut ranked: Vec<&Bid> = bids.iter().collect();
+    rank_highest_first(&mut ranked, auction.secondary_sort);
+    let ranked = retain_fillable(auction, ranked, observer);
+
+    let Some((winner, outbid)) = ranked.split_first().filter(|_| auction.lots > 0) else {
+        for bid in ranked {
+            observer.on_bid_rejected(bid, RejectReason::NoLotsRemaining);
+        }
+        return Vec::new();
+    };
+    for bid in outbid {
+        observer.on_bid_rejected(bid, RejectReason::OutbidAtClearing);
+    }
+    let price = if winner.proxy {
+        let competing = ranked.get(1).map_or(auction.reserve_price, |next| {
+            next.amount.saturating_add(auction.bid_increment)
+        });
+        competing.max(auction.reserve_price).min(winner.amount)
+    } else {
+        winner.amount
+    };
+    observer.on_allocation(winner, 1, price);
+    vec![Sale::new(winner.bidder_id, price, 1).with_bid(winner)]
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn proxy_auction(reserve_price: i64) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::Proxy)
+            .reserve_price(reserve_price)
+            .bid_increment(5)
+            .build()
+    }
+
+    #[test]
+    fn proxy_returns_empty_for_no_bids() {
+        assert!(proxy_auction(0).resolve_bids(vec![]).is_empty());
+    }
+
+    #[test]
+    fn proxy_winner_pays_second_plus_increment() {
+        let winner = bid![100, 1].with_proxy(true);
+        let bids: Bids = vec![bid![60, 1].with_proxy(true), winner];
+        let sales = proxy_auction(0).resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].bidder_id, winner.id);
+        assert_eq!(sales[0].amount, 65);
+        assert_eq!(sales[0].quantity, 1);
+    }
+
+    #[test]
+    fn proxy_price_capped_at_maximum() {
+        let bids: Bids = vec![bid![98, 1], bid![100, 1].with_proxy(true)];
+        let sales = proxy_auction(0).resolve_bids(bids);
+        assert_eq!(sales[0].amount, 100);
+    }
+
+    #[test]
+    fn single_proxy_bid_pays_reserve() {
+        let bids: Bids = vec![bid![100, 1].with_proxy(true)];
+        let sales = proxy_auction(40).resolve_bids(bids);
+        assert_eq!(sales[0].amount, 40);
+    }
+
+    #[test]
+    fn proxy_competing_bid_below_reserve_ignored() {
+        let bids: Bids = vec![bid![100, 1].with_proxy(true), bid![20, 1]];
+        let sales = proxy_auction(40).resolve_bids(bids);
+        assert_eq!(sales[0].amount, 40);
+    }
+
+    #[test]
+    fn proxy_tied_maximums_earliest_wins_at_maximum() {
+        let early = bid![80, 1].with_proxy(true).with_submitted_at(1);
+        let late = bid![80, 1].with_proxy(true).with_submitted_at(2);
+        let sales = proxy_auction(0).resolve_bids(vec![late, early]);
+        assert_eq!(sales[0].bidder_id, early.id);
+        assert_eq!(sales[0].amount, 80);
+    }
+
+    #[test]
+    fn fixed_bid_winner_pays_own_amount() {
+        let fixed = bid![90, 1];
+        let bids: Bids = vec![bid![70, 1].with_proxy(true), fixed];
+        let sales = proxy_auction(0).resolve_bids(bids);
+        assert_eq!(sales[0].bidder_id, fixed.id);
+        assert_eq!(sales[0].amount, 90);
+    }
+
+    #[test]
+    fn proxy_beats_fixed_bid_by_increment() {
+        let bids: Bids = vec![bid![50, 1], bid![80, 1].with_proxy(true)];
+        let sales = proxy_auction(0).resolve_bids(bids);
+        assert_eq!(sales[0].amount, 55);
+    }
+
+    #[test]
+    fn proxy_ignores_zero_quantity_bids() {
+        let bids: Bids = vec![bid![50, 0], bid![20, 1]];
+        let second = bids[1].id;
+        let sales = proxy_auction(0).resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].bidder_id, second);
+    }
+}
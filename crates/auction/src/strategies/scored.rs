@@ -0,0 +1,241 @@
+//! Module containing the scored auction algorithm.
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+use uuid::Uuid;
+
+use crate::{
+    collections::HashSet,
+    strategies::fill::{rank_bids, LevelFiller},
+    tick, Auction, AuctionDirection, AuctionObserver, Bid, RejectReason, Sale, Sales,
+    ScoredPricing,
+};
+
+/// Resolves bids into sales ranked by their score rather than their amount.
+///
+/// A bid's score is its amount multiplied by its score weight in a forward
+/// auction and divided by it in a reverse auction, so a heavier weight always
+/// helps. Bids of equal score are filled as equal bids of multi price are.
+/// With every weight at [`crate::SCORE_WEIGHT_UNIT`] the sales are those of
+/// multi price.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// A list of sales for the bids, best score first.
+///
+pub fn scored(auction: &Auction, bids: &[Bid]) -> Sales {
+    scored_observed(auction, bids, &mut ())
+}
+
+/// Resolves bids as [`scored`] does, telling the observer about each
+/// rejection and allocation.
+pub fn scored_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let (mut ranked, below): (Vec<&Bid>, Vec<&Bid>) =
+        bids.iter().partition(|bid| auction.qualifies(bid.amount));
+    for bid in below {
+        observer.on_bid_rejected(bid, RejectReason::BelowReserve);
+    }
+    rank_bids(auction, &mut ranked);
+    ranked.sort_by(|a, b| cmp_score(auction, b, a));
+
+    let mut filler = LevelFiller::new(auction);
+    let mut winning_bids = Vec::new();
+    for level in ranked.chunk_by(|a, b| cmp_score(auction, a, b) == Ordering::Equal) {
+        if !filler.is_full() {
+            winning_bids.extend(filler.fill_level(level, observer));
+        } else if observer.enabled() {
+            filler.reject(level, observer);
+        } else {
+            break;
+        }
+    }
+
+    let runner_up = match auction.scored_pricing {
+        ScoredPricing::OwnAmount => None,
+        ScoredPricing::SecondScore => {
+            let won: HashSet<Uuid> = winning_bids.iter().map(|bid| bid.id).collect();
+            ranked
+                .iter()
+                .find(|bid| bid.quantity > 0 && !won.contains(&bid.id))
+                .copied()
+        }
+    };
+    winning_bids
+        .iter()
+        .map(|bid| {
+            let price = match auction.scored_pricing {
+                ScoredPricing::OwnAmount => bid.amount,
+                ScoredPricing::SecondScore => second_score_price(auction, bid, runner_up),
+            };
+            observer.on_allocation(bid, bid.quantity, price);
+            Sale::new(bid.bidder_id, price, bid.quantity).with_bid(bid)
+        })
+        .collect()
+}
+
+/// Orders two bids by score, the better scoring bid greater.
+fn cmp_score(auction: &Auction, a: &Bid, b: &Bid) -> Ordering {
+    let (a_amount, a_weight) = (i128::from(a.amount), i128::from(a.score_weight));
+    let (b_amount, b_weight) = (i128::from(b.amount), i128::from(b.score_weight));
+    match auction.direction {
+        AuctionDirection::Forward => (a_amount * a_weight).cmp(&(b_amount * b_weight)),
+        AuctionDirection::Reverse => (b_amount * a_weight).cmp(&(a_amount * b_weight)),
+    }
+}
+
+/// The amount that ties the winner's score with the runner up's, rounded by
+/// the auction's rounding policy and never worse for the winner than their
+/// own amount.
+///
+/// Without a runner up forward auctions charge the reserve price and reverse
+/// auctions the price ceiling, or the winner's own amount without one.
+fn second_score_price(auction: &Auction, winner: &Bid, runner_up: Option<&Bid>) -> i64 {
+    let weight = i128::from(winner.score_weight);
+    match (auction.direction, runner_up) {
+        (AuctionDirection::Forward, Some(runner_up)) => {
+            let score = i128::from(runner_up.amount) * i128::from(runner_up.score_weight);
+            let price = tick::round_price(auction, score, weight).unwrap_or(i64::MAX);
+            price.clamp(
+                auction.reserve_price,
+                winner.amount.max(auction.reserve_price),
+            )
+        }
+        (AuctionDirection::Forward, None) => auction.reserve_price.min(winner.amount),
+        (AuctionDirection::Reverse, Some(runner_up)) => {
+            let score = i128::from(runner_up.amount) * weight;
+            let price = tick::round_price(auction, score, i128::from(runner_up.score_weight))
+                .unwrap_or(i64::MAX)
+                .max(winner.amount);
+            auction
+                .price_ceiling
+                .map_or(price, |ceiling| price.min(ceiling))
+        }
+        (AuctionDirection::Reverse, None) => auction.price_ceiling.unwrap_or(winner.amount),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn scored_auction(lots: usize) -> AuctionBuilder {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::Scored)
+            .lots(lots)
+    }
+
+    #[test]
+    fn scored_default_weights_match_multi_price() {
+        for seed in 0..20 {
+            let bids = generators::large_quantities(seed, 60, 49, 4);
+            for direction in [AuctionDirection::Forward, AuctionDirection::Reverse] {
+                let multi_price = AuctionBuilder::new()
+                    .strategy(AuctionStrategy::MultiPrice)
+                    .direction(direction)
+                    .lots(15)
+                    .reserve_price(10)
+                    .build();
+                let scored = scored_auction(15)
+                    .direction(direction)
+                    .reserve_price(10)
+                    .build();
+                assert_eq!(
+                    scored.resolve_bids_ref(&bids),
+                    multi_price.resolve_bids_ref(&bids)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn scored_reverse_prefers_quality_over_price() {
+        let auction = scored_auction(1)
+            .direction(AuctionDirection::Reverse)
+            .build();
+        let cheap = bid![100, 1];
+        let quality = bid![120, 1].with_score_weight(15_000);
+        let sales = auction.resolve_bids(vec![cheap, quality]);
+        assert_eq!(
+            sales,
+            vec![Sale::new(quality.id, 120, 1).with_bid(&quality)]
+        );
+    }
+
+    #[test]
+    fn scored_forward_prefers_quality_over_price() {
+        let auction = scored_auction(1).build();
+        let rich = bid![100, 1];
+        let quality = bid![80, 1].with_score_weight(15_000);
+        let sales = auction.resolve_bids(vec![rich, quality]);
+        assert_eq!(sales, vec![Sale::new(quality.id, 80, 1).with_bid(&quality)]);
+    }
+
+    #[test]
+    fn scored_second_score_ties_winner_with_runner_up() {
+        let reverse = scored_auction(1)
+            .direction(AuctionDirection::Reverse)
+            .scored_pricing(ScoredPricing::SecondScore)
+            .build();
+        let quality = bid![120, 1].with_score_weight(15_000);
+        let sales = reverse.resolve_bids(vec![bid![100, 1], quality]);
+        assert_eq!(
+            sales,
+            vec![Sale::new(quality.id, 150, 1).with_bid(&quality)]
+        );
+
+        let forward = scored_auction(1)
+            .scored_pricing(ScoredPricing::SecondScore)
+            .reserve_price(10)
+            .build();
+        let quality = bid![80, 1].with_score_weight(15_000);
+        let sales = forward.resolve_bids(vec![bid![100, 1], quality]);
+        assert_eq!(sales, vec![Sale::new(quality.id, 66, 1).with_bid(&quality)]);
+        let sales = forward.resolve_bids(vec![quality]);
+        assert_eq!(sales, vec![Sale::new(quality.id, 10, 1).with_bid(&quality)]);
+    }
+
+    #[test]
+    fn scored_second_score_follows_rounding_policy() {
+        let price = |policy| {
+            let auction = scored_auction(1)
+                .scored_pricing(ScoredPricing::SecondScore)
+                .rounding_policy(policy)
+                .build();
+            let quality = bid![80, 1].with_score_weight(15_000);
+            auction.resolve_bids(vec![bid![100, 1], quality])[0].amount
+        };
+        assert_eq!(price(RoundingPolicy::TowardBuyer), 66);
+        assert_eq!(price(RoundingPolicy::TowardSeller), 67);
+        assert_eq!(price(RoundingPolicy::Nearest), 67);
+    }
+
+    #[test]
+    fn scored_rejects_non_positive_weight() {
+        assert_eq!(
+            bid![10, 1].with_score_weight(0).validate(),
+            Err(BidError::NonPositiveScoreWeight(0))
+        );
+        assert_eq!(
+            bid![10, 1].with_score_weight(-5).validate(),
+            Err(BidError::NonPositiveScoreWeight(-5))
+        );
+    }
+
+    #[test]
+    fn scored_pricing_needs_scored_strategy() {
+        let built = AuctionBuilder::new()
+            .scored_pricing(ScoredPricing::SecondScore)
+            .try_build();
+        assert!(matches!(
+            built,
+            Err(AuctionConfigError::StrategyMismatch(_))
+        ));
+    }
+}
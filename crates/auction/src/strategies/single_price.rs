@@ -1,8 +1,27 @@
 //! Module containing the single price auction algorithm.
-use crate::{Auction, Bid, Bids, Sale, Sales};
+use alloc::vec::Vec;
+
+use uuid::Uuid;
+
+use crate::{
+    collections::{self, HashMap, HashSet},
+    strategies::fill::{
+        fill_lots, rank_rest, rank_tiered, rank_top_bids, select_top_bids, LevelFiller,
+    },
+    tick, Auction, AuctionDirection, AuctionObserver, Bid, Bids, PricingRule, Sale, Sales,
+};
+
+/// Up to this many lots the best bids are picked with a heap, beyond it with
+/// a linear time selection.
+const HEAP_SELECTION_MAX_LOTS: usize = 64;
 
 /// Resolves bids into sales using the single price algorithm.
 ///
+/// Only the best price levels are ranked when they fill every lot, which is
+/// the usual case when there are far more bids than lots. Bids are ranked by
+/// priority tier before amount, while the clearing price is still set by the
+/// lowest winning amount whatever its tier.
+///
 /// # Arguments
 /// * `auction` - The auction to resolve bids for.
 /// * `bids` - The bids to resolve.
@@ -10,40 +29,170 @@ use crate::{Auction, Bid, Bids, Sale, Sales};
 /// # Returns
 /// A list of sales for the bids.
 ///
-pub fn single_price(auction: &Auction, mut bids: Bids) -> Sales {
-    bids.sort_by(|a, b| b.cmp(a));
-
-    let mut remaining_lots = auction.lots;
-    let mut winning_bids = Vec::new();
-    for bid in bids.iter() {
-        if bid.amount < auction.reserve_price {
-            break;
-        }
-        if bid.quantity <= remaining_lots {
-            remaining_lots -= bid.quantity;
-            winning_bids.push(*bid);
-        } else if remaining_lots > 0 {
-            let new_bid = Bid::new(bid.amount, remaining_lots);
-            winning_bids.push(new_bid);
-            remaining_lots = 0;
-        } else {
-            break;
+pub fn single_price(auction: &Auction, bids: &[Bid]) -> Sales {
+    single_price_observed(auction, bids, &mut ())
+}
+
+/// Resolves bids as [`single_price`] does, telling the observer about each
+/// rejection and allocation.
+pub fn single_price_observed(
+    auction: &Auction,
+    bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    if bids.iter().any(|bid| bid.priority.is_some()) {
+        let winning_bids = fill_all(auction, bids, observer);
+        return price_uniformly(auction, bids, &winning_bids, observer);
+    }
+    let top = if auction.lots <= HEAP_SELECTION_MAX_LOTS {
+        rank_top_bids(auction, bids)
+    } else {
+        select_top_bids(auction, bids)
+    };
+    let winning_bids = fill_top(auction, bids, top, observer);
+    price_uniformly(auction, bids, &winning_bids, observer)
+}
+
+/// Fills the lots from the best bids, going on to the rest of the bids when
+/// the best leave lots unfilled.
+///
+/// The best bids are whole price levels at the top of the ranking, so filling
+/// the rest after them fills the lots as ranking every bid would. The
+/// observer is told about every bid left out.
+fn fill_top(
+    auction: &Auction,
+    bids: &[Bid],
+    (ranked, complete): (Vec<&Bid>, bool),
+    observer: &mut impl AuctionObserver,
+) -> Bids {
+    let mut filler = LevelFiller::new(auction);
+    let mut winning_bids = filler.fill_ranked(&ranked, observer);
+    let rest = if !complete && !filler.is_full() {
+        rank_rest(auction, bids, &ranked)
+    } else {
+        Vec::new()
+    };
+    winning_bids.extend(filler.fill_ranked(&rest, observer));
+    if observer.enabled() {
+        let considered: HashSet<Uuid> = ranked.iter().chain(&rest).map(|bid| bid.id).collect();
+        for bid in bids.iter().filter(|bid| !considered.contains(&bid.id)) {
+            filler.reject(core::slice::from_ref(bid), observer);
         }
     }
+    winning_bids
+}
+
+/// Fills the lots from every bid, ranked.
+fn fill_all(auction: &Auction, bids: &[Bid], observer: &mut impl AuctionObserver) -> Bids {
+    let mut ranked: Vec<&Bid> = bids.iter().collect();
+    rank_tiered(auction, &mut ranked);
+    fill_lots(
+        auction,
+        &ranked,
+        |bid| auction.qualifies(bid.amount),
+        observer,
+    )
+}
+
+/// Charges every winning bid the clearing price set by the auction's pricing
+/// rule, telling the observer about each sale.
+pub fn price_uniformly(
+    auction: &Auction,
+    bids: &[Bid],
+    winning_bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    let price = match auction.pricing_rule {
+        PricingRule::LowestAccepted => lowest_winning_amount(auction, winning_bids),
+        PricingRule::HighestRejected => highest_rejected_price(auction, bids, winning_bids),
+    };
+    price.map_or_else(Vec::new, |price| {
+        charge_uniformly(winning_bids, price, observer)
+    })
+}
 
-    let lowest_winning_bid_amount = match winning_bids.last() {
-        None => return Vec::new(),
-        Some(bid) => bid.amount,
+/// The price of the best qualifying bid that was not completely filled,
+/// None when nothing was.
+///
+/// A partially filled marginal bid has units rejected at its own amount, so it
+/// sets the price. When every bid is filled the price falls back to the
+/// reserve price of the last lot sold, or the price ceiling of a reverse
+/// auction, and to the last winner's amount for a reverse auction without one.
+/// Winners never pay more than the lowest winner's amount, so a better bid
+/// skipped for refusing its fill does not raise the price.
+fn highest_rejected_price(auction: &Auction, bids: &[Bid], winning_bids: &[Bid]) -> Option<i64> {
+    let lowest_winner = lowest_winning_amount(auction, winning_bids)?;
+    let mut filled: HashMap<Uuid, usize> = collections::map_with_capacity(winning_bids.len());
+    for bid in winning_bids {
+        *filled.entry(bid.id).or_default() += bid.quantity;
+    }
+    let rejected = bids
+        .iter()
+        .filter(|bid| auction.qualifies(bid.amount))
+        .filter(|bid| bid.quantity > filled.get(&bid.id).copied().unwrap_or_default())
+        .map(|bid| bid.amount);
+    let price = match auction.direction {
+        AuctionDirection::Forward => {
+            let sold: usize = winning_bids.iter().map(|bid| bid.quantity).sum();
+            let reserve_price = auction.reserve_at(sold.saturating_sub(1));
+            let price = rejected.max().unwrap_or(reserve_price).max(reserve_price);
+            tick::snap_price(auction, price).min(lowest_winner)
+        }
+        AuctionDirection::Reverse => {
+            let price = rejected
+                .min()
+                .or(auction.price_ceiling)
+                .unwrap_or(lowest_winner);
+            tick::snap_price(auction, price).max(lowest_winner)
+        }
     };
+    Some(price)
+}
+
+/// The lowest winning amount, or the highest winning offer of a reverse
+/// auction. Without tiers this is the last winner's amount.
+fn lowest_winning_amount(auction: &Auction, winning_bids: &[Bid]) -> Option<i64> {
+    let amounts = winning_bids.iter().map(|bid| bid.amount);
+    match auction.direction {
+        AuctionDirection::Forward => amounts.min(),
+        AuctionDirection::Reverse => amounts.max(),
+    }
+}
+
+/// Charges every winning bid the lowest winning amount, the highest winning
+/// offer for reverse auctions.
+pub fn price_at_lowest_winner(
+    auction: &Auction,
+    winning_bids: &[Bid],
+    observer: &mut impl AuctionObserver,
+) -> Sales {
+    lowest_winning_amount(auction, winning_bids).map_or_else(Vec::new, |price| {
+        charge_uniformly(winning_bids, price, observer)
+    })
+}
 
+/// Charges every winning bid the price, telling the observer about each sale.
+fn charge_uniformly(
+    winning_bids: &[Bid],
+    price: i64,
+    observer: &mut impl AuctionObserver,
+) -> Sales {
     winning_bids
         .iter()
-        .map(|bid| Sale::new(bid.id, lowest_winning_bid_amount, bid.quantity))
+        .map(|bid| {
+            observer.on_allocation(bid, bid.quantity, price);
+            Sale::new(bid.bidder_id, price, bid.quantity).with_bid(bid)
+        })
         .collect()
 }
 
 #[cfg(test)]
 mod test {
+    use super::{fill_all, fill_top, price_at_lowest_winner};
+    use crate::strategies::{
+        fill::{rank_top_bids, select_top_bids},
+        record_requested,
+    };
     use crate::*;
 
     #[test]
@@ -105,4 +254,365 @@ mod test {
         assert_eq!(sales.len(), 1);
         assert_eq!(sales[0].amount, 55);
     }
+
+    fn tiered_auction(lots: usize) -> Auction {
+        AuctionBuilder::new()
+            .lots(lots)
+            .reserve_schedule(vec![(2, 100), (2, 120)])
+            .build()
+    }
+
+    #[test]
+    fn single_price_reserve_schedule_stops_below_next_tier() {
+        let bids: Bids = vec![bid![150, 1], bid![130, 1], bid![110, 1], bid![105, 1]];
+        let sales = tiered_auction(4).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.amount == 130));
+    }
+
+    #[test]
+    fn single_price_reserve_schedule_boundary_is_inclusive() {
+        let bids: Bids = vec![bid![150, 1], bid![130, 1], bid![120, 1], bid![110, 1]];
+        let sales = tiered_auction(4).resolve_bids(bids);
+        assert_eq!(sales.len(), 3);
+        assert!(sales.iter().all(|sale| sale.amount == 120));
+    }
+
+    #[test]
+    fn single_price_reserve_schedule_partially_fills_to_qualifying_lots() {
+        let bids: Bids = vec![bid![110, 3]];
+        let sales = tiered_auction(4).resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].quantity, 2);
+        assert_eq!(sales[0].amount, 110);
+    }
+
+    #[test]
+    fn single_price_reserve_schedule_bid_spans_tiers() {
+        let bids: Bids = vec![bid![125, 4]];
+        let sales = tiered_auction(4).resolve_bids(bids);
+        assert_eq!(sales[0].quantity, 4);
+    }
+
+    #[test]
+    fn single_price_reserve_schedule_below_first_tier_sells_nothing() {
+        let bids: Bids = vec![bid![99, 2]];
+        assert!(tiered_auction(4).resolve_bids(bids).is_empty());
+    }
+
+    #[test]
+    fn single_price_lots_past_schedule_keep_last_reserve() {
+        let auction = AuctionBuilder::new()
+            .lots(3)
+            .reserve_schedule(vec![(1, 100)])
+            .build();
+        assert_eq!(auction.resolve_bids(vec![bid![100, 3]])[0].quantity, 3);
+        assert!(auction.resolve_bids(vec![bid![99, 3]]).is_empty());
+    }
+
+    #[test]
+    fn single_price_reserve_schedule_with_many_lots() {
+        let mut bids: Bids = (0..60).map(|_| bid![30, 1]).collect();
+        bids.extend((0..60).map(|_| bid![15, 1]));
+        let auction = AuctionBuilder::new()
+            .lots(100)
+            .reserve_schedule(vec![(50, 10), (50, 20)])
+            .build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 60);
+        assert!(sales.iter().all(|sale| sale.amount == 30));
+    }
+
+    #[test]
+    fn single_price_partial_fill_keeps_bidder_id() {
+        let bids: Bids = vec![bid![10, 2], bid![20, 1]];
+        let partial_id = bids[0].id;
+        let auction = AuctionBuilder::new().lots(2).build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales[1].quantity, 1);
+        assert_eq!(sales[1].bidder_id, partial_id);
+    }
+
+    #[test]
+    fn single_price_sales_reference_input_bids() {
+        let bids: Bids = vec![bid![10, 3], bid![20, 2], bid![15, 4], bid![30, 1]];
+        let ids: Vec<_> = bids.iter().map(|bid| bid.id).collect();
+        let auction = AuctionBuilder::new().lots(5).build();
+        let sales = auction.resolve_bids(bids);
+        assert!(!sales.is_empty());
+        assert!(sales.iter().all(|sale| ids.contains(&sale.bidder_id)));
+    }
+
+    fn reverse_auction(lots: usize) -> AuctionBuilder {
+        AuctionBuilder::new()
+            .direction(AuctionDirection::Reverse)
+            .lots(lots)
+    }
+
+    #[test]
+    fn single_price_reverse_buys_cheapest_offers() {
+        let bids: Bids = vec![bid![25, 1], bid![10, 1], bid![40, 1]];
+        let sales = reverse_auction(2).build().resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 25);
+        assert_eq!(sales[1].amount, 25);
+    }
+
+    #[test]
+    fn single_price_reverse_price_ceiling_applied() {
+        let bids: Bids = vec![bid![25, 1], bid![10, 1], bid![40, 1]];
+        let sales = reverse_auction(3)
+            .price_ceiling(30)
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.amount <= 30));
+    }
+
+    #[test]
+    fn single_price_reverse_ignores_reserve_price() {
+        let bids: Bids = vec![bid![25, 1], bid![10, 1]];
+        let sales = reverse_auction(2)
+            .reserve_price(50)
+            .build()
+            .resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+    }
+
+    #[test]
+    fn single_price_reverse_partially_fulfilled() {
+        let bids: Bids = vec![bid![15, 3], bid![10, 1]];
+        let sales = reverse_auction(2).build().resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[0].amount, 15);
+        assert_eq!(sales[1].amount, 15);
+        assert_eq!(sales[0].quantity, 1);
+        assert_eq!(sales[1].quantity, 1);
+    }
+
+    #[test]
+    fn single_price_all_or_nothing_bid_skipped_for_smaller_bid() {
+        let all_or_nothing = bid![25, 3].with_all_or_nothing(true);
+        let bids: Bids = vec![bid![30, 1], all_or_nothing, bid![10, 1]];
+        let auction = AuctionBuilder::new().lots(2).build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.bidder_id != all_or_nothing.id));
+        assert_eq!(sales[0].amount, 10);
+        assert_eq!(sales[1].amount, 10);
+    }
+
+    #[test]
+    fn single_price_all_or_nothing_bid_filled_when_it_fits() {
+        let all_or_nothing = bid![25, 2].with_all_or_nothing(true);
+        let bids: Bids = vec![bid![30, 1], all_or_nothing, bid![10, 1]];
+        let auction = AuctionBuilder::new().lots(3).build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[1].bidder_id, all_or_nothing.id);
+        assert_eq!(sales[1].quantity, 2);
+    }
+
+    #[test]
+    fn single_price_all_or_nothing_can_leave_lots_unsold() {
+        let bids: Bids = vec![bid![30, 1], bid![25, 3].with_all_or_nothing(true)];
+        let auction = AuctionBuilder::new().lots(2).build();
+        let result = auction.resolve(bids).unwrap();
+        assert_eq!(result.lots_sold(), 1);
+        assert_eq!(result.lots_unsold(), 1);
+    }
+
+    #[test]
+    fn single_price_min_quantity_met_exactly_partially_fills() {
+        let minimum = bid![20, 5].with_min_quantity(2).unwrap();
+        let bids: Bids = vec![bid![30, 1], minimum, bid![10, 2]];
+        let auction = AuctionBuilder::new().lots(3).build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert_eq!(sales[1].bidder_id, minimum.id);
+        assert_eq!(sales[1].quantity, 2);
+    }
+
+    #[test]
+    fn single_price_min_quantity_not_met_skips_bid() {
+        let minimum = bid![20, 5].with_min_quantity(3).unwrap();
+        let bids: Bids = vec![bid![30, 1], minimum, bid![10, 2]];
+        let auction = AuctionBuilder::new().lots(3).build();
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.bidder_id != minimum.id));
+        assert_eq!(sales[1].amount, 10);
+        assert_eq!(sales[1].quantity, 2);
+    }
+
+    #[test]
+    fn single_price_top_levels_match_full_ranking() {
+        for seed in 0..200 {
+            let mut bids = generators::large_quantities(seed, 300, 49, 4);
+            let bidder = bids[0].bidder_id;
+            for (index, bid) in bids.iter_mut().enumerate() {
+                match index % 7 {
+                    0 => *bid = bid.with_all_or_nothing(true),
+                    1 => *bid = bid.with_submitted_at(index as Timestamp % 5),
+                    2 => *bid = bid.with_bidder_id(bidder),
+                    3 => *bid = bid.with_min_quantity(bid.quantity).unwrap(),
+                    _ => {}
+                }
+            }
+            for direction in [AuctionDirection::Forward, AuctionDirection::Reverse] {
+                let mut builder = AuctionBuilder::new()
+                    .lots(seed as usize % 40 + 1)
+                    .reserve_price(5)
+                    .price_ceiling(45)
+                    .direction(direction);
+                if seed % 2 == 0 {
+                    builder = builder.max_lots_per_bidder(3);
+                }
+                if seed % 3 == 0 {
+                    builder = builder.allocation(AllocationPolicy::ProRata);
+                }
+                let auction = builder.build();
+                let mut expected =
+                    price_at_lowest_winner(&auction, &fill_all(&auction, &bids, &mut ()), &mut ());
+                record_requested(&mut expected, &bids);
+                assert_eq!(auction.resolve_bids(bids.clone()), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn single_price_selection_matches_heap_and_full_ranking() {
+        for seed in 0..100 {
+            let mut bids = generators::large_quantities(seed, 400, 49, 4);
+            for (index, bid) in bids.iter_mut().enumerate() {
+                if index % 5 == 0 {
+                    *bid = bid.with_all_or_nothing(true);
+                }
+            }
+            for direction in [AuctionDirection::Forward, AuctionDirection::Reverse] {
+                let auction = AuctionBuilder::new()
+                    .lots(seed as usize * 7)
+                    .reserve_price(5)
+                    .price_ceiling(45)
+                    .direction(direction)
+                    .build();
+                let expected =
+                    price_at_lowest_winner(&auction, &fill_all(&auction, &bids, &mut ()), &mut ());
+                let heap = fill_top(&auction, &bids, rank_top_bids(&auction, &bids), &mut ());
+                let select = fill_top(&auction, &bids, select_top_bids(&auction, &bids), &mut ());
+                assert_eq!(price_at_lowest_winner(&auction, &heap, &mut ()), expected);
+                assert_eq!(price_at_lowest_winner(&auction, &select, &mut ()), expected);
+                let heap_ids: Vec<_> = rank_top_bids(&auction, &bids)
+                    .0
+                    .iter()
+                    .map(|b| b.id)
+                    .collect();
+                let select_ids: Vec<_> = select_top_bids(&auction, &bids)
+                    .0
+                    .iter()
+                    .map(|b| b.id)
+                    .collect();
+                assert_eq!(heap_ids, select_ids);
+            }
+        }
+    }
+
+    fn pricing_auction(pricing_rule: PricingRule, lots: usize) -> Auction {
+        AuctionBuilder::new()
+            .lots(lots)
+            .reserve_price(5)
+            .pricing_rule(pricing_rule)
+            .build()
+    }
+
+    #[test]
+    fn single_price_pricing_rules_differ() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 1], bid![10, 1]];
+        let lowest = pricing_auction(PricingRule::LowestAccepted, 2).resolve_bids(bids.clone());
+        let highest = pricing_auction(PricingRule::HighestRejected, 2).resolve_bids(bids);
+        assert_eq!(lowest.clearing_price(), Some(20));
+        assert_eq!(highest.clearing_price(), Some(10));
+        assert!(highest.iter().all(|sale| sale.amount == 10));
+        assert_eq!(highest.total_quantity(), 2);
+    }
+
+    #[test]
+    fn single_price_highest_rejected_partial_marginal_sets_price() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 2], bid![10, 1]];
+        let sales = pricing_auction(PricingRule::HighestRejected, 2).resolve_bids(bids);
+        assert_eq!(sales.total_quantity(), 2);
+        assert!(sales.iter().all(|sale| sale.amount == 20));
+    }
+
+    #[test]
+    fn single_price_highest_rejected_falls_back_to_reserve() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 1], bid![3, 1]];
+        let sales = pricing_auction(PricingRule::HighestRejected, 3).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.amount == 5));
+    }
+
+    #[test]
+    fn single_price_highest_rejected_never_above_lowest_winner() {
+        let bids: Bids = vec![
+            bid![30, 1],
+            bid![25, 3].with_all_or_nothing(true),
+            bid![20, 1],
+        ];
+        let sales = pricing_auction(PricingRule::HighestRejected, 2).resolve_bids(bids);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.amount == 20));
+    }
+
+    #[test]
+    fn single_price_highest_rejected_reverse_uses_lowest_rejected_offer() {
+        let bids: Bids = vec![bid![10, 1], bid![20, 1], bid![30, 1], bid![60, 1]];
+        let auction = AuctionBuilder::new()
+            .lots(2)
+            .direction(AuctionDirection::Reverse)
+            .price_ceiling(50)
+            .pricing_rule(PricingRule::HighestRejected)
+            .build();
+        let sales = auction.resolve_bids(bids.clone());
+        assert!(sales.iter().all(|sale| sale.amount == 30));
+        let sales = auction.resolve_bids(bids[..2].to_vec());
+        assert!(sales.iter().all(|sale| sale.amount == 50));
+    }
+
+    #[test]
+    fn single_price_member_tier_beats_higher_public_bid() {
+        let public = bid![150, 1];
+        let member = bid![100, 1].with_priority(1);
+        let auction = AuctionBuilder::new().lots(1).build();
+        let sales = auction.resolve_bids(vec![public, member]);
+        assert_eq!(sales, vec![Sale::new(member.id, 100, 1).with_bid(&member)]);
+        assert_eq!(sales.clearing_price(), Some(100));
+    }
+
+    #[test]
+    fn single_price_tiered_clearing_price_is_lowest_winner() {
+        let bids: Bids = vec![
+            bid![150, 1],
+            bid![90, 1].with_priority(1),
+            bid![120, 1].with_priority(0),
+            bid![80, 1],
+        ];
+        let sales = AuctionBuilder::new().lots(3).build().resolve_bids(bids);
+        assert_eq!(sales.len(), 3);
+        assert_eq!(sales.clearing_price(), Some(90));
+        assert!(sales.iter().all(|sale| sale.amount == 90));
+        assert!(sales.iter().all(|sale| sale.quantity == 1));
+    }
+
+    #[test]
+    fn pricing_rule_needs_single_price() {
+        let result = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .pricing_rule(PricingRule::HighestRejected)
+            .try_build();
+        assert!(matches!(
+            result,
+            Err(AuctionConfigError::StrategyMismatch(_))
+        ));
+    }
 }
@@ -0,0 +1,200 @@
+//! Module containing the Vickrey-Clarke-Groves auction algorithm.
+use alloc::vec::Vec;
+
+use uuid::Uuid;
+
+use crate::{
+    collections::HashMap,
+    strategies::fill::{fill_lots, rank_bids},
+    tick, Auction, AuctionObserver, Bid, Sale, Sales,
+};
+
+/// Resolves bids into sales using the Vickrey-Clarke-Groves algorithm.
+///
+/// Lots are allocated as by `multi_price`, then each winning bidder pays the
+/// value the other bidders lose by them taking part: what the others would
+/// win without them, less what the others win with them. The counterfactual
+/// for each bidder refills the already ranked bids with theirs left out.
+///
+/// The payment is spread over the bidder's units and rounded to a whole cent
+/// per unit, or onto the tick grid, by the auction's rounding policy. Rounding
+/// toward the buyer, the default, means a bidder never pays more than their
+/// VCG payment. No unit is sold below the reserve price.
+///
+/// # Arguments
+/// * `auction` - The auction to resolve bids for.
+/// * `bids` - The bids to resolve.
+///
+/// # Returns
+/// A list of sales for the winning bids, each at its bidder's price per unit.
+///
+pub fn vcg(auction: &Auction, bids: &[Bid]) -> Sales {
+    vcg_observed(auction, bids, &mut ())
+}
+
+/// Resolves bids as [`vcg`] does, telling the observer about each rejection
+/// and allocation. The fills without each winner are not observed.
+pub fn vcg_observed(auction: &Auction, bids: &[Bid], observer: &mut impl AuctionObserver) -> Sales {
+    let mut ranked: Vec<&Bid> = bids.iter().collect();
+    rank_bids(auction, &mut ranked);
+    let qualifies = |bid: &Bid| auction.qualifies(bid.amount);
+
+    let winning_bids = fill_lots(auction, &ranked, qualifies, observer);
+    let welfare = total_value(&winning_bids);
+    let mut winners: HashMap<Uuid, (i128, usize)> = HashMap::new();
+    for bid in &winning_bids {
+        let (value, units) = winners.entry(bid.bidder_id).or_default();
+        *value += value_of(bid);
+        *units += bid.quantity;
+    }
+
+    let prices: HashMap<Uuid, i64> = winners
+        .into_iter()
+        .map(|(bidder_id, (value, units))| {
+            let others: Vec<&Bid> = ranked
+                .iter()
+                .copied()
+                .filter(|bid| bid.bidder_id != bidder_id)
+                .collect();
+            let without = total_value(&fill_lots(auction, &others, qualifies, &mut ()));
+            let payment = (without - (welfare - value)).max(0);
+            let per_unit = tick::round_price(auction, payment, units as i128).unwrap_or(i64::MAX);
+            (bidder_id, per_unit.max(auction.reserve_price))
+        })
+        .collect();
+
+    winning_bids
+        .iter()
+        .map(|bid| {
+            let price = prices[&bid.bidder_id];
+            observer.on_allocation(bid, bid.quantity, price);
+            Sale::new(bid.bidder_id, price, bid.quantity).with_bid(bid)
+        })
+        .collect()
+}
+
+/// The value of a filled bid to its bidder.
+fn value_of(bid: &Bid) -> i128 {
+    i128::from(bid.amount) * bid.quantity as i128
+}
+
+/// The total value of the filled bids to their bidders.
+fn total_value(bids: &[Bid]) -> i128 {
+    bids.iter().map(value_of).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::*;
+
+    fn vcg_auction(lots: usize, reserve_price: i64) -> Auction {
+        AuctionBuilder::new()
+            .strategy(AuctionStrategy::Vcg)
+            .lots(lots)
+            .reserve_price(reserve_price)
+            .build()
+    }
+
+    #[test]
+    fn vcg_unit_demand_pays_highest_losing_bid() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 1], bid![10, 1], bid![5, 1]];
+        let sales = vcg_auction(2, 0).resolve_bids(bids.clone());
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bids[0].id, 10, 1).with_bid(&bids[0]),
+                Sale::new(bids[1].id, 10, 1).with_bid(&bids[1])
+            ]
+        );
+    }
+
+    #[test]
+    fn vcg_single_lot_is_second_price() {
+        let bids: Bids = vec![bid![30, 1], bid![25, 1]];
+        let sales = vcg_auction(1, 0).resolve_bids(bids);
+        assert_eq!(sales.len(), 1);
+        assert_eq!(sales[0].amount, 25);
+    }
+
+    #[test]
+    fn vcg_multi_unit_payment_is_externality() {
+        let bidder = Uuid::new_v4();
+        let bids: Bids = vec![
+            bid![50, 2].with_bidder_id(bidder),
+            bid![40, 1],
+            bid![30, 1],
+            bid![20, 2],
+        ];
+        let sales = vcg_auction(3, 0).resolve_bids(bids.clone());
+        // Without the bidder the others win 40 + 30 + 20 = 90, with them 40,
+        // so the 50 of displaced value is shared over two units. Without the
+        // 40 bid the others win 100 + 30, with it 100.
+        assert_eq!(
+            sales,
+            vec![
+                Sale::new(bidder, 25, 2).with_bid(&bids[0]),
+                Sale::new(bids[1].id, 30, 1).with_bid(&bids[1])
+            ]
+        );
+    }
+
+    #[test]
+    fn vcg_rounds_price_per_unit_down() {
+        let bidder = Uuid::new_v4();
+        let bids: Bids = vec![bid![50, 3].with_bidder_id(bidder), bid![10, 1], bid![9, 1]];
+        // The bidder displaces 10 + 9 = 19 of value over three units.
+        let sales = vcg_auction(3, 0).resolve_bids(bids.clone());
+        assert_eq!(sales, vec![Sale::new(bidder, 6, 3).with_bid(&bids[0])]);
+    }
+
+    #[test]
+    fn vcg_respects_reserve_price() {
+        let bids: Bids = vec![bid![30, 1], bid![20, 1]];
+        let sales = vcg_auction(2, 15).resolve_bids(bids);
+        assert!(sales.iter().all(|sale| sale.amount == 15));
+        assert_eq!(sales.len(), 2);
+    }
+
+    #[test]
+    fn vcg_per_unit_price_follows_rounding_policy() {
+        let price = |policy, tick_size| {
+            let auction = AuctionBuilder::new()
+                .strategy(AuctionStrategy::Vcg)
+                .lots(4)
+                .tick_size(tick_size)
+                .rounding_policy(policy)
+                .build();
+            let bids: Bids = vec![bid![30, 3], bid![26, 1], bid![20, 1]];
+            auction.resolve_bids(bids)[0].amount
+        };
+        assert_eq!(price(RoundingPolicy::TowardBuyer, 1), 6);
+        assert_eq!(price(RoundingPolicy::TowardSeller, 1), 7);
+        assert_eq!(price(RoundingPolicy::Nearest, 1), 7);
+        assert_eq!(price(RoundingPolicy::TowardBuyer, 2), 6);
+        assert_eq!(price(RoundingPolicy::TowardSeller, 2), 8);
+        assert_eq!(price(RoundingPolicy::Nearest, 2), 6);
+    }
+
+    #[test]
+    fn vcg_never_charges_above_bid() {
+        for seed in 0..20 {
+            let bids = generators::large_quantities(seed, 60, 49, 4);
+            let auction = vcg_auction(seed as usize + 3, 5);
+            let sales = auction.resolve_bids_ref(&bids);
+            assert_eq!(verify(&auction, &bids, &sales), Ok(()));
+            let multi = AuctionBuilder::new()
+                .strategy(AuctionStrategy::MultiPrice)
+                .lots(seed as usize + 3)
+                .reserve_price(5)
+                .build()
+                .resolve_bids_ref(&bids);
+            assert_eq!(sales.len(), multi.len());
+            for (sale, paid) in sales.iter().zip(&multi) {
+                assert_eq!(sale.quantity, paid.quantity);
+                assert!(sale.amount <= paid.amount);
+            }
+        }
+    }
+}
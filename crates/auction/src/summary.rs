@@ -0,0 +1,416 @@
+//! Module containing the statistics of a set of sales.
+use core::{iter::Sum, ops::Add};
+
+use uuid::Uuid;
+
+use crate::{
+    collections::{HashMap, HashSet},
+    Sale, Sales,
+};
+
+/// What one bidder owes, gathered from all of their sales.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BidderSettlement {
+    quantity: usize,
+    total_amount: i128,
+    sales: Sales,
+}
+
+impl BidderSettlement {
+    /// The number of units the bidder won.
+    pub const fn quantity(&self) -> usize {
+        self.quantity
+    }
+
+    /// The total the bidder is charged in cents, an `i128` as it can exceed
+    /// any one sale.
+    pub const fn total_amount(&self) -> i128 {
+        self.total_amount
+    }
+
+    /// The bidder's sales, in the order they were made.
+    pub const fn sales(&self) -> &Sales {
+        &self.sales
+    }
+}
+
+/// The revenue and units of sales added up, collected from any iterator of
+/// sales with [`Iterator::sum`].
+///
+/// Each total is None once it overflows, and stays None as more sales are
+/// added.
+///
+/// ```
+/// use auction::{Sale, SalesTotals, Uuid};
+///
+/// let sales = [Sale::new(Uuid::nil(), 30, 2), Sale::new(Uuid::nil(), 20, 1)];
+/// let totals: SalesTotals = sales.iter().filter(|sale| sale.amount() > 25).sum();
+/// assert_eq!(totals.revenue(), Some(60));
+/// assert_eq!(totals.quantity(), Some(2));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SalesTotals {
+    revenue: Option<i128>,
+    quantity: Option<usize>,
+}
+
+impl SalesTotals {
+    /// The totals of no sales.
+    pub const ZERO: Self = Self {
+        revenue: Some(0),
+        quantity: Some(0),
+    };
+
+    /// The total charged across the sales, None if it overflows an `i128`.
+    pub const fn revenue(&self) -> Option<i128> {
+        self.revenue
+    }
+
+    /// The number of units sold, None if it overflows a `usize`.
+    pub const fn quantity(&self) -> Option<usize> {
+        self.quantity
+    }
+
+    /// The totals with one more sale added.
+    fn with_sale(self, sale: &Sale) -> Self {
+        Self {
+            revenue: self
+                .revenue
+                .and_then(|revenue| revenue.checked_add(sale.total())),
+            quantity: self
+                .quantity
+                .and_then(|quantity| quantity.checked_add(sale.quantity)),
+        }
+    }
+}
+
+impl Default for SalesTotals {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl Add for SalesTotals {
+    type Output = Self;
+
+    /// Adds the totals of two sets of sales, such as two batches.
+    fn add(self, other: Self) -> Self {
+        Self {
+            revenue: self
+                .revenue
+                .zip(other.revenue)
+                .and_then(|(a, b)| a.checked_add(b)),
+            quantity: self
+                .quantity
+                .zip(other.quantity)
+                .and_then(|(a, b)| a.checked_add(b)),
+        }
+    }
+}
+
+impl Add<&Sale> for SalesTotals {
+    type Output = Self;
+
+    fn add(self, sale: &Sale) -> Self {
+        self.with_sale(sale)
+    }
+}
+
+impl Sum for SalesTotals {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Add::add)
+    }
+}
+
+impl Sum<Sale> for SalesTotals {
+    fn sum<I: Iterator<Item = Sale>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, |totals, sale| totals.with_sale(&sale))
+    }
+}
+
+impl<'a> Sum<&'a Sale> for SalesTotals {
+    fn sum<I: Iterator<Item = &'a Sale>>(iter: I) -> Self {
+        iter.fold(Self::ZERO, Self::with_sale)
+    }
+}
+
+/// Statistics over sales, available on [`Sales`](crate::Sales) and any slice
+/// of sales.
+///
+/// Losing all-pay sales, which have zero quantity, add to the revenue but are
+/// not counted as sold units or winners.
+pub trait SalesSummary {
+    /// The total charged across the sales, accumulated in `i128` so that
+    /// revenue beyond `i64` is kept, None if even that overflows.
+    fn total_revenue(&self) -> Option<i128>;
+
+    /// The number of units sold.
+    fn total_quantity(&self) -> usize;
+
+    /// The revenue and units sold, each None if it overflows.
+    fn totals(&self) -> SalesTotals;
+
+    /// The revenue per unit sold in cents, rounded down, None when nothing
+    /// sold, the revenue overflows or the average does not fit in an `i64`.
+    fn average_price(&self) -> Option<i64>;
+
+    /// The lowest price a unit sold at, None when nothing sold.
+    fn clearing_price(&self) -> Option<i64>;
+
+    /// The number of distinct bidders who won units.
+    fn winner_count(&self) -> usize;
+
+    /// The sales grouped by bidder, None if any bidder's quantity or total
+    /// overflows.
+    fn by_bidder(&self) -> Option<HashMap<Uuid, BidderSettlement>>;
+}
+
+impl SalesSummary for [Sale] {
+    fn total_revenue(&self) -> Option<i128> {
+        self.iter()
+            .try_fold(0_i128, |revenue, sale| revenue.checked_add(sale.total()))
+    }
+
+    fn total_quantity(&self) -> usize {
+        self.iter().map(|sale| sale.quantity).sum()
+    }
+
+    fn totals(&self) -> SalesTotals {
+        self.iter().sum()
+    }
+
+    fn average_price(&self) -> Option<i64> {
+        let quantity = i128::try_from(self.total_quantity()).ok()?;
+        if quantity == 0 {
+            return None;
+        }
+        i64::try_from(self.total_revenue()?.div_euclid(quantity)).ok()
+    }
+
+    fn clearing_price(&self) -> Option<i64> {
+        self.iter()
+            .filter(|sale| sale.quantity > 0)
+            .map(|sale| sale.amount)
+            .min()
+    }
+
+    fn winner_count(&self) -> usize {
+        self.iter()
+            .filter(|sale| sale.quantity > 0)
+            .map(|sale| sale.bidder_id)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    fn by_bidder(&self) -> Option<HashMap<Uuid, BidderSettlement>> {
+        let mut settlements: HashMap<Uuid, BidderSettlement> = HashMap::new();
+        for sale in self {
+            let settlement = settlements.entry(sale.bidder_id).or_default();
+            settlement.quantity = settlement.quantity.checked_add(sale.quantity)?;
+            settlement.total_amount = settlement.total_amount.checked_add(sale.total())?;
+            settlement.sales.push(*sale);
+        }
+        Some(settlements)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::*;
+
+    #[test]
+    fn summary_of_empty_sales() {
+        let sales: Sales = Vec::new();
+        assert_eq!(sales.total_revenue(), Some(0));
+        assert_eq!(sales.total_quantity(), 0);
+        assert_eq!(sales.average_price(), None);
+        assert_eq!(sales.clearing_price(), None);
+        assert_eq!(sales.winner_count(), 0);
+    }
+
+    #[test]
+    fn summary_of_multi_price_sales() {
+        let bidder = Uuid::new_v4();
+        let sales: Sales = vec![
+            Sale::new(bidder, 30, 1),
+            Sale::new(bidder, 20, 2),
+            Sale::new(Uuid::new_v4(), 15, 1),
+        ];
+        assert_eq!(sales.total_revenue(), Some(85));
+        assert_eq!(sales.total_quantity(), 4);
+        assert_eq!(sales.average_price(), Some(21));
+        assert_eq!(sales.clearing_price(), Some(15));
+        assert_eq!(sales.winner_count(), 2);
+    }
+
+    #[test]
+    fn summary_counts_all_pay_losers_in_revenue_only() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::AllPay)
+            .lots(1)
+            .build();
+        let sales = auction.resolve_bids(vec![bid![30, 1], bid![10, 1]]);
+        assert_eq!(sales.total_revenue(), Some(40));
+        assert_eq!(sales.total_quantity(), 1);
+        assert_eq!(sales.clearing_price(), Some(30));
+        assert_eq!(sales.winner_count(), 1);
+    }
+
+    #[test]
+    fn summary_revenue_beyond_i64_fits() {
+        let sales: Sales = vec![
+            Sale::new(Uuid::new_v4(), i64::MAX, 1),
+            Sale::new(Uuid::new_v4(), 1, 1),
+        ];
+        assert_eq!(sales.total_revenue(), Some(i128::from(i64::MAX) + 1));
+        assert_eq!(sales.average_price(), Some(i64::MAX / 2 + 1));
+        assert_eq!(sales.clearing_price(), Some(1));
+    }
+
+    #[test]
+    fn summary_sale_total_beyond_i64_fits() {
+        let sales: Sales = vec![Sale::new(Uuid::new_v4(), i64::MAX, 2)];
+        assert_eq!(sales.total_revenue(), Some(i128::from(i64::MAX) * 2));
+        assert_eq!(sales.average_price(), Some(i64::MAX));
+    }
+
+    #[test]
+    fn summary_revenue_overflow_is_none() {
+        let sales: Sales = vec![
+            Sale::new(Uuid::new_v4(), i64::MAX, usize::MAX),
+            Sale::new(Uuid::new_v4(), i64::MAX, usize::MAX),
+        ];
+        assert_eq!(sales.total_revenue(), None);
+    }
+
+    #[test]
+    fn by_bidder_settles_each_bidder() {
+        let alice = Uuid::new_v4();
+        let bob = Uuid::new_v4();
+        let bids: Bids = vec![
+            bid![30, 1].with_bidder_id(alice),
+            bid![25, 2].with_bidder_id(bob),
+            bid![20, 2].with_bidder_id(alice),
+            bid![10, 1].with_bidder_id(bob),
+        ];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(5)
+            .build();
+        let settlements = auction.resolve_bids(bids).by_bidder().unwrap();
+        assert_eq!(settlements.len(), 2);
+
+        let alice = &settlements[&alice];
+        assert_eq!(alice.quantity(), 3);
+        assert_eq!(alice.total_amount(), 70);
+        let amounts: Vec<_> = alice.sales().iter().map(Sale::amount).collect();
+        assert_eq!(amounts, vec![30, 20]);
+
+        let bob = &settlements[&bob];
+        assert_eq!(bob.quantity(), 2);
+        assert_eq!(bob.total_amount(), 50);
+        assert_eq!(bob.sales().len(), 1);
+    }
+
+    #[test]
+    fn by_bidder_of_empty_sales() {
+        let sales: Sales = Vec::new();
+        assert!(sales.by_bidder().unwrap().is_empty());
+    }
+
+    #[test]
+    fn by_bidder_total_beyond_i64_fits() {
+        let bidder = Uuid::new_v4();
+        let sales: Sales = vec![Sale::new(bidder, i64::MAX, 1), Sale::new(bidder, 1, 1)];
+        let settlements = sales.by_bidder().unwrap();
+        assert_eq!(
+            settlements[&bidder].total_amount(),
+            i128::from(i64::MAX) + 1
+        );
+    }
+
+    #[test]
+    fn by_bidder_overflow_is_none() {
+        let bidder = Uuid::new_v4();
+        let sales: Sales = vec![Sale::new(bidder, 1, usize::MAX), Sale::new(bidder, 1, 1)];
+        assert_eq!(sales.by_bidder(), None);
+    }
+
+    #[test]
+    fn totals_sum_from_an_iterator() {
+        let bids: Bids = vec![bid![30, 2], bid![20, 1], bid![10, 1]];
+        let sales = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .build()
+            .resolve_bids(bids);
+        let totals: SalesTotals = sales.iter().sum();
+        assert_eq!(totals, sales.totals());
+        assert_eq!(totals.revenue(), Some(80));
+        assert_eq!(totals.quantity(), Some(3));
+        let owned: SalesTotals = sales.into_iter().filter(|sale| sale.amount < 30).sum();
+        assert_eq!((owned.revenue(), owned.quantity()), (Some(20), Some(1)));
+    }
+
+    #[test]
+    fn totals_of_no_sales_are_zero() {
+        let totals: SalesTotals = core::iter::empty::<Sale>().sum();
+        assert_eq!(totals, SalesTotals::ZERO);
+        assert_eq!(totals, SalesTotals::default());
+        assert_eq!(totals.revenue(), Some(0));
+    }
+
+    #[test]
+    fn totals_revenue_overflow_is_none() {
+        let sales: Sales = vec![
+            Sale::new(Uuid::new_v4(), i64::MAX, usize::MAX),
+            Sale::new(Uuid::new_v4(), i64::MAX, usize::MAX),
+            Sale::new(Uuid::new_v4(), 1, 1),
+        ];
+        let totals = sales.totals();
+        assert_eq!(totals.revenue(), None);
+        assert_eq!(totals.quantity(), None);
+        assert_eq!(sales[2..].totals().revenue(), Some(1));
+    }
+
+    #[test]
+    fn totals_quantity_overflow_keeps_revenue() {
+        let sales: Sales = vec![
+            Sale::new(Uuid::new_v4(), 0, usize::MAX),
+            Sale::new(Uuid::new_v4(), 5, 1),
+        ];
+        let totals = sales.totals();
+        assert_eq!(totals.revenue(), Some(5));
+        assert_eq!(totals.quantity(), None);
+    }
+
+    #[test]
+    fn totals_add_across_batches() {
+        let first: Sales = vec![Sale::new(Uuid::new_v4(), 10, 2)];
+        let second: Sales = vec![Sale::new(Uuid::new_v4(), 15, 1)];
+        let totals = first.totals() + second.totals();
+        assert_eq!((totals.revenue(), totals.quantity()), (Some(35), Some(3)));
+        assert_eq!(first.totals() + &second[0], totals);
+        let batches: SalesTotals = [first.totals(), second.totals()].into_iter().sum();
+        assert_eq!(batches, totals);
+
+        let huge: Sales = vec![Sale::new(Uuid::new_v4(), i64::MAX, usize::MAX)];
+        let overflowed = huge.totals() + huge.totals();
+        assert_eq!(overflowed.revenue(), None);
+        assert_eq!(overflowed.quantity(), None);
+        assert_eq!((overflowed + totals).revenue(), None);
+    }
+
+    #[test]
+    fn summary_works_on_slices() {
+        let sales: Sales = vec![
+            Sale::new(Uuid::new_v4(), 10, 1),
+            Sale::new(Uuid::new_v4(), 20, 1),
+        ];
+        assert_eq!(sales[1..].total_revenue(), Some(20));
+    }
+}
@@ -0,0 +1,345 @@
+//! Module for recording metrics of resolutions and sessions, behind the
+//! `metrics` feature.
+use std::time::Duration;
+
+use metrics::{counter, histogram, Label};
+use uuid::Uuid;
+
+use crate::{
+    bid_cap, collections::HashSet, tick, Auction, AuctionDirection, Bid, BidRejection, Sale,
+    SalesSummary,
+};
+
+/// The labels every metric of the auction carries, its `auction_id` when it
+/// has a metrics label.
+fn labels(auction: &Auction) -> Vec<Label> {
+    auction
+        .metrics_label
+        .iter()
+        .map(|label| Label::new("auction_id", label.clone()))
+        .collect()
+}
+
+/// The auction's labels along with the reason.
+fn with_reason(auction: &Auction, reason: &'static str) -> Vec<Label> {
+    let mut labels = labels(auction);
+    labels.push(Label::new("reason", reason));
+    labels
+}
+
+/// Records a resolution: the bids processed, each bid that made no sale by
+/// why, how long it took, the revenue and the clearing price.
+pub fn resolved(auction: &Auction, bids: &[Bid], sales: &[Sale], duration: Duration) {
+    counter!("auction_bids_processed_total", labels(auction)).increment(bids.len() as u64);
+    let sold: HashSet<Uuid> = sales.iter().filter_map(|sale| sale.bid_id).collect();
+    let mismatched = auction.currency_rejected(bids);
+    for bid in bids.iter().filter(|bid| !sold.contains(&bid.id)) {
+        counter!(
+            "auction_bids_rejected_total",
+            with_reason(auction, rejection(auction, bid, &mismatched))
+        )
+        .increment(1);
+    }
+    histogram!("auction_resolution_duration_seconds", labels(auction)).record(duration);
+    if let Some(revenue) = sales.total_revenue() {
+        histogram!("auction_revenue_cents", labels(auction)).record(revenue as f64);
+    }
+    let prices = sales
+        .iter()
+        .filter(|sale| sale.quantity > 0)
+        .map(|sale| sale.amount);
+    let clearing_price = match auction.direction {
+        AuctionDirection::Forward => prices.min(),
+        AuctionDirection::Reverse => prices.max(),
+    };
+    if let Some(price) = clearing_price {
+        histogram!("auction_clearing_price_cents", labels(auction)).record(price as f64);
+    }
+}
+
+/// Why a bid made no sale, as a metric label, given the bids the currency
+/// policy left out.
+fn rejection(auction: &Auction, bid: &Bid, mismatched: &HashSet<Uuid>) -> &'static str {
+    if mismatched.contains(&bid.id) {
+        "currency_mismatch"
+    } else if bid.quantity == 0 {
+        "zero_quantity"
+    } else if tick::is_rejected(auction, bid) {
+        "off_tick"
+    } else if bid_cap::is_rejected(auction, bid) {
+        "exceeds_bid_cap"
+    } else if !auction.qualifies(bid.amount) {
+        "below_reserve"
+    } else {
+        "outbid"
+    }
+}
+
+/// Records a bid a session accepted.
+pub fn submitted(auction: &Auction) {
+    counter!("auction_session_bids_submitted_total", labels(auction)).increment(1);
+}
+
+/// Records a bid a session refused, by why.
+pub fn refused(auction: &Auction, rejection: &BidRejection) {
+    let reason = match rejection {
+        BidRejection::Invalid(_) => "invalid",
+        BidRejection::BelowReserve(_) => "below_reserve",
+        BidRejection::OffTick(_) => "off_tick",
+        BidRejection::Expired => "expired",
+        BidRejection::DuplicateBidId(_) => "duplicate_bid_id",
+        BidRejection::UnknownBidId(_) => "unknown_bid_id",
+        BidRejection::Closed => "closed",
+    };
+    counter!(
+        "auction_session_bids_refused_total",
+        with_reason(auction, reason)
+    )
+    .increment(1);
+}
+
+/// Records a bid withdrawn from a session.
+pub fn withdrawn(auction: &Auction) {
+    counter!("auction_session_bids_withdrawn_total", labels(auction)).increment(1);
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::BTreeMap,
+        sync::{Arc, Mutex},
+    };
+
+    use metrics::{
+        Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+        SharedString, Unit,
+    };
+
+    use crate::*;
+
+    /// The metrics captured, by name and labels.
+    type Captured = Arc<Mutex<BTreeMap<String, Vec<f64>>>>;
+
+    /// Records every counter increase and histogram value under its key.
+    #[derive(Default)]
+    struct Capture(Captured);
+
+    struct Handle(String, Captured);
+
+    impl CounterFn for Handle {
+        fn increment(&self, value: u64) {
+            self.1
+                .lock()
+                .unwrap()
+                .entry(self.0.clone())
+                .or_default()
+                .push(value as f64);
+        }
+
+        fn absolute(&self, _value: u64) {}
+    }
+
+    impl HistogramFn for Handle {
+        fn record(&self, value: f64) {
+            self.1
+                .lock()
+                .unwrap()
+                .entry(self.0.clone())
+                .or_default()
+                .push(value);
+        }
+    }
+
+    impl Capture {
+        fn handle(&self, key: &Key) -> Arc<Handle> {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            let name = if labels.is_empty() {
+                key.name().to_string()
+            } else {
+                format!("{}{{{}}}", key.name(), labels.join(","))
+            };
+            Arc::new(Handle(name, self.0.clone()))
+        }
+
+        /// The total of every value recorded under the key.
+        fn total(&self, key: &str) -> f64 {
+            self.0
+                .lock()
+                .unwrap()
+                .get(key)
+                .map_or(0.0, |values| values.iter().sum())
+        }
+
+        /// The values recorded under the key.
+        fn values(&self, key: &str) -> Vec<f64> {
+            self.0.lock().unwrap().get(key).cloned().unwrap_or_default()
+        }
+    }
+
+    impl Recorder for Capture {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.handle(key))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.handle(key))
+        }
+    }
+
+    #[test]
+    fn metrics_of_a_resolution() {
+        let capture = Capture::default();
+        let auction = AuctionBuilder::new()
+            .lots(2)
+            .reserve_price(10)
+            .tick_size(5)
+            .build();
+        let bids: Bids = vec![
+            bid![30, 1],
+            bid![20, 1],
+            bid![15, 1],
+            bid![5, 1],
+            bid![22, 1],
+        ];
+        metrics::with_local_recorder(&capture, || auction.resolve_bids(bids));
+        assert_eq!(capture.total("auction_bids_processed_total"), 5.0);
+        assert_eq!(
+            capture.total("auction_bids_rejected_total{reason=outbid}"),
+            1.0
+        );
+        assert_eq!(
+            capture.total("auction_bids_rejected_total{reason=below_reserve}"),
+            1.0
+        );
+        assert_eq!(
+            capture.total("auction_bids_rejected_total{reason=off_tick}"),
+            1.0
+        );
+        assert_eq!(capture.values("auction_revenue_cents"), [40.0]);
+        assert_eq!(capture.values("auction_clearing_price_cents"), [20.0]);
+        assert_eq!(
+            capture.values("auction_resolution_duration_seconds").len(),
+            1
+        );
+    }
+
+    #[test]
+    fn metrics_reject_bids_filtered_for_their_currency() {
+        let capture = Capture::default();
+        let usd = Currency::USD;
+        let auction = AuctionBuilder::new()
+            .lots(2)
+            .currency(usd)
+            .currency_mismatch(CurrencyMismatch::Filter)
+            .build();
+        let bids: Bids = vec![
+            bid![30, 1].with_currency(usd),
+            bid![20, 1].with_currency(Currency::EUR),
+            bid![10, 1],
+        ];
+        metrics::with_local_recorder(&capture, || auction.resolve_bids(bids));
+        assert_eq!(
+            capture.total("auction_bids_rejected_total{reason=currency_mismatch}"),
+            2.0
+        );
+        assert_eq!(
+            capture.total("auction_bids_rejected_total{reason=outbid}"),
+            0.0
+        );
+    }
+
+    #[test]
+    fn metrics_reject_a_batch_refused_for_its_currency() {
+        let capture = Capture::default();
+        let usd = Currency::USD;
+        let auction = AuctionBuilder::new()
+            .currency(usd)
+            .currency_mismatch(CurrencyMismatch::Reject)
+            .build();
+        let bids: Bids = vec![
+            bid![30, 1].with_currency(usd),
+            bid![5, 1].with_currency(Currency::EUR),
+        ];
+        metrics::with_local_recorder(&capture, || auction.resolve_bids(bids));
+        assert_eq!(
+            capture.total("auction_bids_rejected_total{reason=currency_mismatch}"),
+            2.0
+        );
+        assert_eq!(
+            capture.total("auction_bids_rejected_total{reason=below_reserve}"),
+            0.0
+        );
+    }
+
+    #[test]
+    fn metrics_carry_the_auction_label() {
+        let capture = Capture::default();
+        let auction = AuctionBuilder::new().metrics_label("spring-sale").build();
+        metrics::with_local_recorder(&capture, || auction.resolve_bids(vec![bid![30, 1]]));
+        assert_eq!(
+            capture.total("auction_bids_processed_total{auction_id=spring-sale}"),
+            1.0
+        );
+        assert_eq!(capture.total("auction_bids_processed_total"), 0.0);
+        assert_eq!(
+            capture.values("auction_clearing_price_cents{auction_id=spring-sale}"),
+            [30.0]
+        );
+    }
+
+    #[test]
+    fn metrics_of_a_session() {
+        let capture = Capture::default();
+        let auction = AuctionBuilder::new()
+            .lots(1)
+            .reserve_price(10)
+            .metrics_label("live")
+            .build();
+        let mut session = AuctionSession::new(auction);
+        let accepted = bid![20, 1];
+        metrics::with_local_recorder(&capture, || {
+            session.submit(accepted).unwrap();
+            session.submit(bid![30, 1]).unwrap();
+            session.submit(bid![5, 1]).unwrap_err();
+            session.submit(accepted).unwrap_err();
+            session.withdraw(accepted.id()).unwrap();
+            session.close().unwrap();
+        });
+        assert_eq!(
+            capture.total("auction_session_bids_submitted_total{auction_id=live}"),
+            2.0
+        );
+        assert_eq!(
+            capture
+                .total("auction_session_bids_refused_total{auction_id=live,reason=below_reserve}"),
+            1.0
+        );
+        assert_eq!(
+            capture.total(
+                "auction_session_bids_refused_total{auction_id=live,reason=duplicate_bid_id}"
+            ),
+            1.0
+        );
+        assert_eq!(
+            capture.total("auction_session_bids_withdrawn_total{auction_id=live}"),
+            1.0
+        );
+        assert_eq!(
+            capture.total("auction_bids_processed_total{auction_id=live}"),
+            1.0
+        );
+    }
+}
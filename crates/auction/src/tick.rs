@@ -0,0 +1,222 @@
+//! Module for keeping bid amounts on the auction's tick grid.
+use alloc::borrow::Cow;
+
+use crate::{Auction, AuctionDirection, Bid, TickAnchor, TickPolicy};
+
+/// The bids as the auction sees them on its tick grid.
+///
+/// Off-grid bids are dropped or rounded down, going by the tick policy. The
+/// bids are borrowed untouched when there is no grid to apply.
+pub fn apply_tick_size<'a>(auction: &Auction, bids: &'a [Bid]) -> Cow<'a, [Bid]> {
+    let Some(tick_size) = auction.tick_size.filter(|&tick_size| tick_size > 1) else {
+        return Cow::Borrowed(bids);
+    };
+    let anchor = anchor(auction);
+    let on_grid = bids
+        .iter()
+        .filter_map(|bid| match auction.tick_policy {
+            TickPolicy::Reject => is_on_grid(bid.amount, anchor, tick_size).then_some(*bid),
+            TickPolicy::RoundDown => {
+                round_down(bid.amount, anchor, tick_size).map(|amount| bid.with_amount(amount))
+            }
+        })
+        .collect();
+    Cow::Owned(on_grid)
+}
+
+/// The derived price `numerator / denominator` rounded onto the auction's
+/// tick grid, or to a whole cent without one, by its rounding policy. None if
+/// it does not fit in an `i64`.
+pub fn round_price(auction: &Auction, numerator: i128, denominator: i128) -> Option<i64> {
+    let tick_size = auction
+        .tick_size
+        .filter(|&tick_size| tick_size > 1)
+        .map_or(1, i128::from);
+    let anchor = i128::from(anchor(auction));
+    let ticks = auction
+        .rounding_policy
+        .divide(numerator - anchor * denominator, denominator * tick_size);
+    i64::try_from(anchor + ticks * tick_size).ok()
+}
+
+/// The price moved onto the auction's tick grid, up to the next tick in a
+/// forward auction and down in a reverse one, so a reserve price stays met and
+/// a price ceiling unbroken. The price is kept as is without a grid, or when
+/// the next tick does not fit in an `i64`.
+pub fn snap_price(auction: &Auction, price: i64) -> i64 {
+    let Some(tick_size) = auction.tick_size.filter(|&tick_size| tick_size > 1) else {
+        return price;
+    };
+    let anchor = anchor(auction);
+    match auction.direction {
+        AuctionDirection::Forward => round_up(price, anchor, tick_size),
+        AuctionDirection::Reverse => round_down(price, anchor, tick_size),
+    }
+    .unwrap_or(price)
+}
+
+/// Whether the auction rejects the bid for being off its tick grid.
+pub fn is_rejected(auction: &Auction, bid: &Bid) -> bool {
+    match auction.tick_size {
+        Some(tick_size) if tick_size > 1 && auction.tick_policy == TickPolicy::Reject => {
+            !is_on_grid(bid.amount, anchor(auction), tick_size)
+        }
+        _ => false,
+    }
+}
+
+/// The price the grid is counted from.
+const fn anchor(auction: &Auction) -> i64 {
+    match auction.tick_anchor {
+        TickAnchor::Zero => 0,
+        TickAnchor::ReservePrice => auction.reserve_price,
+    }
+}
+
+/// Whether the amount is a whole number of ticks from the anchor.
+fn is_on_grid(amount: i64, anchor: i64, tick_size: i64) -> bool {
+    (i128::from(amount) - i128::from(anchor)).rem_euclid(i128::from(tick_size)) == 0
+}
+
+/// The amount rounded down to the grid, None if that falls below `i64::MIN`.
+fn round_down(amount: i64, anchor: i64, tick_size: i64) -> Option<i64> {
+    let offset = (i128::from(amount) - i128::from(anchor)).rem_euclid(i128::from(tick_size));
+    i64::try_from(i128::from(amount) - offset).ok()
+}
+
+/// The amount rounded up to the grid, None if that rises above `i64::MAX`.
+fn round_up(amount: i64, anchor: i64, tick_size: i64) -> Option<i64> {
+    let offset = (i128::from(anchor) - i128::from(amount)).rem_euclid(i128::from(tick_size));
+    i64::try_from(i128::from(amount) + offset).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::*;
+
+    fn tick_auction(strategy: AuctionStrategy, policy: TickPolicy) -> Auction {
+        AuctionBuilder::new()
+            .strategy(strategy)
+            .lots(3)
+            .tick_size(10)
+            .tick_policy(policy)
+            .build()
+    }
+
+    #[test]
+    fn tick_rejects_off_grid_bids() {
+        let bids: Bids = vec![bid![105, 1], bid![100, 1], bid![90, 1]];
+        let auction = tick_auction(AuctionStrategy::MultiPrice, TickPolicy::Reject);
+        let sales = auction.resolve_bids(bids.clone());
+        let bidders: Vec<_> = sales.iter().map(|sale| sale.bidder_id).collect();
+        assert_eq!(bidders, vec![bids[1].id, bids[2].id]);
+    }
+
+    #[test]
+    fn tick_rejection_is_reported() {
+        let bids: Bids = vec![bid![105, 1], bid![100, 1]];
+        let auction = tick_auction(AuctionStrategy::SinglePrice, TickPolicy::Reject);
+        let resolution = auction.resolve_bids_full(bids.clone(), 0);
+        assert_eq!(resolution.rejected().len(), 1);
+        assert_eq!(resolution.rejected()[0].bid().id(), bids[0].id);
+        assert_eq!(resolution.rejected()[0].reason(), RejectReason::OffTick);
+    }
+
+    #[test]
+    fn tick_round_down_reorders_nearly_equal_bids() {
+        let higher = bid![109, 1].with_submitted_at(200);
+        let earlier = bid![101, 1].with_submitted_at(100);
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(1)
+            .tick_size(10)
+            .tick_policy(TickPolicy::RoundDown)
+            .build();
+        let sales = auction.resolve_bids(vec![higher, earlier]);
+        assert_eq!(
+            sales,
+            vec![Sale::new(earlier.id, 100, 1).with_bid(&earlier)]
+        );
+    }
+
+    #[test]
+    fn tick_single_price_clears_on_grid() {
+        let bids: Bids = vec![bid![157, 1], bid![143, 1], bid![128, 1], bid![99, 1]];
+        let auction = tick_auction(AuctionStrategy::SinglePrice, TickPolicy::RoundDown);
+        let sales = auction.resolve_bids(bids);
+        assert_eq!(sales.len(), 3);
+        assert!(sales.iter().all(|sale| sale.amount == 120));
+
+        let highest_rejected = |direction: AuctionDirection| {
+            AuctionBuilder::new()
+                .lots(3)
+                .direction(direction)
+                .reserve_price(15)
+                .price_ceiling(25)
+                .tick_size(10)
+                .pricing_rule(PricingRule::HighestRejected)
+                .build()
+        };
+        let sales = highest_rejected(AuctionDirection::Forward)
+            .resolve_bids(vec![bid![30, 1], bid![20, 1]]);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.amount == 20));
+        let sales =
+            highest_rejected(AuctionDirection::Reverse).resolve_bids(vec![bid![10, 1], bid![0, 1]]);
+        assert_eq!(sales.len(), 2);
+        assert!(sales.iter().all(|sale| sale.amount == 20));
+    }
+
+    #[test]
+    fn tick_grid_can_start_at_reserve() {
+        let bids: Bids = vec![bid![105, 1], bid![100, 1], bid![115, 1]];
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .reserve_price(5)
+            .tick_size(10)
+            .tick_anchor(TickAnchor::ReservePrice)
+            .build();
+        let sales = auction.resolve_bids(bids.clone());
+        let bidders: Vec<_> = sales.iter().map(|sale| sale.bidder_id).collect();
+        assert_eq!(bidders, vec![bids[2].id, bids[0].id]);
+    }
+
+    #[test]
+    fn tick_of_one_resolves_as_today() {
+        for seed in 0..20 {
+            let bids = generators::large_quantities(seed, 100, 999, 3);
+            for strategy in [AuctionStrategy::SinglePrice, AuctionStrategy::MultiPrice] {
+                for policy in [TickPolicy::Reject, TickPolicy::RoundDown] {
+                    let plain = AuctionBuilder::new()
+                        .strategy(strategy.clone())
+                        .lots(10)
+                        .build();
+                    let ticked = AuctionBuilder::new()
+                        .strategy(strategy.clone())
+                        .lots(10)
+                        .tick_size(1)
+                        .tick_policy(policy)
+                        .build();
+                    assert_eq!(
+                        ticked.resolve_bids(bids.clone()),
+                        plain.resolve_bids(bids.clone())
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tick_applies_to_streamed_and_explained_sales() {
+        let bids: Bids = vec![bid![105, 1], bid![100, 1], bid![90, 1]];
+        let auction = tick_auction(AuctionStrategy::MultiPrice, TickPolicy::Reject);
+        let sales = auction.resolve_bids(bids.clone());
+        let streamed: Sales = auction.resolve_iter(bids.clone()).collect();
+        let (explained, outcomes) = auction.resolve_explained(bids);
+        assert_eq!(streamed, sales);
+        assert_eq!(explained, sales);
+        assert_eq!(outcomes.len(), 3);
+        assert_eq!(outcomes[2].outcome(), Outcome::OffTick);
+    }
+}
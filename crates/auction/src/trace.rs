@@ -0,0 +1,99 @@
+//! Module for tracing the resolution pipeline, behind the `tracing` feature.
+use tracing::{debug, info, info_span, Span};
+
+use crate::{tick, Auction, Bid, Sale, SalesSummary};
+
+/// A span around one resolution, carrying the auction settings.
+pub fn resolve_span(auction: &Auction, bids: &[Bid]) -> Span {
+    info_span!(
+        "resolve_bids",
+        lots = auction.lots,
+        reserve_price = auction.reserve_price,
+        strategy = ?auction.strategy,
+        bids = bids.len(),
+    )
+}
+
+/// Emits an event for every bid that cannot win, followed by the result.
+pub fn resolved(auction: &Auction, bids: &[Bid], sales: &[Sale]) {
+    for bid in bids {
+        if let Some(reason) = rejection(auction, bid) {
+            debug!(bid_id = %bid.id, amount = bid.amount, reason, "bid rejected");
+        }
+    }
+    let clearing_price = sales.last().map(|sale| sale.amount);
+    let revenue = sales.total_revenue();
+    info!(
+        clearing_price,
+        revenue,
+        sales = sales.len(),
+        "bids resolved"
+    );
+}
+
+/// Why the bid cannot win, if it is rejected outright.
+fn rejection(auction: &Auction, bid: &Bid) -> Option<&'static str> {
+    if bid.quantity == 0 {
+        Some("zero quantity")
+    } else if tick::is_rejected(auction, bid) {
+        Some("off tick")
+    } else if !auction.qualifies(bid.amount) {
+        Some("below reserve")
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io,
+        sync::{Arc, Mutex},
+    };
+
+    use crate::*;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture(resolve: impl FnOnce()) -> String {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, resolve);
+        let output = captured.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn tracing_events_fire_for_small_auction() {
+        let auction = AuctionBuilder::new().lots(2).reserve_price(10).build();
+        let bids: Bids = vec![bid![30, 1], bid![20, 1], bid![5, 1], bid![15, 0]];
+        let output = capture(|| {
+            auction.resolve_bids(bids);
+        });
+
+        assert!(
+            output.contains("resolve_bids{lots=2 reserve_price=10 strategy=SinglePrice bids=4}")
+        );
+        assert_eq!(output.matches("bid rejected").count(), 2);
+        assert!(output.contains("amount=5 reason=\"below reserve\""));
+        assert!(output.contains("amount=15 reason=\"zero quantity\""));
+        assert!(output.contains("bids resolved clearing_price=20 revenue=40 sales=2"));
+    }
+}
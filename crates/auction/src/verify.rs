@@ -0,0 +1,334 @@
+//! Module for checking the structural invariants of a resolution.
+use alloc::vec::Vec;
+
+use uuid::Uuid;
+
+use crate::{
+    collections::{self, HashMap},
+    Auction, AuctionDirection, AuctionStrategy, Bid, Sale,
+};
+
+/// Enum representing a broken invariant in the sales of a resolution.
+///
+/// Each variant carries the index of the offending sale where there is one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// The sale's bidder placed none of the bids.
+    UnknownBidder { sale: usize, bidder_id: Uuid },
+    /// The sales sell more units than the auction has lots.
+    LotsExceeded { sold: usize, lots: usize },
+    /// The sale is for more units than any of its bidder's bids.
+    QuantityExceedsBid {
+        sale: usize,
+        quantity: usize,
+        bid_quantity: usize,
+    },
+    /// The sale is below the reserve price of a forward auction, or of the
+    /// last lot it takes under a reserve schedule.
+    BelowReserve {
+        sale: usize,
+        amount: i64,
+        reserve_price: i64,
+    },
+    /// The sale is above the price ceiling of a reverse auction.
+    AboveCeiling {
+        sale: usize,
+        amount: i64,
+        price_ceiling: i64,
+    },
+    /// The sale's amount differs from the first sale of a single price auction,
+    /// leaving out sales at the buy-now price.
+    PriceMismatch {
+        sale: usize,
+        expected: i64,
+        actual: i64,
+    },
+}
+
+/// Checks the sales resolved from the bids against the auction's invariants.
+///
+/// Only the largest bid of each bidder is kept while checking, and nothing else
+/// is allocated unless an invariant is broken. Under a reserve schedule the
+/// sales are taken to claim lots in the order given, best first, as the
+/// built-in strategies order them.
+///
+/// # Arguments
+/// * `auction` - The auction the bids were resolved against.
+/// * `bids` - The bids that were resolved.
+/// * `sales` - The sales the resolution produced.
+///
+/// # Returns
+/// Nothing if every invariant holds, otherwise each violation found.
+///
+pub fn verify(auction: &Auction, bids: &[Bid], sales: &[Sale]) -> Result<(), Vec<Violation>> {
+    let mut largest_bid: HashMap<Uuid, Option<usize>> = collections::map_with_capacity(sales.len());
+    for sale in sales {
+        largest_bid.entry(sale.bidder_id).or_default();
+    }
+    for bid in bids {
+        if let Some(largest) = largest_bid.get_mut(&bid.bidder_id) {
+            *largest = Some(largest.unwrap_or_default().max(bid.quantity));
+        }
+    }
+
+    let single_price = matches!(
+        auction.strategy,
+        AuctionStrategy::SinglePrice
+            | AuctionStrategy::Dutch { .. }
+            | AuctionStrategy::Japanese { .. }
+            | AuctionStrategy::PostedPrice
+    );
+    let mut violations = Vec::new();
+    let mut sold: usize = 0;
+    let mut clearing_price = None;
+    for (index, sale) in sales.iter().enumerate() {
+        let reserve_price =
+            auction.reserve_at(sold.saturating_add(sale.quantity.saturating_sub(1)));
+        sold = sold.saturating_add(sale.quantity);
+        match largest_bid.get(&sale.bidder_id).copied().flatten() {
+            None => violations.push(Violation::UnknownBidder {
+                sale: index,
+                bidder_id: sale.bidder_id,
+            }),
+            Some(bid_quantity) if sale.quantity > bid_quantity => {
+                violations.push(Violation::QuantityExceedsBid {
+                    sale: index,
+                    quantity: sale.quantity,
+                    bid_quantity,
+                });
+            }
+            Some(_) => {}
+        }
+        match auction.direction {
+            AuctionDirection::Forward if sale.amount < reserve_price => {
+                violations.push(Violation::BelowReserve {
+                    sale: index,
+                    amount: sale.amount,
+                    reserve_price,
+                });
+            }
+            AuctionDirection::Reverse => match auction.price_ceiling {
+                Some(price_ceiling) if sale.amount > price_ceiling => {
+                    violations.push(Violation::AboveCeiling {
+                        sale: index,
+                        amount: sale.amount,
+                        price_ceiling,
+                    });
+                }
+                _ => {}
+            },
+            AuctionDirection::Forward => {}
+        }
+        if auction.buy_now_price == Some(sale.amount) {
+            continue;
+        }
+        match clearing_price {
+            Some(expected) if single_price && sale.amount != expected => {
+                violations.push(Violation::PriceMismatch {
+                    sale: index,
+                    expected,
+                    actual: sale.amount,
+                });
+            }
+            Some(_) => {}
+            None => clearing_price = Some(sale.amount),
+        }
+    }
+    if sold > auction.lots {
+        violations.push(Violation::LotsExceeded {
+            sold,
+            lots: auction.lots,
+        });
+    }
+
+    if violations.is_empty() {
+        Ok(())
+    } else {
+        Err(violations)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use uuid::Uuid;
+
+    use crate::*;
+
+    #[test]
+    fn verify_accepts_every_built_in_strategy() {
+        let strategies = [
+            AuctionStrategy::SinglePrice,
+            AuctionStrategy::MultiPrice,
+            AuctionStrategy::Dutch {
+                start_price: 60,
+                decrement: 3,
+            },
+            AuctionStrategy::AllPay,
+            AuctionStrategy::GeneralizedSecondPrice,
+            AuctionStrategy::Vcg,
+            AuctionStrategy::Lottery { seed: 5 },
+            AuctionStrategy::Scored,
+            AuctionStrategy::PostedPrice,
+            AuctionStrategy::Japanese {
+                start: None,
+                increment: 3,
+            },
+        ];
+        for strategy in strategies {
+            let auction = AuctionBuilder::new()
+                .strategy(strategy)
+                .lots(10)
+                .reserve_price(10)
+                .build();
+            let bids = generators::large_quantities(5, 100, 49, 4);
+            let sales = auction.resolve_bids_ref(&bids);
+            assert_eq!(verify(&auction, &bids, &sales), Ok(()));
+        }
+    }
+
+    #[test]
+    fn verify_accepts_reverse_auction() {
+        let auction = AuctionBuilder::new()
+            .direction(AuctionDirection::Reverse)
+            .price_ceiling(20)
+            .lots(3)
+            .build();
+        let bids: Bids = vec![bid![15, 2], bid![10, 2], bid![30, 5]];
+        let sales = auction.resolve_bids_ref(&bids);
+        assert_eq!(verify(&auction, &bids, &sales), Ok(()));
+    }
+
+    #[test]
+    fn verify_reports_unknown_bidder() {
+        let auction = AuctionBuilder::new().build();
+        let stranger = Uuid::new_v4();
+        let result = verify(&auction, &[bid![10, 1]], &[Sale::new(stranger, 10, 1)]);
+        assert_eq!(
+            result,
+            Err(vec![Violation::UnknownBidder {
+                sale: 0,
+                bidder_id: stranger
+            }])
+        );
+    }
+
+    #[test]
+    fn verify_reports_lots_and_quantity_exceeded() {
+        let auction = AuctionBuilder::new().lots(2).build();
+        let bid = bid![10, 2];
+        let result = verify(&auction, &[bid], &[Sale::new(bid.id(), 10, 3)]);
+        assert_eq!(
+            result,
+            Err(vec![
+                Violation::QuantityExceedsBid {
+                    sale: 0,
+                    quantity: 3,
+                    bid_quantity: 2
+                },
+                Violation::LotsExceeded { sold: 3, lots: 2 },
+            ])
+        );
+    }
+
+    #[test]
+    fn verify_skips_buy_now_sales_in_price_check() {
+        let auction = AuctionBuilder::new().lots(3).buy_now_price(100).build();
+        let bids = [bid![150, 1], bid![40, 1], bid![30, 1]];
+        let sales = auction.resolve_bids_ref(&bids);
+        assert_eq!(sales[0].amount(), 100);
+        assert_eq!(verify(&auction, &bids, &sales), Ok(()));
+    }
+
+    #[test]
+    fn verify_reports_sale_below_its_reserve_tier() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(3)
+            .reserve_schedule(vec![(1, 100), (2, 120)])
+            .build();
+        let bids = [bid![150, 1], bid![110, 2]];
+        let sales = [
+            Sale::new(bids[0].id(), 150, 1),
+            Sale::new(bids[1].id(), 110, 2),
+        ];
+        assert_eq!(
+            verify(&auction, &bids, &sales),
+            Err(vec![Violation::BelowReserve {
+                sale: 1,
+                amount: 110,
+                reserve_price: 120
+            }])
+        );
+    }
+
+    #[test]
+    fn verify_accepts_tiered_resolution() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .lots(4)
+            .reserve_schedule(vec![(2, 100), (2, 120)])
+            .build();
+        let bids = [bid![150, 1], bid![110, 2], bid![125, 2]];
+        let sales = auction.resolve_bids_ref(&bids);
+        assert_eq!(verify(&auction, &bids, &sales), Ok(()));
+    }
+
+    #[test]
+    fn verify_reports_below_reserve_and_price_mismatch() {
+        let auction = AuctionBuilder::new().lots(2).reserve_price(8).build();
+        let bids = [bid![10, 1], bid![9, 1]];
+        let sales = [
+            Sale::new(bids[0].id(), 10, 1),
+            Sale::new(bids[1].id(), 5, 1),
+        ];
+        assert_eq!(
+            verify(&auction, &bids, &sales),
+            Err(vec![
+                Violation::BelowReserve {
+                    sale: 1,
+                    amount: 5,
+                    reserve_price: 8
+                },
+                Violation::PriceMismatch {
+                    sale: 1,
+                    expected: 10,
+                    actual: 5
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn verify_reports_above_ceiling() {
+        let auction = AuctionBuilder::new()
+            .strategy(AuctionStrategy::MultiPrice)
+            .direction(AuctionDirection::Reverse)
+            .price_ceiling(20)
+            .build();
+        let bid = bid![25, 1];
+        let result = verify(&auction, &[bid], &[Sale::new(bid.id(), 25, 1)]);
+        assert_eq!(
+            result,
+            Err(vec![Violation::AboveCeiling {
+                sale: 0,
+                amount: 25,
+                price_ceiling: 20
+            }])
+        );
+    }
+
+    #[test]
+    fn verify_reports_sale_to_zero_quantity_bid() {
+        let auction = AuctionBuilder::new().build();
+        let bid = bid![10, 0];
+        let result = verify(&auction, &[bid], &[Sale::new(bid.id(), 10, 1)]);
+        assert_eq!(
+            result,
+            Err(vec![Violation::QuantityExceedsBid {
+                sale: 0,
+                quantity: 1,
+                bid_quantity: 0
+            }])
+        );
+    }
+}
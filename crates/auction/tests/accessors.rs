@@ -0,0 +1,26 @@
+#![cfg(feature = "std")]
+use auction::*;
+
+#[test]
+fn accessors_round_trip_through_resolution() {
+    let bids: Bids = vec![Bid::new(10, 2), Bid::new(20, 1)];
+    let partial = bids[0];
+    assert_eq!(partial.amount(), 10);
+    assert_eq!(partial.quantity(), 2);
+
+    let auction = AuctionBuilder::new()
+        .strategy(AuctionStrategy::MultiPrice)
+        .lots(2)
+        .reserve_price(5)
+        .build();
+    assert_eq!(auction.lots(), 2);
+    assert_eq!(auction.reserve_price(), 5);
+    assert!(matches!(auction.strategy(), AuctionStrategy::MultiPrice));
+
+    let sales = auction.resolve_bids(bids);
+    assert_eq!(sales[1].bidder_id(), partial.id());
+    assert_eq!(sales[1].amount(), 10);
+    assert_eq!(sales[1].quantity(), 1);
+    assert_eq!(sales[1].total(), 10);
+    assert_eq!(sales[0].total(), 20);
+}
@@ -0,0 +1,16 @@
+#![cfg(feature = "std")]
+use auction::bid;
+use uuid::Uuid;
+
+const RESERVE: i64 = 5;
+
+#[test]
+fn bid_macro_works_from_another_crate() {
+    let id = Uuid::new_v4();
+    let quantity = 2;
+    let bids = [bid![RESERVE * 2, quantity], bid![id; 30, 1], bid![7]];
+    assert_eq!(bids[0].amount(), 10);
+    assert_eq!(bids[0].quantity(), 2);
+    assert_eq!(bids[1].id(), id);
+    assert_eq!(bids[2].quantity(), 1);
+}
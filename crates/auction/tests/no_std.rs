@@ -0,0 +1,32 @@
+//! Resolution through the API left without the `std` feature, run with
+//! `cargo test --no-default-features --test no_std`.
+use auction::{bid, AuctionBuilder, AuctionStrategy, Bids, SalesSummary};
+use uuid::Uuid;
+
+fn bids() -> Bids {
+    (1..=4)
+        .map(|n| bid![Uuid::from_u128(n); n as i64 * 10, 1])
+        .collect()
+}
+
+#[test]
+fn resolve_bids_without_std() {
+    let bids = bids();
+    let auction = AuctionBuilder::new().lots(2).reserve_price(15).build();
+    let sales = auction.resolve_bids(bids.clone());
+    assert_eq!(sales.len(), 2);
+    assert!(sales.iter().all(|sale| sale.amount() == 30));
+    assert_eq!(sales[0].bidder_id(), bids[3].id());
+    assert_eq!(sales.total_revenue(), Some(60));
+}
+
+#[test]
+fn resolve_bids_by_strategy_without_std() {
+    let auction = AuctionBuilder::new()
+        .strategy(AuctionStrategy::MultiPrice)
+        .lots(3)
+        .build();
+    let result = auction.resolve(bids()).unwrap();
+    assert_eq!(result.lots_sold(), 3);
+    assert_eq!(result.revenue(), 90);
+}
@@ -0,0 +1,44 @@
+#![cfg(feature = "std")]
+
+use auction::prelude::*;
+
+#[test]
+fn prelude_covers_resolving_an_auction() {
+    let auction: Auction = AuctionBuilder::new()
+        .strategy(AuctionStrategy::SinglePrice)
+        .direction(AuctionDirection::Forward)
+        .lots(2)
+        .build();
+    let bids: Bids = vec![bid![30, 1], bid![20, 1], bid![10]];
+    let winner: Uuid = bids[0].id();
+    let sales: Sales = auction.resolve_bids(bids.clone());
+    let first: &Sale = &sales[0];
+    assert_eq!(first.bidder_id(), winner);
+    assert_eq!(sales.total_revenue(), Some(40));
+    let totals: SalesTotals = sales.iter().sum();
+    assert_eq!(totals.quantity(), Some(2));
+    let result: Result<AuctionResult, AuctionError> = auction.resolve(bids.clone());
+    assert_eq!(result.unwrap().lots_sold(), 2);
+    let strategy: &dyn Strategy = auction.strategy();
+    assert_eq!(strategy.resolve(&auction, &bids).len(), 2);
+    let _: Bid = bid![Uuid::nil(); 5, 1];
+}
+
+#[test]
+fn strategy_functions_are_callable_directly() {
+    let auction = AuctionBuilder::new().lots(2).build();
+    let bids = [bid![30, 1], bid![20, 1], bid![10, 1]];
+    let prices = |sales: Sales| sales.iter().map(Sale::amount).collect::<Vec<_>>();
+    assert_eq!(
+        prices(auction::strategies::single_price(&auction, &bids)),
+        [20, 20]
+    );
+    assert_eq!(
+        prices(auction::strategies::multi_price(&auction, &bids)),
+        [30, 20]
+    );
+    assert_eq!(
+        prices(auction::strategies::dutch(&auction, &bids, 40, 5)),
+        [20, 20]
+    );
+}
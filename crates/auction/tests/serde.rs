@@ -0,0 +1,234 @@
+#![cfg(feature = "serde")]
+use auction::*;
+
+fn round_trip<T: serde::Serialize + serde::de::DeserializeOwned>(value: &T) -> T {
+    let json = serde_json::to_string(value).unwrap();
+    serde_json::from_str(&json).unwrap()
+}
+
+fn sample_bids() -> Bids {
+    vec![
+        Bid::new(10, 2).with_submitted_at(5),
+        Bid::new(20, 1).with_all_or_nothing(true),
+        Bid::new(15, 3).with_expires_at(100),
+    ]
+}
+
+fn sample_auction() -> Auction {
+    AuctionBuilder::new()
+        .strategy(AuctionStrategy::MultiPrice)
+        .lots(4)
+        .reserve_price(12)
+        .build()
+}
+
+#[test]
+fn bids_round_trip() {
+    let bids = sample_bids();
+    let restored: Bids = round_trip(&bids);
+    for (bid, restored) in bids.iter().zip(&restored) {
+        assert_eq!(bid.id(), restored.id());
+        assert_eq!(bid.bidder_id(), restored.bidder_id());
+        assert_eq!(bid.amount(), restored.amount());
+        assert_eq!(bid.quantity(), restored.quantity());
+        assert_eq!(bid.submitted_at(), restored.submitted_at());
+        assert_eq!(bid.all_or_nothing(), restored.all_or_nothing());
+        assert_eq!(bid.expires_at(), restored.expires_at());
+    }
+    let auction = sample_auction();
+    assert_eq!(auction.resolve_bids(bids), auction.resolve_bids(restored));
+}
+
+#[test]
+fn bid_optional_fields_may_be_omitted() {
+    let bid: Bid = serde_json::from_str(r#"{"amount": 10, "quantity": 2}"#).unwrap();
+    assert_eq!(bid.amount(), 10);
+    assert_eq!(bid.quantity(), 2);
+    assert_eq!(bid.bidder_id(), bid.id());
+    assert_eq!(bid.submitted_at(), None);
+}
+
+#[test]
+fn sales_round_trip() {
+    let sales = sample_auction().resolve_bids(sample_bids());
+    assert_eq!(round_trip(&sales), sales);
+}
+
+#[test]
+fn auction_round_trip() {
+    let auction = sample_auction();
+    let restored: Auction = round_trip(&auction);
+    assert_eq!(restored.lots(), auction.lots());
+    assert_eq!(restored.reserve_price(), auction.reserve_price());
+    let bids = sample_bids();
+    assert_eq!(
+        auction.resolve_bids(bids.clone()),
+        restored.resolve_bids(bids)
+    );
+}
+
+#[test]
+fn strategies_round_trip() {
+    let strategies = vec![
+        AuctionStrategy::SinglePrice,
+        AuctionStrategy::MultiPrice,
+        AuctionStrategy::Dutch {
+            start_price: 30,
+            decrement: 5,
+        },
+        AuctionStrategy::AllPay,
+        AuctionStrategy::GeneralizedSecondPrice,
+        AuctionStrategy::Proxy,
+        AuctionStrategy::Vcg,
+        AuctionStrategy::Lottery { seed: 7 },
+        AuctionStrategy::Scored,
+        AuctionStrategy::PostedPrice,
+        AuctionStrategy::Japanese {
+            start: Some(30),
+            increment: 5,
+        },
+    ];
+    for strategy in strategies {
+        let restored = round_trip(&strategy);
+        let auction = AuctionBuilder::new().strategy(strategy).lots(1).build();
+        let restored = AuctionBuilder::new().strategy(restored).lots(1).build();
+        let bids = sample_bids();
+        assert_eq!(
+            auction.resolve_bids(bids.clone()),
+            restored.resolve_bids(bids)
+        );
+    }
+}
+
+#[derive(Debug)]
+struct Nothing;
+
+impl Strategy for Nothing {
+    fn resolve(&self, _auction: &Auction, _bids: &[Bid]) -> Sales {
+        Vec::new()
+    }
+}
+
+#[test]
+fn custom_strategy_cannot_be_serialized() {
+    let auction = AuctionBuilder::new()
+        .custom_strategy(Box::new(Nothing))
+        .build();
+    assert!(serde_json::to_string(&auction).is_err());
+}
+
+#[test]
+fn builder_round_trip() {
+    let builder = AuctionBuilder::new()
+        .strategy(AuctionStrategy::MultiPrice)
+        .lots(4)
+        .reserve_price(12);
+    let restored: AuctionBuilder = round_trip(&builder);
+    let bids = sample_bids();
+    assert_eq!(
+        builder.build().resolve_bids(bids.clone()),
+        restored.build().resolve_bids(bids)
+    );
+}
+
+#[test]
+fn result_round_trip() {
+    let result = sample_auction().resolve(sample_bids()).unwrap();
+    let restored: AuctionResult = round_trip(&result);
+    assert_eq!(restored.sales(), result.sales());
+    assert_eq!(restored.clearing_price(), result.clearing_price());
+    assert_eq!(restored.revenue(), result.revenue());
+}
+
+fn sample_session() -> AuctionSession {
+    let mut session = AuctionSession::new(sample_auction())
+        .with_closes_at(100)
+        .with_extension(10, 5, 120);
+    for bid in sample_bids().into_iter().filter(|bid| bid.amount() >= 12) {
+        session.submit_at(bid, 50).unwrap();
+    }
+    let withdrawn = Bid::new(30, 1).with_submitted_at(60);
+    session.submit_at(withdrawn, 60).unwrap();
+    session.withdraw(withdrawn.id()).unwrap();
+    session.submit_at(Bid::new(25, 1), 95).unwrap();
+    session
+}
+
+fn assert_restored(mut session: AuctionSession, mut restored: AuctionSession) {
+    assert_eq!(restored.closes_at(), Some(105));
+    assert_eq!(restored.closes_at(), session.closes_at());
+    let timestamps = |session: &AuctionSession| -> Vec<_> {
+        session
+            .bids()
+            .iter()
+            .chain(session.withdrawals())
+            .map(|bid| (bid.id(), bid.submitted_at(), bid.expires_at()))
+            .collect()
+    };
+    assert_eq!(timestamps(&restored), timestamps(&session));
+    let duplicate = session.bids()[0];
+    assert_eq!(
+        restored.submit(duplicate),
+        Err(BidRejection::DuplicateBidId(duplicate.id()))
+    );
+    assert_eq!(restored.close(), session.close());
+}
+
+#[test]
+fn session_snapshot_round_trip() {
+    let session = sample_session();
+    let restored = AuctionSession::restore(round_trip(&session.snapshot()));
+    assert_restored(session, restored);
+}
+
+#[test]
+fn session_snapshot_bincode_round_trip() {
+    let session = sample_session();
+    let bytes = bincode::serialize(&session.snapshot()).unwrap();
+    let snapshot: SessionSnapshot = bincode::deserialize(&bytes).unwrap();
+    assert_restored(session, AuctionSession::restore(snapshot));
+}
+
+#[test]
+fn simulation_report_round_trip() {
+    use auction::simulation::{simulate, BidderModel, Valuation};
+
+    let model = Valuation::LogNormal {
+        mu: 4.0,
+        sigma: 0.3,
+    };
+    let models: [(&dyn BidderModel, usize); 1] = [(&model, 4)];
+    let report = simulate(&sample_auction(), &models, 50, 11);
+    assert_eq!(round_trip(&report), report);
+    assert_eq!(round_trip(&model), model);
+}
+
+#[test]
+fn builder_fields_may_be_omitted() {
+    let builder: AuctionBuilder =
+        serde_json::from_str(r#"{"lots": 2, "strategy": "MultiPrice"}"#).unwrap();
+    let auction = builder.try_build().unwrap();
+    assert_eq!(auction.lots(), 2);
+    assert!(matches!(auction.strategy(), AuctionStrategy::MultiPrice));
+    assert_eq!(auction.reserve_price(), 0);
+}
+
+#[test]
+fn event_log_round_trip_replays() {
+    let auction = AuctionBuilder::new()
+        .currency(Currency::EUR)
+        .lots(2)
+        .reserve_price(12)
+        .build();
+    let bids: Bids = sample_bids()
+        .into_iter()
+        .map(|bid| bid.with_currency(Currency::EUR))
+        .collect();
+    let (sales, events) = auction.resolve_with_events(bids);
+    let events = round_trip(&events);
+    assert_eq!(replay_events(&events), sales);
+    assert!(matches!(
+        events.last().unwrap().kind(),
+        EventKind::AuctionClosed { revenue: Some(_) }
+    ));
+}
@@ -0,0 +1,162 @@
+//! The request and response bodies of the service, and resolving one into
+//! the other.
+use auction::{
+    AuctionBuilder, AuctionError, AuctionStrategy, Bids, RejectedBid, Sales, SalesSummary,
+    Timestamp,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// The body of `POST /auctions/resolve`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResolveRequest {
+    pub lots: usize,
+    #[serde(default)]
+    pub reserve_price: i64,
+    /// The strategy, single price unless given.
+    #[serde(default = "single_price")]
+    pub strategy: AuctionStrategy,
+    pub bids: Bids,
+    /// The time of resolution, bids expiring by then are rejected. Zero
+    /// unless given.
+    #[serde(default)]
+    pub now: Timestamp,
+}
+
+const fn single_price() -> AuctionStrategy {
+    AuctionStrategy::SinglePrice
+}
+
+/// The body of a successful resolution.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResolveResponse {
+    pub sales: Sales,
+    /// The lowest price lots sold at, None when nothing sold.
+    pub clearing_price: Option<i64>,
+    pub lots_sold: usize,
+    /// The total revenue in cents.
+    pub revenue: i128,
+    /// Every bid that won nothing, with the reason why.
+    pub rejected: Vec<RejectedBid>,
+}
+
+/// The body of a rejected request.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorBody {
+    /// A stable name for the kind of error, such as `invalid_bid`.
+    pub code: String,
+    pub message: String,
+    /// The bids the error is about, empty when it is about the whole
+    /// request.
+    pub bid_ids: Vec<Uuid>,
+}
+
+impl ErrorBody {
+    /// The body for a request body that is not a valid resolve request.
+    pub fn invalid_request(message: impl Into<String>) -> Self {
+        Self {
+            code: "invalid_request".to_string(),
+            message: message.into(),
+            bid_ids: Vec::new(),
+        }
+    }
+}
+
+impl From<AuctionError> for ErrorBody {
+    fn from(error: AuctionError) -> Self {
+        let (code, bid_ids) = match &error {
+            AuctionError::Config(_) => ("invalid_auction", Vec::new()),
+            AuctionError::InvalidBid { bid_id, .. } => ("invalid_bid", vec![*bid_id]),
+            AuctionError::StrategyMismatch { bid_id, .. } => ("strategy_mismatch", vec![*bid_id]),
+            AuctionError::CurrencyMismatch { bid_ids } => ("currency_mismatch", bid_ids.clone()),
+            AuctionError::AmountOverflow => ("amount_overflow", Vec::new()),
+            _ => ("unresolvable", Vec::new()),
+        };
+        Self {
+            code: code.to_string(),
+            message: error.to_string(),
+            bid_ids,
+        }
+    }
+}
+
+/// Resolve the request, failing with the crate's error if the auction or a
+/// bid is invalid or the revenue overflows.
+pub fn resolve(request: ResolveRequest) -> Result<ResolveResponse, AuctionError> {
+    let auction = AuctionBuilder::new()
+        .lots(request.lots)
+        .reserve_price(request.reserve_price)
+        .strategy(request.strategy)
+        .try_build()?;
+    auction.check_bids(&request.bids)?;
+    let resolution = auction.resolve_bids_full(request.bids, request.now);
+    let sales = resolution.sales();
+    let revenue = sales.total_revenue().ok_or(AuctionError::AmountOverflow)?;
+    Ok(ResolveResponse {
+        clearing_price: sales
+            .iter()
+            .filter(|sale| sale.quantity() > 0)
+            .map(|sale| sale.amount())
+            .min(),
+        lots_sold: sales.total_quantity(),
+        revenue,
+        rejected: resolution.rejected().clone(),
+        sales: resolution.into_sales(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use auction::{Bid, RejectReason};
+
+    use super::*;
+
+    fn request(json: &str) -> ResolveRequest {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn resolve_reports_sales_and_rejections() {
+        let response = resolve(request(
+            r#"{
+                "lots": 2,
+                "reserve_price": 50,
+                "bids": [
+                    {"id": "00000000-0000-0000-0000-000000000001", "amount": 150, "quantity": 1},
+                    {"id": "00000000-0000-0000-0000-000000000002", "amount": 120, "quantity": 1},
+                    {"id": "00000000-0000-0000-0000-000000000003", "amount": 40, "quantity": 1}
+                ]
+            }"#,
+        ))
+        .unwrap();
+        assert_eq!(response.clearing_price, Some(120));
+        assert_eq!((response.lots_sold, response.revenue), (2, 240));
+        assert_eq!(response.rejected.len(), 1);
+        assert_eq!(response.rejected[0].bid().amount(), 40);
+        assert_eq!(response.rejected[0].reason(), RejectReason::BelowReserve);
+    }
+
+    #[test]
+    fn invalid_bid_names_the_bid() {
+        let bid = Bid::with_id(Uuid::from_u128(7), 10, 0);
+        let error = resolve(ResolveRequest {
+            lots: 1,
+            reserve_price: 0,
+            strategy: AuctionStrategy::MultiPrice,
+            bids: vec![bid],
+            now: 0,
+        })
+        .unwrap_err();
+        let body = ErrorBody::from(error);
+        assert_eq!(body.code, "invalid_bid");
+        assert_eq!(body.bid_ids, vec![bid.id()]);
+    }
+
+    #[test]
+    fn invalid_auction_is_rejected() {
+        let error = resolve(request(r#"{"lots": 0, "bids": []}"#)).unwrap_err();
+        let body = ErrorBody::from(error);
+        assert_eq!(body.code, "invalid_auction");
+        assert!(body.bid_ids.is_empty());
+    }
+}
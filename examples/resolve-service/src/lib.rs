@@ -0,0 +1,43 @@
+//! Example HTTP service resolving auctions.
+//!
+//! `POST /auctions/resolve` takes the auction and its bids as JSON and
+//! returns the sales, clearing price and rejected bids. Requests the crate
+//! refuses are answered with `400 Bad Request` and an [`api::ErrorBody`].
+#![warn(clippy::all, clippy::nursery)]
+
+pub mod api;
+
+use axum::{
+    extract::rejection::JsonRejection,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Json, Router,
+};
+
+use crate::api::{ErrorBody, ResolveRequest, ResolveResponse};
+
+/// The service's routes.
+pub fn router() -> Router {
+    Router::new().route("/auctions/resolve", post(resolve))
+}
+
+/// A request answered with `400 Bad Request`.
+#[derive(Debug)]
+pub struct BadRequest(ErrorBody);
+
+impl IntoResponse for BadRequest {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, Json(self.0)).into_response()
+    }
+}
+
+async fn resolve(
+    request: Result<Json<ResolveRequest>, JsonRejection>,
+) -> Result<Json<ResolveResponse>, BadRequest> {
+    let Json(request) = request
+        .map_err(|rejection| BadRequest(ErrorBody::invalid_request(rejection.body_text())))?;
+    api::resolve(request)
+        .map(Json)
+        .map_err(|error| BadRequest(error.into()))
+}
@@ -0,0 +1,11 @@
+//! Serves the resolution service on `AUCTION_SERVICE_ADDR`, by default
+//! `127.0.0.1:3000`.
+use tokio::net::TcpListener;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let addr =
+        std::env::var("AUCTION_SERVICE_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
+    let listener = TcpListener::bind(&addr).await?;
+    axum::serve(listener, resolve_service::router()).await
+}
@@ -0,0 +1,78 @@
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+};
+use http_body_util::BodyExt;
+use resolve_service::{api::ErrorBody, router};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+/// Post the body to the router in process, returning the status and JSON.
+async fn post(body: String) -> (StatusCode, Value) {
+    let request = Request::builder()
+        .method(Method::POST)
+        .uri("/auctions/resolve")
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .unwrap();
+    let response = router().oneshot(request).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    (status, serde_json::from_slice(&bytes).unwrap())
+}
+
+#[tokio::test]
+async fn resolves_an_auction() {
+    let (status, body) = post(
+        json!({
+            "lots": 3,
+            "strategy": "MultiPrice",
+            "bids": [
+                {"id": "00000000-0000-0000-0000-000000000001", "amount": 30, "quantity": 2},
+                {"id": "00000000-0000-0000-0000-000000000002", "amount": 20, "quantity": 2},
+                {"id": "00000000-0000-0000-0000-000000000003", "amount": 10, "quantity": 1}
+            ]
+        })
+        .to_string(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["clearing_price"], 20);
+    assert_eq!(body["lots_sold"], 3);
+    assert_eq!(body["revenue"], 80);
+    assert_eq!(body["sales"].as_array().unwrap().len(), 2);
+    assert_eq!(body["rejected"][0]["reason"], "OutbidAtClearing");
+}
+
+#[tokio::test]
+async fn invalid_bid_is_a_bad_request() {
+    let (status, body) = post(
+        json!({
+            "lots": 1,
+            "bids": [{"id": "00000000-0000-0000-0000-000000000009", "amount": -5, "quantity": 1}]
+        })
+        .to_string(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let body: ErrorBody = serde_json::from_value(body).unwrap();
+    assert_eq!(body.code, "invalid_bid");
+    assert_eq!(
+        body.bid_ids,
+        vec!["00000000-0000-0000-0000-000000000009".parse().unwrap()]
+    );
+}
+
+#[tokio::test]
+async fn invalid_auction_is_a_bad_request() {
+    let (status, body) = post(json!({"lots": 0, "bids": []}).to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_auction");
+}
+
+#[tokio::test]
+async fn malformed_body_is_a_bad_request() {
+    let (status, body) = post(r#"{"bids": []}"#.to_string()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["code"], "invalid_request");
+}
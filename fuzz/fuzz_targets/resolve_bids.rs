@@ -0,0 +1,79 @@
+//! Fuzzes `resolve_bids`, aborting on any panic or broken invariant.
+//!
+//! The input is decoded as a four byte header followed by four bytes per bid:
+//!
+//! * header: strategy and flags, lots, reserve price as a little endian `u16`.
+//! * bid: amount as a little endian `u16`, quantity, flags.
+#![no_main]
+
+use auction::{
+    verify, AllocationPolicy, AuctionBuilder, AuctionDirection, AuctionStrategy, Bid, Bids,
+};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let Some((header, bids)) = data.split_first_chunk::<4>() else {
+        return;
+    };
+    let [config, lots, reserve_low, reserve_high] = *header;
+    let reserve_price = i64::from(u16::from_le_bytes([reserve_low, reserve_high]));
+
+    let strategy = match config % 11 {
+        0 => AuctionStrategy::SinglePrice,
+        1 => AuctionStrategy::MultiPrice,
+        2 => AuctionStrategy::Dutch {
+            start_price: reserve_price + i64::from(lots) * 100,
+            decrement: i64::from(lots % 7) + 1,
+        },
+        3 => AuctionStrategy::AllPay,
+        4 => AuctionStrategy::GeneralizedSecondPrice,
+        5 => AuctionStrategy::Vcg,
+        6 => AuctionStrategy::Lottery {
+            seed: u64::from(reserve_price as u16),
+        },
+        7 => AuctionStrategy::Scored,
+        8 => AuctionStrategy::Japanese {
+            start: None,
+            increment: i64::from(lots % 7) + 1,
+        },
+        9 => AuctionStrategy::PostedPrice,
+        _ => AuctionStrategy::Proxy,
+    };
+    let mut builder = AuctionBuilder::new()
+        .strategy(strategy)
+        .lots(usize::from(lots))
+        .reserve_price(reserve_price);
+    if config & 0x40 != 0 {
+        builder = builder.allocation(AllocationPolicy::ProRata);
+    }
+    if config & 0x80 != 0 {
+        builder = builder
+            .direction(AuctionDirection::Reverse)
+            .price_ceiling(reserve_price);
+    }
+    let Ok(auction) = builder.try_build() else {
+        return;
+    };
+
+    let bids: Bids = bids.chunks_exact(4).map(decode_bid).collect();
+    let sales = auction.resolve_bids_ref(&bids);
+    if let Err(violations) = verify(&auction, &bids, &sales) {
+        panic!("invariants broken: {violations:?}");
+    }
+});
+
+fn decode_bid(bytes: &[u8]) -> Bid {
+    let amount = i64::from(u16::from_le_bytes([bytes[0], bytes[1]]));
+    let quantity = usize::from(bytes[2]);
+    let flags = bytes[3];
+    let mut bid = Bid::new(amount, quantity)
+        .with_all_or_nothing(flags & 0x01 != 0)
+        .with_proxy(flags & 0x02 != 0);
+    if flags & 0x04 != 0 {
+        bid = bid.with_submitted_at(u64::from(flags >> 4));
+    }
+    if flags & 0x08 != 0 {
+        bid = bid.with_min_quantity(quantity / 2).unwrap_or(bid);
+    }
+    bid
+}